@@ -0,0 +1,49 @@
+// Tile cover of the `tile_material` feature: bevel, hover glow, reveal dissolve and explosion
+// shockwave, set per tile by the board plugin
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct TileMaterial {
+    color: vec4<f32>,
+    // bevel, glow, dissolve, unused
+    effects: vec4<f32>,
+    // world position of the bomb, radius of the wave, strength
+    shockwave: vec4<f32>,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: TileMaterial;
+
+// Value noise of the dissolve, from 0 to 1
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let bevel = material.effects.x;
+    let glow = material.effects.y;
+    let dissolve = material.effects.z;
+
+    // the cover crumbles in patches of a twelfth of the tile
+    if (dissolve > 0.0 && hash(floor(in.uv * 12.0)) < dissolve) {
+        discard;
+    }
+
+    var color = material.color.rgb;
+    // top and left edges lit, bottom and right ones shaded. The uv origin is the top left
+    if (in.uv.x < bevel || in.uv.y < bevel) {
+        color = mix(color, vec3<f32>(1.0), 0.35);
+    } else if (in.uv.x > 1.0 - bevel || in.uv.y > 1.0 - bevel) {
+        color = color * 0.6;
+    }
+    color = mix(color, vec3<f32>(1.0), glow * 0.2);
+
+    // bright ring spreading out of the exploded bomb
+    let strength = material.shockwave.w;
+    if (strength > 0.0) {
+        let distance = length(in.world_position.xy - material.shockwave.xy);
+        let ring = 1.0 - clamp(abs(distance - material.shockwave.z) / 24.0, 0.0, 1.0);
+        color = mix(color, vec3<f32>(1.0, 0.8, 0.5), ring * strength * 0.6);
+    }
+
+    return vec4<f32>(color, material.color.a * (1.0 - dissolve * 0.5));
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::Vec2;
+
+// Axis aligned bounding box
+#[derive(Debug, Copy, Clone)]
+pub struct Bounds2 {
+    // Bottom left position
+    pub position: Vec2,
+    // Size of the box
+    pub size: Vec2,
+}
+
+impl Bounds2 {
+    // Is the given point inside the bounds?
+    pub fn in_bounds(&self, coords: Vec2) -> bool {
+        coords.x >= self.position.x
+            && coords.y >= self.position.y
+            && coords.x <= self.position.x + self.size.x
+            && coords.y <= self.position.y + self.size.y
+    }
+}
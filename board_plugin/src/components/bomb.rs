@@ -1,6 +1,6 @@
 use bevy::prelude::Component;
 
 // Bomb component
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct Bomb;
@@ -1,7 +1,7 @@
 use bevy::prelude::Component;
 
 // Bomb neighbor component
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub struct BombNeighbor {
     // Number of neighbor bombs
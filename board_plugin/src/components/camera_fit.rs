@@ -0,0 +1,11 @@
+use crate::tween::Tween;
+use bevy::prelude::{Component, Vec2};
+
+// Move of the 2D camera framing the boards after a new one spawned, from and to a position and
+// a projection scale. Removed once the camera got there
+#[derive(Debug, Copy, Clone, Component)]
+pub struct CameraFit {
+    pub(crate) from: (Vec2, f32),
+    pub(crate) to: (Vec2, f32),
+    pub(crate) tween: Tween,
+}
@@ -0,0 +1,16 @@
+use crate::generation::PregeneratedBoard;
+use bevy::prelude::Component;
+use bevy::tasks::Task;
+
+// Board whose tile map is generated in the background, replaced by the board once the tile map
+// is ready. Its child spins meanwhile
+#[derive(Component)]
+pub struct GeneratingBoard {
+    pub(crate) task: Task<PregeneratedBoard>,
+    // Tile map of the finished task, held until the other boards are generated too
+    pub(crate) done: Option<PregeneratedBoard>,
+}
+
+// Spinner shown while a board is generated
+#[derive(Debug, Copy, Clone, Component)]
+pub struct GenerationSpinner;
@@ -1,9 +1,18 @@
 pub use bomb::Bomb;
 pub use bomb_neighbor::BombNeighbor;
-pub use coordinates::Coordinates;
+pub use camera_fit::CameraFit;
+pub use generating_board::{GeneratingBoard, GenerationSpinner};
+pub use minesweeper_core::Coordinates;
+pub use player::PlayerId;
+pub use tile_cursor::TileCursor;
+pub use tile_spawner::{ProgressFill, TileSpawner};
 pub use uncover::Uncover;
 
 mod bomb;
 mod bomb_neighbor;
-mod coordinates;
+mod camera_fit;
+mod generating_board;
+mod player;
+mod tile_cursor;
+mod tile_spawner;
 mod uncover;
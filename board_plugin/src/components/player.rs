@@ -0,0 +1,12 @@
+// Local player behind a board action. Player one plays with the mouse, as well as with the tile
+// cursor of a keyboard controlled board. Shared boards give their tile cursor to player two
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PlayerId {
+    One,
+    Two,
+}
+
+impl PlayerId {
+    pub const ALL: [Self; 2] = [Self::One, Self::Two];
+}
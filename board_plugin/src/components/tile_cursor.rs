@@ -0,0 +1,13 @@
+use crate::components::{Coordinates, PlayerId};
+use bevy::prelude::Component;
+
+// Tile cursor of a keyboard controlled or shared board, child of the board
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct TileCursor {
+    // Selected tile
+    #[cfg_attr(feature = "debug", reflect(ignore))]
+    pub coordinates: Coordinates,
+    // Player moving the cursor
+    pub player: PlayerId,
+}
@@ -0,0 +1,32 @@
+use crate::resources::{Board, BoardPreview};
+use bevy::prelude::{Component, Entity};
+
+// Tile entities of a board of too many tiles to spawn at once, spawned a batch per frame. Holds
+// the board until its last tile is spawned, the board then being inserted on its entity
+#[derive(Debug, Component)]
+pub struct TileSpawner {
+    pub(crate) board: Option<Board>,
+    // Index of the next tile to spawn, row by row from the bottom
+    pub(crate) next: usize,
+    // Preview shown once spawned, the play starting right away without one
+    pub(crate) preview: Option<BoardPreview>,
+    // Is the play recorded?
+    pub(crate) record: bool,
+    // Progress bar entity, child of the board
+    pub(crate) progress_bar: Entity,
+}
+
+impl TileSpawner {
+    // Share of the tiles spawned, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        let map_size = self.board.as_ref().map_or((1, 1), |b| b.options.map_size);
+        let tile_count = map_size.0 as usize * map_size.1 as usize;
+        (self.next as f32 / tile_count as f32).min(1.)
+    }
+}
+
+// Filled part of a progress bar, as wide as the bar once full
+#[derive(Debug, Copy, Clone, Component)]
+pub struct ProgressFill {
+    pub(crate) width: f32,
+}
@@ -1,6 +1,41 @@
+use crate::tween::Tween;
 use bevy::prelude::Component;
 
-// Uncover component, indicates a covered tile that should be uncovered
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
-pub struct Uncover;
+// Uncover component, indicates a covered tile that should be uncovered. The cover waits for
+// its cascade delay, then fades out
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(Debug, Copy, Clone, PartialEq, Component)]
+pub struct Uncover {
+    // Seconds left before the fade
+    pub delay: f32,
+    pub(crate) fade: Tween,
+}
+
+impl Uncover {
+    pub fn new(delay: f32, duration: f32) -> Self {
+        Self {
+            delay,
+            fade: Tween::new(duration),
+        }
+    }
+
+    // Plays `delta` more seconds, the fade starting once the delay is over
+    pub fn advance(&mut self, delta: f32) {
+        let waited = self.delay.min(delta);
+        self.delay -= waited;
+        self.fade.advance(delta - waited);
+    }
+
+    // Opacity left to the cover, full until the delay is over then down to zero
+    pub fn opacity(&self) -> f32 {
+        if self.delay > 0. {
+            return 1.;
+        }
+        1. - self.fade.progress()
+    }
+
+    // Is the cover gone?
+    pub fn is_done(&self) -> bool {
+        self.delay <= 0. && self.fade.is_done()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::components::{Coordinates, PlayerId};
+use crate::resources::{BoardDiff, BoardOptions, Treasure};
+use crate::solver::Deduction;
+use bevy::prelude::{Entity, Message};
+
+pub use minesweeper_core::Action;
+
+// Board events carry the entity of their board, several boards may be played at once. The tile
+// events also carry their player, several players may share a board
+
+// Sent by the host app to spawn another board while the plugin is running
+#[derive(Message, Debug, Clone)]
+pub struct SpawnBoardEvent(pub BoardOptions);
+
+// Sent when the play starts on the board, after the preview if any
+#[derive(Message, Debug, Copy, Clone)]
+pub struct BoardStartedEvent {
+    pub board: Entity,
+}
+
+// Sent to play a move on a board. Every change of a board state goes through these events,
+// applied one after the other in the order they were sent, so the same events always give the
+// same board: the player input, the replays, the undos and the host commands share them
+#[derive(Message, Debug, Copy, Clone)]
+pub struct BoardActionEvent {
+    pub board: Entity,
+    pub action: Action,
+    pub player: PlayerId,
+}
+
+// Sent when every safe tile is uncovered
+#[derive(Message, Debug, Copy, Clone)]
+pub struct BoardCompletedEvent {
+    pub board: Entity,
+}
+
+// Sent when a bomb is uncovered
+#[derive(Message, Debug, Copy, Clone)]
+pub struct BombExplosionEvent {
+    pub board: Entity,
+    // Player who uncovered the bomb
+    pub player: PlayerId,
+}
+
+// Sent when a bomb is uncovered with a life left or on a relaxed board, the move is taken back
+// and the bombs are flagged instead of ending the game
+#[derive(Message, Debug, Clone)]
+pub struct LifeLostEvent {
+    pub board: Entity,
+    // Bombs uncovered by the move
+    pub bombs: Vec<Coordinates>,
+    // Bombs flagged by the chain reaction of the uncovered ones, with their step in the chain
+    pub chain: Vec<(Coordinates, u32)>,
+    // Player who uncovered the bombs
+    pub player: PlayerId,
+    pub lives_left: u8,
+}
+
+// Sent when a treasure is uncovered, once its bonus is granted
+#[derive(Message, Debug, Copy, Clone)]
+pub struct TreasureFoundEvent {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    pub treasure: Treasure,
+    // Player who uncovered the treasure
+    pub player: PlayerId,
+}
+
+// Sent by host apps to play a board from code, for bots, tutorials and demos. The commands are
+// checked like the player input, then played as the moves of player one
+#[derive(Message, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BoardCommand {
+    UncoverTile {
+        board: Entity,
+        coordinates: Coordinates,
+    },
+    ToggleFlag {
+        board: Entity,
+        coordinates: Coordinates,
+    },
+    ChordTile {
+        board: Entity,
+        coordinates: Coordinates,
+    },
+}
+
+impl BoardCommand {
+    // Board and tile the command acts on
+    pub fn tile(&self) -> (Entity, Coordinates) {
+        match *self {
+            Self::UncoverTile { board, coordinates }
+            | Self::ToggleFlag { board, coordinates }
+            | Self::ChordTile { board, coordinates } => (board, coordinates),
+        }
+    }
+
+    // Move of the command
+    pub fn action(&self) -> Action {
+        match *self {
+            Self::UncoverTile { coordinates, .. } => Action::Uncover(coordinates),
+            Self::ToggleFlag { coordinates, .. } => Action::ToggleFlag(coordinates),
+            Self::ChordTile { coordinates, .. } => Action::Chord(coordinates),
+        }
+    }
+}
+
+// Sent when the player clicks a tile left to guess from, the second click uncovers it
+#[derive(Message, Debug, Clone)]
+pub struct GuessOddsEvent {
+    pub board: Entity,
+    // Clicked tile
+    pub selected: Coordinates,
+    // Bomb probability of every tile left to guess from
+    pub odds: Vec<(Coordinates, f32)>,
+}
+
+// Sent when the visible state of the board changes, for opponents following the game
+#[derive(Message, Debug, Clone)]
+pub struct BoardDiffEvent {
+    pub board: Entity,
+    pub diff: BoardDiff,
+}
+
+// Sent when a hint is requested and the solver found a tile
+#[derive(Message, Debug, Copy, Clone)]
+pub struct HintEvent {
+    pub board: Entity,
+    pub deduction: Deduction,
+}
@@ -0,0 +1,256 @@
+use crate::resources::{tile_map::TileMap, BoardOptions, Generation, TilePayload, Treasure};
+use crate::solver::Solver;
+use bevy::log;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::{App, Resource};
+use minesweeper_core::{Coordinates, Game};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// Generates the tile map of a board, host apps can register their own implementations
+pub trait BoardGenerator: Send + Sync + 'static {
+    // Places the bombs, and optionally the opening tile, of a new tile map
+    fn generate(&self, options: &BoardOptions, rng: &mut dyn RngCore) -> TileMap;
+}
+
+// Bombs placed uniformly at random, with their mines stacked on multi-mine boards
+#[derive(Debug, Copy, Clone, Default)]
+pub struct UniformGenerator;
+
+impl BoardGenerator for UniformGenerator {
+    fn generate(&self, options: &BoardOptions, rng: &mut dyn RngCore) -> TileMap {
+        let mut tile_map = options.empty_tile_map();
+        let bomb_count = options.bomb_count.resolve(tile_map.tile_count());
+        tile_map.set_bombs(bomb_count, rng);
+        if options.max_mines > 1 {
+            tile_map.stack_mines(options.max_mines, rng);
+        }
+        tile_map
+    }
+}
+
+// Regenerates the bombs until the solver clears the board from the opening without guessing
+#[derive(Debug, Copy, Clone)]
+pub struct NoGuessGenerator {
+    // Boards tried before giving up on a no guess layout
+    pub max_attempts: usize,
+}
+
+impl Default for NoGuessGenerator {
+    fn default() -> Self {
+        Self { max_attempts: 1000 }
+    }
+}
+
+impl BoardGenerator for NoGuessGenerator {
+    fn generate(&self, options: &BoardOptions, rng: &mut dyn RngCore) -> TileMap {
+        for attempt in 1..=self.max_attempts {
+            let mut tile_map = UniformGenerator.generate(options, rng);
+            let opening = match tile_map.random_empty_tile(rng) {
+                Some(c) => c,
+                None => continue,
+            };
+
+            let mut solver = Solver::new(&tile_map);
+            solver.reveal(opening);
+            if solver.solve() {
+                log::info!("Generated a no guess board in {attempt} attempts");
+                tile_map.set_opening(Some(opening));
+                return tile_map;
+            }
+        }
+
+        log::warn!(
+            "No guess generation failed after {} attempts, the board may require guessing",
+            self.max_attempts
+        );
+        let mut tile_map = UniformGenerator.generate(options, rng);
+        let opening = tile_map.random_empty_tile(rng);
+        tile_map.set_opening(opening);
+        tile_map
+    }
+}
+
+// Regenerates the bombs until the safe tiles are all connected, dense boards being connected by
+// relocating the bombs of the last attempt
+#[derive(Debug, Copy, Clone)]
+pub struct ConnectedGenerator {
+    // Boards tried before relocating bombs
+    pub max_attempts: usize,
+}
+
+impl Default for ConnectedGenerator {
+    fn default() -> Self {
+        Self { max_attempts: 100 }
+    }
+}
+
+impl BoardGenerator for ConnectedGenerator {
+    fn generate(&self, options: &BoardOptions, rng: &mut dyn RngCore) -> TileMap {
+        for attempt in 1..=self.max_attempts {
+            let tile_map = UniformGenerator.generate(options, rng);
+            if tile_map.is_connected() {
+                log::info!("Generated a connected board in {attempt} attempts");
+                return tile_map;
+            }
+        }
+
+        let mut tile_map = UniformGenerator.generate(options, rng);
+        if !tile_map.connect_safe_tiles(rng) {
+            log::warn!("Some safe tiles of the board are only sealed off by holes");
+        }
+        tile_map
+    }
+}
+
+// Deals the same tile map on every board, for hand made puzzles and regression boards
+#[derive(Debug, Clone)]
+pub struct FixedGenerator(pub TileMap);
+
+impl BoardGenerator for FixedGenerator {
+    fn generate(&self, _options: &BoardOptions, _rng: &mut dyn RngCore) -> TileMap {
+        self.0.clone()
+    }
+}
+
+impl FixedGenerator {
+    // Options dealing the tile map once the generator is registered under `name`, the other
+    // options coming from `base`. The tile map holds its own holes and its player picks the
+    // opening
+    pub fn options(&self, name: &str, base: BoardOptions) -> BoardOptions {
+        BoardOptions {
+            map_size: (self.0.width(), self.0.height()),
+            grid: self.0.grid(),
+            kernel: self.0.kernel().clone(),
+            wrap: self.0.wraps(),
+            mask: None,
+            bomb_count: (self.0.bomb_count() as u16).into(),
+            max_mines: self.0.max_mines(),
+            safe_start: false,
+            seed: None,
+            generation: Generation::Custom(name.to_string()),
+            ..base
+        }
+    }
+}
+
+// Board generators selectable by name through `Generation`. Must be used as a resource
+#[derive(Resource)]
+pub struct BoardGenerators {
+    generators: HashMap<String, Arc<dyn BoardGenerator>>,
+}
+
+impl Default for BoardGenerators {
+    fn default() -> Self {
+        let mut generators = Self {
+            generators: HashMap::default(),
+        };
+        generators.register(Generation::Uniform.name(), UniformGenerator);
+        generators.register(Generation::NoGuess.name(), NoGuessGenerator::default());
+        generators.register(Generation::Connected.name(), ConnectedGenerator::default());
+        generators
+    }
+}
+
+impl BoardGenerators {
+    // Registers a generator, replacing any generator with the same name
+    pub fn register(&mut self, name: &str, generator: impl BoardGenerator) {
+        self.generators
+            .insert(name.to_string(), Arc::new(generator));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn BoardGenerator> {
+        self.generators.get(name).map(|g| g.as_ref())
+    }
+
+    // Generator selected by the options, shareable with background tasks
+    pub fn generator(&self, generation: &Generation) -> Arc<dyn BoardGenerator> {
+        let name = generation.name();
+        match self.generators.get(name) {
+            Some(generator) => generator.clone(),
+            None => {
+                log::error!("Unknown board generator {name:?}, falling back to uniform");
+                Arc::new(UniformGenerator)
+            }
+        }
+    }
+
+    // Generates a tile map with the generator selected by the options
+    pub fn generate(&self, options: &BoardOptions, seed: u64) -> TileMap {
+        generate_tile_map(self.generator(&options.generation).as_ref(), options, seed)
+    }
+}
+
+// Seconds taken off the time by a time bonus treasure
+pub const TREASURE_TIME_BONUS: u16 = 10;
+// One treasure out of `EXTRA_LIFE_RATE` is an extra life, the others are time bonuses
+const EXTRA_LIFE_RATE: usize = 4;
+
+// Generates the tile map of a board from its seed, along with its opening tile and treasures.
+// Every random choice derives from the seed, so the same board can be generated again
+pub fn generate_tile_map(
+    generator: &dyn BoardGenerator,
+    options: &BoardOptions,
+    seed: u64,
+) -> TileMap {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut tile_map = generator.generate(options, &mut rng);
+    if options.safe_start && tile_map.opening().is_none() {
+        let opening = tile_map.random_empty_tile(&mut rng);
+        tile_map.set_opening(opening);
+    }
+    if options.treasures > 0 {
+        place_treasures(&mut tile_map, options.treasures, &mut rng);
+    }
+    tile_map
+}
+
+// Hides treasures under the safe tiles left covered by the opening, so every treasure is found
+// by a move
+fn place_treasures(tile_map: &mut TileMap, count: u16, rng: &mut impl Rng) {
+    let mut game = Game::new(tile_map.clone());
+    game.open();
+    let mut tiles: Vec<Coordinates> = tile_map
+        .tiles()
+        .filter(|(c, tile)| !tile.is_bomb() && game.is_covered(*c))
+        .map(|(c, _)| c)
+        .collect();
+    let count = (count as usize).min(tiles.len());
+    let (chosen, _) = tiles.partial_shuffle(rng, count);
+    for (i, coords) in chosen.iter().enumerate() {
+        let treasure = match i % EXTRA_LIFE_RATE {
+            0 => Treasure::ExtraLife,
+            _ => Treasure::TimeBonus(TREASURE_TIME_BONUS),
+        };
+        tile_map.set_payload(*coords, Some(TilePayload::Treasure(treasure)));
+    }
+}
+
+// Tile map generated ahead of time, used by the next board created with matching options
+// instead of generating one. Must be used as a resource
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct PregeneratedBoard {
+    pub options: BoardOptions,
+    pub seed: u64,
+    pub tile_map: TileMap,
+}
+
+// Lets host apps register custom board generators
+pub trait RegisterBoardGenerator {
+    fn register_board_generator(&mut self, name: &str, generator: impl BoardGenerator)
+        -> &mut Self;
+}
+
+impl RegisterBoardGenerator for App {
+    fn register_board_generator(
+        &mut self,
+        name: &str,
+        generator: impl BoardGenerator,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(BoardGenerators::default)
+            .register(name, generator);
+        self
+    }
+}
@@ -0,0 +1,131 @@
+use crate::components::Coordinates;
+use bevy::prelude::Vec2;
+use std::cmp::Ordering;
+
+pub use minesweeper_core::{GridKind, NeighborKernel};
+
+// Tile layout of a tiling in board space.
+// Board space starts at the bottom left corner of the board, tile sizes are the tile widths
+pub trait Grid {
+    // Center of a tile in board space
+    fn tile_center(&self, coordinates: Coordinates, tile_size: f32) -> Vec2;
+
+    // Size of the sprite of a tile
+    fn tile_extent(&self, tile_size: f32) -> Vec2;
+
+    // Size of a board of `width` x `height` tiles
+    fn board_size(&self, width: u16, height: u16, tile_size: f32) -> Vec2;
+
+    // Tile containing a board space position, the map bounds are left to the caller
+    fn tile_at(&self, position: Vec2, tile_size: f32) -> Option<Coordinates>;
+}
+
+// Layout of the grid selection option, the tile neighbors are defined by the core
+pub trait GridLayout {
+    fn grid(&self) -> &'static dyn Grid;
+}
+
+impl GridLayout for GridKind {
+    fn grid(&self) -> &'static dyn Grid {
+        match self {
+            Self::Square => &SquareGrid,
+            Self::Hex => &HexGrid,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SquareGrid;
+
+impl Grid for SquareGrid {
+    fn tile_center(&self, coordinates: Coordinates, tile_size: f32) -> Vec2 {
+        Vec2::new(
+            (coordinates.x as f32 * tile_size) + (tile_size / 2.),
+            (coordinates.y as f32 * tile_size) + (tile_size / 2.),
+        )
+    }
+
+    fn tile_extent(&self, tile_size: f32) -> Vec2 {
+        Vec2::splat(tile_size)
+    }
+
+    fn board_size(&self, width: u16, height: u16, tile_size: f32) -> Vec2 {
+        Vec2::new(width as f32 * tile_size, height as f32 * tile_size)
+    }
+
+    fn tile_at(&self, position: Vec2, tile_size: f32) -> Option<Coordinates> {
+        if position.x < 0. || position.y < 0. {
+            return None;
+        }
+        Some(Coordinates {
+            x: (position.x / tile_size) as u16,
+            y: (position.y / tile_size) as u16,
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct HexGrid;
+
+impl HexGrid {
+    // Height of a hexagon of the given width
+    fn tile_height(tile_size: f32) -> f32 {
+        tile_size * 2. / 3f32.sqrt()
+    }
+
+    // Vertical distance between two rows, which overlap by a quarter of the tile height
+    fn row_spacing(tile_size: f32) -> f32 {
+        Self::tile_height(tile_size) * 0.75
+    }
+
+    // Horizontal shift of a row, in tiles
+    fn row_offset(y: i32) -> f32 {
+        if y % 2 == 1 {
+            0.5
+        } else {
+            0.
+        }
+    }
+}
+
+impl Grid for HexGrid {
+    fn tile_center(&self, coordinates: Coordinates, tile_size: f32) -> Vec2 {
+        let offset = Self::row_offset(coordinates.y as i32);
+        Vec2::new(
+            (coordinates.x as f32 + offset + 0.5) * tile_size,
+            coordinates.y as f32 * Self::row_spacing(tile_size) + Self::tile_height(tile_size) / 2.,
+        )
+    }
+
+    fn tile_extent(&self, tile_size: f32) -> Vec2 {
+        Vec2::new(tile_size, Self::tile_height(tile_size))
+    }
+
+    fn board_size(&self, width: u16, height: u16, tile_size: f32) -> Vec2 {
+        let shift = if height > 1 { 0.5 } else { 0. };
+        Vec2::new(
+            (width as f32 + shift) * tile_size,
+            height.saturating_sub(1) as f32 * Self::row_spacing(tile_size)
+                + Self::tile_height(tile_size),
+        )
+    }
+
+    fn tile_at(&self, position: Vec2, tile_size: f32) -> Option<Coordinates> {
+        // hexagons are the Voronoi cells of their centers: the closest center wins
+        let row = ((position.y - Self::tile_height(tile_size) / 2.) / Self::row_spacing(tile_size))
+            .round() as i32;
+        (row - 1..=row + 1)
+            .flat_map(|y| {
+                let column = (position.x / tile_size - Self::row_offset(y) - 0.5).round() as i32;
+                (column - 1..=column + 1).map(move |x| (x, y))
+            })
+            .filter(|&(x, y)| x >= 0 && y >= 0)
+            .map(|(x, y)| Coordinates {
+                x: x as u16,
+                y: y as u16,
+            })
+            .map(|c| (c, self.tile_center(c, tile_size).distance_squared(position)))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(c, _)| c)
+    }
+}
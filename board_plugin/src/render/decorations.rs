@@ -0,0 +1,155 @@
+use crate::components::Coordinates;
+use crate::grid::GridLayout;
+use crate::resources::{tile_map::TileMap, BoardAssets};
+use bevy::prelude::*;
+
+// Dashes of the wrapped edges: the left and right edges, then the top and bottom ones
+const WRAP_COLORS: [Color; 2] = [Color::srgb(0.2, 0.7, 0.8), Color::srgb(0.9, 0.55, 0.2)];
+
+// Edge strips of a tile cover, children of the cover under its flag
+pub(crate) fn spawn_bevel(
+    parent: &mut ChildSpawnerCommands,
+    size: f32,
+    board_assets: &BoardAssets,
+) {
+    let decorations = &board_assets.decorations;
+    let width = size * decorations.bevel;
+    let (lit, shaded) = decorations.bevel_colors(board_assets.tile_colors.covered);
+    let edge = size / 2. - width / 2.;
+    // top and left lit, bottom and right shaded
+    let strips = [
+        (lit, Vec2::new(size, width), Vec2::new(0., edge)),
+        (lit, Vec2::new(width, size), Vec2::new(-edge, 0.)),
+        (shaded, Vec2::new(size, width), Vec2::new(0., -edge)),
+        (shaded, Vec2::new(width, size), Vec2::new(edge, 0.)),
+    ];
+    for (color, strip, position) in strips {
+        parent
+            .spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(strip),
+                    ..Default::default()
+                },
+                Transform::from_translation(position.extend(0.5)),
+            ))
+            .insert(Name::new("Bevel"));
+    }
+}
+
+// Lines between the tiles, above the revealed tiles and under their contents and covers
+pub(crate) fn spawn_grid_lines(
+    parent: &mut ChildSpawnerCommands,
+    tile_map: &TileMap,
+    tile_size: f32,
+    board_size: Vec2,
+    board_assets: &BoardAssets,
+) {
+    let decorations = &board_assets.decorations;
+    let width = decorations.grid_lines;
+    // the outer edges are left to the frame
+    let columns = (1..tile_map.width()).map(|x| {
+        let x = x as f32 * tile_size;
+        (
+            Vec2::new(width, board_size.y),
+            Vec2::new(x, board_size.y / 2.),
+        )
+    });
+    let rows = (1..tile_map.height()).map(|y| {
+        let y = y as f32 * tile_size;
+        (
+            Vec2::new(board_size.x, width),
+            Vec2::new(board_size.x / 2., y),
+        )
+    });
+    for (line, position) in columns.chain(rows) {
+        parent
+            .spawn((
+                Sprite {
+                    color: decorations.grid_line_color,
+                    custom_size: Some(line),
+                    ..Default::default()
+                },
+                Transform::from_translation(position.extend(1.5)),
+            ))
+            .insert(Name::new("Grid Line"));
+    }
+}
+
+// Dashes along the edges of a wrapped board, outside of its bounds and frame: the touching
+// edges share a color and each dash faces a row or column continuing across the edge
+pub(crate) fn spawn_wrap_edges(
+    parent: &mut ChildSpawnerCommands,
+    tile_map: &TileMap,
+    tile_size: f32,
+    board_size: Vec2,
+    board_assets: &BoardAssets,
+) {
+    let grid = tile_map.grid().grid();
+    let width = tile_size * 0.15;
+    let dash = tile_size * 0.6;
+    let gap = board_assets.decorations.frame + width;
+    let center = |x: u16, y: u16| grid.tile_center(Coordinates { x, y }, tile_size);
+    let rows = (0..tile_map.height()).flat_map(|y| {
+        let y = center(0, y).y;
+        [Vec2::new(-gap, y), Vec2::new(board_size.x + gap, y)]
+            .map(|position| (WRAP_COLORS[0], Vec2::new(width, dash), position))
+    });
+    let columns = (0..tile_map.width()).flat_map(|x| {
+        let x = center(x, 0).x;
+        [Vec2::new(x, -gap), Vec2::new(x, board_size.y + gap)]
+            .map(|position| (WRAP_COLORS[1], Vec2::new(dash, width), position))
+    });
+    for (color, strip, position) in rows.chain(columns) {
+        parent
+            .spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(strip),
+                    ..Default::default()
+                },
+                Transform::from_translation(position.extend(0.)),
+            ))
+            .insert(Name::new("Wrapped Edge"));
+    }
+}
+
+// Frame around the board, outside of its bounds
+pub(crate) fn spawn_frame(
+    parent: &mut ChildSpawnerCommands,
+    board_size: Vec2,
+    board_assets: &BoardAssets,
+) {
+    let decorations = &board_assets.decorations;
+    let width = decorations.frame;
+    let outer = board_size + Vec2::splat(width * 2.);
+    let center = board_size / 2.;
+    let strips = [
+        // top and bottom, corners included
+        (
+            Vec2::new(outer.x, width),
+            Vec2::new(center.x, board_size.y + width / 2.),
+        ),
+        (Vec2::new(outer.x, width), Vec2::new(center.x, -width / 2.)),
+        (
+            Vec2::new(width, board_size.y),
+            Vec2::new(-width / 2., center.y),
+        ),
+        (
+            Vec2::new(width, board_size.y),
+            Vec2::new(board_size.x + width / 2., center.y),
+        ),
+    ];
+    for (strip, position) in strips {
+        parent
+            .spawn((
+                Sprite {
+                    color: decorations.frame_color,
+                    custom_size: Some(strip),
+                    ..Default::default()
+                },
+                Transform::from_translation(position.extend(0.)),
+            ))
+            .insert(Name::new("Board Frame"));
+    }
+}
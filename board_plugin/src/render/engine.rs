@@ -0,0 +1,288 @@
+use bevy::ecs::schedule::IntoScheduleConfigs;
+use bevy::ecs::system::{ScheduleSystem, SystemParam};
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+use bevy::window::{PresentMode, PrimaryWindow};
+
+// Engine APIs reworked by the Bevy releases: the 2D camera, the primary window, the app states
+// and the 2D materials. The board plugin and the host app reach them through this module, so an
+// engine update mostly changes this file
+
+// Query filter of the 2D camera drawing the boards
+pub type Is2dCamera = With<Camera2d>;
+
+// Spawns the 2D camera, which draws the UI as well
+pub fn spawn_2d_camera(cmds: &mut Commands) -> Entity {
+    cmds.spawn((Camera2d, Name::new("2D camera"))).id()
+}
+
+// Orthographic projection of a 2D camera
+pub fn orthographic(projection: &Projection) -> Option<&OrthographicProjection> {
+    match projection {
+        Projection::Orthographic(o) => Some(o),
+        _ => None,
+    }
+}
+
+// Zoom of a 2D camera, above 1 when zoomed out
+pub fn camera_zoom(projection: &Projection) -> f32 {
+    orthographic(projection).map_or(1., |o| o.scale)
+}
+
+pub fn set_camera_zoom(projection: &mut Projection, zoom: f32) {
+    if let Projection::Orthographic(o) = projection {
+        o.scale = zoom;
+    }
+}
+
+// Primary window, missing from the headless apps
+pub type PrimaryWindowQuery<'w, 's> = Query<'w, 's, &'static Window, With<PrimaryWindow>>;
+
+// Window size, in logical pixels
+pub fn window_size(window: &Window) -> Vec2 {
+    Vec2::new(window.width(), window.height())
+}
+
+// Window cursor position in logical pixels, from the top left corner
+pub fn cursor_position(window: &Window) -> Option<Vec2> {
+    window.cursor_position()
+}
+
+// Window plugin opening `window` as the primary window
+pub fn window_plugin(window: Window) -> WindowPlugin {
+    WindowPlugin {
+        primary_window: Some(window),
+        ..Default::default()
+    }
+}
+
+// Sets the window size, in logical pixels
+pub fn set_window_size(window: &mut Window, (width, height): (u32, u32)) {
+    window.resolution.set(width as f32, height as f32);
+}
+
+pub fn vsync(window: &Window) -> bool {
+    window.present_mode != PresentMode::AutoNoVsync
+}
+
+pub fn set_vsync(window: &mut Window, vsync: bool) {
+    window.present_mode = match vsync {
+        true => PresentMode::AutoVsync,
+        false => PresentMode::AutoNoVsync,
+    };
+}
+
+// States pushed over and waiting under the current one, as the pause screen over a game. A
+// state isn't exited when another one is pushed over it, nor entered again when that one is
+// popped. Must be used as a resource
+#[derive(Resource)]
+pub struct StateStack<S: States> {
+    paused: Vec<S>,
+    // State back on top after the last pop
+    resumed: Option<S>,
+}
+
+impl<S: States> Default for StateStack<S> {
+    fn default() -> Self {
+        Self {
+            paused: Vec::new(),
+            resumed: None,
+        }
+    }
+}
+
+// Current app state and the switch to the next one, made before the next update. Only the
+// first switch of a frame is made, and switching to the current state does nothing: the
+// switches return whether they were made
+#[derive(SystemParam)]
+pub struct StateSwitch<'w, 's, S: FreelyMutableState> {
+    current: Res<'w, State<S>>,
+    next: ResMut<'w, NextState<S>>,
+    stack: ResMut<'w, StateStack<S>>,
+    cmds: Commands<'w, 's>,
+}
+
+impl<S: FreelyMutableState> StateSwitch<'_, '_, S> {
+    pub fn current(&self) -> &S {
+        self.current.get()
+    }
+
+    fn switch(&mut self, state: S) -> bool {
+        if !matches!(*self.next, NextState::Unchanged) || *self.current.get() == state {
+            return false;
+        }
+        self.next.set(state);
+        self.stack.resumed = None;
+        true
+    }
+
+    // Replaces the current state, the paused ones stay under it
+    pub fn set(&mut self, state: S) -> bool {
+        self.switch(state)
+    }
+
+    // Pauses the current state under `state`
+    pub fn push(&mut self, state: S) -> bool {
+        let current = self.current.get().clone();
+        if !self.switch(state) {
+            return false;
+        }
+        self.stack.paused.push(current);
+        true
+    }
+
+    // Leaves the current state for the one paused under it
+    pub fn pop(&mut self) -> bool {
+        let previous = match self.stack.paused.last() {
+            Some(s) => s.clone(),
+            None => return false,
+        };
+        if !self.switch(previous) {
+            return false;
+        }
+        self.stack.resumed = self.stack.paused.pop();
+        true
+    }
+
+    // Leaves the current state and the paused ones for `state`
+    pub fn replace(&mut self, state: S) -> bool {
+        if !self.switch(state) {
+            return false;
+        }
+        for paused in std::mem::take(&mut self.stack.paused).into_iter().rev() {
+            self.cmds.run_schedule(OnExit(paused));
+        }
+        true
+    }
+}
+
+// Systems of the app states, minding the state stack: the enter systems don't run when the
+// state is resumed, the exit ones when it is paused, and the update ones in the current state
+// only
+pub trait StateSystems {
+    // Adds the state at `state`, with an empty stack
+    fn insert_stacked_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self;
+
+    fn on_enter<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+
+    fn on_update<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+
+    fn on_exit<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self;
+}
+
+impl StateSystems for App {
+    fn insert_stacked_state<S: FreelyMutableState>(&mut self, state: S) -> &mut Self {
+        self.insert_state(state).init_resource::<StateStack<S>>()
+    }
+
+    fn on_enter<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        let resumed = state.clone();
+        let entered = move |stack: Option<Res<StateStack<S>>>| {
+            stack.is_none_or(|s| s.resumed.as_ref() != Some(&resumed))
+        };
+        self.add_systems(OnEnter(state), systems.run_if(entered))
+    }
+
+    fn on_update<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        self.add_systems(Update, systems.run_if(in_state(state)))
+    }
+
+    fn on_exit<S: States, M>(
+        &mut self,
+        state: S,
+        systems: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    ) -> &mut Self {
+        let paused = state.clone();
+        let exited = move |stack: Option<Res<StateStack<S>>>| {
+            stack.is_none_or(|s| !s.paused.contains(&paused))
+        };
+        self.add_systems(OnExit(state), systems.run_if(exited))
+    }
+}
+
+#[cfg(feature = "tile_material")]
+pub use material_2d::*;
+
+// Tile material of the GPU, drawn on the 2D meshes
+#[cfg(feature = "tile_material")]
+mod material_2d {
+    use crate::render::material::TileMaterial;
+    use bevy::prelude::*;
+    use bevy::render::render_asset::RenderAssets;
+    use bevy::render::render_resource::{AsBindGroupShaderType, ShaderType};
+    use bevy::render::texture::GpuImage;
+    use bevy::shader::ShaderRef;
+    use bevy::sprite_render::{AlphaMode2d, Material2d, Material2dPlugin};
+
+    // Fragment shader of the tile material, in the assets of the host app
+    const TILE_SHADER: &str = "shaders/tile.wgsl";
+
+    // Uniform of the shader: the color, the effects and the shockwave
+    #[derive(Debug, Clone, Default, ShaderType)]
+    pub struct TileMaterialUniform {
+        pub color: Vec4,
+        // bevel, glow, dissolve, unused
+        pub effects: Vec4,
+        pub shockwave: Vec4,
+    }
+
+    impl AsBindGroupShaderType<TileMaterialUniform> for TileMaterial {
+        fn as_bind_group_shader_type(
+            &self,
+            _images: &RenderAssets<GpuImage>,
+        ) -> TileMaterialUniform {
+            TileMaterialUniform {
+                color: LinearRgba::from(self.color).to_vec4(),
+                effects: Vec4::new(self.bevel, self.glow, self.dissolve, 0.),
+                shockwave: self.shockwave,
+            }
+        }
+    }
+
+    impl Material2d for TileMaterial {
+        fn fragment_shader() -> ShaderRef {
+            TILE_SHADER.into()
+        }
+
+        fn alpha_mode(&self) -> AlphaMode2d {
+            AlphaMode2d::Blend
+        }
+    }
+
+    pub fn add_material_2d(app: &mut App) {
+        app.add_plugins(Material2dPlugin::<TileMaterial>::default());
+    }
+
+    // Quad mesh of a tile, its uv origin at the top left
+    pub fn quad_mesh(size: Vec2) -> Mesh {
+        Rectangle::from_size(size).into()
+    }
+
+    // Components drawing a mesh with a material
+    pub fn material_mesh(
+        mesh: Handle<Mesh>,
+        material: Handle<TileMaterial>,
+    ) -> (Mesh2d, MeshMaterial2d<TileMaterial>) {
+        (Mesh2d(mesh), MeshMaterial2d(material))
+    }
+}
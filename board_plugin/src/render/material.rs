@@ -0,0 +1,296 @@
+use crate::components::Uncover;
+use crate::events::BombExplosionEvent;
+use crate::grid::GridLayout;
+use crate::render::engine::{self, TileMaterialUniform};
+use crate::render::systems::explosion::EXPLOSION_DURATION;
+use crate::render::systems::WorldCursor;
+use crate::resources::{Board, BoardAssets, BoardPreview, ReplayPlayback};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy::render::render_resource::AsBindGroup;
+
+// Speed of the explosion shockwave, in tile sizes per second
+const SHOCKWAVE_SPEED: f32 = 24.;
+
+// Shader drawn tile cover, its effects set per tile without child entities. Covers of the
+// square colored boards get one once spawned, replacing their sprite
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+#[uniform(0, TileMaterialUniform)]
+pub struct TileMaterial {
+    pub color: Color,
+    // Chiseled edges, as a share of the tile size
+    pub bevel: f32,
+    // Hover highlight, from 0 to 1
+    pub glow: f32,
+    // Share of the cover dissolved by the reveal, from 0 to 1
+    pub dissolve: f32,
+    // World position of the exploded bomb, radius of the wave and its strength, from 0 to 1
+    pub shockwave: Vec4,
+}
+
+impl TileMaterial {
+    fn new(color: Color, bevel: f32) -> Self {
+        Self {
+            color,
+            bevel,
+            glow: 0.,
+            dissolve: 0.,
+            shockwave: Vec4::ZERO,
+        }
+    }
+}
+
+// Cover of a tile, swapped for a tile material mesh once spawned
+#[derive(Debug, Copy, Clone, Component)]
+pub struct TileCover;
+
+// Material shared by the covers of a board with the same color, the covers glowing or dissolving
+// get a material of their own until they are back to the shared look or gone
+#[derive(Debug, Clone, Component)]
+struct CoverMaterial {
+    shared: Handle<TileMaterial>,
+}
+
+// Shared materials, by board entity, color and bevel. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+struct SharedTileMaterials(HashMap<(Entity, [u32; 4], u32), Handle<TileMaterial>>);
+
+// Shockwave of an explosion crossing the covers of a board
+#[derive(Debug, Copy, Clone)]
+struct Shockwave {
+    board: Entity,
+    center: Vec2,
+    age: f32,
+}
+
+// Running shockwaves. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+struct Shockwaves(Vec<Shockwave>);
+
+// Draws the covers of the square colored boards with the tile material, enabled by the
+// `tile_material` feature
+pub struct TileMaterialPlugin;
+
+impl Plugin for TileMaterialPlugin {
+    fn build(&self, app: &mut App) {
+        engine::add_material_2d(app);
+        app.init_resource::<SharedTileMaterials>()
+            .init_resource::<Shockwaves>()
+            .add_systems(
+                Update,
+                (
+                    attach_tile_materials,
+                    dissolve_covers,
+                    hover_glow,
+                    start_shockwaves,
+                    move_shockwaves,
+                ),
+            );
+    }
+}
+
+// Swaps the sprites of the new plain covers for meshes of the tile material. The meshes are
+// shared by the covers of the same size and the materials by the covers of a board, a single
+// uniform buffer and bind group for all of them
+#[allow(clippy::too_many_arguments)]
+fn attach_tile_materials(
+    mut cmds: Commands,
+    board_assets: Res<BoardAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<TileMaterial>>,
+    mut shared: ResMut<SharedTileMaterials>,
+    covers: Query<(Entity, &Sprite), Added<TileCover>>,
+    parents: Query<&ChildOf>,
+    entities: Query<Entity>,
+    mut quads: Local<HashMap<u32, Handle<Mesh>>>,
+) {
+    if covers.iter().next().is_none() {
+        return;
+    }
+    // the materials of the despawned boards go with them
+    shared
+        .0
+        .retain(|(board, _, _), _| entities.get(*board).is_ok());
+    let bevel = board_assets.decorations.bevel;
+    for (entity, sprite) in covers.iter() {
+        // textured covers, the hexagons, keep their sprite
+        if sprite.image != Handle::default() {
+            continue;
+        }
+        let size = sprite.custom_size.unwrap_or(Vec2::ONE);
+        let quad = quads
+            .entry(size.x.to_bits())
+            .or_insert_with(|| meshes.add(engine::quad_mesh(size)))
+            .clone();
+        let mut board = entity;
+        while let Ok(parent) = parents.get(board) {
+            board = parent.parent();
+        }
+        let color = sprite.color.to_srgba().to_f32_array().map(f32::to_bits);
+        let material = shared
+            .0
+            .entry((board, color, bevel.to_bits()))
+            .or_insert_with(|| materials.add(TileMaterial::new(sprite.color, bevel)))
+            .clone();
+        cmds.entity(entity)
+            .remove::<Sprite>()
+            .insert(engine::material_mesh(quad, material.clone()))
+            .insert(CoverMaterial { shared: material });
+    }
+}
+
+// Material of a cover to change its effects, its own one once it leaves the shared material
+fn own_material<'a>(
+    cmds: &mut Commands,
+    materials: &'a mut Assets<TileMaterial>,
+    cover: Entity,
+    handle: &MeshMaterial2d<TileMaterial>,
+    material: &CoverMaterial,
+) -> Option<&'a mut TileMaterial> {
+    if handle.0 != material.shared {
+        return materials.get_mut(&handle.0);
+    }
+    let shared = materials.get(&material.shared)?.clone();
+    let own = materials.add(shared);
+    cmds.entity(cover).insert(MeshMaterial2d(own.clone()));
+    materials.get_mut(&own)
+}
+
+// Dissolves the covers of the uncovered tiles over their fade
+fn dissolve_covers(
+    mut cmds: Commands,
+    mut materials: ResMut<Assets<TileMaterial>>,
+    covers: Query<
+        (
+            Entity,
+            &Uncover,
+            &MeshMaterial2d<TileMaterial>,
+            &CoverMaterial,
+        ),
+        Changed<Uncover>,
+    >,
+) {
+    for (entity, uncover, handle, material) in covers.iter() {
+        if let Some(own) = own_material(&mut cmds, &mut materials, entity, handle, material) {
+            own.dissolve = 1. - uncover.opacity();
+        }
+    }
+}
+
+// Lights the cover under the cursor, which takes the shared material back once left
+#[allow(clippy::too_many_arguments)]
+fn hover_glow(
+    mut cmds: Commands,
+    cursor: WorldCursor,
+    board_assets: Res<BoardAssets>,
+    playback: Option<Res<ReplayPlayback>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    covers: Query<(
+        &MeshMaterial2d<TileMaterial>,
+        &CoverMaterial,
+        Option<&Uncover>,
+    )>,
+    mut materials: ResMut<Assets<TileMaterial>>,
+    mut glowing: Local<Option<Entity>>,
+) {
+    // the player doesn't control replays
+    let hovered = cursor
+        .position()
+        .filter(|_| board_assets.hover_highlight && playback.is_none())
+        .and_then(|position| {
+            boards
+                .iter()
+                .filter(|b| b.options.controls.mouse() && !b.game.is_exploded())
+                .find_map(|b| b.covered_tiles.get(&b.tile_at(position)?).copied())
+        });
+    if hovered == *glowing {
+        return;
+    }
+    if let Some((cover, (handle, material, uncover))) =
+        glowing.and_then(|c| Some((c, covers.get(c).ok()?)))
+    {
+        match uncover {
+            // the dissolving covers keep their material until they are gone
+            Some(_) => {
+                if let Some(own) = materials.get_mut(&handle.0) {
+                    own.glow = 0.;
+                }
+            }
+            None => {
+                cmds.entity(cover)
+                    .insert(MeshMaterial2d(material.shared.clone()));
+            }
+        }
+    }
+    if let Some((cover, (handle, material, _))) =
+        hovered.and_then(|c| Some((c, covers.get(c).ok()?)))
+    {
+        if let Some(own) = own_material(&mut cmds, &mut materials, cover, handle, material) {
+            own.glow = 1.;
+        }
+    }
+    *glowing = hovered;
+}
+
+// Sends a shockwave out of the exploded bombs, unless the board doesn't shake
+fn start_shockwaves(
+    boards: Query<&Board>,
+    mut shockwaves: ResMut<Shockwaves>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+) {
+    for event in bomb_explosion_evr.read() {
+        let board = match boards.get(event.board) {
+            Ok(b) if b.options.explosion.shake > 0. => b,
+            _ => continue,
+        };
+        let tile_map = board.tile_map();
+        let bomb = board
+            .game
+            .history()
+            .last()
+            .and_then(|delta| delta.revealed.iter().find(|c| tile_map.is_bomb_at(**c)));
+        if let Some(bomb) = bomb {
+            let center = tile_map.grid().grid().tile_center(*bomb, board.tile_size);
+            shockwaves.0.push(Shockwave {
+                board: board.entity,
+                center: board.bounds.position + center,
+                age: 0.,
+            });
+        }
+    }
+}
+
+// Spreads the shockwaves over the covers of their board, fading them until the end of the
+// explosion. The materials shared by the covers are changed once each
+fn move_shockwaves(
+    time: Res<Time>,
+    boards: Query<&Board>,
+    covers: Query<&MeshMaterial2d<TileMaterial>>,
+    mut materials: ResMut<Assets<TileMaterial>>,
+    mut shockwaves: ResMut<Shockwaves>,
+) {
+    if shockwaves.0.is_empty() {
+        return;
+    }
+    for shockwave in shockwaves.0.iter_mut() {
+        shockwave.age += time.delta_secs();
+        let board = match boards.get(shockwave.board) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        let radius = shockwave.age * SHOCKWAVE_SPEED * board.tile_size;
+        let strength = (1. - shockwave.age / EXPLOSION_DURATION).max(0.);
+        let wave = shockwave.center.extend(radius).extend(strength);
+        let handles: HashSet<&Handle<TileMaterial>> = board
+            .covered_tiles
+            .values()
+            .filter_map(|cover| covers.get(*cover).ok().map(|m| &m.0))
+            .collect();
+        for handle in handles {
+            if let Some(material) = materials.get_mut(handle) {
+                material.shockwave = wave;
+            }
+        }
+    }
+    shockwaves.0.retain(|s| s.age < EXPLOSION_DURATION);
+}
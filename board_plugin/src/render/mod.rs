@@ -0,0 +1,775 @@
+mod decorations;
+pub mod engine;
+#[cfg(feature = "tile_material")]
+pub mod material;
+pub mod screen_space;
+mod systems;
+
+pub use systems::explosion::{ScreenShake, EXPLOSION_DURATION};
+
+use crate::bounds::Bounds2;
+use crate::components::*;
+use crate::events::*;
+use crate::generation::{generate_tile_map, BoardGenerators, PregeneratedBoard};
+use crate::grid::{GridKind, GridLayout};
+use crate::resources::{
+    tile::Tile, tile_map::TileMap, Board, BoardAssets, BoardControls, BoardOptions, BoardPosition,
+    BoardPreview, GameTimer, InputMap, PointerMode, Replay, ReplayPlayback, ReplayRecorder,
+    TilePayload, TileSize, Unopened,
+};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use engine::{PrimaryWindowQuery, StateSystems};
+use minesweeper_core::Game;
+use rand::{thread_rng, Rng};
+use std::ops::Range;
+use systems::chunks::BoardChunks;
+use systems::long_press::LongPress;
+
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::bevy_egui::{EguiPlugin, EguiPrimaryContextPass};
+
+// Board plugin, the boards only live and play while `running_state` is active.
+// A board is created from the `BoardOptions` resource on enter, host apps may add more
+// boards with `SpawnBoardEvent`
+pub struct BoardPlugin<T> {
+    pub running_state: T,
+}
+
+impl<T: States> Plugin for BoardPlugin<T> {
+    fn build(&self, app: &mut App) {
+        let running = self.running_state.clone();
+        app.init_resource::<BoardAssets>()
+            .init_resource::<BoardGenerators>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<InputMap>()
+            .init_resource::<LongPress>()
+            .init_resource::<PointerMode>()
+            .init_resource::<GameTimer>()
+            .on_enter(running.clone(), (create_board, systems::timer::reset_timer))
+            .on_update(
+                running.clone(),
+                (
+                    (
+                        spawn_requested_boards,
+                        systems::generation::spawn_generated_boards,
+                        systems::generation::spin_spinners,
+                        systems::streaming::stream_tiles,
+                        systems::camera::fit_camera,
+                        systems::camera::move_camera,
+                        systems::commands::board_commands,
+                        systems::timer::tick_timer,
+                        systems::preview::preview_input,
+                        systems::input::input_handling,
+                        systems::pointer::switch_pointer_mode,
+                        systems::pointer::hover_tool,
+                        systems::feedback::tile_feedback,
+                        systems::long_press::long_press_input,
+                        systems::long_press::show_long_press,
+                        systems::cursor::cursor_input,
+                    ),
+                    (
+                        systems::wheel::wheel_marks,
+                        systems::replay::play_replay,
+                        systems::ghost::race_ghost,
+                        systems::ghost::show_ghost,
+                        systems::reducer::apply_actions,
+                        systems::uncover::uncover_tiles,
+                        systems::explosion::explode,
+                        systems::chunks::draw_chunks,
+                        systems::mark::grow_flags,
+                        systems::hint::hint_input,
+                        systems::hint::show_hint,
+                        systems::heatmap::heatmap_input,
+                        systems::heatmap::update_heatmap,
+                        systems::guess::show_guess_odds,
+                        systems::diff::send_board_diffs,
+                        systems::undo::undo_input,
+                    ),
+                ),
+            )
+            .on_exit(running.clone(), cleanup_board)
+            // the explosions play out after leaving the running state
+            .add_systems(
+                Update,
+                (
+                    systems::explosion::update_particles,
+                    systems::explosion::detonate_chains,
+                    systems::explosion::shake_camera,
+                    systems::pixels::sample_sprites,
+                ),
+            )
+            // after the update systems spawned and despawned their entities
+            .add_systems(PostUpdate, systems::layers::apply_render_layers)
+            .add_message::<BoardActionEvent>()
+            .add_message::<BoardCommand>()
+            .add_message::<BoardCompletedEvent>()
+            .add_message::<BombExplosionEvent>()
+            .add_message::<LifeLostEvent>()
+            .add_message::<HintEvent>()
+            .add_message::<BoardStartedEvent>()
+            .add_message::<TreasureFoundEvent>()
+            .add_message::<GuessOddsEvent>()
+            .add_message::<BoardDiffEvent>()
+            .add_message::<SpawnBoardEvent>();
+        #[cfg(feature = "tile_material")]
+        app.add_plugins(material::TileMaterialPlugin);
+        info!("Loaded Board Plugin");
+
+        #[cfg(feature = "debug")]
+        {
+            // registering custom component to be able to edit it in inspector
+            app.register_type::<Bomb>();
+            app.register_type::<BombNeighbor>();
+            app.register_type::<Uncover>();
+            app.register_type::<TileCursor>();
+
+            // board overlays and panel, the host app may already run egui for its inspector
+            if !app.is_plugin_added::<EguiPlugin>() {
+                app.add_plugins(EguiPlugin::default());
+            }
+            app.init_resource::<systems::debug::DebugOverlay>()
+                .add_systems(
+                    EguiPrimaryContextPass,
+                    (systems::debug::debug_panel, systems::debug::debug_overlays)
+                        .run_if(in_state(running)),
+                );
+        }
+    }
+}
+
+// Generates the bomb counter text 2d components for a given value, `size` being the tile width
+fn bomb_count_text(count: u8, size: f32, board_assets: &BoardAssets) -> impl Bundle {
+    (
+        Text2d::new(count.to_string()),
+        TextFont {
+            font: board_assets.bomb_counter_font.clone(),
+            font_size: size * board_assets.text_scale,
+            ..Default::default()
+        },
+        TextColor(board_assets.number_palette.color(count)),
+        TextLayout::new_with_justify(Justify::Center),
+    )
+}
+
+fn adaptative_tile_size(window: &Window, (min, max): (f32, f32), tile_map: &TileMap) -> f32 {
+    // board size for a tile size of 1
+    let grid = tile_map.grid().grid();
+    let unit = grid.board_size(tile_map.width(), tile_map.height(), 1.);
+    let max_width = window.width() / unit.x;
+    let max_height = window.height() / unit.y;
+
+    max_width.min(max_height).clamp(min, max)
+}
+
+// Sprite shaped as a tile of the grid, `size` being the tile width
+pub(crate) fn tile_sprite(
+    grid: GridKind,
+    color: Color,
+    size: f32,
+    board_assets: &BoardAssets,
+) -> Sprite {
+    let mut sprite = Sprite {
+        color,
+        custom_size: Some(grid.grid().tile_extent(size)),
+        ..Default::default()
+    };
+    if grid == GridKind::Hex {
+        sprite.image = board_assets.hex_image.clone();
+    }
+    sprite
+}
+
+// Spawns the treasure sprite in the top right corner of a tile, under its cover
+fn spawn_treasure(parent: &mut ChildSpawnerCommands, size: f32, board_assets: &BoardAssets) {
+    parent.spawn((
+        Sprite {
+            image: board_assets.treasure_image.clone(),
+            custom_size: Some(Vec2::splat(size / 2.)),
+            ..Default::default()
+        },
+        Transform::from_xyz(size / 4., size / 4., 1.5),
+        Name::new("Treasure"),
+    ));
+}
+
+// Spawns a tile cover sprite of the given size
+pub(crate) fn spawn_cover(
+    parent: &mut ChildSpawnerCommands,
+    grid: GridKind,
+    size: f32,
+    board_assets: &BoardAssets,
+) -> Entity {
+    let sprite = match board_assets.sheet_for(grid) {
+        Some(sheet) => sheet.sprite(sheet.covered, size),
+        None => tile_sprite(grid, board_assets.tile_colors.covered, size, board_assets),
+    };
+    let mut cmd = parent.spawn((sprite, Transform::from_xyz(0., 0., 2.)));
+    // the sprite sheets and the hexagons bring their own look, the tile material draws its
+    // bevel
+    let bevel = board_assets.decorations.bevel > 0.
+        && grid == GridKind::Square
+        && board_assets.sheet_for(grid).is_none();
+    if material_covers(grid, board_assets) {
+        #[cfg(feature = "tile_material")]
+        cmd.insert(material::TileCover);
+    } else if bevel {
+        cmd.with_children(|parent| decorations::spawn_bevel(parent, size, board_assets));
+    }
+    cmd.insert(Name::new("Tile Cover")).id()
+}
+
+// Are the covers drawn by the tile material? Only the plain square covers are, with the
+// `tile_material` feature
+pub(crate) fn material_covers(grid: GridKind, board_assets: &BoardAssets) -> bool {
+    cfg!(feature = "tile_material")
+        && grid == GridKind::Square
+        && board_assets.sheet_for(grid).is_none()
+}
+
+// Spawns the tiles of the map in the `range` of indices, row by row from the bottom
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_tiles(
+    parent: &mut ChildSpawnerCommands,
+    tile_map: &TileMap,
+    range: Range<usize>,
+    size: f32,
+    padding: f32,
+    board_assets: &BoardAssets,
+    tiles: &mut HashMap<Coordinates, Entity>,
+    covered_tiles: &mut HashMap<Coordinates, Entity>,
+) {
+    let grid = tile_map.grid();
+    let width = tile_map.width() as usize;
+    // Tiles
+    for index in range {
+        let (x, y) = (index % width, index / width);
+        let tile = &tile_map[y][x];
+        // holes of shaped boards
+        if !tile.exists() {
+            continue;
+        }
+        let coordinates = Coordinates {
+            x: x as u16,
+            y: y as u16,
+        };
+        let position = grid.grid().tile_center(coordinates, size);
+        let transform = Transform::from_translation(position.extend(1.));
+        let mut cmd = parent.spawn_empty();
+        cmd.insert(Name::new(format!("Tile ({x}, {y})")));
+        tiles.insert(coordinates, cmd.id());
+
+        // Tile cover, removed when the tile is uncovered
+        cmd.with_children(|parent| {
+            let entity = spawn_cover(parent, grid, size - padding, board_assets);
+            covered_tiles.insert(coordinates, entity);
+        });
+
+        // treasures show once their tile is uncovered
+        if let Some(TilePayload::Treasure(_)) = tile_map.payload_at(coordinates) {
+            cmd.with_children(|parent| spawn_treasure(parent, size - padding, board_assets));
+        }
+
+        // the sprite sheet frames show the bombs and the bomb counts, up to 8 so the
+        // multi-mine boards draw their own
+        let sheet = board_assets
+            .sheet_for(grid)
+            .filter(|_| tile_map.max_mines() == 1);
+        if let Some(sheet) = sheet {
+            cmd.insert((sheet.sprite(sheet.frame(*tile), size - padding), transform));
+            match tile {
+                Tile::Bomb(_) => {
+                    cmd.insert(Bomb);
+                }
+                Tile::BombNeighbor(v) => {
+                    cmd.insert(BombNeighbor { count: *v });
+                }
+                Tile::Empty | Tile::Void => (),
+            }
+            continue;
+        }
+
+        cmd.insert((
+            tile_sprite(
+                grid,
+                board_assets.tile_colors.revealed,
+                size - padding,
+                board_assets,
+            ),
+            transform,
+        ));
+        match tile {
+            // If the tile is a bomb, add the matching component and a sprite child
+            Tile::Bomb(mines) => {
+                cmd.insert(Bomb).with_children(|parent| {
+                    parent.spawn((
+                        Sprite {
+                            image: board_assets.bomb_image.clone(),
+                            custom_size: Some(Vec2::splat(size - padding)),
+                            ..Default::default()
+                        },
+                        Transform::from_xyz(0., 0., 1.),
+                    ));
+                    // stacked mines are counted in a corner
+                    if *mines > 1 {
+                        let corner = (size - padding) / 3.;
+                        parent.spawn((
+                            bomb_count_text(*mines, corner, board_assets),
+                            Transform::from_xyz(corner, -corner, 2.),
+                        ));
+                    }
+                });
+            }
+            // If the tile is a bomb neighbour, add the matching component and a text child
+            Tile::BombNeighbor(v) => {
+                cmd.insert(BombNeighbor { count: *v })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            bomb_count_text(*v, size - padding, board_assets),
+                            Transform::from_xyz(0., 0., 1.),
+                        ));
+                    });
+            }
+            Tile::Empty | Tile::Void => (),
+        }
+    }
+}
+
+// Spawns the board of the running state from the `BoardOptions` resource
+#[allow(clippy::too_many_arguments)]
+pub fn create_board(
+    mut cmds: Commands,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Res<BoardAssets>,
+    generators: Res<BoardGenerators>,
+    windows: PrimaryWindowQuery,
+    playback: Option<Res<ReplayPlayback>>,
+    pregenerated: Option<Res<PregeneratedBoard>>,
+) {
+    let options = match board_options {
+        Some(o) => o.clone(),
+        None => BoardOptions::default(),
+    };
+    // seeded boards, replays included, must be generated from their seed
+    let pregenerated = pregenerated
+        .filter(|_| options.seed.is_none())
+        .map(|p| p.clone());
+    cmds.remove_resource::<PregeneratedBoard>();
+
+    if let Ok(win) = windows.single() {
+        new_board(
+            &mut cmds,
+            options,
+            &board_assets,
+            &generators,
+            pregenerated,
+            win,
+            playback.is_some(),
+        );
+    }
+}
+
+// Spawns the additional boards requested by the host app
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_requested_boards(
+    mut cmds: Commands,
+    board_assets: Res<BoardAssets>,
+    generators: Res<BoardGenerators>,
+    windows: PrimaryWindowQuery,
+    playback: Option<Res<ReplayPlayback>>,
+    mut spawn_board_evr: MessageReader<SpawnBoardEvent>,
+) {
+    let win = match windows.single() {
+        Ok(w) => w,
+        Err(_) => return,
+    };
+    for SpawnBoardEvent(options) in spawn_board_evr.read() {
+        new_board(
+            &mut cmds,
+            options.clone(),
+            &board_assets,
+            &generators,
+            None,
+            win,
+            playback.is_some(),
+        );
+    }
+}
+
+// Spawns a board, then shows its preview or starts the play right away. Slow generations run
+// in the background, the board being spawned once its tile map is ready
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn new_board(
+    cmds: &mut Commands,
+    options: BoardOptions,
+    board_assets: &BoardAssets,
+    generators: &BoardGenerators,
+    pregenerated: Option<PregeneratedBoard>,
+    window: &Window,
+    playback: bool,
+) {
+    // impossible boards would have their bombs clamped, or no safe start
+    if let Err(e) = options.validate() {
+        error!("Invalid board options: {e}");
+        return;
+    }
+    let pregenerated = pregenerated.filter(|p| p.options.generates_like(&options));
+    if pregenerated.is_none() && options.generates_slowly() {
+        generate_board(cmds, options, board_assets, generators);
+        return;
+    }
+    let board = spawn_board(
+        cmds,
+        options,
+        board_assets,
+        generators,
+        pregenerated,
+        window,
+    );
+    // replays skip the preview, the board was already accepted when it was recorded
+    let preview = board
+        .options
+        .preview_rerolls
+        .filter(|_| !playback)
+        .map(|rerolls_left| BoardPreview { rerolls_left });
+    present_board(cmds, board, preview, !playback, board_assets);
+}
+
+// Height of the progress bar of the boards spawning their tiles over several frames
+const PROGRESS_BAR_HEIGHT: f32 = 12.;
+// Widest progress bar, narrower boards showing a bar of 60% of their width
+const PROGRESS_BAR_WIDTH: f32 = 400.;
+
+// Shows the preview of a spawned board or starts its play. The boards of too many tiles first
+// spawn their tiles over the next frames behind a progress bar, and only become boards once done
+pub(crate) fn present_board(
+    cmds: &mut Commands,
+    board: Board,
+    preview: Option<BoardPreview>,
+    record: bool,
+    board_assets: &BoardAssets,
+) {
+    if !board.options.streams_tiles() {
+        show_board(cmds, board, preview, record);
+        return;
+    }
+
+    info!("Spawning the tiles over the next frames");
+    let size = Vec2::new(
+        PROGRESS_BAR_WIDTH.min(board.bounds.size.x * 0.6),
+        PROGRESS_BAR_HEIGHT,
+    );
+    let center = board.bounds.size / 2.;
+    let bar = cmds
+        .spawn((
+            Sprite {
+                color: board_assets.tile_colors.background,
+                custom_size: Some(size),
+                ..Default::default()
+            },
+            Transform::from_translation(center.extend(3.)),
+            Name::new("Progress bar"),
+        ))
+        .with_children(|parent| {
+            // grows from the left end of the bar
+            parent.spawn((
+                Sprite {
+                    color: board_assets.tile_colors.covered,
+                    custom_size: Some(size),
+                    ..Default::default()
+                },
+                Transform {
+                    translation: Vec3::new(-size.x / 2., 0., 0.1),
+                    scale: Vec3::new(0., 1., 1.),
+                    ..Default::default()
+                },
+                ProgressFill { width: size.x },
+            ));
+        })
+        .id();
+    cmds.entity(board.entity)
+        .add_children(&[bar])
+        .insert(TileSpawner {
+            board: Some(board),
+            next: 0,
+            preview,
+            record,
+            progress_bar: bar,
+        });
+}
+
+// Side of the spinner of the boards generated in the background
+const SPINNER_SIZE: f32 = 64.;
+
+// Generates the tile map of a board on the async compute pool, a spinner standing in the middle
+// of the board meanwhile
+fn generate_board(
+    cmds: &mut Commands,
+    options: BoardOptions,
+    board_assets: &BoardAssets,
+    generators: &BoardGenerators,
+) {
+    info!(
+        "Generating a {}x{} board in the background",
+        options.map_size.0, options.map_size.1
+    );
+    let position = match options.position {
+        BoardPosition::Centered { offset } => offset,
+        BoardPosition::Custom(p) => p,
+    };
+    let transform = Transform::from_translation(position + Vec3::Z * options.z_offset);
+    let layers = options.render_layers();
+    let generator = generators.generator(&options.generation);
+    // drawn now, the board generated from the task is spawned with the same seed
+    let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let tile_map = generate_tile_map(generator.as_ref(), &options, seed);
+        PregeneratedBoard {
+            options,
+            seed,
+            tile_map,
+        }
+    });
+    cmds.spawn_empty()
+        .insert(Name::new("Generating board"))
+        .insert(GeneratingBoard { task, done: None })
+        .insert((transform, Visibility::default()))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image: board_assets.bomb_image.clone(),
+                    custom_size: Some(Vec2::splat(SPINNER_SIZE)),
+                    ..Default::default()
+                },
+                layers,
+                Name::new("Spinner"),
+                GenerationSpinner,
+            ));
+        });
+}
+
+// Generates a tile map and spawns its board, still fully covered
+pub(crate) fn spawn_board(
+    cmds: &mut Commands,
+    options: BoardOptions,
+    board_assets: &BoardAssets,
+    generators: &BoardGenerators,
+    pregenerated: Option<PregeneratedBoard>,
+    window: &Window,
+) -> Board {
+    let (seed, tile_map) = match pregenerated.filter(|p| p.options.generates_like(&options)) {
+        Some(p) => {
+            info!("Using a pregenerated board");
+            (p.seed, p.tile_map)
+        }
+        None => {
+            // every random choice derives from the seed, so the board can be replayed
+            let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+            (seed, generators.generate(&options, seed))
+        }
+    };
+    info!("Board seed: {seed}");
+
+    #[cfg(feature = "debug")]
+    // Tile map debugging
+    info!("{}", tile_map.console_output());
+
+    // define the size of the tiles in world space
+    let tile_size = match options.tile_size {
+        TileSize::Fixed(v) => v as f32,
+        TileSize::Adaptive { min, max } => adaptative_tile_size(window, (min, max), &tile_map),
+    };
+    // pixel perfect tiles cover whole pixels
+    let tile_size = match board_assets.pixel_perfect {
+        true => tile_size.floor().max(1.),
+        false => tile_size,
+    };
+
+    // deduce the size of the complete board
+    let grid = tile_map.grid().grid();
+    let board_size = grid.board_size(tile_map.width(), tile_map.height(), tile_size);
+    info!("Board size: {board_size}");
+
+    // define the board anchor position (bottom left)
+    let board_position = match options.position {
+        BoardPosition::Centered { offset } => {
+            Vec3::new(-(board_size.x / 2.), -(board_size.y / 2.), 0.) + offset
+        }
+        BoardPosition::Custom(p) => p,
+    };
+    let board_position = match board_assets.pixel_perfect {
+        true => board_position.round(),
+        false => board_position,
+    };
+
+    let chunked = options.chunked_rendering();
+    let capacity = match chunked {
+        true => 0,
+        false => (tile_map.width() as usize) * (tile_map.height() as usize),
+    };
+    let mut tiles = HashMap::with_capacity(capacity);
+    let mut covered_tiles = HashMap::with_capacity(capacity);
+
+    // spawn the board
+    let mut board_cmds = cmds.spawn_empty();
+    if chunked {
+        info!("Drawing the board in chunks");
+        board_cmds.insert(BoardChunks::default());
+    }
+    let board_entity = board_cmds
+        .insert(Name::new("Board"))
+        .insert((
+            Transform::from_translation(board_position + Vec3::Z * options.z_offset),
+            Visibility::default(),
+        ))
+        .with_children(|parent| {
+            // shaped boards show their shape instead of a rectangular background
+            if options.mask.is_none() {
+                parent.spawn((
+                    Sprite {
+                        color: board_assets.tile_colors.background,
+                        custom_size: Some(board_size),
+                        ..Default::default()
+                    },
+                    Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
+                    Name::new("Background"),
+                ));
+            }
+            if board_assets.decorations.frame > 0. {
+                decorations::spawn_frame(parent, board_size, board_assets);
+            }
+            if tile_map.wraps() {
+                decorations::spawn_wrap_edges(
+                    parent,
+                    &tile_map,
+                    tile_size,
+                    board_size,
+                    board_assets,
+                );
+            }
+            // the lines would cross the holes of shaped boards
+            let grid_lines = board_assets.decorations.grid_lines > 0.
+                && tile_map.grid() == GridKind::Square
+                && options.mask.is_none()
+                && !chunked;
+            if grid_lines {
+                decorations::spawn_grid_lines(
+                    parent,
+                    &tile_map,
+                    tile_size,
+                    board_size,
+                    board_assets,
+                );
+            }
+
+            // spawn the tiles, large boards are drawn in chunks once spawned and the boards of
+            // too many tiles spawn them over several frames
+            if !chunked && !options.streams_tiles() {
+                let tile_count = tile_map.width() as usize * tile_map.height() as usize;
+                spawn_tiles(
+                    parent,
+                    &tile_map,
+                    0..tile_count,
+                    tile_size,
+                    options.tile_padding,
+                    board_assets,
+                    &mut tiles,
+                    &mut covered_tiles,
+                );
+            }
+
+            // keyboard players start on the opening tile, or the middle of the board. The mouse
+            // is left to player one on shared boards
+            if options.controls.cursor() {
+                let player = match options.controls {
+                    BoardControls::Shared => PlayerId::Two,
+                    BoardControls::Mouse | BoardControls::Keyboard => PlayerId::One,
+                };
+                let coordinates = tile_map.opening().unwrap_or(Coordinates {
+                    x: tile_map.width() / 2,
+                    y: tile_map.height() / 2,
+                });
+                systems::cursor::spawn_tile_cursor(
+                    parent,
+                    tile_map.grid(),
+                    coordinates,
+                    player,
+                    tile_size,
+                    options.tile_padding,
+                    board_assets,
+                );
+            }
+        })
+        .id();
+
+    Board {
+        game: Game::new(tile_map)
+            .with_strict_flags(options.strict_flags)
+            .with_no_flags(options.no_flags),
+        lives: options.lives,
+        bounds: Bounds2 {
+            position: board_position.truncate(),
+            size: board_size,
+        },
+        tile_size,
+        tiles,
+        covered_tiles,
+        entity: board_entity,
+        seed,
+        options,
+        assists: Default::default(),
+        found_treasures: Default::default(),
+        question_marks: Default::default(),
+        chain: Default::default(),
+        actions: Default::default(),
+    }
+}
+
+// Inserts a board whose tiles are all spawned, previewed or started
+pub(crate) fn show_board(
+    cmds: &mut Commands,
+    board: Board,
+    preview: Option<BoardPreview>,
+    record: bool,
+) {
+    match preview {
+        Some(preview) => {
+            cmds.entity(board.entity).insert(preview);
+        }
+        None => start_board(cmds, &board, record),
+    }
+    cmds.entity(board.entity).insert(board);
+}
+
+// Starts the play on a spawned board, the reducer opening it before the first move
+pub(crate) fn start_board(cmds: &mut Commands, board: &Board, record: bool) {
+    cmds.entity(board.entity).insert(Unopened);
+
+    // record the player actions, unless replaying
+    if record {
+        cmds.entity(board.entity).insert(ReplayRecorder {
+            replay: Replay {
+                options: BoardOptions {
+                    seed: Some(board.seed),
+                    preview_rerolls: None,
+                    ..board.options.clone()
+                },
+                events: Vec::new(),
+            },
+        });
+    }
+}
+
+// Despawns the boards, along with their preview and recorder components, and drops the boards
+// still generated or spawning their tiles. An armed hint is dropped with them
+pub fn cleanup_board(
+    mut cmds: Commands,
+    mut mode: ResMut<PointerMode>,
+    boards: Query<Entity, Or<(With<Board>, With<GeneratingBoard>, With<TileSpawner>)>>,
+) {
+    for entity in boards.iter() {
+        cmds.entity(entity).despawn();
+    }
+    mode.hint_armed = false;
+}
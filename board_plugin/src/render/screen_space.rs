@@ -0,0 +1,75 @@
+use crate::render::engine;
+use bevy::prelude::*;
+
+// Mapping of the window positions to the world seen by a 2D camera. The window positions are
+// in logical pixels from the top left corner, like the cursor and touch positions, the physical
+// ones being scaled down on HiDPI monitors first
+#[derive(Debug, Copy, Clone)]
+pub struct ScreenSpace {
+    // Window size, in logical pixels
+    pub window_size: Vec2,
+    // Physical pixels per logical pixel
+    pub scale_factor: f32,
+    // Camera space corners of the view, bottom left and top right, from its orthographic
+    // projection scaled by its zoom
+    pub view_min: Vec2,
+    pub view_max: Vec2,
+    // Camera to world transform, moved and zoomed by the host or shaken by the explosions
+    pub camera: Mat4,
+}
+
+impl ScreenSpace {
+    // Window seen by a camera left at the origin, one world unit per logical pixel
+    pub fn new(window_size: Vec2, scale_factor: f32) -> Self {
+        Self {
+            window_size,
+            scale_factor,
+            view_min: -window_size / 2.,
+            view_max: window_size / 2.,
+            camera: Mat4::IDENTITY,
+        }
+    }
+
+    pub fn from_window(window: &Window) -> Self {
+        Self::new(engine::window_size(window), window.scale_factor())
+    }
+
+    // Same window seen by the given camera
+    pub fn with_camera(
+        self,
+        transform: &GlobalTransform,
+        projection: &OrthographicProjection,
+    ) -> Self {
+        Self {
+            view_min: projection.area.min,
+            view_max: projection.area.max,
+            camera: transform.to_matrix(),
+            ..self
+        }
+    }
+
+    // World position under a window position in logical pixels
+    pub fn window_to_world(&self, position: Vec2) -> Vec2 {
+        // the window y axis points down, the world one up
+        let ratio = position / self.window_size;
+        let ratio = Vec2::new(ratio.x, 1. - ratio.y);
+        let view = self.view_min + ratio * (self.view_max - self.view_min);
+        self.camera.transform_point3(view.extend(0.)).truncate()
+    }
+
+    // World position under a window position in physical pixels
+    pub fn physical_to_world(&self, position: Vec2) -> Vec2 {
+        self.window_to_world(position / self.scale_factor)
+    }
+
+    // Window position in logical pixels showing a world position
+    pub fn world_to_window(&self, world: Vec2) -> Vec2 {
+        let view = self
+            .camera
+            .inverse()
+            .transform_point3(world.extend(0.))
+            .truncate();
+        let ratio = (view - self.view_min) / (self.view_max - self.view_min);
+        Vec2::new(ratio.x, 1. - ratio.y) * self.window_size
+    }
+}
@@ -0,0 +1,79 @@
+use crate::components::CameraFit;
+use crate::render::engine::{self, Is2dCamera};
+use crate::render::systems::explosion::ScreenShake;
+use crate::resources::{Board, BoardPreview};
+use crate::tween::Tween;
+use bevy::prelude::*;
+
+// Frames the boards once a new one spawned. The camera starts from the zoom showing the new
+// boards as large as the boards framed before, and moves over the animation duration of the new
+// board
+pub fn fit_camera(
+    mut cmds: Commands,
+    new_boards: Query<&Board, (Added<Board>, Without<BoardPreview>)>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    mut cameras: Query<(Entity, &mut Transform, &mut Projection), Is2dCamera>,
+    mut framed: Local<Option<Vec2>>,
+) {
+    let duration = match new_boards.iter().next() {
+        Some(board) => board.options.animations.camera,
+        None => return,
+    };
+    let (min, max) = boards.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), b| {
+            let bounds = b.bounds;
+            (
+                min.min(bounds.position),
+                max.max(bounds.position + bounds.size),
+            )
+        },
+    );
+    let size = max - min;
+    let to = ((min + max) / 2., 1.);
+    // a larger board starts zoomed out, a smaller one zoomed in
+    let zoom = framed.map_or(1., |framed| (size / framed).max_element());
+    *framed = Some(size);
+
+    for (entity, mut transform, mut projection) in cameras.iter_mut() {
+        if duration <= 0. {
+            transform.translation = to.0.extend(transform.translation.z);
+            engine::set_camera_zoom(&mut projection, to.1);
+            cmds.entity(entity).remove::<CameraFit>();
+            continue;
+        }
+        let from = (
+            transform.translation.truncate(),
+            engine::camera_zoom(&projection) * zoom,
+        );
+        cmds.entity(entity).insert(CameraFit {
+            from,
+            to,
+            tween: Tween::new(duration),
+        });
+    }
+}
+
+// Moves the camera along its fit, eased out
+pub fn move_camera(
+    mut cmds: Commands,
+    time: Res<Time>,
+    shake: Res<ScreenShake>,
+    mut cameras: Query<(Entity, &mut CameraFit, &mut Transform, &mut Projection)>,
+) {
+    for (entity, mut fit, mut transform, mut projection) in cameras.iter_mut() {
+        fit.tween.advance(time.delta_secs());
+        let progress = fit.tween.eased();
+        let ((from, from_scale), (to, to_scale)) = (fit.from, fit.to);
+        // the explosions shaking the camera meanwhile
+        let position = from.lerp(to, progress).extend(transform.translation.z);
+        transform.translation = position + shake.offset();
+        engine::set_camera_zoom(
+            &mut projection,
+            from_scale + (to_scale - from_scale) * progress,
+        );
+        if fit.tween.is_done() {
+            cmds.entity(entity).remove::<CameraFit>();
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::components::Coordinates;
+use crate::resources::tile::Tile;
+use crate::resources::{Board, BoardAssets, BoardSilhouette, TileState};
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::tasks::ComputeTaskPool;
+use std::collections::VecDeque;
+
+// Tiles per chunk side
+const CHUNK_SIZE: u16 = 32;
+// Tiles painted per frame, the rest of a massive reveal being painted on the next frames
+const PAINT_BUDGET: usize = 16_384;
+// Pixels per tile side in the chunk images, the last row and column separate the tiles
+const TILE_PIXELS: usize = 8;
+
+// 3x5 glyphs drawn on the tiles, a row per byte with the 3 lowest bits as pixels
+type Glyph = [u8; 5];
+
+static DIGITS: [Glyph; 9] = [
+    [0b000, 0b000, 0b000, 0b000, 0b000],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+];
+// counts above 8, on multi-mine boards
+static PLUS: Glyph = [0b000, 0b010, 0b111, 0b010, 0b000];
+static FLAG: Glyph = [0b110, 0b111, 0b110, 0b100, 0b100];
+static BOMB: Glyph = [0b010, 0b111, 0b111, 0b111, 0b010];
+
+// Chunk images of a board drawn in chunks, component of the board entity.
+// The images are created by the first drawing, then only the changed tiles are redrawn
+#[derive(Debug, Default, Component)]
+pub struct BoardChunks {
+    chunks: Vec<Chunk>,
+    // Chunks per row
+    columns: u16,
+    // Tile states of the last drawing
+    drawn: BoardSilhouette,
+    // Changed tiles left to paint, closest to the last move first
+    pending: VecDeque<(Coordinates, TileState)>,
+}
+
+#[derive(Debug)]
+struct Chunk {
+    image: Handle<Image>,
+    // Bottom left tile
+    origin: Coordinates,
+    width: u16,
+    height: u16,
+}
+
+// Draws the chunks of the new boards, and redraws the tiles changed by the last moves. A
+// massive reveal is painted over several frames, cascading out of the uncovered tile
+pub fn draw_chunks(
+    mut cmds: Commands,
+    mut boards: Query<(Ref<Board>, &mut BoardChunks)>,
+    mut images: ResMut<Assets<Image>>,
+    board_assets: Res<BoardAssets>,
+) {
+    for (board, mut chunks) in boards.iter_mut() {
+        if chunks.chunks.is_empty() {
+            spawn_chunks(&mut cmds, &board, &mut chunks, &mut images, &board_assets);
+        }
+        if board.is_changed() {
+            queue_changes(&board, &mut chunks);
+        }
+        if !chunks.pending.is_empty() {
+            paint_pending(&board, &mut chunks, &mut images, &board_assets);
+        }
+    }
+}
+
+// Queues the tiles changed since the last drawing, by distance to the tile of the last player
+// move
+fn queue_changes(board: &Board, chunks: &mut BoardChunks) {
+    let silhouette = BoardSilhouette::from_board(board);
+    let mut changes = chunks.drawn.diff(&silhouette).changes;
+    chunks.drawn = silhouette;
+    let last_move = board.actions.iter().rev().find(|a| a.is_move());
+    if let Some(origin) = last_move.and_then(|a| a.coordinates()) {
+        changes.sort_by_key(|(c, _)| c.x.abs_diff(origin.x).max(c.y.abs_diff(origin.y)));
+    }
+    // painted after the tiles queued before, which they may change again
+    chunks.pending.extend(changes);
+}
+
+// Paints the next queued tiles within the frame budget, the chunks in parallel on the compute
+// task pool
+fn paint_pending(
+    board: &Board,
+    chunks: &mut BoardChunks,
+    images: &mut Assets<Image>,
+    board_assets: &BoardAssets,
+) {
+    let count = chunks.pending.len().min(PAINT_BUDGET);
+    let mut batches: HashMap<usize, Vec<(Coordinates, TileState)>> = HashMap::default();
+    for (coords, state) in chunks.pending.drain(..count) {
+        let index = (coords.y / CHUNK_SIZE) * chunks.columns + coords.x / CHUNK_SIZE;
+        batches
+            .entry(index as usize)
+            .or_default()
+            .push((coords, state));
+    }
+    // the pixels leave their image while a task paints them
+    let mut jobs: Vec<(&Chunk, Vec<u8>, Vec<(Coordinates, TileState)>)> = batches
+        .into_iter()
+        .filter_map(|(index, tiles)| {
+            let chunk = &chunks.chunks[index];
+            let image = images.get_mut(&chunk.image)?;
+            Some((chunk, image.data.take()?, tiles))
+        })
+        .collect();
+    let tile_map = board.tile_map();
+    ComputeTaskPool::get().scope(|scope| {
+        for (chunk, data, tiles) in jobs.iter_mut() {
+            let chunk = *chunk;
+            scope.spawn(async move {
+                for (coords, state) in tiles.iter() {
+                    let tile = tile_map[coords.y as usize][coords.x as usize];
+                    paint_tile(data, chunk, *coords, tile, *state, board_assets);
+                }
+            });
+        }
+    });
+    for (chunk, data, _) in jobs {
+        if let Some(image) = images.get_mut(&chunk.image) {
+            image.data = Some(data);
+        }
+    }
+}
+
+// Spawns a sprite per chunk of the board, with every tile covered
+fn spawn_chunks(
+    cmds: &mut Commands,
+    board: &Board,
+    chunks: &mut BoardChunks,
+    images: &mut Assets<Image>,
+    board_assets: &BoardAssets,
+) {
+    let tile_map = board.tile_map();
+    let (width, height) = (tile_map.width(), tile_map.height());
+    chunks.columns = width.div_ceil(CHUNK_SIZE);
+    chunks.drawn = BoardSilhouette::covered(width, height);
+
+    for y in (0..height).step_by(CHUNK_SIZE as usize) {
+        for x in (0..width).step_by(CHUNK_SIZE as usize) {
+            let mut chunk = Chunk {
+                image: Handle::default(),
+                origin: Coordinates { x, y },
+                width: CHUNK_SIZE.min(width - x),
+                height: CHUNK_SIZE.min(height - y),
+            };
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: (chunk.width as usize * TILE_PIXELS) as u32,
+                    height: (chunk.height as usize * TILE_PIXELS) as u32,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[0, 0, 0, 0],
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            );
+            // keeps the pixel art sharp
+            image.sampler = ImageSampler::nearest();
+            let mut data = image.data.take().unwrap_or_default();
+            for ty in y..y + chunk.height {
+                for tx in x..x + chunk.width {
+                    let coords = Coordinates { x: tx, y: ty };
+                    let tile = tile_map[ty as usize][tx as usize];
+                    paint_tile(
+                        &mut data,
+                        &chunk,
+                        coords,
+                        tile,
+                        TileState::Covered,
+                        board_assets,
+                    );
+                }
+            }
+            image.data = Some(data);
+            chunk.image = images.add(image);
+
+            let size = Vec2::new(chunk.width as f32, chunk.height as f32) * board.tile_size;
+            let position = Vec2::new(x as f32, y as f32) * board.tile_size + size / 2.;
+            cmds.entity(board.entity).with_children(|parent| {
+                parent
+                    .spawn((
+                        Sprite {
+                            image: chunk.image.clone(),
+                            custom_size: Some(size),
+                            ..Default::default()
+                        },
+                        Transform::from_translation(position.extend(1.)),
+                    ))
+                    .insert(Name::new(format!("Chunk ({x}, {y})")));
+            });
+            chunks.chunks.push(chunk);
+        }
+    }
+}
+
+// Paints a tile in the pixels of its chunk image
+fn paint_tile(
+    data: &mut [u8],
+    chunk: &Chunk,
+    coords: Coordinates,
+    tile: Tile,
+    state: TileState,
+    board_assets: &BoardAssets,
+) {
+    let colors = board_assets.tile_colors;
+    let (background, glyph) = match (state, tile) {
+        (TileState::Covered, _) => (colors.covered, None),
+        (TileState::Flagged, _) => (colors.covered, Some((&FLAG, Color::srgb(1., 0., 0.)))),
+        (TileState::Revealed, Tile::Bomb(_)) => (colors.revealed, Some((&BOMB, colors.bomb))),
+        (TileState::Revealed, Tile::BombNeighbor(count)) => {
+            let digit = DIGITS.get(count as usize).unwrap_or(&PLUS);
+            let color = board_assets.number_palette.color(count);
+            (colors.revealed, Some((digit, color)))
+        }
+        (TileState::Revealed, Tile::Empty | Tile::Void) => (colors.revealed, None),
+    };
+
+    let image_width = chunk.width as usize * TILE_PIXELS;
+    let left = (coords.x - chunk.origin.x) as usize * TILE_PIXELS;
+    // image rows go down while the board rows go up
+    let top = (chunk.origin.y + chunk.height - 1 - coords.y) as usize * TILE_PIXELS;
+    for y in 0..TILE_PIXELS {
+        for x in 0..TILE_PIXELS {
+            let color = if !tile.exists() {
+                Color::srgba(0., 0., 0., 0.)
+            } else if x == TILE_PIXELS - 1 || y == TILE_PIXELS - 1 {
+                colors.background
+            } else {
+                match glyph {
+                    Some((glyph, color)) if glyph_pixel(glyph, x, y) => color,
+                    _ => background,
+                }
+            };
+            let i = ((top + y) * image_width + left + x) * 4;
+            data[i..i + 4].copy_from_slice(&rgba8(color));
+        }
+    }
+}
+
+// Is the pixel of the tile part of the glyph, centered in the tile?
+fn glyph_pixel(glyph: &Glyph, x: usize, y: usize) -> bool {
+    (2..5).contains(&x) && (1..6).contains(&y) && (glyph[y - 1] >> (4 - x)) & 1 == 1
+}
+
+fn rgba8(color: Color) -> [u8; 4] {
+    color.to_srgba().to_u8_array()
+}
@@ -0,0 +1,33 @@
+use crate::components::PlayerId;
+use crate::events::{BoardActionEvent, BoardCommand};
+use crate::resources::{Board, BoardPreview, ReplayPlayback};
+use bevy::prelude::*;
+
+// Checks the board commands like the player input, and forwards them as actions of player one.
+// Previewed, replayed and exploded boards and the tiles off the board ignore them
+pub fn board_commands(
+    boards: Query<&Board, Without<BoardPreview>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut board_command_evr: MessageReader<BoardCommand>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+) {
+    for command in board_command_evr.read() {
+        let (entity, coordinates) = command.tile();
+        let board = match boards.get(entity) {
+            Ok(b) if playback.is_none() && !b.game.is_exploded() => b,
+            _ => {
+                warn!("Ignored {command:?}, the board isn't playable");
+                continue;
+            }
+        };
+        if !board.tile_map().exists(coordinates) {
+            warn!("Ignored {command:?}, the tile is off the board");
+            continue;
+        }
+        board_action_ewr.write(BoardActionEvent {
+            board: entity,
+            action: command.action(),
+            player: PlayerId::One,
+        });
+    }
+}
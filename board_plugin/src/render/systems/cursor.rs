@@ -0,0 +1,118 @@
+use crate::components::{Coordinates, PlayerId, TileCursor};
+use crate::events::{Action, BoardActionEvent, BoardCommand};
+use crate::grid::{GridKind, GridLayout};
+use crate::render::systems::is_allowed;
+use crate::render::tile_sprite;
+use crate::resources::{Board, BoardAssets, BoardPreview, InputGate, ReplayPlayback};
+use bevy::prelude::*;
+
+// Spawns the tile cursor of a keyboard controlled or shared board, colored after its player
+pub(crate) fn spawn_tile_cursor(
+    parent: &mut ChildSpawnerCommands,
+    grid: GridKind,
+    coordinates: Coordinates,
+    player: PlayerId,
+    tile_size: f32,
+    padding: f32,
+    board_assets: &BoardAssets,
+) {
+    let position = grid.grid().tile_center(coordinates, tile_size);
+    let color = match player {
+        PlayerId::One => Color::srgba(0., 0.5, 1., 0.5),
+        PlayerId::Two => Color::srgba(1., 0.5, 0., 0.5),
+    };
+    parent
+        .spawn((
+            tile_sprite(grid, color, tile_size - padding, board_assets),
+            Transform::from_translation(position.extend(10.)),
+        ))
+        .insert(Name::new("Tile Cursor"))
+        .insert(TileCursor {
+            coordinates,
+            player,
+        });
+}
+
+// Moves the tile cursors with the arrow keys, Space uncovers the selected tile and F flags it.
+// Gamepads drive the cursors as well: d-pad to move, South to uncover and East to flag
+#[allow(clippy::too_many_arguments)]
+pub fn cursor_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    playback: Option<Res<ReplayPlayback>>,
+    gate: Option<Res<InputGate>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    mut cursors: Query<(&mut TileCursor, &mut Transform, &ChildOf)>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+) {
+    // the player doesn't control replays
+    if playback.is_some() {
+        return;
+    }
+    let pressed = |key: KeyCode, button: GamepadButton| {
+        keys.just_pressed(key) || gamepads.iter().any(|g| g.just_pressed(button))
+    };
+    let (dx, dy) = [
+        (KeyCode::ArrowLeft, GamepadButton::DPadLeft, (-1, 0)),
+        (KeyCode::ArrowRight, GamepadButton::DPadRight, (1, 0)),
+        (KeyCode::ArrowDown, GamepadButton::DPadDown, (0, -1)),
+        (KeyCode::ArrowUp, GamepadButton::DPadUp, (0, 1)),
+    ]
+    .into_iter()
+    .filter(|(key, button, _)| pressed(*key, *button))
+    .fold((0, 0), |(x, y), (_, _, (dx, dy))| (x + dx, y + dy));
+    let uncover = pressed(KeyCode::Space, GamepadButton::South);
+    let mark = pressed(KeyCode::KeyF, GamepadButton::East);
+
+    for (mut cursor, mut transform, parent) in cursors.iter_mut() {
+        // previewed boards aren't playable yet and an exploded board only accepts undos
+        let board = match boards.get(parent.parent()) {
+            Ok(b) if !b.game.is_exploded() => b,
+            _ => continue,
+        };
+        if (dx, dy) != (0, 0) {
+            let (width, height) = (board.tile_map().width(), board.tile_map().height());
+            let coordinates = Coordinates {
+                x: (cursor.coordinates.x as i32 + dx).clamp(0, width as i32 - 1) as u16,
+                y: (cursor.coordinates.y as i32 + dy).clamp(0, height as i32 - 1) as u16,
+            };
+            let grid = board.tile_map().grid().grid();
+            let position = grid.tile_center(coordinates, board.tile_size);
+            transform.translation = position.extend(transform.translation.z);
+            cursor.coordinates = coordinates;
+        }
+
+        let coordinates = cursor.coordinates;
+        let uncover = uncover
+            && is_allowed(
+                &gate,
+                BoardCommand::UncoverTile {
+                    board: board.entity,
+                    coordinates,
+                },
+            );
+        let mark = mark
+            && is_allowed(
+                &gate,
+                BoardCommand::ToggleFlag {
+                    board: board.entity,
+                    coordinates,
+                },
+            );
+        if uncover {
+            info!("Trying to uncover tile on {coordinates}");
+            board_action_ewr.write(BoardActionEvent {
+                board: board.entity,
+                action: Action::Uncover(coordinates),
+                player: cursor.player,
+            });
+        } else if mark {
+            info!("Trying to mark tile on {coordinates}");
+            board_action_ewr.write(BoardActionEvent {
+                board: board.entity,
+                action: Action::ToggleFlag(coordinates),
+                player: cursor.player,
+            });
+        }
+    }
+}
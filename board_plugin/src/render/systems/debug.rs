@@ -0,0 +1,252 @@
+use crate::components::Coordinates;
+use crate::grid::{GridKind, GridLayout};
+use crate::render::systems::WorldCursor;
+use crate::resources::{Board, BoardAssets};
+use crate::solver::Deduction;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts};
+
+// Debug overlays of the boards, toggled from the debug panel. Must be used as a resource
+#[derive(Resource, Debug, Copy, Clone, Default, PartialEq)]
+pub struct DebugOverlay {
+    // Column numbers below the boards and row numbers on their left
+    pub coordinates: bool,
+    // Tile borders of the square grids, neighbor links of the hex grids
+    pub grid: bool,
+}
+
+// Coordinate label or grid line, child of the board
+#[derive(Debug, Copy, Clone, Component)]
+pub struct DebugOverlayPart;
+
+// Details of the hovered tile shown in the panel, recomputed when the tile or the game changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileDetails {
+    board: Entity,
+    coordinates: Coordinates,
+    // Moves and flags of the game, telling its changes
+    progress: (usize, usize),
+    description: String,
+    odds: String,
+}
+
+// Panel of the tile under the cursor, the safe tiles left and the solver odds, with the toggles
+// of the overlays
+pub fn debug_panel(
+    mut egui_contexts: EguiContexts,
+    cursor: WorldCursor,
+    boards: Query<&Board>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut details: Local<Option<TileDetails>>,
+) {
+    let hovered = cursor
+        .position()
+        .and_then(|position| boards.iter().find_map(|b| Some((b, b.tile_at(position)?))));
+    let previous = details.take();
+    *details = hovered.map(|(board, coordinates)| {
+        let progress = (board.game.history().len(), board.game.flags().len());
+        match previous {
+            Some(d)
+                if d.board == board.entity
+                    && d.coordinates == coordinates
+                    && d.progress == progress =>
+            {
+                d
+            }
+            _ => tile_details(board, coordinates, progress),
+        }
+    });
+
+    let ctx = match egui_contexts.ctx_mut() {
+        Ok(ctx) => ctx,
+        Err(_) => return,
+    };
+    let mut toggles = *overlay;
+    egui::Window::new("Board debug").show(ctx, |ui| {
+        ui.checkbox(&mut toggles.coordinates, "Coordinate labels");
+        ui.checkbox(&mut toggles.grid, "Grid lines");
+        ui.separator();
+        for board in boards.iter() {
+            let tile_map = board.tile_map();
+            let revealed = board.game.revealed_tiles().count();
+            let safe_left = tile_map.tile_count() - tile_map.bomb_count() as usize - revealed;
+            ui.label(format!(
+                "Board {:?}: {safe_left} safe tiles left",
+                board.entity
+            ));
+        }
+        ui.separator();
+        match details.as_ref() {
+            Some(d) => {
+                ui.label(format!("Tile {}: {}", d.coordinates, d.description));
+                ui.label(format!("Bomb odds: {}", d.odds));
+            }
+            None => {
+                ui.label("No tile under the cursor");
+            }
+        }
+    });
+    // only actual toggles rebuild the overlays
+    if toggles != *overlay {
+        *overlay = toggles;
+    }
+}
+
+fn tile_details(board: &Board, coordinates: Coordinates, progress: (usize, usize)) -> TileDetails {
+    let tile_map = board.tile_map();
+    let content = match (
+        tile_map.is_bomb_at(coordinates),
+        tile_map.bomb_count_at(coordinates),
+    ) {
+        (true, _) => "bomb".to_string(),
+        (false, 0) => "empty".to_string(),
+        (false, count) => count.to_string(),
+    };
+    let state = match (
+        board.game.is_covered(coordinates),
+        board.game.is_flagged(coordinates),
+    ) {
+        (false, _) => "revealed",
+        (true, true) => "flagged",
+        (true, false) => "covered",
+    };
+
+    // from what the player sees, the endgame enumeration goes past the deductions
+    let solver = board.game.solver();
+    let deduction = solver.deductions().into_iter().find(|d| match d {
+        Deduction::Safe(c) | Deduction::Bomb(c) => *c == coordinates,
+    });
+    let odds = match deduction {
+        _ if !board.game.is_covered(coordinates) => "-".to_string(),
+        Some(Deduction::Safe(_)) => "0% (deduced safe)".to_string(),
+        Some(Deduction::Bomb(_)) => "100% (deduced bomb)".to_string(),
+        None => solver
+            .endgame_odds()
+            .and_then(|odds| odds.into_iter().find(|(c, _)| *c == coordinates))
+            .map_or("unknown, too many tiles left".to_string(), |(_, p)| {
+                format!("{:.0}%", p * 100.)
+            }),
+    };
+
+    TileDetails {
+        board: board.entity,
+        coordinates,
+        progress,
+        description: format!("{content}, {state}"),
+        odds,
+    }
+}
+
+// Rebuilds the overlays of every board when they are toggled or a board is spawned
+pub fn debug_overlays(
+    mut cmds: Commands,
+    overlay: Res<DebugOverlay>,
+    board_assets: Res<BoardAssets>,
+    boards: Query<&Board>,
+    added: Query<(), Added<Board>>,
+    parts: Query<Entity, With<DebugOverlayPart>>,
+) {
+    if !overlay.is_changed() && added.iter().next().is_none() {
+        return;
+    }
+    for entity in parts.iter() {
+        cmds.entity(entity).despawn();
+    }
+    for board in boards.iter() {
+        cmds.entity(board.entity).with_children(|parent| {
+            if overlay.coordinates {
+                spawn_coordinate_labels(parent, board, &board_assets);
+            }
+            if overlay.grid {
+                spawn_grid_lines(parent, board);
+            }
+        });
+    }
+}
+
+fn spawn_coordinate_labels(
+    parent: &mut ChildSpawnerCommands,
+    board: &Board,
+    board_assets: &BoardAssets,
+) {
+    let tile_map = board.tile_map();
+    let grid = tile_map.grid().grid();
+    let size = board.tile_size;
+    let label = |value: u16, position: Vec2| {
+        (
+            Text2d::new(value.to_string()),
+            TextFont {
+                font: board_assets.bomb_counter_font.clone(),
+                font_size: size / 3.,
+                ..Default::default()
+            },
+            TextColor(Color::srgb(1., 1., 0.)),
+            TextLayout::new_with_justify(Justify::Center),
+            Transform::from_translation(position.extend(8.)),
+        )
+    };
+    for x in 0..tile_map.width() {
+        let center = grid.tile_center(Coordinates { x, y: 0 }, size);
+        parent
+            .spawn(label(x, center - Vec2::new(0., size * 0.75)))
+            .insert(Name::new("Column Label"))
+            .insert(DebugOverlayPart);
+    }
+    for y in 0..tile_map.height() {
+        let center = grid.tile_center(Coordinates { x: 0, y }, size);
+        parent
+            .spawn(label(y, center - Vec2::new(size * 0.75, 0.)))
+            .insert(Name::new("Row Label"))
+            .insert(DebugOverlayPart);
+    }
+}
+
+fn spawn_grid_lines(parent: &mut ChildSpawnerCommands, board: &Board) {
+    let tile_map = board.tile_map();
+    let grid = tile_map.grid().grid();
+    let size = board.tile_size;
+    let lines: Vec<(Vec2, Vec2)> = match tile_map.grid() {
+        GridKind::Square => {
+            let board_size = grid.board_size(tile_map.width(), tile_map.height(), size);
+            let columns = (0..=tile_map.width()).map(|x| {
+                let x = x as f32 * size;
+                (Vec2::new(x, 0.), Vec2::new(x, board_size.y))
+            });
+            let rows = (0..=tile_map.height()).map(|y| {
+                let y = y as f32 * size;
+                (Vec2::new(0., y), Vec2::new(board_size.x, y))
+            });
+            columns.chain(rows).collect()
+        }
+        // hexagon borders don't line up, their neighbors are linked instead
+        GridKind::Hex => tile_map
+            .tiles()
+            .flat_map(|(coords, _)| {
+                tile_map
+                    .neighbors(coords)
+                    .filter(move |n| (n.y, n.x) > (coords.y, coords.x))
+                    .map(move |n| (coords, n))
+            })
+            .map(|(a, b)| (grid.tile_center(a, size), grid.tile_center(b, size)))
+            .collect(),
+    };
+
+    for (start, end) in lines {
+        let delta = end - start;
+        parent
+            .spawn((
+                Sprite {
+                    color: Color::srgba(0., 1., 1., 0.6),
+                    custom_size: Some(Vec2::new(delta.length(), 1.)),
+                    ..Default::default()
+                },
+                Transform {
+                    translation: ((start + end) / 2.).extend(8.),
+                    rotation: Quat::from_rotation_z(delta.y.atan2(delta.x)),
+                    ..Default::default()
+                },
+            ))
+            .insert(Name::new("Grid Line"))
+            .insert(DebugOverlayPart);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::events::BoardDiffEvent;
+use crate::resources::{Board, BoardSilhouette};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+// Sends the visible changes of every board since its last sent diff
+pub fn send_board_diffs(
+    boards: Query<&Board, Changed<Board>>,
+    mut removed: RemovedComponents<Board>,
+    mut sent: Local<HashMap<Entity, BoardSilhouette>>,
+    mut board_diff_ewr: MessageWriter<BoardDiffEvent>,
+) {
+    for entity in removed.read() {
+        sent.remove(&entity);
+    }
+    for board in boards.iter() {
+        let silhouette = BoardSilhouette::from_board(board);
+        let last = sent.entry(board.entity).or_default();
+        let diff = last.diff(&silhouette);
+        // a new board is sent even when fully covered
+        if !diff.is_empty() || (last.width(), last.height()) != diff.size {
+            board_diff_ewr.write(BoardDiffEvent {
+                board: board.entity,
+                diff,
+            });
+        }
+        *last = silhouette;
+    }
+}
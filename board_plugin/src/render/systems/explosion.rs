@@ -0,0 +1,207 @@
+use crate::components::Coordinates;
+use crate::events::{BombExplosionEvent, LifeLostEvent};
+use crate::grid::GridLayout;
+use crate::render::engine::Is2dCamera;
+use crate::resources::Board;
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+use std::f32::consts::TAU;
+
+// Duration of the explosion effects, in seconds
+pub const EXPLOSION_DURATION: f32 = 0.8;
+
+const PARTICLE_COLORS: [Color; 4] = [
+    Color::srgb(1., 0.65, 0.),
+    Color::srgb(1., 1., 0.),
+    Color::srgb(1., 0., 0.),
+    Color::srgb(0.25, 0.25, 0.25),
+];
+
+// Particle of an explosion burst. Particles aren't part of the board, so they play out
+// after the board is despawned
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+}
+
+// Bomb of a chain reaction waiting for its turn to blow up. Like the particles, detonations
+// aren't part of the board
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Detonation {
+    board: Entity,
+    coordinates: Coordinates,
+    delay: f32,
+}
+
+// Camera shake of the explosions. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct ScreenShake {
+    amplitude: f32,
+    remaining: f32,
+    // Camera offset of the last frame
+    offset: Vec3,
+}
+
+impl ScreenShake {
+    // Camera offset of the shake this frame, for the host app systems moving the camera
+    pub fn offset(&self) -> Vec3 {
+        self.offset
+    }
+}
+
+// Bursts particles out of the uncovered bombs and shakes the camera, the bombs costing a life
+// included
+pub fn explode(
+    mut cmds: Commands,
+    boards: Query<&Board>,
+    mut shake: ResMut<ScreenShake>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut life_lost_evr: MessageReader<LifeLostEvent>,
+) {
+    for event in bomb_explosion_evr.read() {
+        let board = match boards.get(event.board) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        // the bombs uncovered by the last move
+        let tile_map = board.tile_map();
+        let bombs: Vec<Coordinates> = board
+            .game
+            .history()
+            .last()
+            .into_iter()
+            .flat_map(|delta| delta.revealed.iter().copied())
+            .filter(|c| tile_map.is_bomb_at(*c))
+            .collect();
+        burst(&mut cmds, board, &bombs, &mut shake);
+        queue_chain(&mut cmds, board, &board.chain);
+    }
+    // the move was taken back, the bombs come with the event
+    for event in life_lost_evr.read() {
+        if let Ok(board) = boards.get(event.board) {
+            burst(&mut cmds, board, &event.bombs, &mut shake);
+            queue_chain(&mut cmds, board, &event.chain);
+        }
+    }
+}
+
+// Queues the bursts of the chained bombs, each one step after the other
+fn queue_chain(cmds: &mut Commands, board: &Board, chain: &[(Coordinates, u32)]) {
+    for (coordinates, step) in chain.iter() {
+        cmds.spawn_empty()
+            .insert(Name::new("Chain Detonation"))
+            .insert(Detonation {
+                board: board.entity,
+                coordinates: *coordinates,
+                delay: *step as f32 * board.options.animations.chain,
+            });
+    }
+}
+
+// Bursts the chained bombs once their turn comes, the detonations of a despawned board are
+// dropped
+pub fn detonate_chains(
+    mut cmds: Commands,
+    time: Res<Time>,
+    boards: Query<&Board>,
+    mut shake: ResMut<ScreenShake>,
+    mut detonations: Query<(Entity, &mut Detonation)>,
+) {
+    for (entity, mut detonation) in detonations.iter_mut() {
+        detonation.delay -= time.delta_secs();
+        if detonation.delay > 0. {
+            continue;
+        }
+        cmds.entity(entity).despawn();
+        if let Ok(board) = boards.get(detonation.board) {
+            burst(&mut cmds, board, &[detonation.coordinates], &mut shake);
+        }
+    }
+}
+
+fn burst(cmds: &mut Commands, board: &Board, bombs: &[Coordinates], shake: &mut ScreenShake) {
+    let effects = board.options.explosion;
+    if effects.shake > 0. {
+        shake.amplitude = shake.amplitude.max(effects.shake);
+        shake.remaining = EXPLOSION_DURATION;
+    }
+
+    let mut rng = thread_rng();
+    let grid = board.tile_map().grid().grid();
+    for coords in bombs {
+        let center = grid.tile_center(*coords, board.tile_size);
+        let position = (board.bounds.position + center).extend(board.options.z_offset + 50.);
+        for _ in 0..effects.particles {
+            let angle = rng.gen_range(0. ..TAU);
+            let speed = board.tile_size * rng.gen_range(2. ..8.);
+            let color = PARTICLE_COLORS[rng.gen_range(0..PARTICLE_COLORS.len())];
+            cmds.spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(board.tile_size / 5.)),
+                    ..Default::default()
+                },
+                Transform::from_translation(position),
+            ))
+            .insert(Name::new("Explosion Particle"))
+            .insert(board.options.render_layers())
+            .insert(Particle {
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                age: 0.,
+                lifetime: rng.gen_range(EXPLOSION_DURATION / 2. ..EXPLOSION_DURATION),
+            });
+        }
+    }
+}
+
+// Moves the particles, fading and shrinking them until their end
+pub fn update_particles(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            cmds.entity(entity).despawn();
+            continue;
+        }
+        // the burst slows down as it spreads
+        particle.velocity *= 1. - delta * 2.;
+        transform.translation += particle.velocity.extend(0.) * delta;
+        let left = 1. - particle.age / particle.lifetime;
+        sprite.color.set_alpha(left);
+        transform.scale = Vec3::splat(left);
+    }
+}
+
+// Shakes the 2d camera, less and less until the end of the shake
+pub fn shake_camera(
+    time: Res<Time>,
+    mut shake: ResMut<ScreenShake>,
+    mut cameras: Query<&mut Transform, Is2dCamera>,
+) {
+    if shake.remaining <= 0. && shake.offset == Vec3::ZERO {
+        return;
+    }
+    shake.remaining = (shake.remaining - time.delta_secs()).max(0.);
+    let offset = match shake.remaining > 0. {
+        true => {
+            let mut rng = thread_rng();
+            let direction = Vec2::new(rng.gen_range(-1. ..1.), rng.gen_range(-1. ..1.));
+            (direction * shake.amplitude * shake.remaining / EXPLOSION_DURATION).extend(0.)
+        }
+        false => {
+            shake.amplitude = 0.;
+            Vec3::ZERO
+        }
+    };
+
+    for mut transform in cameras.iter_mut() {
+        transform.translation += offset - shake.offset;
+    }
+    shake.offset = offset;
+}
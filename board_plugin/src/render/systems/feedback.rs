@@ -0,0 +1,115 @@
+use crate::components::Coordinates;
+use crate::grid::GridLayout;
+use crate::render::systems::WorldCursor;
+use crate::render::{material_covers, tile_sprite};
+use crate::resources::{Board, BoardAssets, BoardPreview, InputMap, ReplayPlayback};
+use bevy::prelude::*;
+use std::iter;
+
+// Hover highlight or pressed look of a tile, child of the board
+#[derive(Debug, Copy, Clone, Component)]
+pub struct TileFeedback;
+
+// Tiles showing a feedback: the board, its hovered tile and its pressed tiles
+type Feedback = (Entity, Coordinates, Vec<Coordinates>);
+
+// Highlights the tile under the cursor, and shows the tiles held down as pressed: the hovered
+// tile with the uncover binding, along with its neighbors for the chord gesture, the chord
+// binding or both uncover and flag bindings. The feedback follows the cursor and goes away when
+// the bindings are released
+#[allow(clippy::too_many_arguments)]
+pub fn tile_feedback(
+    mut cmds: Commands,
+    cursor: WorldCursor,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    board_assets: Res<BoardAssets>,
+    playback: Option<Res<ReplayPlayback>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    feedbacks: Query<Entity, With<TileFeedback>>,
+    mut shown: Local<Option<Feedback>>,
+) {
+    // the player doesn't control replays
+    let hovered = cursor.position().filter(|_| playback.is_none());
+    let feedback = hovered.and_then(|position| {
+        boards
+            .iter()
+            .filter(|b| b.options.controls.mouse() && !b.game.is_exploded())
+            .find_map(|b| Some((b, b.tile_at(position)?)))
+    });
+    let feedback = feedback.map(|(board, coordinates)| {
+        let uncover = input_map.uncover.pressed(&mouse, &keys);
+        let chord = input_map.chord.pressed(&mouse, &keys)
+            || (uncover && input_map.flag.pressed(&mouse, &keys));
+        let held: Vec<Coordinates> = match (chord, uncover) {
+            (true, _) => iter::once(coordinates)
+                .chain(board.tile_map().neighbors(coordinates))
+                .collect(),
+            (false, true) => vec![coordinates],
+            (false, false) => Vec::new(),
+        };
+        // only the covered tiles can be pressed, flags hold them up
+        let pressed = held
+            .into_iter()
+            .filter(|c| board.game.is_covered(*c) && !board.game.is_flagged(*c))
+            .collect();
+        (board.entity, coordinates, pressed)
+    });
+    if feedback == *shown && !board_assets.is_changed() {
+        return;
+    }
+
+    for entity in feedbacks.iter() {
+        cmds.entity(entity).despawn();
+    }
+    if let Some((entity, hovered, pressed)) = &feedback {
+        if let Ok(board) = boards.get(*entity) {
+            spawn_feedback(&mut cmds, board, *hovered, pressed, &board_assets);
+        }
+    }
+    *shown = feedback;
+}
+
+fn spawn_feedback(
+    cmds: &mut Commands,
+    board: &Board,
+    hovered: Coordinates,
+    pressed: &[Coordinates],
+    board_assets: &BoardAssets,
+) {
+    let grid = board.tile_map().grid();
+    let size = board.tile_size - board.options.tile_padding;
+    let position = |coords: Coordinates, z: f32| {
+        let center = grid.grid().tile_center(coords, board.tile_size);
+        Transform::from_translation(center.extend(z))
+    };
+    cmds.entity(board.entity).with_children(|parent| {
+        // pressed tiles look uncovered and empty, above the covers and below the hints
+        for coords in pressed.iter() {
+            let mut cmd = match board_assets.sheet_for(grid) {
+                Some(sheet) => {
+                    parent.spawn((sheet.sprite(sheet.empty, size), position(*coords, 4.)))
+                }
+                None => parent.spawn((
+                    tile_sprite(grid, board_assets.tile_colors.revealed, size, board_assets),
+                    position(*coords, 4.),
+                )),
+            };
+            cmd.insert(Name::new("Pressed Tile")).insert(TileFeedback);
+        }
+
+        // the tile material lights the hovered covers itself
+        let glowing = material_covers(grid, board_assets) && board.game.is_covered(hovered);
+        if !board_assets.hover_highlight || glowing {
+            return;
+        }
+        parent
+            .spawn((
+                tile_sprite(grid, Color::srgba(1., 1., 1., 0.2), size, board_assets),
+                position(hovered, 6.),
+            ))
+            .insert(Name::new("Hovered Tile"))
+            .insert(TileFeedback);
+    });
+}
@@ -0,0 +1,64 @@
+use crate::components::{GeneratingBoard, GenerationSpinner};
+use crate::generation::BoardGenerators;
+use crate::render::engine::PrimaryWindowQuery;
+use crate::render::new_board;
+use crate::resources::{BoardAssets, ReplayPlayback};
+use bevy::prelude::*;
+use futures_lite::future;
+use std::f32::consts::TAU;
+
+// Turns of the spinners per second
+const SPIN_SPEED: f32 = 0.75;
+
+// Spawns the boards generated in the background in place of their spinners, once every one of
+// them is generated. The boards of a versus game start together, whichever task ended first
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_generated_boards(
+    mut cmds: Commands,
+    board_assets: Res<BoardAssets>,
+    generators: Res<BoardGenerators>,
+    windows: PrimaryWindowQuery,
+    playback: Option<Res<ReplayPlayback>>,
+    mut generating: Query<(Entity, &mut GeneratingBoard)>,
+) {
+    let win = match windows.single() {
+        Ok(w) => w,
+        Err(_) => return,
+    };
+    for (_, mut generating) in generating.iter_mut() {
+        if generating.done.is_none() {
+            generating.done = future::block_on(future::poll_once(&mut generating.task));
+        }
+    }
+    if generating.iter().any(|(_, g)| g.done.is_none()) {
+        return;
+    }
+
+    for (entity, mut generating) in generating.iter_mut() {
+        let pregenerated = match generating.done.take() {
+            Some(p) => p,
+            None => continue,
+        };
+        info!("Background generation over");
+        cmds.entity(entity).despawn();
+        new_board(
+            &mut cmds,
+            pregenerated.options.clone(),
+            &board_assets,
+            &generators,
+            Some(pregenerated),
+            win,
+            playback.is_some(),
+        );
+    }
+}
+
+// Turns the spinners of the boards being generated
+pub fn spin_spinners(
+    time: Res<Time>,
+    mut spinners: Query<&mut Transform, With<GenerationSpinner>>,
+) {
+    for mut transform in spinners.iter_mut() {
+        transform.rotate(Quat::from_rotation_z(-TAU * SPIN_SPEED * time.delta_secs()));
+    }
+}
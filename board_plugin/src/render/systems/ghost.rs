@@ -0,0 +1,88 @@
+use crate::components::Coordinates;
+use crate::render::tile_sprite;
+use crate::resources::{Board, BoardAssets, BoardPreview, GameTimer, Ghost};
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use minesweeper_core::{Game, GameState};
+
+// Translucent tile over a cover, uncovered by the ghost run at this time of the game
+#[derive(Debug, Copy, Clone, Component)]
+pub struct GhostTile(pub Coordinates);
+
+// Plays the ghost run on its own game as the play time of the board goes, until the board is
+// over. Both runs are timed on the `GameTimer`, so the pauses don't set them apart
+pub fn race_ghost(
+    timer: Res<GameTimer>,
+    ghost: Option<ResMut<Ghost>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+) {
+    let mut ghost = match ghost {
+        Some(g) => g,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) if b.game.state() == GameState::Playing => b,
+        _ => return,
+    };
+    let ghost = &mut *ghost;
+    let game = ghost.game.get_or_insert_with(|| {
+        let mut game = Game::new(board.tile_map().clone())
+            .with_strict_flags(board.options.strict_flags)
+            .with_no_flags(board.options.no_flags);
+        game.open();
+        game
+    });
+    while let Some(event) = ghost.replay.events.get(ghost.next) {
+        if event.time > timer.elapsed() {
+            break;
+        }
+        game.apply(event.action());
+        ghost.next += 1;
+    }
+}
+
+// Shows the tiles uncovered by the ghost over the covers of the board, and takes them off once
+// the player uncovers them too
+pub fn show_ghost(
+    mut cmds: Commands,
+    ghost: Option<Res<Ghost>>,
+    boards: Query<&Board>,
+    board_assets: Res<BoardAssets>,
+    ghost_tiles: Query<(Entity, &GhostTile, &ChildOf)>,
+) {
+    let game = match ghost.as_ref().and_then(|g| g.game.as_ref()) {
+        Some(g) => g,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    let mut shown: HashSet<Coordinates> = HashSet::default();
+    for (entity, GhostTile(coords), parent) in ghost_tiles.iter() {
+        // undone by the ghost, or the cover was replaced
+        if game.is_revealed(*coords) && board.covered_tiles.get(coords) == Some(&parent.parent()) {
+            shown.insert(*coords);
+        } else {
+            cmds.entity(entity).despawn();
+        }
+    }
+
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    let color = Color::srgba(1., 1., 1., 0.3);
+    for (coords, cover) in board.covered_tiles.iter() {
+        if shown.contains(coords) || !game.is_revealed(*coords) {
+            continue;
+        }
+        cmds.entity(*cover).with_children(|parent| {
+            parent
+                .spawn((
+                    tile_sprite(grid, color, size, &board_assets), // under the hints
+                    Transform::from_xyz(0., 0., 1.5),
+                ))
+                .insert(Name::new("Ghost"))
+                .insert(GhostTile(*coords));
+        });
+    }
+}
@@ -0,0 +1,85 @@
+use crate::events::{Action, BoardActionEvent, GuessOddsEvent};
+use crate::render::tile_sprite;
+use crate::resources::{Board, BoardAssets};
+use bevy::prelude::*;
+
+// Odds label or selection highlight of a tile left to guess from, child of its cover.
+// Holds the board entity
+#[derive(Debug, Copy, Clone, Component)]
+pub struct GuessOdds(pub Entity);
+
+// Shows the bomb odds of the tiles left to guess from, until a tile of the board is uncovered
+pub fn show_guess_odds(
+    mut cmds: Commands,
+    boards: Query<&Board>,
+    board_assets: Res<BoardAssets>,
+    mut guess_odds_evr: MessageReader<GuessOddsEvent>,
+    mut board_action_evr: MessageReader<BoardActionEvent>,
+    labels: Query<(Entity, &GuessOdds)>,
+) {
+    let triggered: Vec<Entity> = board_action_evr
+        .read()
+        .filter(|e| matches!(e.action, Action::Uncover(_) | Action::Chord(_)))
+        .map(|e| e.board)
+        .collect();
+    let guesses: Vec<&GuessOddsEvent> = guess_odds_evr.read().collect();
+    for (entity, label) in labels.iter() {
+        if triggered.contains(&label.0) || guesses.iter().any(|e| e.board == label.0) {
+            cmds.entity(entity).despawn();
+        }
+    }
+
+    for (i, event) in guesses.iter().enumerate() {
+        // only the last guess of a board is shown, until one of its tiles is uncovered
+        if triggered.contains(&event.board)
+            || guesses[i + 1..].iter().any(|e| e.board == event.board)
+        {
+            continue;
+        }
+        if let Ok(board) = boards.get(event.board) {
+            label_guess_odds(&mut cmds, board, event, &board_assets);
+        }
+    }
+}
+
+fn label_guess_odds(
+    cmds: &mut Commands,
+    board: &Board,
+    event: &GuessOddsEvent,
+    board_assets: &BoardAssets,
+) {
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    for (coords, odds) in event.odds.iter() {
+        let cover = match board.covered_tiles.get(coords) {
+            Some(e) => *e,
+            None => continue,
+        };
+        cmds.entity(cover).with_children(|parent| {
+            parent
+                .spawn((
+                    Text2d::new(format!("{:.0}%", odds * 100.)),
+                    TextFont {
+                        font: board_assets.bomb_counter_font.clone(),
+                        font_size: size / 3.,
+                        ..Default::default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    Transform::from_xyz(0., 0., 3.),
+                ))
+                .insert(Name::new("Guess Odds"))
+                .insert(GuessOdds(board.entity));
+
+            if *coords == event.selected {
+                parent
+                    .spawn((
+                        tile_sprite(grid, Color::srgba(1., 1., 0., 0.5), size, board_assets),
+                        Transform::from_xyz(0., 0., 2.),
+                    ))
+                    .insert(Name::new("Guess Selection"))
+                    .insert(GuessOdds(board.entity));
+            }
+        });
+    }
+}
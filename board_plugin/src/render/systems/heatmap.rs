@@ -0,0 +1,152 @@
+use crate::components::Coordinates;
+use crate::render::systems::{focused_board, WorldCursor};
+use crate::render::tile_sprite;
+use crate::resources::{
+    Board, BoardAssets, BoardPreview, BoardSilhouette, InputMap, ReplayPlayback, TileState,
+};
+use crate::solver::BombOdds;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+// Distinct tints of the heatmap, the odds are rounded to the closest one
+const HEAT_STEPS: f32 = 20.;
+
+// Bomb odds overlay of a board, tinting its covered tiles from safe green to bomb red.
+// Keeps the silhouette the odds were last updated from, and the tints of the covered tiles
+#[derive(Component)]
+pub struct Heatmap {
+    odds: BombOdds,
+    silhouette: BoardSilhouette,
+    tints: HashMap<Coordinates, Entity>,
+    // Heat step shown by the tiles away from the numbers
+    interior: Option<u8>,
+}
+
+// Tint of a covered tile, child of its cover. Holds the board entity
+#[derive(Debug, Copy, Clone, Component)]
+pub struct HeatTint(pub Entity);
+
+// Odds rounded to a heat step
+fn heat_step(odds: f32) -> u8 {
+    (odds.clamp(0., 1.) * HEAT_STEPS).round() as u8
+}
+
+fn heat_color(step: u8) -> Color {
+    let heat = step as f32 / HEAT_STEPS;
+    Color::srgba(heat, 1. - heat, 0., 0.45)
+}
+
+// Shows or hides the heatmap of the focused board when the heatmap binding is pressed
+#[allow(clippy::too_many_arguments)]
+pub fn heatmap_input(
+    mut cmds: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    cursor: WorldCursor,
+    playback: Option<Res<ReplayPlayback>>,
+    mut boards: Query<(&mut Board, Option<&Heatmap>), Without<BoardPreview>>,
+    tints: Query<&HeatTint>,
+) {
+    if playback.is_some() || !input_map.heatmap.just_pressed(&mouse, &keys) {
+        return;
+    }
+    let (mut board, heatmap) = match focused_board(&cursor, boards.iter().map(|(b, _)| b))
+        .and_then(|entity| boards.get_mut(entity).ok())
+    {
+        Some(b) => b,
+        None => return,
+    };
+    if board.options.competitive {
+        info!("No odds heatmap in competitive games");
+        return;
+    }
+
+    match heatmap {
+        Some(heatmap) => {
+            // the tints of the uncovered tiles went with their covers
+            for tint in heatmap.tints.values() {
+                if tints.get(*tint).is_ok_and(|t| t.0 == board.entity) {
+                    cmds.entity(*tint).despawn();
+                }
+            }
+            cmds.entity(board.entity).remove::<Heatmap>();
+        }
+        None => {
+            info!("Showing the bomb odds");
+            board.assists.heatmaps += 1;
+            let solver = board.game.solver();
+            cmds.entity(board.entity).insert(Heatmap {
+                odds: BombOdds::new(&solver),
+                silhouette: BoardSilhouette::from_board(&board),
+                tints: HashMap::default(),
+                interior: None,
+            });
+        }
+    }
+}
+
+// Follows the uncovered and covered again tiles, updating the odds around them only and the
+// tints whose heat step changed
+pub fn update_heatmap(
+    mut cmds: Commands,
+    board_assets: Res<BoardAssets>,
+    mut boards: Query<(&Board, &mut Heatmap), Or<(Changed<Board>, Added<Heatmap>)>>,
+    mut sprites: Query<&mut Sprite, With<HeatTint>>,
+) {
+    for (board, mut heatmap) in boards.iter_mut() {
+        let heatmap = &mut *heatmap;
+        let silhouette = BoardSilhouette::from_board(board);
+        // the solver ignores the flags
+        let changed: Vec<Coordinates> = heatmap
+            .silhouette
+            .diff(&silhouette)
+            .changes
+            .into_iter()
+            .filter(|(c, state)| {
+                let revealed = heatmap.silhouette.get(*c) == Some(TileState::Revealed);
+                revealed != (*state == TileState::Revealed)
+            })
+            .map(|(c, _)| c)
+            .collect();
+        heatmap.silhouette = silhouette;
+        for coords in changed.iter() {
+            heatmap.tints.remove(coords);
+        }
+
+        let solver = board.game.solver();
+        let updated = heatmap.odds.update(&solver, changed);
+        let interior = heat_step(heatmap.odds.interior());
+        let tiles: Vec<Coordinates> = match heatmap.interior == Some(interior) {
+            true => updated,
+            false => board.covered_tiles.keys().copied().collect(),
+        };
+        heatmap.interior = Some(interior);
+
+        let size = board.tile_size - board.options.tile_padding;
+        let grid = board.tile_map().grid();
+        for coords in tiles {
+            let cover = match board.covered_tiles.get(&coords) {
+                Some(c) => *c,
+                None => continue,
+            };
+            let color = heat_color(heat_step(heatmap.odds.get(coords)));
+            let tint = heatmap.tints.get(&coords).copied();
+            if let Some(mut sprite) = tint.and_then(|t| sprites.get_mut(t).ok()) {
+                sprite.color = color;
+                continue;
+            }
+            cmds.entity(cover).with_children(|parent| {
+                let tint = parent
+                    .spawn((
+                        tile_sprite(grid, color, size, &board_assets),
+                        Transform::from_xyz(0., 0., 2.),
+                    ))
+                    .insert(Name::new("Heat tint"))
+                    .insert(HeatTint(board.entity))
+                    .id();
+                heatmap.tints.insert(coords, tint);
+            });
+        }
+    }
+}
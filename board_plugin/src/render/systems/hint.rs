@@ -0,0 +1,113 @@
+use crate::events::HintEvent;
+use crate::render::systems::{focused_board, WorldCursor};
+use crate::render::tile_sprite;
+use crate::resources::{Board, BoardAssets, BoardPreview, InputMap, PointerMode, ReplayPlayback};
+use crate::solver::Deduction;
+use bevy::prelude::*;
+
+// Highlight of the hinted tile, child of its cover. Holds the board entity
+#[derive(Debug, Copy, Clone, Component)]
+pub struct HintHighlight(pub Entity);
+
+// Asks the solver for a hint on the focused board when the hint binding is pressed. Pressed off
+// the boards, it arms the hint for the board clicked next with the uncover binding
+#[allow(clippy::too_many_arguments)]
+pub fn hint_input(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    cursor: WorldCursor,
+    playback: Option<Res<ReplayPlayback>>,
+    mut mode: ResMut<PointerMode>,
+    mut boards: Query<&mut Board, Without<BoardPreview>>,
+    mut hint_ewr: MessageWriter<HintEvent>,
+) {
+    if playback.is_some() {
+        return;
+    }
+    let focused = if mode.hint_armed && input_map.uncover.just_released(&mouse, &keys) {
+        mode.hint_armed = false;
+        let position = cursor.position();
+        boards
+            .iter()
+            .find(|b| position.is_some_and(|p| b.contains(p)))
+            .map(|b| b.entity)
+    } else if input_map.hint.just_pressed(&mouse, &keys) {
+        let focused = focused_board(&cursor, boards.iter());
+        if focused.is_none() {
+            info!("Hint armed, click a board to get it");
+            mode.hint_armed = true;
+        }
+        focused
+    } else {
+        return;
+    };
+    let mut board = match focused.and_then(|entity| boards.get_mut(entity).ok()) {
+        Some(b) => b,
+        None => return,
+    };
+    if board.options.competitive {
+        info!("No hints in competitive games");
+        return;
+    }
+
+    let hint = board.game.solver().hint(board.game.flags());
+    match hint {
+        None => info!("No tile can be deduced, a guess is required"),
+        Some(deduction) => {
+            info!("Hint: {deduction:?}");
+            board.assists.hints += 1;
+            hint_ewr.write(HintEvent {
+                board: board.entity,
+                deduction,
+            });
+        }
+    }
+}
+
+// Highlights the hinted tile, green when safe and red when it should be flagged
+pub fn show_hint(
+    mut cmds: Commands,
+    boards: Query<&Board>,
+    board_assets: Res<BoardAssets>,
+    mut hint_evr: MessageReader<HintEvent>,
+    highlights: Query<(Entity, &HintHighlight)>,
+) {
+    for event in hint_evr.read() {
+        for (entity, highlight) in highlights.iter() {
+            if highlight.0 == event.board {
+                cmds.entity(entity).despawn();
+            }
+        }
+        if let Ok(board) = boards.get(event.board) {
+            highlight_tile(&mut cmds, board, event.deduction, &board_assets);
+        }
+    }
+}
+
+fn highlight_tile(
+    cmds: &mut Commands,
+    board: &Board,
+    deduction: Deduction,
+    board_assets: &BoardAssets,
+) {
+    let (coords, color) = match deduction {
+        Deduction::Safe(c) => (c, Color::srgba(0., 1., 0., 0.5)),
+        Deduction::Bomb(c) => (c, Color::srgba(1., 0., 0., 0.5)),
+    };
+    let cover = match board.covered_tiles.get(&coords) {
+        Some(e) => *e,
+        None => return,
+    };
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    cmds.entity(cover).with_children(|parent| {
+        parent
+            .spawn((
+                tile_sprite(grid, color, size, board_assets),
+                Transform::from_xyz(0., 0., 2.),
+            ))
+            .insert(Name::new("Hint"))
+            .insert(HintHighlight(board.entity));
+    });
+}
@@ -0,0 +1,153 @@
+use crate::components::{Coordinates, PlayerId};
+use crate::events::{Action, BoardActionEvent, BoardCommand, GuessOddsEvent};
+use crate::render::systems::long_press::LongPress;
+use crate::render::systems::{is_allowed, WorldCursor};
+use crate::resources::{Board, BoardPreview, InputGate, InputMap, PointerMode, ReplayPlayback};
+use bevy::prelude::*;
+
+// Tile left to guess from, when it needs a second click to be uncovered
+fn guess_odds(board: &Board, coordinates: Coordinates) -> Option<Vec<(Coordinates, f32)>> {
+    if board.game.is_flagged(coordinates) {
+        return None;
+    }
+    board
+        .game
+        .solver()
+        .forced_guess()
+        .filter(|odds| odds.iter().any(|(c, _)| *c == coordinates))
+}
+
+// Translates the uncover, flag and chord bindings into actions on the mouse controlled board
+// under the cursor, the mouse belonging to player one. Tiles are uncovered when the binding is
+// released, over the board it was pressed on, or flagged in flag mode. Numbers are chorded when
+// the chord binding is released, or when one of the uncover and flag bindings is released after
+// pressing both. The clicks of an armed hint are left to the hints
+#[allow(clippy::too_many_arguments)]
+pub fn input_handling(
+    cursor: WorldCursor,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mode: Res<PointerMode>,
+    long_press: Res<LongPress>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    playback: Option<Res<ReplayPlayback>>,
+    gate: Option<Res<InputGate>>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+    mut guess_odds_ewr: MessageWriter<GuessOddsEvent>,
+    mut pending_guess: Local<Option<(Entity, Coordinates)>>,
+    mut pressed_board: Local<Option<Entity>>,
+    mut chording: Local<bool>,
+) {
+    // the player doesn't control replays
+    if playback.is_some() || mode.hint_armed {
+        *pressed_board = None;
+        return;
+    }
+    let position = match cursor.position() {
+        Some(p) => p,
+        None => return,
+    };
+    // previewed boards aren't playable yet and an exploded board only accepts undos
+    let hovered = boards
+        .iter()
+        .filter(|b| b.options.controls.mouse() && !b.game.is_exploded())
+        .find_map(|b| Some((b, b.tile_at(position)?)));
+    // moves the input gate refuses are dropped
+    let allowed = |command: BoardCommand| is_allowed(&gate, command);
+
+    let (uncover, flag) = (input_map.uncover, input_map.flag);
+    // the buttons are pressed in any order, the second one turning the press into a chord
+    let both_pressed = (uncover.just_pressed(&mouse, &keys) && flag.pressed(&mouse, &keys))
+        || (flag.just_pressed(&mouse, &keys) && uncover.pressed(&mouse, &keys));
+    if both_pressed {
+        *chording = true;
+        *pressed_board = None;
+    }
+    let chord_released = input_map.chord.just_released(&mouse, &keys)
+        || (*chording
+            && (uncover.just_released(&mouse, &keys) || flag.just_released(&mouse, &keys)));
+    if chord_released {
+        *chording = false;
+        let chorded = hovered.filter(|(b, c)| {
+            allowed(BoardCommand::ChordTile {
+                board: b.entity,
+                coordinates: *c,
+            })
+        });
+        if let Some((board, coordinates)) = chorded {
+            info!("Trying to chord tile on {coordinates}");
+            board_action_ewr.write(BoardActionEvent {
+                board: board.entity,
+                action: Action::Chord(coordinates),
+                player: PlayerId::One,
+            });
+        }
+    }
+
+    if uncover.just_pressed(&mouse, &keys) && !*chording {
+        *pressed_board = hovered.map(|(b, _)| b.entity);
+    }
+    if uncover.just_released(&mouse, &keys) {
+        // releasing out of the pressed board cancels the press, as well as flagging on a hold
+        let pressed = pressed_board.take().filter(|_| !long_press.flagged());
+        let command = |board, coordinates| match mode.flag {
+            true => BoardCommand::ToggleFlag { board, coordinates },
+            false => BoardCommand::UncoverTile { board, coordinates },
+        };
+        let uncovered =
+            hovered.filter(|(b, c)| Some(b.entity) == pressed && allowed(command(b.entity, *c)));
+        if let Some((board, coordinates)) = uncovered.filter(|_| mode.flag) {
+            info!("Trying to mark tile on {coordinates}");
+            board_action_ewr.write(BoardActionEvent {
+                board: board.entity,
+                action: Action::ToggleFlag(coordinates),
+                player: PlayerId::One,
+            });
+        } else if let Some((board, coordinates)) = uncovered {
+            let tile = (board.entity, coordinates);
+
+            // the last guess of the game is only uncovered by a second click, competitive games
+            // don't show its odds
+            let confirm = board.options.confirm_last_guess && !board.options.competitive;
+            let odds =
+                guess_odds(board, coordinates).filter(|_| confirm && *pending_guess != Some(tile));
+            match odds {
+                Some(odds) => {
+                    info!("Down to a guess, click {coordinates} again to uncover it");
+                    *pending_guess = Some(tile);
+                    guess_odds_ewr.write(GuessOddsEvent {
+                        board: board.entity,
+                        selected: coordinates,
+                        odds,
+                    });
+                }
+                None => {
+                    *pending_guess = None;
+                    info!("Trying to uncover tile on {coordinates}");
+                    board_action_ewr.write(BoardActionEvent {
+                        board: board.entity,
+                        action: Action::Uncover(coordinates),
+                        player: PlayerId::One,
+                    });
+                }
+            }
+        }
+    }
+    if flag.just_pressed(&mouse, &keys) && !both_pressed {
+        let flagged = hovered.filter(|(b, c)| {
+            allowed(BoardCommand::ToggleFlag {
+                board: b.entity,
+                coordinates: *c,
+            })
+        });
+        if let Some((board, coordinates)) = flagged {
+            info!("Trying to mark tile on {coordinates}");
+            board_action_ewr.write(BoardActionEvent {
+                board: board.entity,
+                action: Action::ToggleFlag(coordinates),
+                player: PlayerId::One,
+            });
+        }
+    }
+}
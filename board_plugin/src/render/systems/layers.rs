@@ -0,0 +1,40 @@
+use crate::resources::Board;
+use bevy::prelude::*;
+
+// Puts the entities of the boards on the render layer of their board. Render layers aren't
+// inherited: the hierarchy is walked once when the board is added, then only the entities
+// parented since the last frame are looked at
+pub fn apply_render_layers(
+    mut cmds: Commands,
+    new_boards: Query<&Board, Added<Board>>,
+    boards: Query<&Board>,
+    children: Query<&Children>,
+    parents: Query<&ChildOf>,
+    new_children: Query<Entity, Added<ChildOf>>,
+) {
+    // entities without render layers are on the first one
+    if boards.iter().all(|b| b.options.render_layer == 0) {
+        return;
+    }
+    for board in new_boards.iter().filter(|b| b.options.render_layer != 0) {
+        let target = board.options.render_layers();
+        let mut stack = vec![board.entity];
+        while let Some(entity) = stack.pop() {
+            cmds.entity(entity).insert(target.clone());
+            if let Ok(children) = children.get(entity) {
+                stack.extend(children.iter());
+            }
+        }
+    }
+    for entity in new_children.iter() {
+        let mut root = entity;
+        while let Ok(parent) = parents.get(root) {
+            root = parent.parent();
+        }
+        if let Ok(board) = boards.get(root) {
+            if board.options.render_layer != 0 {
+                cmds.entity(entity).insert(board.options.render_layers());
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::components::{Coordinates, PlayerId};
+use crate::events::{BoardActionEvent, BoardCommand};
+use crate::grid::GridLayout;
+use crate::render::systems::{is_allowed, WorldCursor};
+use crate::resources::{Board, BoardPreview, InputGate, InputMap, ReplayPlayback};
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+// Hold duration flagging a touched tile when the long press mode is off, touches have no
+// other way to flag
+pub const DEFAULT_LONG_PRESS: f32 = 0.5;
+
+// Dots of the progress ring, lit clockwise from the top as the hold goes on
+const PROGRESS_DOTS: usize = 12;
+
+// Press held on a covered tile, by the uncover binding or a touch
+#[derive(Debug, Copy, Clone)]
+pub struct Hold {
+    pub board: Entity,
+    pub coordinates: Coordinates,
+    // Id of the holding touch, `None` for the uncover binding
+    pub touch: Option<u64>,
+    pub elapsed: f32,
+    pub duration: f32,
+    // The tile got flagged, releasing the press doesn't uncover it
+    pub flagged: bool,
+}
+
+impl Hold {
+    // Lit dots of the progress ring
+    fn progress(&self) -> usize {
+        ((self.elapsed / self.duration).min(1.) * PROGRESS_DOTS as f32) as usize
+    }
+}
+
+// Hold of the last press, kept after its release for the uncover of the input system.
+// Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct LongPress(pub Option<Hold>);
+
+impl LongPress {
+    // Did the held uncover binding place a flag instead?
+    pub fn flagged(&self) -> bool {
+        self.0.is_some_and(|h| h.flagged && h.touch.is_none())
+    }
+}
+
+// Dot of the progress ring of a hold, child of the board
+#[derive(Debug, Copy, Clone, Component)]
+pub struct HoldProgress;
+
+// Flags the covered tile held by the uncover binding, in long press mode, or by a touch once
+// held long enough. Moving off the tile cancels the hold and a touch tap uncovers the tile
+#[allow(clippy::too_many_arguments)]
+pub fn long_press_input(
+    time: Res<Time>,
+    cursor: WorldCursor,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    touches: Res<Touches>,
+    input_map: Res<InputMap>,
+    playback: Option<Res<ReplayPlayback>>,
+    gate: Option<Res<InputGate>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    mut long_press: ResMut<LongPress>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+) {
+    // the player doesn't control replays
+    if playback.is_some() {
+        return;
+    }
+    let tile_at = |position: Option<Vec2>| {
+        let position = position?;
+        boards
+            .iter()
+            .filter(|b| b.options.controls.mouse() && !b.game.is_exploded())
+            .find_map(|b| Some((b, b.tile_at(position)?)))
+    };
+    let hold_at = |position: Option<Vec2>, touch: Option<u64>, duration: f32| {
+        tile_at(position)
+            .filter(|(b, c)| b.game.is_covered(*c) && !b.game.is_flagged(*c))
+            .map(|(b, coordinates)| Hold {
+                board: b.entity,
+                coordinates,
+                touch,
+                elapsed: 0.,
+                duration,
+                flagged: false,
+            })
+    };
+
+    // a new press replaces the last hold
+    if input_map.uncover.just_pressed(&mouse, &keys) {
+        long_press.0 = input_map
+            .long_press
+            .and_then(|duration| hold_at(cursor.position(), None, duration));
+        return;
+    }
+    if let Some(touch) = touches.iter_just_pressed().next() {
+        let duration = input_map.long_press.unwrap_or(DEFAULT_LONG_PRESS);
+        long_press.0 = hold_at(
+            cursor.touch_position(touch.position()),
+            Some(touch.id()),
+            duration,
+        );
+        return;
+    }
+
+    let mut hold = match long_press.0 {
+        Some(h) => h,
+        None => return,
+    };
+    let on_tile = |position: Option<Vec2>| {
+        tile_at(position).is_some_and(|(b, c)| b.entity == hold.board && c == hold.coordinates)
+    };
+    let position = match hold.touch {
+        // released holds are left to the input system
+        None if !input_map.uncover.pressed(&mouse, &keys) => return,
+        None => cursor.position(),
+        Some(id) => {
+            if let Some(touch) = touches.iter_just_released().find(|t| t.id() == id) {
+                let uncover = BoardCommand::UncoverTile {
+                    board: hold.board,
+                    coordinates: hold.coordinates,
+                };
+                if !hold.flagged
+                    && on_tile(cursor.touch_position(touch.position()))
+                    && is_allowed(&gate, uncover)
+                {
+                    info!("Trying to uncover tile on {}", hold.coordinates);
+                    board_action_ewr.write(BoardActionEvent {
+                        board: hold.board,
+                        action: uncover.action(),
+                        player: PlayerId::One,
+                    });
+                }
+                long_press.0 = None;
+                return;
+            }
+            touches
+                .get_pressed(id)
+                .and_then(|t| cursor.touch_position(t.position()))
+        }
+    };
+    if hold.flagged {
+        return;
+    }
+    // the flag binding turns the press into a chord
+    if !on_tile(position) || input_map.flag.pressed(&mouse, &keys) {
+        long_press.0 = None;
+        return;
+    }
+
+    hold.elapsed += time.delta_secs();
+    let flag = BoardCommand::ToggleFlag {
+        board: hold.board,
+        coordinates: hold.coordinates,
+    };
+    // a refused flag leaves the release to uncover the tile
+    if hold.elapsed >= hold.duration && !is_allowed(&gate, flag) {
+        long_press.0 = None;
+        return;
+    }
+    if hold.elapsed >= hold.duration {
+        info!("Trying to mark tile on {}", hold.coordinates);
+        board_action_ewr.write(BoardActionEvent {
+            board: hold.board,
+            action: flag.action(),
+            player: PlayerId::One,
+        });
+        hold.flagged = true;
+    }
+    long_press.0 = Some(hold);
+}
+
+// Shows the progress of the hold as a ring of dots around the held tile
+pub fn show_long_press(
+    mut cmds: Commands,
+    long_press: Res<LongPress>,
+    boards: Query<&Board>,
+    dots: Query<Entity, With<HoldProgress>>,
+    mut shown: Local<Option<(Entity, Coordinates, usize)>>,
+) {
+    if !long_press.is_changed() {
+        return;
+    }
+    let progress = long_press
+        .0
+        .filter(|h| !h.flagged && h.progress() > 0)
+        .map(|h| (h.board, h.coordinates, h.progress()));
+    if progress == *shown {
+        return;
+    }
+
+    for entity in dots.iter() {
+        cmds.entity(entity).despawn();
+    }
+    if let Some((entity, coords, lit)) = progress {
+        if let Ok(board) = boards.get(entity) {
+            spawn_progress(&mut cmds, board, coords, lit);
+        }
+    }
+    *shown = progress;
+}
+
+fn spawn_progress(cmds: &mut Commands, board: &Board, coords: Coordinates, lit: usize) {
+    let center = board
+        .tile_map()
+        .grid()
+        .grid()
+        .tile_center(coords, board.tile_size);
+    let size = board.tile_size - board.options.tile_padding;
+    let radius = size * 0.35;
+    cmds.entity(board.entity).with_children(|parent| {
+        for i in 0..lit {
+            let angle = FRAC_PI_2 - i as f32 * TAU / PROGRESS_DOTS as f32;
+            let offset = Vec2::new(angle.cos(), angle.sin()) * radius;
+            parent
+                .spawn((
+                    Sprite {
+                        color: Color::WHITE,
+                        custom_size: Some(Vec2::splat(size / 8.)),
+                        ..Default::default()
+                    }, // above the hovered tile feedback
+                    Transform::from_translation((center + offset).extend(7.)),
+                ))
+                .insert(Name::new("Hold Progress"))
+                .insert(HoldProgress);
+        }
+    });
+}
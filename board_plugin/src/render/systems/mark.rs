@@ -0,0 +1,175 @@
+use crate::components::Coordinates;
+use crate::grid::GridKind;
+use crate::resources::{Board, BoardAssets, BoardOptions};
+use crate::tween::Tween;
+use bevy::prelude::*;
+
+// Flag sprite of a tile cover, the other children of the cover draw its bevel
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Flag;
+
+// Flag sprites, with their cover
+pub(crate) type Flags<'w, 's> = Query<'w, 's, (Entity, &'static ChildOf), With<Flag>>;
+
+// Flag growing to its size after being placed
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Grow(Tween);
+
+// Spawns the flag sprite on a tile cover, growing for `duration` seconds
+pub(crate) fn spawn_flag(
+    cmds: &mut Commands,
+    cover: Entity,
+    size: f32,
+    grid: GridKind,
+    board_assets: &BoardAssets,
+    duration: f32,
+) {
+    spawn_stacked_flag(cmds, cover, 0, size, grid, board_assets, duration);
+}
+
+// Spawns the flags of a stack at once, growing for `duration` seconds. The flags restored by an
+// undo are shown right away
+pub(crate) fn spawn_flag_stack(
+    cmds: &mut Commands,
+    cover: Entity,
+    count: u8,
+    size: f32,
+    grid: GridKind,
+    board_assets: &BoardAssets,
+    duration: f32,
+) {
+    for nth in 0..count {
+        spawn_stacked_flag(cmds, cover, nth, size, grid, board_assets, duration);
+    }
+}
+
+// Spawns the `nth` flag of the stack of a tile cover, each flag of a multi-mine board stack
+// raised above the previous one
+fn spawn_stacked_flag(
+    cmds: &mut Commands,
+    cover: Entity,
+    nth: u8,
+    size: f32,
+    grid: GridKind,
+    board_assets: &BoardAssets,
+    duration: f32,
+) {
+    let translation = Vec3::new(0., nth as f32 * size * 0.15, 1. + nth as f32 * 0.1);
+    cmds.entity(cover).with_children(|parent| {
+        let mut cmd = match board_assets.sheet_for(grid) {
+            Some(sheet) => parent.spawn((
+                sheet.sprite(sheet.flagged, size),
+                Transform::from_translation(translation),
+            )),
+            None => parent.spawn((
+                Sprite {
+                    image: board_assets.flag_image.clone(),
+                    custom_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                },
+                Transform::from_translation(translation),
+            )),
+        };
+        cmd.insert(Name::new("Flag")).insert(Flag);
+        if duration > 0. {
+            cmd.insert(Grow(Tween::new(duration))).insert(Transform {
+                translation,
+                scale: Vec3::ZERO,
+                ..Default::default()
+            });
+        }
+    });
+}
+
+// Grows the placed flags to their size
+pub fn grow_flags(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut flags: Query<(Entity, &mut Grow, &mut Transform)>,
+) {
+    for (entity, mut grow, mut transform) in flags.iter_mut() {
+        grow.0.advance(time.delta_secs());
+        transform.scale = Vec3::splat(grow.0.eased());
+        if grow.0.is_done() {
+            cmds.entity(entity).remove::<Grow>();
+        }
+    }
+}
+
+// Despawns the flag sprite of a tile cover
+pub(crate) fn despawn_flag(cmds: &mut Commands, cover: Entity, flags: &Flags) {
+    for (flag, parent) in flags.iter() {
+        if parent.parent() == cover {
+            cmds.entity(flag).despawn();
+        }
+    }
+}
+
+// Toggles the flag sprite on a marked tile cover, unless the board is played without flags. The
+// flags of multi-mine boards stack until they are all removed at once. Returns false when the
+// flags don't change
+pub(crate) fn toggle_flag(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    flags: &Flags,
+    coordinates: Coordinates,
+) -> bool {
+    if board.options.no_flags {
+        return false;
+    }
+    let flagged = match board.game.toggle_flag(coordinates) {
+        Some(f) => f,
+        None => return false,
+    };
+    let cover = match board.covered_tiles.get(&coordinates) {
+        Some(e) => *e,
+        // drawn in chunks
+        None => return true,
+    };
+
+    if flagged {
+        // the flag replaces the question mark
+        if let Some(question) = board.question_marks.remove(&coordinates) {
+            cmds.entity(question).despawn();
+        }
+        let size = board.tile_size - board.options.tile_padding;
+        let grid = board.tile_map().grid();
+        let duration = board.options.animations.flag;
+        let nth = board.game.flag_count(coordinates) - 1;
+        spawn_stacked_flag(cmds, cover, nth, size, grid, board_assets, duration);
+    } else {
+        despawn_flag(cmds, cover, flags);
+    }
+    true
+}
+
+// Are the bombs forced by a single revealed number flagged after each move? When `auto_flag` is
+// enabled and flags are allowed, outside of competitive games
+pub(crate) fn auto_flags(options: &BoardOptions) -> bool {
+    options.auto_flag && !options.no_flags && !options.competitive
+}
+
+// Flags the bombs forced by a single revealed number, as part of the move which revealed it.
+// Returns false when no bomb is forced
+pub(crate) fn flag_forced(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+) -> bool {
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    let duration = board.options.animations.flag;
+    let bombs = board.game.flag_forced_bombs();
+    for coords in bombs.iter() {
+        debug!("Auto flagged {coords}");
+        board.assists.auto_flags += 1;
+        if let Some(question) = board.question_marks.remove(coords) {
+            cmds.entity(question).despawn();
+        }
+        if let Some(cover) = board.covered_tiles.get(coords) {
+            spawn_flag(cmds, *cover, size, grid, board_assets, duration);
+        }
+    }
+    !bombs.is_empty()
+}
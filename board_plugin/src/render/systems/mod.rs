@@ -0,0 +1,91 @@
+pub mod camera;
+pub mod chunks;
+pub mod commands;
+pub mod cursor;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod diff;
+pub mod explosion;
+pub mod feedback;
+pub mod generation;
+pub mod ghost;
+pub mod guess;
+pub mod heatmap;
+pub mod hint;
+pub mod input;
+pub mod layers;
+pub mod long_press;
+pub mod mark;
+pub mod pixels;
+pub mod pointer;
+pub mod preview;
+pub mod reducer;
+pub mod replay;
+pub mod streaming;
+pub mod timer;
+pub mod uncover;
+pub mod undo;
+pub mod wheel;
+
+use crate::events::BoardCommand;
+use crate::render::engine::{self, Is2dCamera, PrimaryWindowQuery};
+use crate::render::screen_space::ScreenSpace;
+use crate::resources::{Board, InputGate};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+// Cursor and touches of the primary window, mapped to the world through the 2D camera
+#[derive(SystemParam)]
+pub struct WorldCursor<'w, 's> {
+    windows: PrimaryWindowQuery<'w, 's>,
+    cameras: Query<'w, 's, (&'static GlobalTransform, &'static Projection), Is2dCamera>,
+}
+
+impl<'w, 's> WorldCursor<'w, 's> {
+    // Primary window seen by the 2D camera, or by a camera at the origin without one
+    pub fn screen_space(&self) -> Option<ScreenSpace> {
+        let screen = ScreenSpace::from_window(self.windows.single().ok()?);
+        let camera = self
+            .cameras
+            .iter()
+            .find_map(|(t, p)| Some((t, engine::orthographic(p)?)));
+        Some(match camera {
+            Some((transform, projection)) => screen.with_camera(transform, projection),
+            None => screen,
+        })
+    }
+
+    // World position under the cursor
+    pub fn position(&self) -> Option<Vec2> {
+        let position = engine::cursor_position(self.windows.single().ok()?)?;
+        Some(self.screen_space()?.window_to_world(position))
+    }
+
+    // World position under a touch, touches are positioned like the cursor
+    pub fn touch_position(&self, position: Vec2) -> Option<Vec2> {
+        Some(self.screen_space()?.window_to_world(position))
+    }
+}
+
+// Board receiving the keyboard actions: the hovered board, or the only board
+pub(crate) fn focused_board<'a>(
+    cursor: &WorldCursor,
+    boards: impl Iterator<Item = &'a Board>,
+) -> Option<Entity> {
+    let boards: Vec<&Board> = boards.collect();
+    match boards.as_slice() {
+        [board] => Some(board.entity),
+        _ => {
+            let position = cursor.position()?;
+            boards
+                .iter()
+                .find(|b| b.contains(position))
+                .map(|b| b.entity)
+        }
+    }
+}
+
+// Is the player move let through by the input gate, if any
+pub(crate) fn is_allowed(gate: &Option<Res<InputGate>>, command: BoardCommand) -> bool {
+    gate.as_ref().is_none_or(|g| g.allows(command))
+}
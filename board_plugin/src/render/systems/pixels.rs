@@ -0,0 +1,49 @@
+use crate::resources::BoardAssets;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+
+// Samples the board sprites to the nearest texel in pixel perfect mode, and back to linear
+// sampling out of it. The sprites are updated as they load, and all at once when the mode changes
+pub fn sample_sprites(
+    board_assets: Res<BoardAssets>,
+    mut images: ResMut<Assets<Image>>,
+    mut image_evr: MessageReader<AssetEvent<Image>>,
+) {
+    let loaded: Vec<AssetId<Image>> = image_evr
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let changed = board_assets.is_changed();
+    if !changed && loaded.is_empty() {
+        return;
+    }
+    let sampler = match board_assets.pixel_perfect {
+        true => ImageSampler::nearest(),
+        false => ImageSampler::linear(),
+    };
+    let mut sprites = vec![
+        board_assets.bomb_image.clone(),
+        board_assets.flag_image.clone(),
+        board_assets.hex_image.clone(),
+        board_assets.treasure_image.clone(),
+    ];
+    if let Some(sheet) = &board_assets.tile_sheet {
+        sprites.push(sheet.texture.clone());
+    }
+    for handle in sprites
+        .iter()
+        .filter(|h| changed || loaded.contains(&h.id()))
+    {
+        // the sampler is only touched when it changes, every change uploads the texture again
+        let sampled = images.get(handle).map(|i| &i.sampler);
+        if sampled.is_none_or(|s| *s == sampler) {
+            continue;
+        }
+        if let Some(image) = images.get_mut(handle) {
+            image.sampler = sampler.clone();
+        }
+    }
+}
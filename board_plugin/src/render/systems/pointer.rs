@@ -0,0 +1,39 @@
+use crate::render::systems::WorldCursor;
+use crate::resources::{Board, InputMap, PointerMode, ReplayPlayback};
+use bevy::prelude::*;
+
+// Switches the uncover binding between uncovering and flagging when the flag mode binding is
+// pressed
+pub fn switch_pointer_mode(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut mode: ResMut<PointerMode>,
+) {
+    if input_map.flag_mode.just_pressed(&mouse, &keys) {
+        mode.flag = !mode.flag;
+        info!("Flag mode {}", if mode.flag { "on" } else { "off" });
+    }
+}
+
+// Tool under the cursor, over the boards the player plays with the mouse
+pub fn hover_tool(
+    cursor: WorldCursor,
+    playback: Option<Res<ReplayPlayback>>,
+    boards: Query<&Board>,
+    mut mode: ResMut<PointerMode>,
+) {
+    let hovered = cursor
+        .position()
+        .filter(|_| playback.is_none())
+        .and_then(|position| {
+            boards.iter().find(|b| {
+                b.options.controls.mouse() && !b.game.is_exploded() && b.contains(position)
+            })
+        });
+    let tool = hovered.map(|_| mode.tool());
+    // set only on changes, for the hosts watching it
+    if mode.hovered != tool {
+        mode.hovered = tool;
+    }
+}
@@ -0,0 +1,51 @@
+use crate::generation::BoardGenerators;
+use crate::render::engine::PrimaryWindowQuery;
+use crate::render::systems::{focused_board, WorldCursor};
+use crate::render::{present_board, spawn_board, start_board};
+use crate::resources::{Board, BoardAssets, BoardOptions, BoardPreview};
+use bevy::prelude::*;
+
+// Rerolls the focused previewed board with R and locks it in with Enter
+#[allow(clippy::too_many_arguments)]
+pub fn preview_input(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: WorldCursor,
+    boards: Query<(&Board, &BoardPreview)>,
+    board_assets: Res<BoardAssets>,
+    generators: Res<BoardGenerators>,
+    windows: PrimaryWindowQuery,
+) {
+    let (board, preview) = match focused_board(&cursor, boards.iter().map(|(b, _)| b))
+        .and_then(|entity| boards.get(entity).ok())
+    {
+        Some(v) => v,
+        None => return,
+    };
+
+    if keys.just_pressed(KeyCode::KeyR) && preview.rerolls_left > 0 {
+        let rerolls_left = preview.rerolls_left - 1;
+        info!("Rerolling the board, {rerolls_left} rerolls left");
+        cmds.entity(board.entity).despawn();
+        let options = BoardOptions {
+            seed: None,
+            ..board.options.clone()
+        };
+        let window = match windows.single() {
+            Ok(w) => w,
+            Err(_) => return,
+        };
+        let board = spawn_board(&mut cmds, options, &board_assets, &generators, None, window);
+        present_board(
+            &mut cmds,
+            board,
+            Some(BoardPreview { rerolls_left }),
+            true,
+            &board_assets,
+        );
+    } else if keys.just_pressed(KeyCode::Enter) {
+        info!("Board locked in");
+        cmds.entity(board.entity).remove::<BoardPreview>();
+        start_board(&mut cmds, board, true);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::components::PlayerId;
+use crate::events::{
+    Action, BoardActionEvent, BoardCompletedEvent, BoardStartedEvent, BombExplosionEvent,
+    LifeLostEvent, TreasureFoundEvent,
+};
+use crate::render::systems::mark::{auto_flags, flag_forced, toggle_flag, Flags};
+use crate::render::systems::uncover::{forgive_move, mark_forgiven, open_board, uncover_tile};
+use crate::render::systems::undo::undo_move;
+use crate::resources::{
+    Board, BoardAssets, GameTimer, ReplayEvent, ReplayPlayback, ReplayRecorder, Unopened,
+};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use minesweeper_core::GameState;
+use std::collections::VecDeque;
+
+// Events sent by the moves played on the boards
+#[derive(SystemParam)]
+pub struct MoveEvents<'w> {
+    pub(crate) board_completed: MessageWriter<'w, BoardCompletedEvent>,
+    pub(crate) bomb_explosion: MessageWriter<'w, BombExplosionEvent>,
+    pub(crate) life_lost: MessageWriter<'w, LifeLostEvent>,
+    pub(crate) treasure_found: MessageWriter<'w, TreasureFoundEvent>,
+}
+
+// Applies the board actions in the order they were sent, the only system changing the games of
+// the boards once they are started. The started boards are opened first. Each action is applied
+// along with the actions following from it, the applied ones going to the action log of their
+// board. The player moves also go to the recording with the state hash of the board, played back
+// replays being checked against their recorded hashes
+#[allow(clippy::too_many_arguments)]
+pub fn apply_actions(
+    mut cmds: Commands,
+    mut boards: Query<(&mut Board, Option<&mut ReplayRecorder>, Has<Unopened>)>,
+    mut playback: Option<ResMut<ReplayPlayback>>,
+    board_assets: Res<BoardAssets>,
+    mut timer: ResMut<GameTimer>,
+    flags: Flags,
+    mut events: MoveEvents,
+    mut board_action_evr: MessageReader<BoardActionEvent>,
+    mut board_started_ewr: MessageWriter<BoardStartedEvent>,
+) {
+    for (mut board, _, unopened) in boards.iter_mut() {
+        if !unopened {
+            continue;
+        }
+        cmds.entity(board.entity).remove::<Unopened>();
+        reduce(
+            &mut cmds,
+            &mut board,
+            &board_assets,
+            &mut timer,
+            &flags,
+            &mut events,
+            Action::Open,
+            PlayerId::One,
+        );
+        board_started_ewr.write(BoardStartedEvent {
+            board: board.entity,
+        });
+    }
+
+    for event in board_action_evr.read() {
+        // the other actions only follow from the moves
+        if !event.action.is_move() {
+            warn!("Ignored the {:?} action, not a player move", event.action);
+            continue;
+        }
+        let (mut board, recorder, _) = match boards.get_mut(event.board) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        let board = &mut *board;
+        let applied = reduce(
+            &mut cmds,
+            board,
+            &board_assets,
+            &mut timer,
+            &flags,
+            &mut events,
+            event.action,
+            event.player,
+        );
+        if !applied {
+            continue;
+        }
+
+        // once the actions following from the move are applied as well
+        let hash = board.game.state_hash();
+        if let Some(mut recorder) = recorder {
+            let replay_event = ReplayEvent::new(event.action, timer.elapsed(), hash);
+            recorder.replay.events.extend(replay_event);
+        }
+        if let Some(playback) = playback.as_mut() {
+            let index = board.actions.iter().filter(|a| a.is_move()).count();
+            playback.check(index - 1, hash);
+        }
+    }
+}
+
+// Applies an action, then the actions following from it, logging the applied ones. Returns false
+// when the action doesn't change the board
+#[allow(clippy::too_many_arguments)]
+fn reduce(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    timer: &mut GameTimer,
+    flags: &Flags,
+    events: &mut MoveEvents,
+    action: Action,
+    player: PlayerId,
+) -> bool {
+    let logged = board.actions.len();
+    let mut queue = VecDeque::from([action]);
+    while let Some(action) = queue.pop_front() {
+        let follow_ups = match action {
+            Action::Uncover(coordinates) | Action::Chord(coordinates) => {
+                let chord = matches!(action, Action::Chord(_));
+                let applied = uncover_tile(
+                    cmds,
+                    board,
+                    board_assets,
+                    timer,
+                    events,
+                    coordinates,
+                    chord,
+                    player,
+                );
+                match (applied, board.game.state()) {
+                    (false, _) => None,
+                    (true, GameState::Lost) if board.forgives_mistake() => {
+                        Some(vec![Action::Forgive])
+                    }
+                    (true, GameState::Playing) => Some(forced_flags(board)),
+                    (true, _) => Some(Vec::new()),
+                }
+            }
+            Action::ToggleFlag(coordinates) => {
+                toggle_flag(cmds, board, board_assets, flags, coordinates).then(Vec::new)
+            }
+            Action::Undo => undo_move(cmds, board, board_assets, flags).then(Vec::new),
+            Action::Open => open_board(cmds, board).then(|| forced_flags(board)),
+            Action::Forgive => forgive_move(cmds, board, board_assets, flags, events, player),
+            Action::MarkForgiven(coordinates) => {
+                mark_forgiven(cmds, board, board_assets, coordinates).then(Vec::new)
+            }
+            Action::FlagForced => flag_forced(cmds, board, board_assets).then(Vec::new),
+        };
+        if let Some(follow_ups) = follow_ups {
+            board.actions.push(action);
+            queue.extend(follow_ups);
+        }
+    }
+    board.actions.len() > logged
+}
+
+// Flagging of the bombs forced by the revealed numbers, following the moves which reveal tiles
+fn forced_flags(board: &Board) -> Vec<Action> {
+    match auto_flags(&board.options) {
+        true => vec![Action::FlagForced],
+        false => Vec::new(),
+    }
+}
@@ -0,0 +1,33 @@
+use crate::components::PlayerId;
+use crate::events::BoardActionEvent;
+use crate::resources::{Board, ReplayPlayback};
+use bevy::prelude::*;
+
+// Sends the recorded actions to every board once the playback reaches their time
+pub fn play_replay(
+    time: Res<Time>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    boards: Query<Entity, With<Board>>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+) {
+    let mut playback = match playback {
+        Some(p) => p,
+        None => return,
+    };
+    playback.elapsed += time.delta_secs() * playback.speed;
+
+    while let Some(event) = playback.replay.events.get(playback.next).copied() {
+        if event.time > playback.elapsed {
+            break;
+        }
+        for board in boards.iter() {
+            board_action_ewr.write(BoardActionEvent {
+                board,
+                action: event.action(),
+                // replays are single player
+                player: PlayerId::One,
+            });
+        }
+        playback.next += 1;
+    }
+}
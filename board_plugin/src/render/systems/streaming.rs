@@ -0,0 +1,59 @@
+use crate::components::{ProgressFill, TileSpawner};
+use crate::render::{show_board, spawn_tiles};
+use crate::resources::{BoardAssets, TILE_SPAWN_BUDGET};
+use bevy::prelude::*;
+
+// Spawns the next tiles of the boards spawning over several frames, within the frame budget.
+// The board is presented once its last tile is spawned
+pub fn stream_tiles(
+    mut cmds: Commands,
+    board_assets: Res<BoardAssets>,
+    mut spawners: Query<(Entity, &mut TileSpawner)>,
+    mut fills: Query<(&ProgressFill, &mut Transform)>,
+    children: Query<&Children>,
+) {
+    for (entity, mut spawner) in spawners.iter_mut() {
+        let next = spawner.next;
+        let board = match spawner.board.as_mut() {
+            Some(b) => b,
+            None => continue,
+        };
+        let tile_map = board.game.tile_map();
+        let tile_count = tile_map.width() as usize * tile_map.height() as usize;
+        let end = (next + TILE_SPAWN_BUDGET).min(tile_count);
+        cmds.entity(entity).with_children(|parent| {
+            spawn_tiles(
+                parent,
+                tile_map,
+                next..end,
+                board.tile_size,
+                board.options.tile_padding,
+                &board_assets,
+                &mut board.tiles,
+                &mut board.covered_tiles,
+            );
+        });
+        spawner.next = end;
+
+        let progress = spawner.progress();
+        let bar_children = children
+            .get(spawner.progress_bar)
+            .into_iter()
+            .flat_map(|c| c.iter());
+        for child in bar_children {
+            if let Ok((fill, mut transform)) = fills.get_mut(child) {
+                transform.scale.x = progress;
+                transform.translation.x = fill.width * (progress - 1.) / 2.;
+            }
+        }
+        if end < tile_count {
+            continue;
+        }
+
+        info!("Tiles spawned");
+        cmds.entity(spawner.progress_bar).despawn();
+        cmds.entity(entity).remove::<TileSpawner>();
+        let board = spawner.board.take().unwrap();
+        show_board(&mut cmds, board, spawner.preview, spawner.record);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::events::BoardStartedEvent;
+use crate::resources::{Board, GameTimer};
+use bevy::prelude::*;
+use minesweeper_core::GameState;
+
+pub fn reset_timer(mut timer: ResMut<GameTimer>) {
+    *timer = GameTimer::default();
+}
+
+// Runs the timer while the played boards are in progress
+pub fn tick_timer(
+    time: Res<Time>,
+    mut timer: ResMut<GameTimer>,
+    boards: Query<&Board>,
+    mut board_started_evr: MessageReader<BoardStartedEvent>,
+) {
+    if board_started_evr.read().count() > 0 {
+        timer.start();
+    }
+    let in_progress = boards.iter().any(|b| b.game.state() == GameState::Playing);
+    timer.tick(time.delta_secs(), in_progress);
+}
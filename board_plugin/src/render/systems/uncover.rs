@@ -0,0 +1,292 @@
+use crate::components::{Coordinates, PlayerId, Uncover};
+use crate::events::{
+    Action, BoardCompletedEvent, BombExplosionEvent, LifeLostEvent, TreasureFoundEvent,
+};
+use crate::grid::GridLayout;
+use crate::render::systems::mark::{spawn_flag_stack, Flags};
+use crate::render::systems::reducer::MoveEvents;
+use crate::render::systems::undo::restore_move;
+use crate::resources::{Board, BoardAssets, ChainReaction, GameTimer, TilePayload, Treasure};
+use bevy::prelude::*;
+use minesweeper_core::GameState;
+
+// Plays an uncovered or chorded tile, marking the covers of the uncovered tiles to be removed.
+// Returns false when the move doesn't change the board
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn uncover_tile(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    timer: &mut GameTimer,
+    events: &mut MoveEvents,
+    coordinates: Coordinates,
+    chord: bool,
+    player: PlayerId,
+) -> bool {
+    let delta = match chord {
+        true => board.game.chord(coordinates),
+        false => board.game.reveal(coordinates),
+    };
+    let revealed = match delta {
+        Some(delta) => delta.revealed.clone(),
+        None => return false,
+    };
+    debug!("Uncovered {} tiles from {coordinates}", revealed.len());
+    uncover_covers(cmds, board, coordinates, &revealed);
+    for (coordinates, treasure) in find_treasures(board, &revealed) {
+        info!("Treasure found at {coordinates}: {treasure:?}");
+        match treasure {
+            Treasure::TimeBonus(seconds) => timer.take_off(seconds as f32),
+            Treasure::ExtraLife => board.lives += 1,
+        }
+        events.treasure_found.write(TreasureFoundEvent {
+            board: board.entity,
+            coordinates,
+            treasure,
+            player,
+        });
+    }
+
+    match board.game.state() {
+        // taken back by the forgive following the move
+        GameState::Lost if board.forgives_mistake() => (),
+        GameState::Lost => {
+            info!("Boom !");
+            if board.options.chain_reaction != ChainReaction::Off {
+                uncover_chain(cmds, board, &revealed);
+            }
+            let chained: Vec<Coordinates> = board.chain.iter().map(|(c, _)| *c).collect();
+            let exploded = [revealed.as_slice(), chained.as_slice()].concat();
+            show_exploded_bombs(cmds, board, &exploded, board_assets);
+            events.bomb_explosion.write(BombExplosionEvent {
+                board: board.entity,
+                player,
+            });
+        }
+        GameState::Won => {
+            info!("Board completed");
+            events.board_completed.write(BoardCompletedEvent {
+                board: board.entity,
+            });
+        }
+        GameState::Playing => (),
+    }
+    true
+}
+
+// Marks the covers of the revealed tiles to be removed, the farther from the uncovered tile
+// the later
+pub(crate) fn uncover_covers(
+    cmds: &mut Commands,
+    board: &mut Board,
+    origin: Coordinates,
+    revealed: &[Coordinates],
+) {
+    let animations = board.options.animations;
+    let grid = board.tile_map().grid().grid();
+    let center = grid.tile_center(origin, board.tile_size);
+    let uncovers: Vec<(Coordinates, Uncover)> = revealed
+        .iter()
+        .map(|c| {
+            let distance = grid.tile_center(*c, board.tile_size).distance(center) / board.tile_size;
+            (
+                *c,
+                Uncover::new(distance * animations.cascade, animations.reveal),
+            )
+        })
+        .collect();
+    for (coords, uncover) in uncovers {
+        for entity in board.remove_covers(&[coords]) {
+            cmds.entity(entity).insert(uncover);
+        }
+    }
+}
+
+// Treasures of the revealed tiles not granted yet
+fn find_treasures(board: &mut Board, revealed: &[Coordinates]) -> Vec<(Coordinates, Treasure)> {
+    let treasures: Vec<(Coordinates, Treasure)> = revealed
+        .iter()
+        .filter_map(|c| match board.tile_map().payload_at(*c) {
+            Some(TilePayload::Treasure(treasure)) => Some((*c, treasure)),
+            _ => None,
+        })
+        .collect();
+    treasures
+        .into_iter()
+        .filter(|(c, _)| board.found_treasures.insert(*c))
+        .collect()
+}
+
+// Uncovers the opening tile and the starting reveals of a started board. Returns false when the
+// board has none
+pub(crate) fn open_board(cmds: &mut Commands, board: &mut Board) -> bool {
+    let delta = match board.game.open() {
+        Some(d) => d,
+        None => return false,
+    };
+    if let Some(opening) = delta.revealed.first() {
+        uncover_covers(cmds, board, *opening, &delta.revealed);
+    }
+    true
+}
+
+// Takes back the losing move and marks its bombs as forgiven instead, spending a life unless the
+// board is relaxed. Returns the marks of the bombs set off by the forgiven ones on boards scoring
+// the chain reaction, the flagged ones left as they are, or none when the game isn't lost
+pub(crate) fn forgive_move(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    flags: &Flags,
+    events: &mut MoveEvents,
+    player: PlayerId,
+) -> Option<Vec<Action>> {
+    let delta = board.game.forgive()?;
+    // relaxed boards flag the bombs for free
+    if !board.options.relaxed {
+        board.lives -= 1;
+        board.assists.forgiven_mistakes += 1;
+        info!("Life spent, {} left", board.lives);
+    }
+    restore_move(cmds, board, &delta, board_assets, flags);
+    let bombs: Vec<Coordinates> = delta
+        .revealed
+        .iter()
+        .copied()
+        .filter(|c| board.tile_map().is_bomb_at(*c))
+        .collect();
+    for bomb in bombs.iter() {
+        show_forgiven(cmds, board, board_assets, *bomb);
+    }
+
+    let chain = match board.options.chain_reaction {
+        ChainReaction::Scored => board.tile_map().chain_reaction(&bombs),
+        _ => Vec::new(),
+    };
+    if !board.options.relaxed {
+        board.assists.chained_bombs += chain.len() as u32;
+    }
+    if !chain.is_empty() {
+        info!("{} bombs flagged by the chain reaction", chain.len());
+    }
+    let marks = chain
+        .iter()
+        .filter(|(c, _)| !board.game.is_flagged(*c))
+        .map(|(c, _)| Action::MarkForgiven(*c))
+        .collect();
+    events.life_lost.write(LifeLostEvent {
+        board: board.entity,
+        bombs,
+        chain,
+        player,
+        lives_left: board.lives,
+    });
+    Some(marks)
+}
+
+// Marks a bomb as forgiven, flagging all of its mines. Returns false when the bomb is already
+// marked, uncovered or not a bomb
+pub(crate) fn mark_forgiven(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    coordinates: Coordinates,
+) -> bool {
+    if !board.game.mark_forgiven(coordinates) {
+        return false;
+    }
+    show_forgiven(cmds, board, board_assets, coordinates);
+    true
+}
+
+// Spawns the flags of a bomb marked as forgiven on its cover
+fn show_forgiven(
+    cmds: &mut Commands,
+    board: &Board,
+    board_assets: &BoardAssets,
+    coordinates: Coordinates,
+) {
+    let cover = match board.covered_tiles.get(&coordinates) {
+        Some(e) => *e,
+        // drawn in chunks
+        None => return,
+    };
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    let duration = board.options.animations.flag;
+    let count = board.game.flag_count(coordinates);
+    spawn_flag_stack(cmds, cover, count, size, grid, board_assets, duration);
+}
+
+// Uncovers the bombs set off by the bombs of the losing move, one step of the chain after the
+// other
+fn uncover_chain(cmds: &mut Commands, board: &mut Board, revealed: &[Coordinates]) {
+    let bombs: Vec<Coordinates> = revealed
+        .iter()
+        .copied()
+        .filter(|c| board.tile_map().is_bomb_at(*c))
+        .collect();
+    let animations = board.options.animations;
+    board.chain = board.tile_map().chain_reaction(&bombs);
+    info!("{} bombs set off by the chain reaction", board.chain.len());
+    let uncovers: Vec<(Coordinates, Uncover)> = board
+        .chain
+        .iter()
+        .map(|(c, step)| {
+            let delay = *step as f32 * animations.chain;
+            (*c, Uncover::new(delay, animations.reveal))
+        })
+        .collect();
+    for (coords, uncover) in uncovers {
+        for entity in board.remove_covers(&[coords]) {
+            cmds.entity(entity).insert(uncover);
+        }
+    }
+}
+
+// Swaps the frame of the uncovered bombs for the exploded bomb, on boards drawn from a sprite sheet
+fn show_exploded_bombs(
+    cmds: &mut Commands,
+    board: &Board,
+    revealed: &[Coordinates],
+    board_assets: &BoardAssets,
+) {
+    let tile_map = board.tile_map();
+    let sheet = match board_assets.sheet_for(tile_map.grid()) {
+        Some(s) => s,
+        None => return,
+    };
+    let size = board.tile_size - board.options.tile_padding;
+    for coords in revealed.iter().filter(|c| tile_map.is_bomb_at(**c)) {
+        if let Some(tile) = board.tiles.get(coords) {
+            cmds.entity(*tile)
+                .insert(sheet.sprite(sheet.exploded, size));
+        }
+    }
+}
+
+// Fades out the covers and question marks marked with `Uncover`, along with their bevels and
+// flags, then removes them
+pub fn uncover_tiles(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut covers: Query<(Entity, &mut Uncover, Option<&mut Sprite>, Option<&Children>)>,
+    mut child_sprites: Query<&mut Sprite, Without<Uncover>>,
+) {
+    for (entity, mut uncover, sprite, children) in covers.iter_mut() {
+        uncover.advance(time.delta_secs());
+        if uncover.is_done() {
+            cmds.entity(entity).despawn();
+            continue;
+        }
+        let opacity = uncover.opacity();
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_alpha(opacity);
+        }
+        for child in children.into_iter().flat_map(|c| c.iter()) {
+            if let Ok(mut sprite) = child_sprites.get_mut(child) {
+                sprite.color.set_alpha(opacity);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::components::PlayerId;
+use crate::events::{Action, BoardActionEvent};
+use crate::render::spawn_cover;
+use crate::render::systems::mark::{despawn_flag, spawn_flag_stack, Flags};
+use crate::render::systems::{focused_board, WorldCursor};
+use crate::resources::{
+    Board, BoardAssets, BoardPreview, InputGate, InputMap, MoveDelta, ReplayPlayback,
+};
+use bevy::prelude::*;
+
+// Asks for an undo on the focused board when the undo binding is pressed
+#[allow(clippy::too_many_arguments)]
+pub fn undo_input(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    cursor: WorldCursor,
+    playback: Option<Res<ReplayPlayback>>,
+    gate: Option<Res<InputGate>>,
+    boards: Query<&Board, Without<BoardPreview>>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+) {
+    // gated games only play the expected moves
+    if playback.is_some() || gate.is_some() || !input_map.undo.just_pressed(&mouse, &keys) {
+        return;
+    }
+    if let Some(board) = focused_board(&cursor, boards.iter()) {
+        board_action_ewr.write(BoardActionEvent {
+            board,
+            action: Action::Undo,
+            player: PlayerId::One,
+        });
+    }
+}
+
+// Reverts the last move, covering its uncovered tiles again and restoring its flags. Returns
+// false when there is no move left to undo
+pub(crate) fn undo_move(
+    cmds: &mut Commands,
+    board: &mut Board,
+    board_assets: &BoardAssets,
+    flags: &Flags,
+) -> bool {
+    if !board.can_undo(board.options.undos) {
+        info!("No move to undo");
+        return false;
+    }
+    let delta = match board.game.undo() {
+        Some(d) => d,
+        None => return false,
+    };
+    info!(
+        "Undoing a move of {} tiles and {} flags",
+        delta.revealed.len(),
+        delta.flags.len()
+    );
+    board.assists.undos += 1;
+    restore_chain(cmds, board, board_assets);
+    restore_move(cmds, board, &delta, board_assets, flags);
+    true
+}
+
+// Covers the bombs uncovered by the chain reaction of an undone explosion again, with their flags
+fn restore_chain(cmds: &mut Commands, board: &mut Board, board_assets: &BoardAssets) {
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    for (coords, _) in std::mem::take(&mut board.chain) {
+        let tile = match board.tiles.get(&coords) {
+            Some(e) => *e,
+            None => continue,
+        };
+        let mut cover = None;
+        cmds.entity(tile).with_children(|parent| {
+            cover = Some(spawn_cover(parent, grid, size, board_assets));
+        });
+        if let Some(cover) = cover {
+            board.covered_tiles.insert(coords, cover);
+            let count = board.game.flag_count(coords);
+            spawn_flag_stack(cmds, cover, count, size, grid, board_assets, 0.);
+        }
+    }
+}
+
+// Covers the tiles of an undone move again and shows its restored flags
+pub(crate) fn restore_move(
+    cmds: &mut Commands,
+    board: &mut Board,
+    delta: &MoveDelta,
+    board_assets: &BoardAssets,
+    flags: &Flags,
+) {
+    let size = board.tile_size - board.options.tile_padding;
+    let grid = board.tile_map().grid();
+    for coords in delta.revealed.iter() {
+        let tile = match board.tiles.get(coords) {
+            Some(e) => *e,
+            None => continue,
+        };
+        cmds.entity(tile).with_children(|parent| {
+            let cover = spawn_cover(parent, grid, size, board_assets);
+            board.covered_tiles.insert(*coords, cover);
+        });
+    }
+
+    // the flag sprites follow the restored flags and stacks, once the tiles are covered again
+    let stacked = delta.stacked.iter().map(|(c, _)| c);
+    let changed = delta
+        .flags
+        .iter()
+        .chain(stacked.filter(|c| !delta.flags.contains(c)));
+    for coords in changed {
+        let cover = match board.covered_tiles.get(coords) {
+            Some(e) => *e,
+            None => continue,
+        };
+        despawn_flag(cmds, cover, flags);
+        // restored at once
+        let count = board.game.flag_count(*coords);
+        spawn_flag_stack(cmds, cover, count, size, grid, board_assets, 0.);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::components::{Coordinates, PlayerId};
+use crate::events::{BoardActionEvent, BoardCommand};
+use crate::render::systems::{is_allowed, WorldCursor};
+use crate::resources::{Board, BoardAssets, BoardPreview, InputGate, InputMap, ReplayPlayback};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use minesweeper_core::GameState;
+
+// Pixels of touchpad scrolling worth a wheel notch
+const PIXELS_PER_NOTCH: f32 = 40.;
+
+// Mark of a covered tile, in the order the wheel cycles through them
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mark {
+    Covered,
+    Flag,
+    Question,
+}
+
+impl Mark {
+    fn of(board: &Board, coordinates: Coordinates) -> Self {
+        if board.game.is_flagged(coordinates) {
+            Self::Flag
+        } else if board.question_marks.contains_key(&coordinates) {
+            Self::Question
+        } else {
+            Self::Covered
+        }
+    }
+
+    // Next mark scrolling up, or previous scrolling down. The flag is skipped when refused
+    fn cycle(&self, up: bool, flag_allowed: bool) -> Self {
+        let next = match (self, up) {
+            (Self::Covered, true) | (Self::Question, false) => Self::Flag,
+            (Self::Flag, true) | (Self::Covered, false) => Self::Question,
+            (Self::Question, true) | (Self::Flag, false) => Self::Covered,
+        };
+        match next {
+            Self::Flag if !flag_allowed => next.cycle(up, true),
+            _ => next,
+        }
+    }
+}
+
+// Spawns the question mark of a covered tile, above its cover
+fn spawn_question_mark(
+    cmds: &mut Commands,
+    tile: Entity,
+    size: f32,
+    assets: &BoardAssets,
+) -> Entity {
+    let question = cmds
+        .spawn((
+            Text2d::new("?"),
+            TextFont {
+                font: assets.bomb_counter_font.clone(),
+                font_size: size * assets.text_scale,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            Transform::from_xyz(0., 0., 3.),
+        ))
+        .insert(Name::new("Question mark"))
+        .id();
+    cmds.entity(tile).add_children(&[question]);
+    question
+}
+
+// Cycles the mark of the hovered covered tile on every wheel notch, when `wheel_marks` is set:
+// covered, flag, question mark and covered again scrolling up, the other way scrolling down
+#[allow(clippy::too_many_arguments)]
+pub fn wheel_marks(
+    mut cmds: Commands,
+    cursor: WorldCursor,
+    input_map: Res<InputMap>,
+    board_assets: Res<BoardAssets>,
+    playback: Option<Res<ReplayPlayback>>,
+    gate: Option<Res<InputGate>>,
+    mut boards: Query<&mut Board, Without<BoardPreview>>,
+    mut wheel_evr: MessageReader<MouseWheel>,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+    mut scrolled: Local<f32>,
+) {
+    let notches: f32 = wheel_evr
+        .read()
+        .map(|e| match e.unit {
+            MouseScrollUnit::Line => e.y,
+            MouseScrollUnit::Pixel => e.y / PIXELS_PER_NOTCH,
+        })
+        .sum();
+    // the player doesn't control replays
+    if !input_map.wheel_marks || playback.is_some() {
+        *scrolled = 0.;
+        return;
+    }
+    *scrolled += notches;
+    if scrolled.abs() < 1. {
+        return;
+    }
+    // one mark per frame, the notches scrolled at once don't skip marks
+    let up = *scrolled > 0.;
+    *scrolled = 0.;
+
+    let position = match cursor.position() {
+        Some(p) => p,
+        None => return,
+    };
+    let (mut board, coordinates) = match boards
+        .iter_mut()
+        .filter(|b| b.options.controls.mouse() && b.game.state() == GameState::Playing)
+        .find_map(|b| {
+            let coordinates = b.tile_at(position)?;
+            Some((b, coordinates))
+        }) {
+        Some(b) => b,
+        None => return,
+    };
+    let tile = match board.tiles.get(&coordinates) {
+        Some(t) if board.game.is_covered(coordinates) => *t,
+        _ => return,
+    };
+
+    let toggle_flag = BoardCommand::ToggleFlag {
+        board: board.entity,
+        coordinates,
+    };
+    let flag_allowed = !board.options.no_flags && is_allowed(&gate, toggle_flag);
+    let mark = Mark::of(&board, coordinates);
+    // the flags the player can't toggle stay
+    if mark == Mark::Flag && !flag_allowed {
+        return;
+    }
+    let next = mark.cycle(up, flag_allowed);
+    if (mark == Mark::Flag || next == Mark::Flag) && flag_allowed {
+        board_action_ewr.write(BoardActionEvent {
+            board: board.entity,
+            action: toggle_flag.action(),
+            player: PlayerId::One,
+        });
+    }
+    if mark == Mark::Question {
+        if let Some(question) = board.question_marks.remove(&coordinates) {
+            cmds.entity(question).despawn();
+        }
+    }
+    if next == Mark::Question {
+        let size = board.tile_size - board.options.tile_padding;
+        let question = spawn_question_mark(&mut cmds, tile, size, &board_assets);
+        board.question_marks.insert(coordinates, question);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::bounds::Bounds2;
+use crate::components::Coordinates;
+use crate::grid::GridLayout;
+use crate::resources::{tile_map::TileMap, AssistUsage, BoardOptions, BoardSilhouette, TileState};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use minesweeper_core::{Action, Game, StateHasher};
+
+// Board state, component of the board root entity once the board is spawned.
+// The board entities present the game, which holds the rules
+#[derive(Debug, Component)]
+pub struct Board {
+    pub game: Game,
+    pub bounds: Bounds2,
+    pub tile_size: f32,
+    // Tile entities, none when the board is drawn in chunks
+    pub tiles: HashMap<Coordinates, Entity>,
+    // Cover entities of the covered tiles, none when the board is drawn in chunks
+    pub covered_tiles: HashMap<Coordinates, Entity>,
+    // Board root entity
+    pub entity: Entity,
+    // Seed the tile map was generated from
+    pub seed: u64,
+    // Options the board was created with
+    pub options: BoardOptions,
+    // Assists used on this board
+    pub assists: AssistUsage,
+    // Lives left, starting from the options and granted by the treasures. Each one spares the
+    // player from a bomb
+    pub lives: u8,
+    // Treasures already granted, undoing their move doesn't grant them again
+    pub found_treasures: HashSet<Coordinates>,
+    // Question marks of the covered tiles, with their sprites. Unlike the flags they are only
+    // shown, the game doesn't know about them
+    pub question_marks: HashMap<Coordinates, Entity>,
+    // Bombs uncovered by the chain reaction of the explosion ending the game, with their step in
+    // the chain. Covered again when the explosion is undone
+    pub chain: Vec<(Coordinates, u32)>,
+    // Actions applied on the game since the start, in order: the player moves along with the
+    // opening, the forgiven mistakes and the forced flags following from them. Replaying them on
+    // a new game of the tile map gives the same game
+    pub actions: Vec<Action>,
+}
+
+// Covered board shown before play starts, until the player locks it in.
+// Component of the board entity when `BoardOptions::preview_rerolls` is set
+#[derive(Debug, Copy, Clone, Component)]
+pub struct BoardPreview {
+    // Remaining seed rerolls
+    pub rerolls_left: u8,
+}
+
+// Board whose play started, opened by the reducer before the first move.
+// Component of the board entity until then
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Unopened;
+
+impl Board {
+    // Tile map of the game
+    pub fn tile_map(&self) -> &TileMap {
+        self.game.tile_map()
+    }
+
+    // Hash of the tile map layout, the uncovered tiles and the flags, the same on every platform
+    // and build. Boards generated from the same options and seed and played the same way share it
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u64(self.tile_map().layout_hash());
+        hasher.write_u64(self.game.state_hash());
+        hasher.finish()
+    }
+
+    // Visible state of a tile, none off the board
+    pub fn tile_state(&self, coordinates: Coordinates) -> Option<TileState> {
+        if !self.tile_map().exists(coordinates) {
+            return None;
+        }
+        Some(match self.game.is_revealed(coordinates) {
+            true => TileState::Revealed,
+            false if self.game.is_flagged(coordinates) => TileState::Flagged,
+            false => TileState::Covered,
+        })
+    }
+
+    // Bomb count shown by a revealed tile, none while the tile is covered
+    pub fn revealed_count(&self, coordinates: Coordinates) -> Option<u8> {
+        self.game
+            .is_revealed(coordinates)
+            .then(|| self.tile_map().bomb_count_at(coordinates))
+    }
+
+    // Visible state of every tile, without any bomb information
+    pub fn silhouette(&self) -> BoardSilhouette {
+        BoardSilhouette::from_board(self)
+    }
+
+    // Translates a world position, see `ScreenSpace` for the window positions, to tile
+    // coordinates
+    pub fn tile_at(&self, position: Vec2) -> Option<Coordinates> {
+        if !self.bounds.in_bounds(position) {
+            return None;
+        }
+
+        // world space to board space
+        let position = position - self.bounds.position;
+        let tile_map = self.tile_map();
+        tile_map
+            .grid()
+            .grid()
+            .tile_at(position, self.tile_size)
+            .filter(|c| c.x < tile_map.width() && c.y < tile_map.height())
+    }
+
+    // Is the world position over the board? Keyboard actions apply to the hovered board
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.bounds.in_bounds(position)
+    }
+
+    // Removes the revealed tiles from the covered tiles, returning their cover and question
+    // mark entities
+    pub fn remove_covers(&mut self, revealed: &[Coordinates]) -> Vec<Entity> {
+        revealed
+            .iter()
+            .flat_map(|c| [self.covered_tiles.remove(c), self.question_marks.remove(c)])
+            .flatten()
+            .collect()
+    }
+
+    // Seconds until the last bomb of the chain reaction blows up
+    pub fn chain_duration(&self) -> f32 {
+        self.chain
+            .last()
+            .map_or(0., |(_, step)| *step as f32 * self.options.animations.chain)
+    }
+
+    // Is a move uncovering a bomb taken back? Relaxed boards forgive every mistake, the others
+    // spend a life
+    pub fn forgives_mistake(&self) -> bool {
+        self.options.relaxed || self.lives > 0
+    }
+
+    // Can the last move be undone with the given number of allowed undos? Relaxed boards have no
+    // limit, competitive boards never undo
+    pub fn can_undo(&self, allowed: u8) -> bool {
+        !self.options.competitive
+            && !self.game.history().is_empty()
+            && (self.options.relaxed || self.assists.undos < allowed as u32)
+    }
+}
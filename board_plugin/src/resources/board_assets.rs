@@ -0,0 +1,242 @@
+use crate::grid::GridKind;
+use crate::resources::tile::Tile;
+use bevy::prelude::*;
+
+// Assets used to render the board. Must be used as a resource
+#[derive(Resource, Debug, Clone)]
+pub struct BoardAssets {
+    // Bomb counter font
+    pub bomb_counter_font: Handle<Font>,
+    // Bomb sprite
+    pub bomb_image: Handle<Image>,
+    // Flag sprite
+    pub flag_image: Handle<Image>,
+    // Hexagonal tile sprite, tinted with the tile colors
+    pub hex_image: Handle<Image>,
+    // Treasure sprite, drawn in a corner of the treasure tiles
+    pub treasure_image: Handle<Image>,
+    // Sprite sheet replacing the tile colors, bomb counter texts and bomb and flag sprites
+    pub tile_sheet: Option<TileSheet>,
+    // Colors of the bomb counters
+    pub number_palette: NumberPalette,
+    // Colors of the board background and tiles
+    pub tile_colors: TileColors,
+    // Bevels, grid lines and frame drawn around the tiles
+    pub decorations: BoardDecorations,
+    // Multiplier of the bomb counter font size, relative to the tile size
+    pub text_scale: f32,
+    // Highlight of the tile under the mouse cursor, the pressed tiles are shown either way
+    pub hover_highlight: bool,
+    // Pixel art rendering: whole pixel tile sizes and board position, and the sprites sampled to
+    // the nearest texel
+    pub pixel_perfect: bool,
+}
+
+impl FromWorld for BoardAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        Self {
+            bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
+            bomb_image: asset_server.load("sprites/bomb.png"),
+            flag_image: asset_server.load("sprites/flag.png"),
+            hex_image: asset_server.load("sprites/hex.png"),
+            treasure_image: asset_server.load("sprites/treasure.png"),
+            tile_sheet: None,
+            number_palette: NumberPalette::Classic,
+            tile_colors: TileColors::CLASSIC,
+            decorations: BoardDecorations::NONE,
+            text_scale: 1.,
+            hover_highlight: true,
+            pixel_perfect: false,
+        }
+    }
+}
+
+impl BoardAssets {
+    // Sprite sheet of the tiles of a grid, its frames only fit square tiles
+    pub fn sheet_for(&self, grid: GridKind) -> Option<&TileSheet> {
+        self.tile_sheet
+            .as_ref()
+            .filter(|_| grid == GridKind::Square)
+    }
+}
+
+// Colors of the bomb counters
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NumberPalette {
+    // White, green, yellow, orange and purple
+    Classic,
+    // Okabe-Ito colors, told apart with the common color vision deficiencies
+    ColorBlind,
+}
+
+impl NumberPalette {
+    // Color of the bomb counter of a given value
+    pub fn color(&self, count: u8) -> Color {
+        match self {
+            Self::Classic => match count {
+                1 => Color::WHITE,
+                2 => Color::srgb(0., 1., 0.),
+                3 => Color::srgb(1., 1., 0.),
+                4 => Color::srgb(1., 0.65, 0.),
+                5..=8 => Color::srgb(0.5, 0., 0.5),
+                // the stacked mines of multi-mine boards
+                9..=12 => Color::srgb(1., 0.08, 0.58),
+                _ => Color::srgb(1., 0., 0.),
+            },
+            Self::ColorBlind => match count {
+                1 => Color::srgb_u8(86, 180, 233),
+                2 => Color::srgb_u8(0, 158, 115),
+                3 => Color::srgb_u8(240, 228, 66),
+                4 => Color::srgb_u8(0, 114, 178),
+                5 => Color::srgb_u8(213, 94, 0),
+                6 => Color::srgb_u8(204, 121, 167),
+                7 => Color::srgb_u8(230, 159, 0),
+                8 => Color::WHITE,
+                _ => Color::BLACK,
+            },
+        }
+    }
+}
+
+// Colors of the board background and tiles
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TileColors {
+    // Board background, showing through the tile padding
+    pub background: Color,
+    pub covered: Color,
+    pub revealed: Color,
+    // Bombs drawn on the chunked boards
+    pub bomb: Color,
+}
+
+impl TileColors {
+    pub const CLASSIC: Self = Self {
+        background: Color::WHITE,
+        covered: Color::srgb(0.25, 0.25, 0.25),
+        revealed: Color::srgb(0.5, 0.5, 0.5),
+        bomb: Color::BLACK,
+    };
+
+    // Light covers on black revealed tiles, the bomb counters standing out the most
+    pub const HIGH_CONTRAST: Self = Self {
+        background: Color::WHITE,
+        covered: Color::srgb(0.7, 0.7, 0.7),
+        revealed: Color::BLACK,
+        bomb: Color::WHITE,
+    };
+
+    // Muted blues and greens of the zen mode
+    pub const CALM: Self = Self {
+        background: Color::srgb(0.85, 0.9, 0.88),
+        covered: Color::srgb(0.36, 0.5, 0.56),
+        revealed: Color::srgb(0.56, 0.68, 0.66),
+        bomb: Color::srgb(0.2, 0.27, 0.3),
+    };
+}
+
+// Extra sprites drawn around the tiles, on the boards of square tiles drawn as tile entities.
+// The sprite sheets bring their own bevels
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoardDecorations {
+    // Chiseled edges of the covered tiles, lit on the top and left sides and shaded on the
+    // others, as a share of the tile size. Zero draws flat covers
+    pub bevel: f32,
+    // Width of the lines between the revealed tiles, hidden under the covers, in pixels
+    pub grid_lines: f32,
+    pub grid_line_color: Color,
+    // Width of the frame around the board, in pixels
+    pub frame: f32,
+    pub frame_color: Color,
+}
+
+impl BoardDecorations {
+    // Plain tiles
+    pub const NONE: Self = Self {
+        bevel: 0.,
+        grid_lines: 0.,
+        grid_line_color: Color::NONE,
+        frame: 0.,
+        frame_color: Color::NONE,
+    };
+
+    // Chiseled covers on a gridded board, in the spirit of the original game
+    pub const CLASSIC: Self = Self {
+        bevel: 0.12,
+        grid_lines: 1.,
+        grid_line_color: Color::srgb(0.25, 0.25, 0.25),
+        frame: 8.,
+        frame_color: Color::srgb(0.35, 0.35, 0.35),
+    };
+
+    // Lit and shaded edge colors of a cover color
+    pub fn bevel_colors(&self, covered: Color) -> (Color, Color) {
+        let covered = covered.to_srgba();
+        let mix = |target: f32, amount: f32| {
+            let channel = |c: f32| c + (target - c) * amount;
+            Color::srgba(
+                channel(covered.red),
+                channel(covered.green),
+                channel(covered.blue),
+                covered.alpha,
+            )
+        };
+        (mix(1., 0.5), mix(0., 0.5))
+    }
+}
+
+// Tile sprite sheet, with the frame indexes of its texture atlas
+#[derive(Debug, Clone)]
+pub struct TileSheet {
+    pub texture: Handle<Image>,
+    pub atlas: Handle<TextureAtlasLayout>,
+    pub covered: usize,
+    pub flagged: usize,
+    pub empty: usize,
+    // Bomb counts from 1 to 8
+    pub numbers: [usize; 8],
+    pub bomb: usize,
+    // Bomb which ended the game
+    pub exploded: usize,
+}
+
+impl TileSheet {
+    // Classic sheet of `sprites/tiles.png`: a row of 16 pixel frames, covered, flagged, empty,
+    // the bomb counts, bomb and exploded bomb
+    pub fn classic(asset_server: &AssetServer, atlases: &mut Assets<TextureAtlasLayout>) -> Self {
+        let atlas = TextureAtlasLayout::from_grid(UVec2::splat(16), 13, 1, None, None);
+        Self {
+            texture: asset_server.load("sprites/tiles.png"),
+            atlas: atlases.add(atlas),
+            covered: 0,
+            flagged: 1,
+            empty: 2,
+            numbers: [3, 4, 5, 6, 7, 8, 9, 10],
+            bomb: 11,
+            exploded: 12,
+        }
+    }
+
+    // Frame of an uncovered tile
+    pub fn frame(&self, tile: Tile) -> usize {
+        match tile {
+            Tile::Bomb(_) => self.bomb,
+            Tile::BombNeighbor(count) => self.numbers[(count as usize).clamp(1, 8) - 1],
+            Tile::Empty | Tile::Void => self.empty,
+        }
+    }
+
+    // Sprite of a frame, `size` being the tile width
+    pub fn sprite(&self, index: usize, size: f32) -> Sprite {
+        Sprite {
+            custom_size: Some(Vec2::splat(size)),
+            ..Sprite::from_atlas_image(
+                self.texture.clone(),
+                TextureAtlas {
+                    layout: self.atlas.clone(),
+                    index,
+                },
+            )
+        }
+    }
+}
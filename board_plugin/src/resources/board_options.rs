@@ -1,4 +1,9 @@
-use bevy::prelude::Vec3;
+use crate::grid::{GridKind, NeighborKernel};
+use crate::resources::tile_map::TileMap;
+use crate::resources::{BoardError, BoardMask, BombPlacement, ScoringRules};
+#[cfg(feature = "render")]
+use bevy::camera::visibility::RenderLayers;
+use bevy::prelude::{Resource, Vec3};
 use serde::{Deserialize, Serialize};
 
 // Tile size options
@@ -36,13 +41,233 @@ impl Default for BoardPosition {
     }
 }
 
+// Bomb placement strategy
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Generation {
+    // Bombs placed uniformly at random
+    Uniform,
+    // Board solvable without guessing from the opening tile, implies a safe start
+    NoGuess,
+    // Safe tiles all connected through shared edges, without pockets sealed off by bombs
+    Connected,
+    // Generator registered by the host app under the given name
+    Custom(String),
+}
+
+impl Generation {
+    // Name of the generator in the `BoardGenerators` registry
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Uniform => "uniform",
+            Self::NoGuess => "no_guess",
+            Self::Connected => "connected",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl Default for Generation {
+    fn default() -> Self {
+        Self::Uniform
+    }
+}
+
+// Input device playing a board
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardControls {
+    // Clicks on the board
+    Mouse,
+    // Tile cursor moved with the arrow keys or a gamepad d-pad
+    Keyboard,
+    // Clicks of player one and the tile cursor of player two, on a cooperative board
+    Shared,
+}
+
+impl BoardControls {
+    // Whether the board is played with the mouse
+    pub fn mouse(&self) -> bool {
+        matches!(self, Self::Mouse | Self::Shared)
+    }
+
+    // Whether the board is played with a tile cursor
+    pub fn cursor(&self) -> bool {
+        matches!(self, Self::Keyboard | Self::Shared)
+    }
+}
+
+impl Default for BoardControls {
+    fn default() -> Self {
+        Self::Mouse
+    }
+}
+
+// Bomb explosion effects
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplosionEffects {
+    // Particles of the burst
+    pub particles: u32,
+    // Camera shake amplitude, in pixels
+    pub shake: f32,
+}
+
+impl ExplosionEffects {
+    // No burst and no shake
+    pub const NONE: Self = Self {
+        particles: 0,
+        shake: 0.,
+    };
+}
+
+impl Default for ExplosionEffects {
+    fn default() -> Self {
+        Self {
+            particles: 40,
+            shake: 8.,
+        }
+    }
+}
+
+// Chain reaction of the exploded bombs: the bombs sharing an edge with a blown up bomb blow up
+// in turn
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ChainReaction {
+    Off,
+    // The chain ripples out of the bomb ending the game, uncovering its bombs before the game
+    // over, for show
+    Ripple,
+    // The chain ripples out of the forgiven bombs too, flagging its bombs, and each chained
+    // bomb takes `ScoringRules::chained_bomb` off the penalty of the mistake
+    Scored,
+}
+
+impl Default for ChainReaction {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+// Durations of the tile animations, in seconds, played off the frame time. Zero plays a
+// change at once
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Animations {
+    // Fade out of the covers of the uncovered tiles
+    pub reveal: f32,
+    // Delay of a cover per tile of distance from the uncovered tile, cascading the openings
+    pub cascade: f32,
+    // Growth of the placed flags
+    pub flag: f32,
+    // Delay between the steps of a chain reaction
+    pub chain: f32,
+    // Camera move framing a new board
+    pub camera: f32,
+}
+
+impl Animations {
+    // Every change shown at once
+    pub const NONE: Self = Self {
+        reveal: 0.,
+        cascade: 0.,
+        flag: 0.,
+        chain: 0.,
+        camera: 0.,
+    };
+
+    // Fades only, nothing moving or growing, for motion-sensitive players
+    pub const REDUCED: Self = Self {
+        reveal: 0.15,
+        cascade: 0.,
+        flag: 0.,
+        chain: 0.,
+        camera: 0.,
+    };
+}
+
+impl Default for Animations {
+    fn default() -> Self {
+        Self {
+            reveal: 0.15,
+            cascade: 0.015,
+            flag: 0.12,
+            chain: 0.12,
+            camera: 0.3,
+        }
+    }
+}
+
+// Bombs of a board, either a count or a share of its tiles. Densities keep custom boards as
+// hard when their size changes
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+// bare numbers, as saved before densities, stay bomb counts
+#[serde(untagged)]
+pub enum BombCount {
+    Absolute(u16),
+    // Share of the tiles, from 0 to 1
+    Density(f32),
+}
+
+impl BombCount {
+    // Bomb count of a board of `tile_count` tiles, densities rounded to the closest count
+    pub fn resolve(&self, tile_count: usize) -> u32 {
+        match self {
+            Self::Absolute(count) => (*count).into(),
+            Self::Density(density) => (density * tile_count as f32).round() as u32,
+        }
+    }
+}
+
+impl From<u16> for BombCount {
+    fn from(count: u16) -> Self {
+        Self::Absolute(count)
+    }
+}
+
+// Tile count above which `BoardRendering::Auto` draws the board in chunks
+pub const CHUNKED_RENDERING_THRESHOLD: usize = 10_000;
+// Tile count above which even uniform boards are generated in the background
+pub const BACKGROUND_GENERATION_THRESHOLD: usize = 250_000;
+// Tile entities spawned per frame, the boards of more tiles spawning them over several frames
+pub const TILE_SPAWN_BUDGET: usize = 2_048;
+
+// Way the tiles of a board are drawn
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardRendering {
+    // Chunks above `CHUNKED_RENDERING_THRESHOLD` tiles, tile entities below
+    Auto,
+    // An entity per tile, with its cover, flag and number as children
+    Tiles,
+    // An image per chunk of tiles, redrawn from the game. Square grids only, hexagonal
+    // boards are always drawn with tile entities
+    Chunks,
+}
+
+impl Default for BoardRendering {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
 // Board generation options. Must be used as a resource
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct BoardOptions {
     // Tile map size
     pub map_size: (u16, u16),
-    // bomb count
-    pub bomb_count: u16,
+    // Tiling of the board
+    pub grid: GridKind,
+    // Tiles counted by the numbers, the neighbors of the tiling by default
+    #[serde(default)]
+    pub kernel: NeighborKernel,
+    // Joins the opposite edges of the board, the tiles of an edge neighboring the other edge
+    #[serde(default)]
+    pub wrap: bool,
+    // Shape of the board, rectangular if not set
+    pub mask: Option<BoardMask>,
+    // Bombs of the board, see `BoardOptions::bombs` for the count
+    pub bomb_count: BombCount,
+    // Most mines stacked on a bomb, from 1 to 3. Multi-mine boards count the stacked mines in
+    // their numbers, which may go above 8
+    #[serde(default = "single_mine")]
+    pub max_mines: u8,
     // Board world position
     pub position: BoardPosition,
     // Tile world size
@@ -51,17 +276,215 @@ pub struct BoardOptions {
     pub tile_padding: f32,
     // Does the board generate a safe place to start
     pub safe_start: bool,
+    // Generation seed, random if not set
+    pub seed: Option<u64>,
+    // Bomb placement strategy
+    pub generation: Generation,
+    // Way the bombs are drawn from the seed. The options saved before it draw them as the first
+    // versions did, so their seeds deal the same boards
+    #[serde(default = "legacy_placement")]
+    pub placement: BombPlacement,
+    // Time penalties of the assists
+    pub scoring: ScoringRules,
+    // Assist flagging the bombs trivially forced by a revealed number
+    pub auto_flag: bool,
+    // Refuses the flags beyond the bomb count, the mines left never going negative
+    #[serde(default)]
+    pub strict_flags: bool,
+    // Refuses every flag, auto flags included, for no-flag (NF) play
+    #[serde(default)]
+    pub no_flags: bool,
+    // Treasures hidden under safe tiles, granting time bonuses and extra lives
+    #[serde(default)]
+    pub treasures: u16,
+    // Shows the covered board before play, with the given number of seed rerolls
+    pub preview_rerolls: Option<u8>,
+    // Undos allowed per game, a bomb only ends the game once they are used up
+    pub undos: u8,
+    // Bombs survived per game, each one flagged and spending a life instead of ending the game
+    #[serde(default)]
+    pub lives: u8,
+    // Relaxed rules: unlimited undos, and every bomb flagged instead of ending the game without
+    // spending a life
+    #[serde(default)]
+    pub relaxed: bool,
+    // Competitive play: hints, the odds heatmap, undos, auto flags and the odds of the last guess
+    // are refused whatever the other options, so the game is never assisted
+    #[serde(default)]
+    pub competitive: bool,
+    // Require a second click, showing the odds, when the endgame is down to a guess
+    pub confirm_last_guess: bool,
+    // Input device playing the board
+    pub controls: BoardControls,
+    // Way the tiles are drawn
+    #[serde(default)]
+    pub rendering: BoardRendering,
+    // Effects of the bomb explosions
+    #[serde(default)]
+    pub explosion: ExplosionEffects,
+    // Bombs blown up along with the exploded ones
+    #[serde(default)]
+    pub chain_reaction: ChainReaction,
+    // Durations of the reveal and flag animations
+    #[serde(default)]
+    pub animations: Animations,
+    // Depth added to the board and its effects, their own depths being relative to it, to
+    // layer the board in the scene of a host game
+    #[serde(default)]
+    pub z_offset: f32,
+    // Render layer of the board and its effects, only drawn by the cameras seeing it
+    #[serde(default)]
+    pub render_layer: u8,
+}
+
+impl BoardOptions {
+    // Default options with the given board, if a tile map can be generated for it
+    pub fn try_new(map_size: (u16, u16), bomb_count: BombCount) -> Result<Self, BoardError> {
+        let options = Self {
+            map_size,
+            bomb_count,
+            ..Default::default()
+        };
+        options.validate()?;
+        Ok(options)
+    }
+
+    // Tile map of the options without bombs, shaped by the mask if any
+    pub fn empty_tile_map(&self) -> TileMap {
+        let (width, height) = self.map_size;
+        let mut tile_map = TileMap::empty_with_grid(width, height, self.grid)
+            .with_kernel(self.kernel.clone())
+            .with_wrap(self.wrap)
+            .with_placement(self.placement);
+        if let Some(mask) = &self.mask {
+            tile_map.apply_mask(mask);
+        }
+        tile_map
+    }
+
+    // Bomb count of the boards of the options
+    pub fn bombs(&self) -> u32 {
+        let tile_count = match &self.mask {
+            Some(_) => self.empty_tile_map().tile_count(),
+            None => self.map_size.0 as usize * self.map_size.1 as usize,
+        };
+        self.bomb_count.resolve(tile_count)
+    }
+
+    // Checks that a tile map can be generated for the options, with a safe start if needed
+    pub fn validate(&self) -> Result<(), BoardError> {
+        let tile_map = self.empty_tile_map();
+        // no guess boards always open safely
+        let safe_start = self.safe_start || self.generation == Generation::NoGuess;
+        let bomb_count = self.bomb_count.resolve(tile_map.tile_count());
+        tile_map.check_bomb_count(bomb_count, safe_start)
+    }
+
+    // Checks options received from outside the game, from other players or shared files. The
+    // sides are bounded before anything is allocated for the tile map
+    pub fn validate_within(&self, max_side: u16) -> Result<(), BoardError> {
+        let (width, height) = self.map_size;
+        if width > max_side || height > max_side {
+            return Err(BoardError::TooLarge {
+                map_size: self.map_size,
+                max_side,
+            });
+        }
+        self.validate()
+    }
+
+    // Is the board drawn in chunks instead of tile entities?
+    pub fn chunked_rendering(&self) -> bool {
+        let tile_count = self.map_size.0 as usize * self.map_size.1 as usize;
+        self.grid == GridKind::Square
+            && match self.rendering {
+                BoardRendering::Auto => tile_count > CHUNKED_RENDERING_THRESHOLD,
+                BoardRendering::Tiles => false,
+                BoardRendering::Chunks => true,
+            }
+    }
+
+    // Render layers of the board entities
+    #[cfg(feature = "render")]
+    pub fn render_layers(&self) -> RenderLayers {
+        RenderLayers::layer(self.render_layer as usize)
+    }
+
+    // Are the tile entities spawned over several frames? Only the boards drawn with tile
+    // entities spawn them
+    pub fn streams_tiles(&self) -> bool {
+        let tile_count = self.map_size.0 as usize * self.map_size.1 as usize;
+        !self.chunked_rendering() && tile_count > TILE_SPAWN_BUDGET
+    }
+
+    // Can the tile map take a noticeable time to generate? Only uniform boards of a reasonable
+    // size are generated at once
+    pub fn generates_slowly(&self) -> bool {
+        let tile_count = self.map_size.0 as usize * self.map_size.1 as usize;
+        self.generation != Generation::Uniform || tile_count > BACKGROUND_GENERATION_THRESHOLD
+    }
+
+    // Do both options generate the same kind of tile maps?
+    pub fn generates_like(&self, other: &Self) -> bool {
+        self.map_size == other.map_size
+            && self.bomb_count == other.bomb_count
+            && self.max_mines == other.max_mines
+            && self.grid == other.grid
+            && self.kernel == other.kernel
+            && self.wrap == other.wrap
+            && self.mask == other.mask
+            && self.safe_start == other.safe_start
+            && self.generation == other.generation
+            && self.placement == other.placement
+            && self.treasures == other.treasures
+    }
 }
 
 impl Default for BoardOptions {
     fn default() -> Self {
         Self {
             map_size: (15, 15),
-            bomb_count: 30,
+            grid: Default::default(),
+            kernel: Default::default(),
+            wrap: false,
+            mask: None,
+            bomb_count: BombCount::Absolute(30),
+            max_mines: 1,
             position: Default::default(),
             tile_size: Default::default(),
             tile_padding: 0.0,
             safe_start: false,
+            seed: None,
+            generation: Default::default(),
+            placement: Default::default(),
+            scoring: Default::default(),
+            auto_flag: false,
+            strict_flags: false,
+            no_flags: false,
+            treasures: 0,
+            preview_rerolls: None,
+            undos: 0,
+            lives: 0,
+            relaxed: false,
+            competitive: false,
+            confirm_last_guess: false,
+            controls: Default::default(),
+            rendering: Default::default(),
+            explosion: Default::default(),
+            chain_reaction: Default::default(),
+            animations: Default::default(),
+            z_offset: 0.,
+            render_layer: 0,
         }
     }
 }
+
+// Single mine bombs of the options saved without `max_mines`
+fn single_mine() -> u8 {
+    1
+}
+
+// Bombs of the options saved without `placement`, drawn as they were then
+fn legacy_placement() -> BombPlacement {
+    BombPlacement::Retry
+}
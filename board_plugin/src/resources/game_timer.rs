@@ -0,0 +1,46 @@
+use bevy::prelude::Resource;
+
+// Play time of the boards, read by the host app for its HUD, stats and leaderboard.
+// Must be used as a resource, reset when the running state is entered. The time only runs
+// once the play started and while a board is in progress, so it stops on a win or a loss and
+// while the running state is paused
+#[derive(Resource, Debug, Copy, Clone, Default)]
+pub struct GameTimer {
+    elapsed: f32,
+    started: bool,
+    running: bool,
+}
+
+impl GameTimer {
+    // Seconds of play
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    // Has the play started on a board, after its preview if any?
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    // Is the time running? Undoing a lost move runs it again
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    // Takes a bonus off the time, never below zero
+    pub(crate) fn take_off(&mut self, seconds: f32) {
+        self.elapsed = (self.elapsed - seconds).max(0.);
+    }
+
+    pub(crate) fn start(&mut self) {
+        self.started = true;
+    }
+
+    // Advances the time by a frame, if the play started and a board is in progress
+    pub(crate) fn tick(&mut self, delta: f32, in_progress: bool) {
+        self.running = self.started && in_progress;
+        if self.running {
+            self.elapsed += delta;
+        }
+    }
+}
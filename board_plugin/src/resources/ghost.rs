@@ -0,0 +1,24 @@
+use crate::resources::Replay;
+use bevy::prelude::Resource;
+use minesweeper_core::Game;
+
+// Earlier run of the board raced by the player: the tiles it had uncovered by the same time of
+// the game show over the covers. Must be used as a resource, inserted by the host app
+#[derive(Resource)]
+pub struct Ghost {
+    pub replay: Replay,
+    // Index of the next event to play
+    pub next: usize,
+    // Game of the run, started along with the board
+    pub game: Option<Game>,
+}
+
+impl Ghost {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            game: None,
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use crate::events::BoardCommand;
+use bevy::prelude::*;
+use serde::de::{self, EnumAccess, IntoDeserializer, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+// Player actions bound to an input. The board plugin plays the board actions, host apps
+// restart and pause the games
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    Uncover,
+    Flag,
+    // Switches the uncover binding to flagging, and back
+    FlagMode,
+    // Uncovers the neighbors of a revealed number surrounded by enough flags
+    Chord,
+    Hint,
+    // Shows or hides the bomb odds of the covered tiles
+    Heatmap,
+    Undo,
+    Restart,
+    // Plays the same board again
+    Retry,
+    Pause,
+    // Saves a picture and the text state of the boards
+    Export,
+    // Switches between the windowed mode and fullscreen
+    Fullscreen,
+    // Shows or hides the streaming overlay
+    Overlay,
+}
+
+impl InputAction {
+    pub const ALL: [Self; 13] = [
+        Self::Uncover,
+        Self::Flag,
+        Self::FlagMode,
+        Self::Chord,
+        Self::Hint,
+        Self::Heatmap,
+        Self::Undo,
+        Self::Restart,
+        Self::Retry,
+        Self::Pause,
+        Self::Export,
+        Self::Fullscreen,
+        Self::Overlay,
+    ];
+}
+
+// Mouse button or key bound to an action
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Mouse(MouseButton),
+    Key(#[serde(deserialize_with = "deserialize_key")] KeyCode),
+}
+
+// Key names of the settings saved before the engine update, renamed after their physical keys
+const LEGACY_KEYS: [(&str, KeyCode); 24] = [
+    ("Snapshot", KeyCode::PrintScreen),
+    ("Scroll", KeyCode::ScrollLock),
+    ("Left", KeyCode::ArrowLeft),
+    ("Up", KeyCode::ArrowUp),
+    ("Right", KeyCode::ArrowRight),
+    ("Down", KeyCode::ArrowDown),
+    ("Back", KeyCode::Backspace),
+    ("Return", KeyCode::Enter),
+    ("Numlock", KeyCode::NumLock),
+    ("Capital", KeyCode::CapsLock),
+    ("Apostrophe", KeyCode::Quote),
+    ("Grave", KeyCode::Backquote),
+    ("Equals", KeyCode::Equal),
+    ("LBracket", KeyCode::BracketLeft),
+    ("RBracket", KeyCode::BracketRight),
+    ("LAlt", KeyCode::AltLeft),
+    ("RAlt", KeyCode::AltRight),
+    ("LControl", KeyCode::ControlLeft),
+    ("RControl", KeyCode::ControlRight),
+    ("LShift", KeyCode::ShiftLeft),
+    ("RShift", KeyCode::ShiftRight),
+    ("LWin", KeyCode::SuperLeft),
+    ("RWin", KeyCode::SuperRight),
+    ("Apps", KeyCode::ContextMenu),
+];
+
+// Reads a key by its current name, or by its legacy one: `A` for `KeyA`, `Key1` for `Digit1`
+// or an entry of `LEGACY_KEYS`
+fn deserialize_key<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KeyCode, D::Error> {
+    // Variant name, read as an identifier like the derived enums do
+    struct KeyName(String);
+
+    impl<'de> Deserialize<'de> for KeyName {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct NameVisitor;
+
+            impl Visitor<'_> for NameVisitor {
+                type Value = KeyName;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a key name")
+                }
+
+                fn visit_str<E: de::Error>(self, name: &str) -> Result<KeyName, E> {
+                    Ok(KeyName(name.to_string()))
+                }
+            }
+
+            deserializer.deserialize_identifier(NameVisitor)
+        }
+    }
+
+    struct KeyVisitor;
+
+    impl<'de> Visitor<'de> for KeyVisitor {
+        type Value = KeyCode;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a key name")
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<KeyCode, A::Error> {
+            let (KeyName(name), variant) = data.variant()?;
+            variant.unit_variant()?;
+            let legacy = LEGACY_KEYS.iter().find(|(old, _)| *old == name);
+            if let Some((_, key)) = legacy {
+                return Ok(*key);
+            }
+            let name = match name.strip_prefix("Key") {
+                Some(digit) if digit.len() == 1 && digit.chars().all(|c| c.is_ascii_digit()) => {
+                    format!("Digit{digit}")
+                }
+                _ if name.len() == 1 && name.chars().all(|c| c.is_ascii_uppercase()) => {
+                    format!("Key{name}")
+                }
+                _ => name,
+            };
+            let key: Result<_, de::value::Error> = KeyCode::deserialize(name.into_deserializer());
+            key.map_err(de::Error::custom)
+        }
+    }
+
+    deserializer.deserialize_enum("KeyCode", &[], KeyVisitor)
+}
+
+impl Binding {
+    pub fn pressed(&self, mouse: &ButtonInput<MouseButton>, keys: &ButtonInput<KeyCode>) -> bool {
+        match self {
+            Self::Mouse(button) => mouse.pressed(*button),
+            Self::Key(key) => keys.pressed(*key),
+        }
+    }
+
+    pub fn just_pressed(
+        &self,
+        mouse: &ButtonInput<MouseButton>,
+        keys: &ButtonInput<KeyCode>,
+    ) -> bool {
+        match self {
+            Self::Mouse(button) => mouse.just_pressed(*button),
+            Self::Key(key) => keys.just_pressed(*key),
+        }
+    }
+
+    pub fn just_released(
+        &self,
+        mouse: &ButtonInput<MouseButton>,
+        keys: &ButtonInput<KeyCode>,
+    ) -> bool {
+        match self {
+            Self::Mouse(button) => mouse.just_released(*button),
+            Self::Key(key) => keys.just_released(*key),
+        }
+    }
+
+    // First mouse button or key pressed this frame
+    pub fn just_pressed_any(
+        mouse: &ButtonInput<MouseButton>,
+        keys: &ButtonInput<KeyCode>,
+    ) -> Option<Self> {
+        mouse
+            .get_just_pressed()
+            .next()
+            .map(|b| Self::Mouse(*b))
+            .or_else(|| keys.get_just_pressed().next().map(|k| Self::Key(*k)))
+    }
+}
+
+// Letter and digit keys swapped by the left-handed preset, mirrored across the middle of the
+// keyboard. The function keys stay in place
+const MIRRORED_KEYS: [(KeyCode, KeyCode); 20] = [
+    (KeyCode::Digit1, KeyCode::Digit0),
+    (KeyCode::Digit2, KeyCode::Digit9),
+    (KeyCode::Digit3, KeyCode::Digit8),
+    (KeyCode::Digit4, KeyCode::Digit7),
+    (KeyCode::Digit5, KeyCode::Digit6),
+    (KeyCode::KeyQ, KeyCode::KeyP),
+    (KeyCode::KeyW, KeyCode::KeyO),
+    (KeyCode::KeyE, KeyCode::KeyI),
+    (KeyCode::KeyR, KeyCode::KeyU),
+    (KeyCode::KeyT, KeyCode::KeyY),
+    (KeyCode::KeyA, KeyCode::Semicolon),
+    (KeyCode::KeyS, KeyCode::KeyL),
+    (KeyCode::KeyD, KeyCode::KeyK),
+    (KeyCode::KeyF, KeyCode::KeyJ),
+    (KeyCode::KeyG, KeyCode::KeyH),
+    (KeyCode::KeyZ, KeyCode::Slash),
+    (KeyCode::KeyX, KeyCode::Period),
+    (KeyCode::KeyC, KeyCode::Comma),
+    (KeyCode::KeyV, KeyCode::KeyM),
+    (KeyCode::KeyB, KeyCode::KeyN),
+];
+
+// Binding of the other hand: left and right mouse buttons swapped, keys mirrored
+fn mirrored(binding: Binding) -> Binding {
+    match binding {
+        Binding::Mouse(MouseButton::Left) => Binding::Mouse(MouseButton::Right),
+        Binding::Mouse(MouseButton::Right) => Binding::Mouse(MouseButton::Left),
+        Binding::Mouse(button) => Binding::Mouse(button),
+        Binding::Key(key) => Binding::Key(
+            MIRRORED_KEYS
+                .iter()
+                .find_map(|&(a, b)| match key {
+                    _ if key == a => Some(b),
+                    _ if key == b => Some(a),
+                    _ => None,
+                })
+                .unwrap_or(key),
+        ),
+    }
+}
+
+// Ready-made bindings of all the actions
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InputPreset {
+    RightHanded,
+    // Uncover on the right mouse button and flag on the left one, keys mirrored for the right
+    // hand
+    LeftHanded,
+}
+
+impl InputPreset {
+    pub const ALL: [Self; 2] = [Self::RightHanded, Self::LeftHanded];
+
+    // Input map of the preset bindings, keeping the long press and wheel options of `input_map`
+    pub fn apply(&self, input_map: &InputMap) -> InputMap {
+        let defaults = InputMap::default();
+        let mut preset = *input_map;
+        for action in InputAction::ALL {
+            let binding = defaults.get(action);
+            let binding = match self {
+                Self::RightHanded => binding,
+                Self::LeftHanded => mirrored(binding),
+            };
+            preset.set(action, binding);
+        }
+        preset
+    }
+}
+
+// Inputs of the player actions, the keyboard controlled boards keep their own keys.
+// Must be used as a resource
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputMap {
+    pub uncover: Binding,
+    pub flag: Binding,
+    pub flag_mode: Binding,
+    pub chord: Binding,
+    pub hint: Binding,
+    pub heatmap: Binding,
+    pub undo: Binding,
+    pub restart: Binding,
+    pub retry: Binding,
+    pub pause: Binding,
+    pub export: Binding,
+    pub fullscreen: Binding,
+    pub overlay: Binding,
+    // Seconds the uncover binding is held on a covered tile to flag it instead, `None` turns
+    // the long press mode off
+    pub long_press: Option<f32>,
+    // Scrolling the wheel over a covered tile cycles its mark: flag, question mark, or none
+    pub wheel_marks: bool,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            uncover: Binding::Mouse(MouseButton::Left),
+            flag: Binding::Mouse(MouseButton::Right),
+            flag_mode: Binding::Key(KeyCode::KeyM),
+            chord: Binding::Mouse(MouseButton::Middle),
+            hint: Binding::Key(KeyCode::KeyH),
+            heatmap: Binding::Key(KeyCode::KeyO),
+            undo: Binding::Key(KeyCode::KeyU),
+            restart: Binding::Key(KeyCode::F2),
+            retry: Binding::Key(KeyCode::F3),
+            pause: Binding::Key(KeyCode::KeyP),
+            export: Binding::Key(KeyCode::F12),
+            fullscreen: Binding::Key(KeyCode::F11),
+            overlay: Binding::Key(KeyCode::F9),
+            long_press: None,
+            wheel_marks: false,
+        }
+    }
+}
+
+impl InputMap {
+    pub fn get(&self, action: InputAction) -> Binding {
+        match action {
+            InputAction::Uncover => self.uncover,
+            InputAction::Flag => self.flag,
+            InputAction::FlagMode => self.flag_mode,
+            InputAction::Chord => self.chord,
+            InputAction::Hint => self.hint,
+            InputAction::Heatmap => self.heatmap,
+            InputAction::Undo => self.undo,
+            InputAction::Restart => self.restart,
+            InputAction::Retry => self.retry,
+            InputAction::Pause => self.pause,
+            InputAction::Export => self.export,
+            InputAction::Fullscreen => self.fullscreen,
+            InputAction::Overlay => self.overlay,
+        }
+    }
+
+    // Preset of the bindings, `None` for custom bindings
+    pub fn preset(&self) -> Option<InputPreset> {
+        InputPreset::ALL
+            .into_iter()
+            .find(|p| p.apply(self) == *self)
+    }
+
+    pub fn set(&mut self, action: InputAction, binding: Binding) {
+        let bound = match action {
+            InputAction::Uncover => &mut self.uncover,
+            InputAction::Flag => &mut self.flag,
+            InputAction::FlagMode => &mut self.flag_mode,
+            InputAction::Chord => &mut self.chord,
+            InputAction::Hint => &mut self.hint,
+            InputAction::Heatmap => &mut self.heatmap,
+            InputAction::Undo => &mut self.undo,
+            InputAction::Restart => &mut self.restart,
+            InputAction::Retry => &mut self.retry,
+            InputAction::Pause => &mut self.pause,
+            InputAction::Export => &mut self.export,
+            InputAction::Fullscreen => &mut self.fullscreen,
+            InputAction::Overlay => &mut self.overlay,
+        };
+        *bound = binding;
+    }
+}
+
+// Restricts the player input to the expected move, for tutorials. Must be used as a resource,
+// the player input is played freely without it
+#[derive(Resource, Debug, Copy, Clone, Default)]
+pub struct InputGate {
+    // Only move the player may play, `None` blocking the input
+    pub expected: Option<BoardCommand>,
+}
+
+impl InputGate {
+    pub fn allows(&self, command: BoardCommand) -> bool {
+        self.expected == Some(command)
+    }
+}
@@ -1,5 +1,27 @@
-pub(crate) mod tile;
-pub(crate) mod tile_map;
+pub use minesweeper_core::{tile, tile_map};
 
+pub use board::*;
+#[cfg(feature = "render")]
+pub use board_assets::*;
 pub use board_options::*;
+pub use game_timer::*;
+pub use ghost::*;
+pub use input_map::*;
+pub use minesweeper_core::{
+    BoardError, BoardMask, BombPlacement, MoveDelta, TilePayload, Treasure,
+};
+pub use pointer_mode::*;
+pub use replay::*;
+pub use scoring::*;
+pub use silhouette::*;
+mod board;
+#[cfg(feature = "render")]
+mod board_assets;
 mod board_options;
+mod game_timer;
+mod ghost;
+mod input_map;
+mod pointer_mode;
+mod replay;
+mod scoring;
+mod silhouette;
@@ -0,0 +1,35 @@
+use bevy::prelude::Resource;
+
+// Tool of the mouse over the boards, drawn by the host app with its own cursor
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PointerTool {
+    // Uncovers the clicked tiles
+    Shovel,
+    // Flags the clicked tiles
+    Flag,
+    // Asks for a hint on the clicked board
+    Magnifier,
+}
+
+// What the uncover binding does on the boards. Must be used as a resource
+#[derive(Resource, Debug, Copy, Clone, Default)]
+pub struct PointerMode {
+    // The uncover binding flags the tiles instead, for touch screens and one-button mice
+    pub flag: bool,
+    // The next click asks for a hint on the clicked board. Armed by the hint binding pressed off
+    // the boards
+    pub hint_armed: bool,
+    // Tool under the cursor, none off the playable boards
+    pub hovered: Option<PointerTool>,
+}
+
+impl PointerMode {
+    // Tool of the next click on a board
+    pub fn tool(&self) -> PointerTool {
+        match (self.hint_armed, self.flag) {
+            (true, _) => PointerTool::Magnifier,
+            (false, true) => PointerTool::Flag,
+            (false, false) => PointerTool::Shovel,
+        }
+    }
+}
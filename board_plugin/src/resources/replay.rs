@@ -0,0 +1,117 @@
+use crate::components::Coordinates;
+use crate::events::Action;
+use crate::resources::BoardOptions;
+use bevy::prelude::{warn, Component, Resource};
+use serde::{Deserialize, Serialize};
+
+// Player action on a tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    Uncover,
+    Mark,
+    // Chords the revealed number at the coordinates
+    Chord,
+    // Undoes the last move, the coordinates are unused
+    Undo,
+}
+
+// Recorded player action
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    pub coordinates: Coordinates,
+    pub action: ReplayAction,
+    // Seconds of play on the `GameTimer`, the recordings of the first versions counted the
+    // seconds since the board creation
+    pub time: f32,
+    // State hash of the board once the action was applied, the replays recorded before the
+    // hashes have none
+    #[serde(default)]
+    pub hash: Option<u64>,
+}
+
+impl ReplayEvent {
+    // Recording of a player move, none for the actions following from the moves, which are
+    // played again along with them
+    pub fn new(action: Action, time: f32, hash: u64) -> Option<Self> {
+        let (action, coordinates) = match action {
+            Action::Uncover(c) => (ReplayAction::Uncover, c),
+            Action::Chord(c) => (ReplayAction::Chord, c),
+            Action::ToggleFlag(c) => (ReplayAction::Mark, c),
+            Action::Undo => (ReplayAction::Undo, Coordinates::default()),
+            Action::Open | Action::Forgive | Action::MarkForgiven(_) | Action::FlagForced => {
+                return None
+            }
+        };
+        Some(Self {
+            coordinates,
+            action,
+            time,
+            hash: Some(hash),
+        })
+    }
+
+    // Move of the recorded action
+    pub fn action(&self) -> Action {
+        match self.action {
+            ReplayAction::Uncover => Action::Uncover(self.coordinates),
+            ReplayAction::Chord => Action::Chord(self.coordinates),
+            ReplayAction::Mark => Action::ToggleFlag(self.coordinates),
+            ReplayAction::Undo => Action::Undo,
+        }
+    }
+}
+
+// Player actions of a game, along with the options (and seed) to generate the same board
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub options: BoardOptions,
+    pub events: Vec<ReplayEvent>,
+}
+
+// Records the player actions on a board. Component of the board entity once the play starts
+#[derive(Debug, Clone, Component)]
+pub struct ReplayRecorder {
+    pub replay: Replay,
+}
+
+// Replays a recorded game instead of reading the player input. Must be used as a resource,
+// the events are played on every board
+#[derive(Resource, Debug, Clone)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    // Playback speed multiplier
+    pub speed: f32,
+    // Replay time reached by the playback
+    pub elapsed: f32,
+    // Index of the next event to play
+    pub next: usize,
+    // Index of the first event whose board state differs from the recording, the boards then
+    // play another game than the recorded one
+    pub desync: Option<usize>,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            speed: 1.,
+            elapsed: 0.,
+            next: 0,
+            desync: None,
+        }
+    }
+
+    // Checks the board state once the `index` event is applied against the recorded one
+    pub fn check(&mut self, index: usize, hash: u64) {
+        let expected = self.replay.events.get(index).and_then(|e| e.hash);
+        if self.desync.is_none() && expected.is_some_and(|e| e != hash) {
+            warn!("Replay out of sync from its action {index}");
+            self.desync = Some(index);
+        }
+    }
+
+    // Have all the events been played?
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+// Assists used during a game
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssistUsage {
+    // Requested hints
+    pub hints: u32,
+    // Times the bomb odds heatmap was shown
+    pub heatmaps: u32,
+    // Automatically flagged tiles
+    pub auto_flags: u32,
+    // Undone moves
+    pub undos: u32,
+    // Bombs hit without losing the game
+    pub forgiven_mistakes: u32,
+    // Bombs flagged by the chain reactions of the forgiven mistakes
+    pub chained_bombs: u32,
+}
+
+impl AssistUsage {
+    // Was the game played without any assist?
+    pub fn is_pure(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// Seconds added to the completion time for every use of an assist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringRules {
+    pub hint: f32,
+    pub heatmap: f32,
+    pub auto_flag: f32,
+    pub undo: f32,
+    pub forgiven_mistake: f32,
+    // Taken off the forgiven mistake penalties, which don't go below zero
+    pub chained_bomb: f32,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            hint: 10.0,
+            heatmap: 20.0,
+            auto_flag: 1.0,
+            undo: 15.0,
+            forgiven_mistake: 30.0,
+            chained_bomb: 5.0,
+        }
+    }
+}
+
+impl ScoringRules {
+    // Total time penalty of the used assists
+    pub fn time_penalty(&self, usage: &AssistUsage) -> f32 {
+        usage.hints as f32 * self.hint
+            + usage.heatmaps as f32 * self.heatmap
+            + usage.auto_flags as f32 * self.auto_flag
+            + usage.undos as f32 * self.undo
+            + self.mistake_penalty(usage)
+    }
+
+    // Penalty of the forgiven mistakes, lowered by their chained bombs
+    fn mistake_penalty(&self, usage: &AssistUsage) -> f32 {
+        let penalty = usage.forgiven_mistakes as f32 * self.forgiven_mistake;
+        (penalty - usage.chained_bombs as f32 * self.chained_bomb).max(0.)
+    }
+}
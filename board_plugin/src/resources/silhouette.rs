@@ -0,0 +1,137 @@
+use crate::components::Coordinates;
+use crate::resources::Board;
+use serde::{Deserialize, Serialize};
+
+// Visible state of a tile, without any bomb information
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TileState {
+    Covered,
+    Revealed,
+    Flagged,
+}
+
+// Tile states of a board, showing the progress of a player without spoiling the bombs
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoardSilhouette {
+    width: u16,
+    height: u16,
+    tiles: Vec<TileState>,
+}
+
+// Tile state changes from a silhouette to another, sent over the network between players
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardDiff {
+    // Board size, the diff only applies to a silhouette of this size
+    pub size: (u16, u16),
+    pub changes: Vec<(Coordinates, TileState)>,
+}
+
+impl BoardDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl BoardSilhouette {
+    pub fn covered(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![TileState::Covered; width as usize * height as usize],
+        }
+    }
+
+    pub fn from_board(board: &Board) -> Self {
+        let game = &board.game;
+        let tile_map = game.tile_map();
+        let mut silhouette = Self::covered(tile_map.width(), tile_map.height());
+        for coords in game.revealed_tiles() {
+            silhouette.set(coords, TileState::Revealed);
+        }
+        for coords in game.flags() {
+            silhouette.set(coords, TileState::Flagged);
+        }
+        silhouette
+    }
+
+    // getter for `width`
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    // getter for `height`
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn get(&self, coords: Coordinates) -> Option<TileState> {
+        self.index(coords).map(|i| self.tiles[i])
+    }
+
+    fn set(&mut self, coords: Coordinates, state: TileState) {
+        if let Some(i) = self.index(coords) {
+            self.tiles[i] = state;
+        }
+    }
+
+    fn index(&self, coords: Coordinates) -> Option<usize> {
+        if coords.x >= self.width || coords.y >= self.height {
+            return None;
+        }
+        Some(coords.y as usize * self.width as usize + coords.x as usize)
+    }
+
+    // Ratio of revealed tiles
+    pub fn progress(&self) -> f32 {
+        if self.tiles.is_empty() {
+            return 0.;
+        }
+        let revealed = self
+            .tiles
+            .iter()
+            .filter(|t| **t == TileState::Revealed)
+            .count();
+        revealed as f32 / self.tiles.len() as f32
+    }
+
+    // Number of flagged tiles
+    pub fn flags(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|t| **t == TileState::Flagged)
+            .count()
+    }
+
+    // Changes turning this silhouette into `other`
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let same_size = (self.width, self.height) == (other.width, other.height);
+        let changes = (0..other.height)
+            .flat_map(|y| (0..other.width).map(move |x| Coordinates { x, y }))
+            .filter_map(|c| {
+                let state = other.get(c)?;
+                let previous = if same_size {
+                    self.get(c)
+                } else {
+                    Some(TileState::Covered)
+                };
+                (previous != Some(state)).then_some((c, state))
+            })
+            .collect();
+        BoardDiff {
+            size: (other.width, other.height),
+            changes,
+        }
+    }
+
+    // Applies the changes of a diff of this board, returning false for a diff of another size.
+    // The changes out of the board are dropped
+    pub fn apply(&mut self, diff: &BoardDiff) -> bool {
+        if diff.size != (self.width, self.height) {
+            return false;
+        }
+        for (coords, state) in diff.changes.iter() {
+            self.set(*coords, *state);
+        }
+        true
+    }
+}
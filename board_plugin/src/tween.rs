@@ -0,0 +1,39 @@
+// Progress of an animation played off the frame time, at the same speed whatever the frame rate
+#[cfg_attr(feature = "debug", derive(bevy::reflect::Reflect))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tween {
+    elapsed: f32,
+    duration: f32,
+}
+
+impl Tween {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            elapsed: 0.,
+            duration,
+        }
+    }
+
+    // Plays `delta` more seconds of the animation
+    pub fn advance(&mut self, delta: f32) {
+        self.elapsed += delta;
+    }
+
+    // Share of the animation played, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0. {
+            return 1.;
+        }
+        (self.elapsed / self.duration).min(1.)
+    }
+
+    // Progress eased out, fast then slowing down to the end
+    pub fn eased(&self) -> f32 {
+        1. - (1. - self.progress()).powi(2)
+    }
+
+    // Is the animation over?
+    pub fn is_done(&self) -> bool {
+        self.progress() >= 1.
+    }
+}
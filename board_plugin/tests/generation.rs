@@ -0,0 +1,27 @@
+// Boards of the core presets, dealt by the board generation of the game
+use board_plugin::generation::{generate_tile_map, UniformGenerator};
+use board_plugin::resources::{BoardOptions, BombCount};
+use minesweeper_core::Preset;
+
+// The terminal front-end deals the boards of the game for the same preset and seed
+#[test]
+fn presets_deal_the_uniform_boards() {
+    for preset in Preset::ALL {
+        let options = BoardOptions {
+            map_size: preset.map_size(),
+            bomb_count: BombCount::Absolute(preset.bomb_count()),
+            safe_start: true,
+            ..Default::default()
+        };
+        for seed in [0, 42, u64::MAX] {
+            let dealt = generate_tile_map(&UniformGenerator, &options, seed);
+            let tile_map = preset.tile_map(seed);
+            assert_eq!(
+                dealt.layout_hash(),
+                tile_map.layout_hash(),
+                "{preset:?} {seed}"
+            );
+            assert_eq!(dealt.opening(), tile_map.opening(), "{preset:?} {seed}");
+        }
+    }
+}
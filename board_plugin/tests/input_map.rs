@@ -0,0 +1,31 @@
+// Key bindings saved in the settings, under their current names and the ones from before the
+// engine update
+
+use bevy::prelude::*;
+use board_plugin::resources::Binding;
+
+fn binding(text: &str) -> Binding {
+    ron::from_str(text).unwrap()
+}
+
+#[test]
+fn reads_legacy_key_names() {
+    assert_eq!(binding("Key(M)"), Binding::Key(KeyCode::KeyM));
+    assert_eq!(binding("Key(Key1)"), Binding::Key(KeyCode::Digit1));
+    assert_eq!(binding("Key(Return)"), Binding::Key(KeyCode::Enter));
+    assert_eq!(binding("Key(LShift)"), Binding::Key(KeyCode::ShiftLeft));
+}
+
+#[test]
+fn round_trips_current_key_names() {
+    for key in [
+        KeyCode::KeyA,
+        KeyCode::Digit0,
+        KeyCode::ArrowUp,
+        KeyCode::F5,
+    ] {
+        let text = ron::to_string(&Binding::Key(key)).unwrap();
+        assert_eq!(binding(&text), Binding::Key(key));
+    }
+    assert_eq!(binding("Mouse(Right)"), Binding::Mouse(MouseButton::Right));
+}
@@ -0,0 +1,77 @@
+// Window to world mapping of the cursor, on HiDPI monitors and through moved or zoomed cameras
+#![cfg(feature = "render")]
+
+use bevy::prelude::*;
+use board_plugin::screen_space::ScreenSpace;
+
+// Window size in logical pixels
+fn window() -> Vec2 {
+    Vec2::new(800., 600.)
+}
+
+// Projection of a 2D camera on the window, centered on the camera, its area scaled like the
+// camera system does
+fn projection(scale: f32) -> OrthographicProjection {
+    OrthographicProjection {
+        area: Rect::from_center_half_size(Vec2::ZERO, window() / 2. * scale),
+        scale,
+        ..OrthographicProjection::default_2d()
+    }
+}
+
+fn assert_close(a: Vec2, b: Vec2) {
+    assert!((a - b).length() < 1e-3, "{a} != {b}");
+}
+
+#[test]
+fn window_center_is_the_camera_position() {
+    let screen = ScreenSpace::new(window(), 1.);
+    assert_close(screen.window_to_world(window() / 2.), Vec2::ZERO);
+    // the window origin is its top left corner
+    assert_close(
+        screen.window_to_world(Vec2::ZERO),
+        Vec2::new(-window().x / 2., window().y / 2.),
+    );
+
+    let camera = GlobalTransform::from_translation(Vec3::new(120., -40., 999.));
+    let screen = screen.with_camera(&camera, &projection(1.));
+    assert_close(screen.window_to_world(window() / 2.), Vec2::new(120., -40.));
+    assert_close(
+        screen.window_to_world(window() / 2. + Vec2::new(10., 20.)),
+        Vec2::new(130., -60.),
+    );
+}
+
+#[test]
+fn physical_positions_are_scaled_down_on_hidpi() {
+    let screen = ScreenSpace::new(window(), 2.);
+    let logical = Vec2::new(100., 250.);
+    assert_close(
+        screen.physical_to_world(logical * 2.),
+        screen.window_to_world(logical),
+    );
+    assert_close(screen.window_to_world(logical), Vec2::new(-300., 50.));
+}
+
+#[test]
+fn zoomed_cameras_scale_the_offsets() {
+    let camera = GlobalTransform::from_translation(Vec3::new(50., 50., 999.));
+    let screen = ScreenSpace::new(window(), 1.).with_camera(&camera, &projection(0.5));
+    assert_close(
+        screen.window_to_world(window() / 2. + Vec2::new(100., -60.)),
+        Vec2::new(100., 80.),
+    );
+    // zoomed out
+    let screen = ScreenSpace::new(window(), 1.).with_camera(&camera, &projection(2.));
+    assert_close(screen.window_to_world(Vec2::ZERO), Vec2::new(-750., 650.));
+}
+
+#[test]
+fn world_to_window_inverts_window_to_world() {
+    let camera = GlobalTransform::from_translation(Vec3::new(-300., 75., 999.));
+    let screen = ScreenSpace::new(window(), 1.5).with_camera(&camera, &projection(1.25));
+    for position in [Vec2::ZERO, window() / 2., Vec2::new(13., 577.), window()] {
+        let world = screen.window_to_world(position);
+        assert_close(screen.world_to_window(world), position);
+    }
+}
@@ -0,0 +1,38 @@
+// Opponent diffs applied to a silhouette, as received from a peer
+use board_plugin::components::Coordinates;
+use board_plugin::resources::{BoardDiff, BoardSilhouette, TileState};
+
+fn diff(size: (u16, u16), changes: &[((u16, u16), TileState)]) -> BoardDiff {
+    BoardDiff {
+        size,
+        changes: changes
+            .iter()
+            .map(|&((x, y), state)| (Coordinates { x, y }, state))
+            .collect(),
+    }
+}
+
+#[test]
+fn rejects_diffs_of_another_size() {
+    let mut silhouette = BoardSilhouette::covered(9, 9);
+    let huge = diff((u16::MAX, u16::MAX), &[((0, 0), TileState::Revealed)]);
+    assert!(!silhouette.apply(&huge));
+    assert_eq!(silhouette, BoardSilhouette::covered(9, 9));
+}
+
+#[test]
+fn drops_changes_out_of_the_board() {
+    let mut silhouette = BoardSilhouette::covered(9, 9);
+    let changes = [
+        ((1, 2), TileState::Revealed),
+        ((9, 0), TileState::Flagged),
+        ((0, 500), TileState::Revealed),
+    ];
+    assert!(silhouette.apply(&diff((9, 9), &changes)));
+    assert_eq!(
+        silhouette.get(Coordinates { x: 1, y: 2 }),
+        Some(TileState::Revealed)
+    );
+    assert_eq!(silhouette.flags(), 0);
+    assert_eq!((silhouette.width(), silhouette.height()), (9, 9));
+}
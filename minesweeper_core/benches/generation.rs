@@ -0,0 +1,60 @@
+// Tile map generation and flood fill timings, from the classic presets up to huge dense boards.
+// Run with `cargo bench -p minesweeper_core`
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use minesweeper_core::{Game, TileMap};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::hint::black_box;
+
+// Board sizes and bomb densities
+const BOARDS: [(&str, u16, u16, f32); 5] = [
+    ("beginner", 9, 9, 0.12),
+    ("expert", 30, 16, 0.21),
+    ("large", 100, 100, 0.2),
+    ("huge", 500, 500, 0.2),
+    ("huge_dense", 500, 500, 0.9),
+];
+
+fn bomb_count(width: u16, height: u16, density: f32) -> u32 {
+    (width as f32 * height as f32 * density) as u32
+}
+
+fn set_bombs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("set_bombs");
+    group.sample_size(20);
+    for (name, width, height, density) in BOARDS {
+        let bomb_count = bomb_count(width, height, density);
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            let mut rng = StdRng::seed_from_u64(0);
+            b.iter(|| {
+                let mut tile_map = TileMap::empty(width, height);
+                tile_map.set_bombs(bomb_count, &mut rng);
+                black_box(tile_map)
+            })
+        });
+    }
+    group.finish();
+}
+
+// Uncovering the opening of sparse boards, which reveals most of the tiles
+fn flood_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flood_fill");
+    group.sample_size(20);
+    for (name, size) in [("expert", 30), ("large", 100), ("huge", 500)] {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut tile_map = TileMap::empty(size, size);
+        tile_map.set_bombs(bomb_count(size, size, 0.05), &mut rng);
+        let opening = tile_map.random_empty_tile(&mut rng).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter_batched(
+                || Game::new(tile_map.clone()),
+                |mut game| black_box(game.reveal(opening).map(|d| d.revealed.len())),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, set_bombs, flood_fill);
+criterion_main!(benches);
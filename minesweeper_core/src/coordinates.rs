@@ -1,9 +1,10 @@
-use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Sub};
 
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
@@ -24,11 +25,12 @@ impl Add for Coordinates {
 impl Add<(i8, i8)> for Coordinates {
     type Output = Self;
 
+    // wraps around below 0, out of any tile map bounds
     fn add(self, (x, y): (i8, i8)) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
-
-        Self { x, y }
+        Self {
+            x: self.x.wrapping_add_signed(x.into()),
+            y: self.y.wrapping_add_signed(y.into()),
+        }
     }
 }
 
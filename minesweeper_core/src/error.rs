@@ -0,0 +1,125 @@
+use crate::Coordinates;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+// Board configurations no tile map can be generated for
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BoardError {
+    // The map has no tile, from a 0 size or a mask excluding everything
+    EmptyMap,
+    // More bombs than tiles
+    TooManyBombs {
+        bomb_count: u32,
+        tile_count: usize,
+    },
+    // Too many bombs to keep a tile and its neighbors free for the safe start
+    NoSafeStart {
+        bomb_count: u32,
+        max_bomb_count: usize,
+    },
+    // Neighbor kernel the numbers can't be played with, see `NeighborKernel::is_valid`
+    InvalidKernel,
+    // Wrapped hexagonal map of an odd row count, whose top and bottom rows don't fit together
+    OddWrappedHexRows,
+    // A side longer than the bound of options received from outside the game
+    TooLarge {
+        map_size: (u16, u16),
+        max_side: u16,
+    },
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMap => write!(f, "the board has no tile"),
+            Self::TooManyBombs {
+                bomb_count,
+                tile_count,
+            } => write!(
+                f,
+                "{bomb_count} bombs don't fit on a board of {tile_count} tiles"
+            ),
+            Self::NoSafeStart {
+                bomb_count,
+                max_bomb_count,
+            } => write!(
+                f,
+                "{bomb_count} bombs leave no room for a safe start, the board fits {max_bomb_count} at most"
+            ),
+            Self::InvalidKernel => write!(
+                f,
+                "the neighbor kernel must have at most 8 deltas, in opposite pairs"
+            ),
+            Self::OddWrappedHexRows => {
+                write!(f, "wrapped hexagonal boards need an even number of rows")
+            }
+            Self::TooLarge {
+                map_size: (width, height),
+                max_side,
+            } => write!(
+                f,
+                "a {width}x{height} board is too large, the sides are bounded to {max_side} tiles"
+            ),
+        }
+    }
+}
+
+impl Error for BoardError {}
+
+// Text boards `TileMap::from_text` can't read
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParseMapError {
+    // The text has no row of tiles
+    NoTiles,
+    // A row not as long as the top one
+    UnevenRow {
+        y: u16,
+        length: usize,
+        width: usize,
+    },
+    UnknownTile {
+        coordinates: Coordinates,
+        found: char,
+    },
+    // A written bomb count not matching the bombs around the tile
+    WrongCount {
+        coordinates: Coordinates,
+        written: u8,
+        actual: u8,
+    },
+    // More columns or rows than a tile map holds
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl Display for ParseMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTiles => write!(f, "the text has no row of tiles"),
+            Self::UnevenRow { y, length, width } => write!(
+                f,
+                "row {y} has {length} tiles, the board is {width} tiles wide"
+            ),
+            Self::UnknownTile { coordinates, found } => {
+                write!(f, "unknown tile {found:?} on {coordinates}")
+            }
+            Self::WrongCount {
+                coordinates,
+                written,
+                actual,
+            } => write!(
+                f,
+                "the tile on {coordinates} reads {written} instead of {actual}"
+            ),
+            Self::TooLarge { width, height } => write!(
+                f,
+                "the board is {width}x{height} tiles, tile maps hold up to {0}x{0}",
+                u16::MAX
+            ),
+        }
+    }
+}
+
+impl Error for ParseMapError {}
@@ -0,0 +1,521 @@
+use crate::solver::{Deduction, Solver};
+use crate::tile_map::framed;
+use crate::{Coordinates, StateHasher, Tile, TileMap, TileSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+// Move on a game, played by a player or following from the rules of the board. Games of the same
+// tile map applying the same actions in the same order end up in the same state, with the same
+// state hash
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Uncover(Coordinates),
+    // Uncovers the covered neighbors of the revealed number
+    Chord(Coordinates),
+    ToggleFlag(Coordinates),
+    // Reverts the last move
+    Undo,
+    // Uncovers the opening tile and the starting reveals, before any player move
+    Open,
+    // Takes back the losing move of a spent life, see `Game::forgive`
+    Forgive,
+    // Marks a bomb as forgiven, see `Game::mark_forgiven`
+    MarkForgiven(Coordinates),
+    // Flags the bombs forced by a single revealed number, see `Game::flag_forced_bombs`
+    FlagForced,
+}
+
+impl Action {
+    // Tile the action is played on, none for the undos and the actions on the whole board
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        match *self {
+            Self::Uncover(c) | Self::Chord(c) | Self::ToggleFlag(c) | Self::MarkForgiven(c) => {
+                Some(c)
+            }
+            Self::Undo | Self::Open | Self::Forgive | Self::FlagForced => None,
+        }
+    }
+
+    // Is the action played by a player? The others follow from the player moves and the board
+    // rules, and are played again along with them
+    pub fn is_move(&self) -> bool {
+        match self {
+            Self::Uncover(_) | Self::Chord(_) | Self::ToggleFlag(_) | Self::Undo => true,
+            Self::Open | Self::Forgive | Self::MarkForgiven(_) | Self::FlagForced => false,
+        }
+    }
+}
+
+// Tiles changed by a player move, reverted by an undo
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MoveDelta {
+    // Uncovered tiles, including the propagation to empty neighbors, closest to the uncovered
+    // tile first
+    pub revealed: Vec<Coordinates>,
+    // Tiles whose flag was toggled, including the flags removed by uncovering
+    pub flags: Vec<Coordinates>,
+    // Flag stacks changed on multi-mine boards, with their flag count before the move
+    pub stacked: Vec<(Coordinates, u8)>,
+}
+
+// Progress of a game
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameState {
+    Playing,
+    // Every safe tile is uncovered
+    Won,
+    // A bomb was uncovered
+    Lost,
+}
+
+// Play of a tile map: covered tiles, flags and move history.
+// Moves are refused once the game is over, until an undo
+#[derive(Debug, Clone)]
+pub struct Game {
+    tile_map: TileMap,
+    covered: TileSet,
+    flags: TileSet,
+    // Flag count of the flagged tiles holding more than one flag, on multi-mine boards
+    stacks: HashMap<Coordinates, u8>,
+    // Player moves, most recent last
+    history: Vec<MoveDelta>,
+    // Moves tried while playing, including the refused ones and the undone ones
+    clicks: u32,
+    // Moves tried while playing which changed nothing
+    wasted_clicks: u32,
+    exploded: bool,
+    // Refuses the flags beyond the bomb count
+    strict_flags: bool,
+    // Refuses every flag, for no-flag (NF) play
+    no_flags: bool,
+}
+
+impl Game {
+    // Starts a game with every tile covered
+    pub fn new(tile_map: TileMap) -> Self {
+        let (width, height) = (tile_map.width(), tile_map.height());
+        let mut covered = TileSet::new(width, height);
+        covered.extend(tile_map.tiles().map(|(c, _)| c));
+        Self {
+            tile_map,
+            covered,
+            flags: TileSet::new(width, height),
+            stacks: HashMap::new(),
+            history: Vec::new(),
+            clicks: 0,
+            wasted_clicks: 0,
+            exploded: false,
+            strict_flags: false,
+            no_flags: false,
+        }
+    }
+
+    // Strict games refuse the flags beyond the bomb count, so the mines left never go negative
+    pub fn with_strict_flags(mut self, strict_flags: bool) -> Self {
+        self.strict_flags = strict_flags;
+        self
+    }
+
+    // No-flag games refuse every flag, the forced bombs included. The forgiven bombs are still
+    // marked, see `mark_forgiven`
+    pub fn with_no_flags(mut self, no_flags: bool) -> Self {
+        self.no_flags = no_flags;
+        self
+    }
+
+    // getter for `tile_map`
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    // getter for `flags`
+    pub fn flags(&self) -> &TileSet {
+        &self.flags
+    }
+
+    // getter for `history`
+    pub fn history(&self) -> &[MoveDelta] {
+        &self.history
+    }
+
+    // getter for `clicks`
+    pub fn clicks(&self) -> u32 {
+        self.clicks
+    }
+
+    // getter for `wasted_clicks`
+    pub fn wasted_clicks(&self) -> u32 {
+        self.wasted_clicks
+    }
+
+    pub fn is_covered(&self, coords: Coordinates) -> bool {
+        self.covered.contains(coords)
+    }
+
+    pub fn is_revealed(&self, coords: Coordinates) -> bool {
+        self.tile_map.exists(coords) && !self.covered.contains(coords)
+    }
+
+    pub fn is_flagged(&self, coords: Coordinates) -> bool {
+        self.flags.contains(coords)
+    }
+
+    // Flags placed on a tile, up to the most mines a bomb holds on multi-mine boards
+    pub fn flag_count(&self, coords: Coordinates) -> u8 {
+        match self.is_flagged(coords) {
+            true => self.stacks.get(&coords).copied().unwrap_or(1),
+            false => 0,
+        }
+    }
+
+    // Flags around a tile, the count chording compares to the number of the tile
+    pub fn flagged_neighbors(&self, coords: Coordinates) -> usize {
+        self.tile_map
+            .neighbors(coords)
+            .map(|c| self.flag_count(c) as usize)
+            .sum()
+    }
+
+    // Covered neighbors of a tile, flagged or not
+    pub fn covered_neighbors(&self, coords: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tile_map
+            .neighbors(coords)
+            .filter(|c| self.covered.contains(*c))
+    }
+
+    // Mine count minus the flags, negative when the player placed too many flags
+    pub fn mines_left(&self) -> i64 {
+        let stacked: usize = self.stacks.values().map(|s| *s as usize - 1).sum();
+        self.tile_map.mine_count() as i64 - (self.flags.len() + stacked) as i64
+    }
+
+    // Flags which can still be placed, unlimited outside strict and no-flag games
+    fn flags_allowed(&self) -> usize {
+        match (self.no_flags, self.strict_flags) {
+            (true, _) => 0,
+            (false, true) => self.mines_left().max(0) as usize,
+            (false, false) => usize::MAX,
+        }
+    }
+
+    // Has a bomb been uncovered?
+    pub fn is_exploded(&self) -> bool {
+        self.exploded
+    }
+
+    // Is every safe tile uncovered?
+    pub fn is_completed(&self) -> bool {
+        !self.exploded && self.tile_map.bomb_count() as usize == self.covered.len()
+    }
+
+    pub fn state(&self) -> GameState {
+        if self.exploded {
+            GameState::Lost
+        } else if self.is_completed() {
+            GameState::Won
+        } else {
+            GameState::Playing
+        }
+    }
+
+    // Tiles which aren't covered anymore
+    pub fn revealed_tiles(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tile_map
+            .tiles()
+            .map(|(c, _)| c)
+            .filter(|c| !self.covered.contains(*c))
+    }
+
+    // Plain text of the tile map, followed by the player view: the revealed tiles, `?` for the
+    // covered tiles and `F` for the flags
+    pub fn text_output(&self) -> String {
+        let rows = (0..self.tile_map.height()).rev().map(|y| {
+            (0..self.tile_map.width())
+                .map(|x| {
+                    let coords = Coordinates { x, y };
+                    let tile = self.tile_map[y as usize][x as usize];
+                    match (self.is_covered(coords), self.is_flagged(coords)) {
+                        _ if !tile.exists() => tile.text_output(),
+                        (true, true) => 'F',
+                        (true, false) => '?',
+                        (false, _) => tile.text_output(),
+                    }
+                })
+                .collect()
+        });
+        let view = framed("Player view:", self.tile_map.width(), rows);
+        format!("{}\n{view}", self.tile_map.text_output())
+    }
+
+    // Solver knowing what the player sees
+    pub fn solver(&self) -> Solver<'_> {
+        Solver::with_state(&self.tile_map, self.revealed_tiles(), [])
+    }
+
+    // Hash of the covered tiles, the flags and the explosion, to tell apart two games of the
+    // same tile map which should be in the same state. Stable across platforms and builds
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        for (coords, _) in self.tile_map.tiles() {
+            hasher.write_u8(self.covered.contains(coords) as u8);
+            hasher.write_u8(self.flag_count(coords));
+        }
+        hasher.write_u8(self.exploded as u8);
+        hasher.finish()
+    }
+
+    // Plays an action, returning whether it changed the game
+    pub fn apply(&mut self, action: Action) -> bool {
+        match action {
+            Action::Uncover(coords) => self.reveal(coords).is_some(),
+            Action::Chord(coords) => self.chord(coords).is_some(),
+            Action::ToggleFlag(coords) => self.toggle_flag(coords).is_some(),
+            Action::Undo => self.undo().is_some(),
+            Action::Open => self.open().is_some(),
+            Action::Forgive => self.forgive().is_some(),
+            Action::MarkForgiven(coords) => self.mark_forgiven(coords),
+            Action::FlagForced => !self.flag_forced_bombs().is_empty(),
+        }
+    }
+
+    // Uncovers the opening tile and the starting reveals of the tile map, without recording a
+    // move
+    pub fn open(&mut self) -> Option<MoveDelta> {
+        let starts: Vec<Coordinates> = self
+            .tile_map
+            .opening()
+            .into_iter()
+            .chain(self.tile_map.reveals().iter().copied())
+            .collect();
+        let mut opened: Option<MoveDelta> = None;
+        for coords in starts {
+            if let Some(delta) = self.uncover(coords) {
+                let opened = opened.get_or_insert_with(MoveDelta::default);
+                opened.revealed.extend(delta.revealed);
+                opened.flags.extend(delta.flags);
+                opened.stacked.extend(delta.stacked);
+            }
+        }
+        opened
+    }
+
+    // Uncovers a covered tile, propagating to the neighbors of empty tiles.
+    // Flagged tiles must be unflagged first
+    pub fn reveal(&mut self, coords: Coordinates) -> Option<&MoveDelta> {
+        let playing = self.state() == GameState::Playing;
+        let delta = self.uncover(coords);
+        self.click(playing, delta.is_some());
+        self.history.push(delta?);
+        self.history.last()
+    }
+
+    // Uncovers the covered neighbors of a revealed number, as a single move, once as many
+    // neighbors are flagged as its bomb count. Flagged neighbors stay covered
+    pub fn chord(&mut self, coords: Coordinates) -> Option<&MoveDelta> {
+        let playing = self.state() == GameState::Playing;
+        let delta = self.chord_delta(coords);
+        self.click(playing, delta.is_some());
+        self.history.push(delta?);
+        self.history.last()
+    }
+
+    fn chord_delta(&mut self, coords: Coordinates) -> Option<MoveDelta> {
+        if self.state() != GameState::Playing || self.covered.contains(coords) {
+            return None;
+        }
+        let count = match self.tile_map[coords.y as usize][coords.x as usize] {
+            Tile::BombNeighbor(count) => count as usize,
+            _ => return None,
+        };
+        if self.flagged_neighbors(coords) != count {
+            return None;
+        }
+        let neighbors: Vec<Coordinates> = self.covered_neighbors(coords).collect();
+
+        // a misplaced flag explodes a bomb, stopping the chord
+        let mut delta = MoveDelta::default();
+        for neighbor in neighbors {
+            if let Some(d) = self.uncover(neighbor) {
+                delta.revealed.extend(d.revealed);
+                delta.flags.extend(d.flags);
+                delta.stacked.extend(d.stacked);
+            }
+        }
+        if delta.revealed.is_empty() {
+            return None;
+        }
+        Some(delta)
+    }
+
+    fn uncover(&mut self, coords: Coordinates) -> Option<MoveDelta> {
+        if self.state() != GameState::Playing
+            || !self.covered.contains(coords)
+            || self.is_flagged(coords)
+        {
+            return None;
+        }
+
+        // breadth first, the tiles leaving the covered set as they are queued so that each
+        // tile is queued once
+        let mut delta = MoveDelta::default();
+        self.covered.remove(coords);
+        let mut queue = VecDeque::from([coords]);
+        while let Some(coords) = queue.pop_front() {
+            delta.revealed.push(coords);
+            // the propagation clears the flags in its way
+            let stack = self.flag_count(coords);
+            if self.unflag(coords) {
+                delta.flags.push(coords);
+            }
+            if stack > 1 {
+                delta.stacked.push((coords, stack));
+            }
+            match self.tile_map[coords.y as usize][coords.x as usize] {
+                Tile::Bomb(_) => self.exploded = true,
+                Tile::Empty => {
+                    for neighbor in self.tile_map.neighbors(coords) {
+                        if self.covered.remove(neighbor) {
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+                Tile::BombNeighbor(_) | Tile::Void => (),
+            }
+        }
+        Some(delta)
+    }
+
+    // Toggles the flag of a covered tile, returning the new flag state. On multi-mine boards
+    // the flags of a tile stack up to the most mines of a bomb before being removed. Strict
+    // games refuse new flags once there are as many flags as mines, no-flag games refuse them all
+    pub fn toggle_flag(&mut self, coords: Coordinates) -> Option<bool> {
+        let playing = self.state() == GameState::Playing;
+        let covered = self.covered.contains(coords);
+        let count = self.flag_count(coords);
+        let removed = count >= self.tile_map.max_mines();
+        let allowed = removed || self.flags_allowed() > 0;
+        self.click(playing, covered && allowed);
+        if !playing || !covered || !allowed {
+            return None;
+        }
+        let mut delta = MoveDelta::default();
+        // single flags removed from single mine boards leave no stack behind
+        if count > 1 || (count == 1 && !removed) {
+            delta.stacked.push((coords, count));
+        }
+        match count == 0 || removed {
+            true => {
+                self.toggle(coords);
+                delta.flags.push(coords);
+            }
+            false => {
+                self.stacks.insert(coords, count + 1);
+            }
+        }
+        self.history.push(delta);
+        Some(!removed)
+    }
+
+    // Marks a bomb the player was forgiven for, flagging all of its mines whatever the flag
+    // rules, as a move of its own which isn't a click. Returns false when the bomb is already
+    // marked, uncovered or not a bomb
+    pub fn mark_forgiven(&mut self, coords: Coordinates) -> bool {
+        let mines = self.tile_map.mines_at(coords);
+        let count = self.flag_count(coords);
+        if self.state() != GameState::Playing || !self.covered.contains(coords) || count >= mines {
+            return false;
+        }
+        let mut delta = MoveDelta::default();
+        match count {
+            0 => {
+                self.flags.insert(coords);
+                delta.flags.push(coords);
+            }
+            _ => delta.stacked.push((coords, count)),
+        }
+        if mines > 1 {
+            self.stacks.insert(coords, mines);
+            if count == 0 {
+                delta.stacked.push((coords, 1));
+            }
+        }
+        self.history.push(delta);
+        true
+    }
+
+    // Takes back the losing move and marks its bombs as forgiven instead, the mistake being paid
+    // for with a life. Returns the taken back move, none when the game isn't lost
+    pub fn forgive(&mut self) -> Option<MoveDelta> {
+        if self.state() != GameState::Lost {
+            return None;
+        }
+        let delta = self.undo()?;
+        for coords in delta.revealed.iter() {
+            if self.tile_map.is_bomb_at(*coords) {
+                self.mark_forgiven(*coords);
+            }
+        }
+        Some(delta)
+    }
+
+    // Flags the bombs trivially forced by a single revealed number, as part of the last move
+    pub fn flag_forced_bombs(&mut self) -> Vec<Coordinates> {
+        if self.state() != GameState::Playing {
+            return Vec::new();
+        }
+        let bombs: Vec<Coordinates> = self
+            .solver()
+            .single_tile_deductions()
+            .into_iter()
+            .filter_map(|d| match d {
+                Deduction::Bomb(c) => Some(c),
+                Deduction::Safe(_) => None,
+            })
+            .filter(|c| !self.is_flagged(*c))
+            .take(self.flags_allowed())
+            .collect();
+        for coords in bombs.iter() {
+            self.flags.insert(*coords);
+            if let Some(delta) = self.history.last_mut() {
+                delta.flags.push(*coords);
+            }
+        }
+        bombs
+    }
+
+    // Reverts the last move, covering its uncovered tiles again and restoring its flags
+    pub fn undo(&mut self) -> Option<MoveDelta> {
+        let delta = self.history.pop()?;
+        self.covered.extend(delta.revealed.iter().copied());
+        for coords in delta.flags.iter() {
+            self.toggle(*coords);
+        }
+        for (coords, count) in delta.stacked.iter() {
+            match count {
+                1 => self.stacks.remove(coords),
+                _ => self.stacks.insert(*coords, *count),
+            };
+        }
+        self.exploded = false;
+        Some(delta)
+    }
+
+    // Counts a player move, the moves tried once the game is over don't count
+    fn click(&mut self, playing: bool, changed: bool) {
+        if playing {
+            self.clicks += 1;
+            if !changed {
+                self.wasted_clicks += 1;
+            }
+        }
+    }
+
+    fn toggle(&mut self, coords: Coordinates) -> bool {
+        !self.unflag(coords) && self.flags.insert(coords)
+    }
+
+    fn unflag(&mut self, coords: Coordinates) -> bool {
+        self.stacks.remove(&coords);
+        self.flags.remove(coords)
+    }
+}
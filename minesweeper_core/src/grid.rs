@@ -0,0 +1,154 @@
+use crate::Coordinates;
+use serde::{Deserialize, Serialize};
+
+// Tiling of the board, defining the tile neighbors
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GridKind {
+    // Square tiles with 8 neighbors
+    #[default]
+    Square,
+    // Pointy top hexagonal tiles with 6 neighbors, odd rows shifted to the right
+    Hex,
+}
+
+impl GridKind {
+    // Neighbor deltas of a tile, they depend on the row for hexagonal offset coordinates
+    pub fn neighbor_deltas(&self, coordinates: Coordinates) -> &'static [(i8, i8)] {
+        match self {
+            Self::Square => &SQUARE_COORDINATES,
+            Self::Hex if coordinates.y % 2 == 1 => &HEX_ODD_ROW_COORDINATES,
+            Self::Hex => &HEX_EVEN_ROW_COORDINATES,
+        }
+    }
+
+    // Deltas of the neighbors sharing an edge with a tile: the orthogonal ones on squares, every
+    // neighbor on hexagons
+    pub fn edge_deltas(&self, coordinates: Coordinates) -> &'static [(i8, i8)] {
+        match self {
+            Self::Square => &SQUARE_EDGE_COORDINATES,
+            Self::Hex => self.neighbor_deltas(coordinates),
+        }
+    }
+}
+
+// Tiles counted by the numbers of a board, the neighbors flooded by the openings and chorded
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NeighborKernel {
+    // Neighbors of the tiling
+    Grid,
+    // Tiles a knight's move away, on any tiling
+    Knight,
+    // Deltas from the tile, at most 8 and coming in opposite pairs
+    Deltas(Vec<(i8, i8)>),
+}
+
+impl Default for NeighborKernel {
+    fn default() -> Self {
+        Self::Grid
+    }
+}
+
+impl NeighborKernel {
+    // Neighbor deltas of a tile on `grid`
+    pub fn deltas(&self, grid: GridKind, coordinates: Coordinates) -> &[(i8, i8)] {
+        match self {
+            Self::Grid => grid.neighbor_deltas(coordinates),
+            Self::Knight => &KNIGHT_COORDINATES,
+            Self::Deltas(deltas) => deltas,
+        }
+    }
+
+    // Can the numbers be played with the kernel? A tile counts the tiles counting it, which
+    // the solver relies on, the tile itself isn't counted and it has at most 8 neighbors, as on
+    // the square grid, so a count stays within 24 with bombs of three mines
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Grid | Self::Knight => true,
+            Self::Deltas(deltas) => {
+                deltas.len() <= 8
+                    && deltas.iter().all(|&(x, y)| {
+                        (x, y) != (0, 0)
+                            && x != i8::MIN
+                            && y != i8::MIN
+                            && deltas.contains(&(-x, -y))
+                    })
+            }
+        }
+    }
+}
+
+// Delta coordinates for all 8 square neighbors
+const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    // Bottom Left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom Right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top Right
+    (1, 1),
+];
+
+// Delta coordinates for the 4 square neighbors sharing an edge
+const SQUARE_EDGE_COORDINATES: [(i8, i8); 4] = [
+    // Bottom
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top
+    (0, 1),
+];
+
+// Delta coordinates for the 8 knight's moves
+const KNIGHT_COORDINATES: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+// Delta coordinates for the 6 hexagonal neighbors of a tile on an even row
+const HEX_EVEN_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom Left
+    (-1, -1),
+    // Bottom Right
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top Right
+    (0, 1),
+];
+
+// Delta coordinates for the 6 hexagonal neighbors of a tile on an odd row
+const HEX_ODD_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom Left
+    (0, -1),
+    // Bottom Right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (0, 1),
+    // Top Right
+    (1, 1),
+];
@@ -0,0 +1,42 @@
+// FNV-1a parameters, the same on every platform and build
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+// FNV-1a hash of the board states. Unlike the std hashers its value never changes between
+// platforms, builds or runs, so hashes can be saved in replays and compared over the network
+#[derive(Debug, Copy, Clone)]
+pub struct StateHasher(u64);
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StateHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_u8(&mut self, byte: u8) {
+        self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        value
+            .to_le_bytes()
+            .into_iter()
+            .for_each(|b| self.write_u8(b));
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        value
+            .to_le_bytes()
+            .into_iter()
+            .for_each(|b| self.write_u8(b));
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
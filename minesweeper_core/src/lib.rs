@@ -0,0 +1,31 @@
+// Minesweeper rules without any engine: tile maps, the play of a game and the solver.
+// Front-ends present a `Game` and forward the player moves to it
+
+// Defaults are written as `impl Default` blocks, as in the board plugin
+#![allow(clippy::derivable_impls)]
+pub mod coordinates;
+pub mod error;
+pub mod game;
+pub mod grid;
+pub mod hash;
+pub mod mask;
+pub mod metrics;
+pub mod preset;
+pub mod solver;
+pub mod tile;
+pub mod tile_layer;
+pub mod tile_map;
+pub mod tile_set;
+
+pub use coordinates::Coordinates;
+pub use error::{BoardError, ParseMapError};
+pub use game::{Action, Game, GameState, MoveDelta};
+pub use grid::{GridKind, NeighborKernel};
+pub use hash::StateHasher;
+pub use mask::BoardMask;
+pub use metrics::Metrics;
+pub use preset::Preset;
+pub use tile::Tile;
+pub use tile_layer::{TileLayer, TilePayload, Treasure};
+pub use tile_map::{BombPlacement, TileMap};
+pub use tile_set::TileSet;
@@ -0,0 +1,89 @@
+use crate::Coordinates;
+use serde::{Deserialize, Serialize};
+
+// Heart stencil, see `BoardMask::from_text`
+const HEART: &str = "
+..###...###..
+.#####.#####.
+#############
+#############
+#############
+.###########.
+..#########..
+...#######...
+....#####....
+.....###.....
+......#......
+";
+
+// Cells of a shaped board. Cells outside of the mask bounds don't exist either
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoardMask {
+    // Existing cells, indexed like the tile map: `rows[y][x]` with the bottom row first
+    rows: Vec<Vec<bool>>,
+}
+
+impl BoardMask {
+    pub fn new(rows: Vec<Vec<bool>>) -> Self {
+        Self { rows }
+    }
+
+    // Parses a text stencil, top row first: `#` marks an existing cell, any other character a hole
+    pub fn from_text(text: &str) -> Self {
+        let rows = text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.trim().chars().map(|c| c == '#').collect())
+            .collect::<Vec<_>>();
+        Self::new(rows.into_iter().rev().collect())
+    }
+
+    // Builds a mask of the given size from a cell predicate
+    fn from_fn(width: u16, height: u16, exists: impl Fn(f32, f32) -> bool) -> Self {
+        // cell centers, relative to the mask center and scaled to [-1, 1]
+        let scale = |v: u16, size: u16| (v as f32 + 0.5) / size as f32 * 2. - 1.;
+        Self::new(
+            (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| exists(scale(x, width), scale(y, height)))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn diamond(width: u16, height: u16) -> Self {
+        Self::from_fn(width, height, |x, y| x.abs() + y.abs() <= 1.)
+    }
+
+    pub fn donut(width: u16, height: u16) -> Self {
+        Self::from_fn(width, height, |x, y| {
+            let distance = (x * x + y * y).sqrt();
+            (0.4..=1.).contains(&distance)
+        })
+    }
+
+    // Triangle pointing up
+    pub fn triangle(width: u16, height: u16) -> Self {
+        Self::from_fn(width, height, |x, y| x.abs() <= (1. - y) / 2.)
+    }
+
+    pub fn heart() -> Self {
+        Self::from_text(HEART)
+    }
+
+    // Bounding size of the mask
+    pub fn size(&self) -> (u16, u16) {
+        let width = self.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        (width as u16, self.rows.len() as u16)
+    }
+
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        self.rows
+            .get(coords.y as usize)
+            .and_then(|row| row.get(coords.x as usize))
+            .copied()
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Game;
+
+// Standard measures of a played game, for the end of game summaries, the stats and the replays
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Metrics {
+    // 3BV of the board, see `TileMap::three_bv`
+    pub three_bv: u32,
+    // 3BV of the revealed tiles, the whole 3BV of a cleared board
+    pub solved_three_bv: u32,
+    // Moves tried while playing, see `Game::clicks`
+    pub clicks: u32,
+    // Moves which changed nothing, such as clicks on revealed tiles
+    pub wasted_clicks: u32,
+    // Seconds of play
+    pub time: f32,
+}
+
+impl Metrics {
+    // Measures a game played in `time` seconds
+    pub fn new(game: &Game, time: f32) -> Self {
+        let tile_map = game.tile_map();
+        Self {
+            three_bv: tile_map.three_bv(),
+            solved_three_bv: tile_map.solved_three_bv(|c| !game.is_covered(c)),
+            clicks: game.clicks(),
+            wasted_clicks: game.wasted_clicks(),
+            time,
+        }
+    }
+
+    // Solved 3BV per second
+    pub fn three_bv_per_second(&self) -> f32 {
+        match self.time > 0. {
+            true => self.solved_three_bv as f32 / self.time,
+            false => 0.,
+        }
+    }
+
+    // Solved 3BV per click, in percent. The opening of a safe start is solved without a click,
+    // so efficient games may go over 100%
+    pub fn efficiency(&self) -> Option<f32> {
+        match self.clicks {
+            0 => None,
+            clicks => Some(self.solved_three_bv as f32 * 100. / clicks as f32),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::TileMap;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+// Classic board sizes and bomb counts, shared by the front-ends
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Preset {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Preset {
+    pub const ALL: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    // Preset of a command line name, in lowercase
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    // Command line name of the preset
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Beginner => "beginner",
+            Self::Intermediate => "intermediate",
+            Self::Expert => "expert",
+        }
+    }
+
+    pub fn map_size(&self) -> (u16, u16) {
+        match self {
+            Self::Beginner => (9, 9),
+            Self::Intermediate => (16, 16),
+            Self::Expert => (30, 16),
+        }
+    }
+
+    pub fn bomb_count(&self) -> u16 {
+        match self {
+            Self::Beginner => 10,
+            Self::Intermediate => 40,
+            Self::Expert => 99,
+        }
+    }
+
+    // Tile map of the preset with its bombs and a safe opening, dealt from the seed like the
+    // uniform boards of the main game with a safe start
+    pub fn tile_map(&self, seed: u64) -> TileMap {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (width, height) = self.map_size();
+        let mut tile_map = TileMap::empty(width, height);
+        tile_map.set_bombs(self.bomb_count().into(), &mut rng);
+        let opening = tile_map.random_empty_tile(&mut rng);
+        tile_map.set_opening(opening);
+        tile_map
+    }
+}
@@ -0,0 +1,534 @@
+use crate::{Coordinates, Tile, TileMap, TileSet};
+use std::collections::{HashMap, HashSet};
+
+// Conclusion drawn by the solver from the visible board
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Deduction {
+    Safe(Coordinates),
+    Bomb(Coordinates),
+}
+
+// Unknown tiles past which the exact endgame enumeration gives up
+pub const ENDGAME_TILES: usize = 20;
+// Tiles of a frontier area past which its odds are only estimated from the numbers around
+pub const AREA_TILES: usize = 18;
+
+// Constraint given by a revealed number: `bombs` of the `tiles` are bombs
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Constraint {
+    tiles: HashSet<Coordinates>,
+    bombs: usize,
+}
+
+// Exhaustive enumeration of the bomb layouts of the unknown tiles
+struct Enumeration {
+    // Constraints over unknown tile indices
+    constraints: Vec<(Vec<usize>, usize)>,
+    // Bomb layout being built
+    layout: Vec<bool>,
+    // Layouts with a bomb on each unknown tile
+    counts: Vec<u64>,
+    // Valid layouts
+    total: u64,
+}
+
+impl Enumeration {
+    // Counts the layouts of the tiles from `index` on, with `bombs` bombs left to place
+    fn run(&mut self, index: usize, bombs: usize) {
+        if bombs > self.layout.len() - index {
+            return;
+        }
+        // every constraint must remain satisfiable
+        for (tiles, target) in self.constraints.iter() {
+            let placed = tiles
+                .iter()
+                .filter(|i| **i < index && self.layout[**i])
+                .count();
+            let open = tiles.iter().filter(|i| **i >= index).count();
+            if placed > *target || placed + open < *target {
+                return;
+            }
+        }
+        if index == self.layout.len() {
+            self.total += 1;
+            for (count, bomb) in self.counts.iter_mut().zip(self.layout.iter()) {
+                if *bomb {
+                    *count += 1;
+                }
+            }
+            return;
+        }
+
+        if bombs > 0 {
+            self.layout[index] = true;
+            self.run(index + 1, bombs - 1);
+            self.layout[index] = false;
+        }
+        self.run(index + 1, bombs);
+    }
+}
+
+// Deterministic solver working only with what the player can see
+#[derive(Debug, Clone)]
+pub struct Solver<'a> {
+    tile_map: &'a TileMap,
+    // Uncovered tiles
+    revealed: TileSet,
+    // Tiles known to be bombs
+    bombs: TileSet,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(tile_map: &'a TileMap) -> Self {
+        let (width, height) = (tile_map.width(), tile_map.height());
+        Self {
+            tile_map,
+            revealed: TileSet::new(width, height),
+            bombs: TileSet::new(width, height),
+        }
+    }
+
+    // Solver starting from the current player knowledge
+    pub fn with_state(
+        tile_map: &'a TileMap,
+        revealed: impl IntoIterator<Item = Coordinates>,
+        bombs: impl IntoIterator<Item = Coordinates>,
+    ) -> Self {
+        let mut solver = Self::new(tile_map);
+        solver.revealed.extend(revealed);
+        solver.bombs.extend(bombs);
+        solver
+    }
+
+    pub fn is_revealed(&self, coords: Coordinates) -> bool {
+        self.revealed.contains(coords)
+    }
+
+    pub fn is_known_bomb(&self, coords: Coordinates) -> bool {
+        self.bombs.contains(coords)
+    }
+
+    // Number displayed by a revealed tile
+    fn number_at(&self, coords: Coordinates) -> usize {
+        match self.tile_map[coords.y as usize][coords.x as usize] {
+            Tile::BombNeighbor(v) => v as usize,
+            _ => 0,
+        }
+    }
+
+    // Constraint of a revealed tile over its covered neighbors that aren't known bombs
+    fn constraint_at(&self, coords: Coordinates) -> Option<Constraint> {
+        let mut known_bombs = 0;
+        let mut tiles = HashSet::new();
+        for neighbor in self.tile_map.neighbors(coords) {
+            if self.bombs.contains(neighbor) {
+                known_bombs += self.tile_map.mines_at(neighbor) as usize;
+            } else if !self.revealed.contains(neighbor) {
+                tiles.insert(neighbor);
+            }
+        }
+        if tiles.is_empty() {
+            return None;
+        }
+        Some(Constraint {
+            tiles,
+            bombs: self.number_at(coords).saturating_sub(known_bombs),
+        })
+    }
+
+    pub fn is_unknown(&self, coords: Coordinates) -> bool {
+        self.tile_map.exists(coords)
+            && !self.revealed.contains(coords)
+            && !self.bombs.contains(coords)
+    }
+
+    // Unknown tiles left, and bombs left among them
+    fn unknown_left(&self) -> (usize, usize) {
+        let unknown = self.tile_map.tile_count() - self.revealed.len() - self.bombs.len();
+        let bombs = (self.tile_map.bomb_count() as usize).saturating_sub(self.bombs.len());
+        (unknown, bombs)
+    }
+
+    fn constraints(&self) -> Vec<Constraint> {
+        self.revealed
+            .iter()
+            .filter_map(|c| self.constraint_at(c))
+            .collect()
+    }
+
+    // Do the numbers count stacked mines? Their tiles may then hide fewer bombs than the count,
+    // only the safe tiles are deduced
+    fn multi_mine(&self) -> bool {
+        self.tile_map.max_mines() > 1
+    }
+
+    // Deductions of a constraint on its own: all its tiles are either safe or bombs
+    fn trivial_deductions(&self, constraint: &Constraint, deductions: &mut HashSet<Deduction>) {
+        if constraint.bombs == 0 {
+            deductions.extend(constraint.tiles.iter().map(|c| Deduction::Safe(*c)));
+        } else if constraint.bombs == constraint.tiles.len() && !self.multi_mine() {
+            deductions.extend(constraint.tiles.iter().map(|c| Deduction::Bomb(*c)));
+        }
+    }
+
+    // Single tile pass: numbers whose covered neighbors are all bombs or all safe
+    pub fn single_tile_deductions(&self) -> Vec<Deduction> {
+        let mut deductions = HashSet::new();
+        for constraint in self.constraints() {
+            self.trivial_deductions(&constraint, &mut deductions);
+        }
+        deductions.into_iter().collect()
+    }
+
+    // Subset pass: when a constraint contains another one, the difference is a constraint too
+    pub fn subset_deductions(&self) -> Vec<Deduction> {
+        let constraints = self.constraints();
+        let mut deductions = HashSet::new();
+        for a in constraints.iter() {
+            for b in constraints.iter() {
+                if a == b || a.bombs > b.bombs || !a.tiles.is_subset(&b.tiles) {
+                    continue;
+                }
+                let difference = Constraint {
+                    tiles: b.tiles.difference(&a.tiles).copied().collect(),
+                    bombs: b.bombs - a.bombs,
+                };
+                self.trivial_deductions(&difference, &mut deductions);
+            }
+        }
+        deductions.into_iter().collect()
+    }
+
+    // Deductions of the cheapest pass making progress
+    pub fn deductions(&self) -> Vec<Deduction> {
+        let deductions = self.single_tile_deductions();
+        if !deductions.is_empty() {
+            return deductions;
+        }
+        self.subset_deductions()
+    }
+
+    // Reveals a tile, cascading through empty tiles like the game does
+    pub fn reveal(&mut self, coords: Coordinates) {
+        let mut queue = vec![coords];
+        while let Some(coords) = queue.pop() {
+            if !self.revealed.insert(coords) {
+                continue;
+            }
+            if self.tile_map[coords.y as usize][coords.x as usize] == Tile::Empty {
+                queue.extend(
+                    self.tile_map
+                        .neighbors(coords)
+                        .filter(|c| !self.revealed.contains(*c)),
+                );
+            }
+        }
+    }
+
+    pub fn apply(&mut self, deduction: Deduction) {
+        match deduction {
+            Deduction::Safe(coords) => self.reveal(coords),
+            Deduction::Bomb(coords) => {
+                self.bombs.insert(coords);
+            }
+        }
+    }
+
+    // Are all the safe tiles revealed?
+    pub fn is_solved(&self) -> bool {
+        self.revealed.len() + self.tile_map.bomb_count() as usize == self.tile_map.tile_count()
+    }
+
+    // Finds a safe tile to uncover, or else a bomb missing from the player flags
+    pub fn hint(&self, flagged: &TileSet) -> Option<Deduction> {
+        let mut solver = self.clone();
+        let mut unflagged_bomb = None;
+        loop {
+            let deductions = solver.deductions();
+            if let Some(safe) = deductions.iter().find(|d| matches!(d, Deduction::Safe(_))) {
+                return Some(*safe);
+            }
+            if deductions.is_empty() {
+                return unflagged_bomb;
+            }
+            // the found bombs may lead to safe tiles
+            for deduction in deductions {
+                if let Deduction::Bomb(coords) = deduction {
+                    if unflagged_bomb.is_none() && !flagged.contains(coords) {
+                        unflagged_bomb = Some(deduction);
+                    }
+                }
+                solver.apply(deduction);
+            }
+        }
+    }
+
+    // Exact bomb probability of every unknown tile, from all the bomb layouts matching the
+    // revealed numbers and the bomb count. Only tractable on endgames, up to `ENDGAME_TILES`,
+    // and on the boards of single mine bombs
+    pub fn endgame_odds(&self) -> Option<Vec<(Coordinates, f32)>> {
+        if self.multi_mine() {
+            return None;
+        }
+        let (width, height) = (self.tile_map.width(), self.tile_map.height());
+        let unknown: Vec<Coordinates> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinates { x, y }))
+            .filter(|c| self.tile_map.exists(*c))
+            .filter(|c| !self.revealed.contains(*c) && !self.bombs.contains(*c))
+            .collect();
+        if unknown.len() > ENDGAME_TILES {
+            return None;
+        }
+        let bombs = (self.tile_map.bomb_count() as usize).checked_sub(self.bombs.len())?;
+
+        let index = |c: &Coordinates| unknown.iter().position(|u| u == c);
+        let mut enumeration = Enumeration {
+            constraints: self
+                .constraints()
+                .into_iter()
+                .map(|c| (c.tiles.iter().filter_map(index).collect(), c.bombs))
+                .collect(),
+            layout: vec![false; unknown.len()],
+            counts: vec![0; unknown.len()],
+            total: 0,
+        };
+        enumeration.run(0, bombs);
+        if enumeration.total == 0 {
+            return None;
+        }
+
+        let total = enumeration.total as f32;
+        Some(
+            unknown
+                .into_iter()
+                .zip(enumeration.counts)
+                .map(|(c, count)| (c, count as f32 / total))
+                .collect(),
+        )
+    }
+
+    // Odds of the tiles left to guess from, when the endgame can't be solved without guessing
+    pub fn forced_guess(&self) -> Option<Vec<(Coordinates, f32)>> {
+        let mut solver = self.clone();
+        loop {
+            let deductions = solver.deductions();
+            if deductions.iter().any(|d| matches!(d, Deduction::Safe(_))) {
+                return None;
+            }
+            if deductions.is_empty() {
+                break;
+            }
+            for deduction in deductions {
+                solver.apply(deduction);
+            }
+        }
+        if solver.is_solved() {
+            return None;
+        }
+
+        // the enumeration may still prove tiles safe, or bombs, beyond the deduction passes
+        let odds = solver.endgame_odds()?;
+        if odds.iter().any(|(_, p)| *p == 0.) {
+            return None;
+        }
+        Some(odds.into_iter().filter(|(_, p)| *p < 1.).collect())
+    }
+
+    // Applies deductions until stuck, returning whether the board got solved without guessing
+    pub fn solve(&mut self) -> bool {
+        loop {
+            let deductions = self.deductions();
+            if deductions.is_empty() {
+                return self.is_solved();
+            }
+            for deduction in deductions {
+                self.apply(deduction);
+            }
+        }
+    }
+}
+
+// Bomb probability of every unknown tile, updated around the changed tiles only so it stays
+// cheap to follow on large boards.
+// The unknown tiles next to revealed numbers are split in areas linked by their numbers, and the
+// bomb layouts of each area are enumerated, weighted by the ways to place the bombs left on the
+// other tiles. The areas past `AREA_TILES` fall back to the odds given by their numbers, and the
+// tiles away from the numbers share the density of the bombs left. The numbers of multi-mine
+// boards don't count bombs, every tile shares the density there
+#[derive(Debug, Clone, Default)]
+pub struct BombOdds {
+    // Odds of the unknown tiles next to a revealed number
+    frontier: HashMap<Coordinates, f32>,
+    // Odds of the other unknown tiles
+    interior: f32,
+}
+
+impl BombOdds {
+    pub fn new(solver: &Solver) -> Self {
+        let mut odds = Self::default();
+        let tiles = solver.tile_map.tiles().map(|(c, _)| c);
+        odds.update(solver, tiles);
+        odds
+    }
+
+    // Bomb probability of an unknown tile
+    pub fn get(&self, coords: Coordinates) -> f32 {
+        self.frontier.get(&coords).copied().unwrap_or(self.interior)
+    }
+
+    // getter for `interior`
+    pub fn interior(&self) -> f32 {
+        self.interior
+    }
+
+    // Recomputes the areas around the tiles revealed, or covered again, since the last update,
+    // the other areas keep the weights of their last update. Returns the tiles whose odds were
+    // recomputed, the interior ones follow `interior`
+    pub fn update(
+        &mut self,
+        solver: &Solver,
+        changed: impl IntoIterator<Item = Coordinates>,
+    ) -> Vec<Coordinates> {
+        let tile_map = solver.tile_map;
+        // the numbers next to a changed tile constrain the tiles two steps away
+        let mut dirty = HashSet::new();
+        for coords in changed {
+            for neighbor in tile_map.neighbors(coords) {
+                dirty.extend(tile_map.neighbors(neighbor));
+                dirty.insert(neighbor);
+            }
+            dirty.insert(coords);
+        }
+        for coords in dirty.iter() {
+            self.frontier.remove(coords);
+        }
+
+        let (unknown, bombs) = solver.unknown_left();
+        let mut done = HashSet::new();
+        for coords in dirty.iter() {
+            if done.contains(coords) || !solver.is_unknown(*coords) || solver.multi_mine() {
+                continue;
+            }
+            let (tiles, constraints) = Self::area(solver, *coords);
+            if tiles.is_empty() {
+                continue;
+            }
+            let odds = Self::area_odds(&tiles, &constraints, unknown - tiles.len(), bombs);
+            done.extend(tiles.iter().copied());
+            self.frontier.extend(tiles.into_iter().zip(odds));
+        }
+
+        let frontier_bombs: f32 = self.frontier.values().sum();
+        let interior = unknown - self.frontier.len();
+        self.interior = match interior {
+            0 => 0.,
+            _ => ((bombs as f32 - frontier_bombs) / interior as f32).clamp(0., 1.),
+        };
+        dirty
+            .into_iter()
+            .filter(|c| solver.is_unknown(*c))
+            .collect()
+    }
+
+    // Unknown tiles linked to a tile through the revealed numbers, with the numbers constraints
+    fn area(solver: &Solver, start: Coordinates) -> (Vec<Coordinates>, Vec<Constraint>) {
+        let tile_map = solver.tile_map;
+        let mut tiles = vec![];
+        let mut constraints = vec![];
+        if !tile_map.neighbors(start).any(|n| solver.is_revealed(n)) {
+            return (tiles, constraints);
+        }
+        let mut seen_tiles = HashSet::from([start]);
+        let mut seen_numbers = HashSet::new();
+        let mut queue = vec![start];
+        while let Some(coords) = queue.pop() {
+            let numbers: Vec<Coordinates> = tile_map
+                .neighbors(coords)
+                .filter(|n| solver.is_revealed(*n) && seen_numbers.insert(*n))
+                .collect();
+            for number in numbers {
+                if let Some(constraint) = solver.constraint_at(number) {
+                    queue.extend(constraint.tiles.iter().filter(|c| seen_tiles.insert(**c)));
+                    constraints.push(constraint);
+                }
+            }
+            tiles.push(coords);
+        }
+        (tiles, constraints)
+    }
+
+    // Odds of the tiles of an area, `others` unknown tiles left outside of it to hold the rest of
+    // the `bombs`
+    fn area_odds(
+        tiles: &[Coordinates],
+        constraints: &[Constraint],
+        others: usize,
+        bombs: usize,
+    ) -> Vec<f32> {
+        // highest ratio of the bombs left by the numbers around each tile, a number without bombs
+        // left proving it safe
+        let estimate = || {
+            tiles
+                .iter()
+                .map(|c| {
+                    let ratios = constraints
+                        .iter()
+                        .filter(|k| k.tiles.contains(c))
+                        .map(|k| k.bombs as f32 / k.tiles.len() as f32);
+                    match ratios.clone().any(|r| r == 0.) {
+                        true => 0.,
+                        false => ratios.fold(0., f32::max).min(1.),
+                    }
+                })
+                .collect()
+        };
+        if tiles.len() > AREA_TILES {
+            return estimate();
+        }
+
+        let index = |c: &Coordinates| tiles.iter().position(|t| t == c);
+        let mut enumeration = Enumeration {
+            constraints: constraints
+                .iter()
+                .map(|k| (k.tiles.iter().filter_map(index).collect(), k.bombs))
+                .collect(),
+            layout: vec![false; tiles.len()],
+            counts: vec![0; tiles.len()],
+            total: 0,
+        };
+        // the layouts with `k` bombs leave C(others, bombs - k) ways to place the rest, the
+        // weights are kept as logarithms relative to the fewest bombs possible
+        let fewest = bombs.saturating_sub(others);
+        let mut weight = 0.;
+        let mut weighted = vec![];
+        for k in fewest..=bombs.min(tiles.len()) {
+            if k > fewest {
+                weight += ((bombs - k + 1) as f64).ln() - ((others + k - bombs) as f64).ln();
+            }
+            enumeration.counts.iter_mut().for_each(|c| *c = 0);
+            enumeration.total = 0;
+            enumeration.run(0, k);
+            if enumeration.total > 0 {
+                weighted.push((weight, enumeration.counts.clone(), enumeration.total));
+            }
+        }
+        let top = weighted
+            .iter()
+            .map(|(w, _, _)| *w)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let mut counts = vec![0f64; tiles.len()];
+        let mut total = 0.;
+        for (weight, layout_counts, layouts) in weighted {
+            let scale = (weight - top).exp();
+            total += scale * layouts as f64;
+            for (count, layout_count) in counts.iter_mut().zip(layout_counts) {
+                *count += scale * layout_count as f64;
+            }
+        }
+        // no layout matches the numbers and the bombs left
+        if total == 0. {
+            return estimate();
+        }
+        counts.into_iter().map(|c| (c / total) as f32).collect()
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(feature = "debug")]
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+// Enum describing a Minesweeper title
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Tile {
+    // Is a bomb, holding one mine or a stack of them on multi-mine boards
+    Bomb(u8),
+    // Is a bomb neighbor
+    BombNeighbor(u8),
+    // Is a empty tile
+    Empty,
+    // Isn't part of the board, on shaped boards
+    Void,
+}
+
+impl Tile {
+    // Is the tile part of the board?
+    pub const fn exists(&self) -> bool {
+        !matches!(self, Self::Void)
+    }
+
+    // Is the tile a bomb?
+    pub const fn is_bomb(&self) -> bool {
+        matches!(self, Self::Bomb(_))
+    }
+
+    // Mines held by the tile, none unless it's a bomb
+    pub const fn mines(&self) -> u8 {
+        match self {
+            Self::Bomb(mines) => *mines,
+            _ => 0,
+        }
+    }
+
+    // Plain character of the tile in the text exports: `*` bomb, `@` and `&` bombs of two and
+    // three mines, `.` empty, the bomb count with letters from `a` for the counts above 9, or
+    // `#` out of the board
+    pub const fn text_output(&self) -> char {
+        match self {
+            Tile::Bomb(2) => '@',
+            Tile::Bomb(mines) if *mines > 2 => '&',
+            Tile::Bomb(_) => '*',
+            Tile::BombNeighbor(v) if *v > 9 => (b'a' + *v - 10) as char,
+            Tile::BombNeighbor(v) => (b'0' + *v) as char,
+            Tile::Empty => '.',
+            Tile::Void => '#',
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        format!(
+            "{}",
+            match self {
+                Tile::Bomb(_) => self.text_output().to_string().bright_red(),
+                Tile::BombNeighbor(v) => match v {
+                    1 => "1".cyan(),
+                    2 => "2".green(),
+                    3 => "3".yellow(),
+                    _ => v.to_string().red(),
+                },
+                Tile::Empty => " ".normal(),
+                Tile::Void => "#".black(),
+            }
+        )
+    }
+}
@@ -0,0 +1,79 @@
+use crate::Coordinates;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Extension payload of a tile for the variant modes, kept in a layer next to the tiles so the
+// rules of the classic tiles don't change
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TilePayload {
+    // Bonus granted to the player uncovering the tile
+    Treasure(Treasure),
+}
+
+// Bonus of a treasure tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Treasure {
+    // Seconds taken off the game time
+    TimeBonus(u16),
+    // Spares the player from the next bomb
+    ExtraLife,
+}
+
+// Sparse values over the tiles of a tile map. Modes with their own payloads keep a layer of
+// their type next to the tile map instead of forking it
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TileLayer<T> {
+    tiles: BTreeMap<Coordinates, T>,
+}
+
+impl<T> Default for TileLayer<T> {
+    fn default() -> Self {
+        Self {
+            tiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> TileLayer<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, coords: Coordinates) -> Option<&T> {
+        self.tiles.get(&coords)
+    }
+
+    pub fn get_mut(&mut self, coords: Coordinates) -> Option<&mut T> {
+        self.tiles.get_mut(&coords)
+    }
+
+    // Sets the value of a tile, returning the value it replaces
+    pub fn insert(&mut self, coords: Coordinates, value: T) -> Option<T> {
+        self.tiles.insert(coords, value)
+    }
+
+    pub fn remove(&mut self, coords: Coordinates) -> Option<T> {
+        self.tiles.remove(&coords)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    // Tiles holding a value, in coordinates order
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &T)> + '_ {
+        self.tiles.iter().map(|(c, v)| (*c, v))
+    }
+}
+
+impl<T> FromIterator<(Coordinates, T)> for TileLayer<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinates, T)>>(iter: I) -> Self {
+        Self {
+            tiles: iter.into_iter().collect(),
+        }
+    }
+}
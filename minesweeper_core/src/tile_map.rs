@@ -0,0 +1,907 @@
+use crate::{
+    BoardError, BoardMask, Coordinates, GridKind, NeighborKernel, ParseMapError, StateHasher, Tile,
+    TileLayer, TilePayload, TileSet,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+// Most mines stacked on a single bomb
+const MOST_STACKED_MINES: u8 = 3;
+
+// Way `TileMap::set_bombs` draws the bomb tiles. Each one deals its own boards from a seed, so
+// the boards saved with a placement (replays, shared boards) are generated with it again
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum BombPlacement {
+    // Random tiles until enough empty ones were drawn, the placement of the first versions.
+    // Slows down on dense boards, retrying the tiles which already hold a bomb
+    Retry,
+    // Partial Fisher-Yates shuffle of the empty tiles
+    Shuffle,
+}
+
+impl Default for BombPlacement {
+    fn default() -> Self {
+        Self::Shuffle
+    }
+}
+
+// Base tile map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    bomb_count: u32,
+    height: u16,
+    width: u16,
+    map: Vec<Vec<Tile>>,
+    // Tiling defining the tile neighbors
+    grid: GridKind,
+    // Tiles counted by the numbers, the neighbors of the tiling by default
+    #[serde(default)]
+    kernel: NeighborKernel,
+    // Do the left and right edges, and the top and bottom edges, touch?
+    #[serde(default)]
+    wrap: bool,
+    // Most mines a bomb may hold, more than one on the multi-mine boards
+    #[serde(default = "single_mine")]
+    max_mines: u8,
+    // Tile to uncover on start, chosen by the generator
+    opening: Option<Coordinates>,
+    // Tiles uncovered on start along with the opening, set by hand made puzzles
+    #[serde(default)]
+    reveals: Vec<Coordinates>,
+    // Extension payloads of the variant modes
+    #[serde(default)]
+    payloads: TileLayer<TilePayload>,
+    // Way the bombs are drawn by `set_bombs`
+    #[serde(default)]
+    placement: BombPlacement,
+}
+
+impl TileMap {
+    pub fn empty(width: u16, height: u16) -> Self {
+        Self::empty_with_grid(width, height, GridKind::Square)
+    }
+
+    pub fn empty_with_grid(width: u16, height: u16, grid: GridKind) -> Self {
+        let map = (0..height)
+            .map(|_| (0..width).map(|_| Tile::Empty).collect())
+            .collect();
+        Self {
+            bomb_count: 0,
+            height,
+            width,
+            map,
+            grid,
+            kernel: NeighborKernel::Grid,
+            wrap: false,
+            max_mines: 1,
+            opening: None,
+            reveals: Vec::new(),
+            payloads: TileLayer::new(),
+            placement: Default::default(),
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs:\n",
+            self.width, self.height, self.bomb_count
+        );
+        let line = (0..=(self.width + 1)).map(|_| '-').collect::<String>();
+        buffer = format!("{buffer}{line}\n");
+        for line in self.iter().rev() {
+            buffer = format!("{buffer}|");
+            for tile in line.iter() {
+                buffer = format!("{buffer}{}", tile.console_output());
+            }
+            buffer = format!("{buffer}|\n");
+        }
+
+        format!("{buffer}{line}")
+    }
+
+    // Plain text of the tile map, top row first, in a frame under a header line
+    pub fn text_output(&self) -> String {
+        let header = format!(
+            "Map ({}, {}) with {} bombs:",
+            self.width, self.height, self.bomb_count
+        );
+        let rows = self
+            .iter()
+            .rev()
+            .map(|line| line.iter().map(Tile::text_output).collect());
+        framed(&header, self.width, rows)
+    }
+
+    // Parses a text board, top row first: `*` bombs, `@` and `&` bombs of two and three mines,
+    // `.` or spaces safe tiles, bomb counts and `#` cells off the board. The header and frame of
+    // `text_output` are skipped and the board ends at the next header, so the text of a game
+    // reads back as its tile map. The bomb counts follow from the bombs, the written ones are
+    // only checked
+    pub fn from_text(text: &str, grid: GridKind) -> Result<Self, ParseMapError> {
+        let mut rows: Vec<Vec<char>> = Vec::new();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            // the spaces of framed rows are tiles
+            let row = match trimmed.strip_prefix('|').and_then(|r| r.strip_suffix('|')) {
+                Some(row) => row,
+                None if trimmed.ends_with(':') && !rows.is_empty() => break,
+                None if trimmed.ends_with(':') || trimmed.chars().all(|c| c == '-') => continue,
+                None => trimmed,
+            };
+            rows.push(row.chars().collect());
+        }
+
+        let width = rows.first().ok_or(ParseMapError::NoTiles)?.len();
+        if width == 0 {
+            return Err(ParseMapError::NoTiles);
+        }
+        let height = rows.len();
+        let (map_width, map_height) = match (u16::try_from(width), u16::try_from(height)) {
+            (Ok(w), Ok(h)) => (w, h),
+            _ => return Err(ParseMapError::TooLarge { width, height }),
+        };
+        let mut tile_map = Self::empty_with_grid(map_width, map_height, grid);
+        let mut bombs = Vec::new();
+        let mut stacks = Vec::new();
+        let mut counts = Vec::new();
+        // the top row comes first
+        for (y, row) in rows.iter().rev().enumerate() {
+            if row.len() != width {
+                return Err(ParseMapError::UnevenRow {
+                    y: y as u16,
+                    length: row.len(),
+                    width,
+                });
+            }
+            for (x, c) in row.iter().enumerate() {
+                let coordinates = Coordinates {
+                    x: x as u16,
+                    y: y as u16,
+                };
+                match c {
+                    '*' => bombs.push(coordinates),
+                    '@' | '&' => {
+                        bombs.push(coordinates);
+                        stacks.push((coordinates, if *c == '@' { 2 } else { 3 }));
+                    }
+                    '.' | ' ' => (),
+                    '#' => tile_map[y][x] = Tile::Void,
+                    '0'..='9' => counts.push((coordinates, *c as u8 - b'0')),
+                    'a'..='z' => counts.push((coordinates, *c as u8 - b'a' + 10)),
+                    _ => {
+                        return Err(ParseMapError::UnknownTile {
+                            coordinates,
+                            found: *c,
+                        })
+                    }
+                }
+            }
+        }
+        tile_map.place_bombs(bombs);
+        if !stacks.is_empty() {
+            for (coordinates, mines) in stacks.iter() {
+                tile_map[coordinates.y as usize][coordinates.x as usize] = Tile::Bomb(*mines);
+            }
+            tile_map.max_mines = stacks.iter().map(|(_, m)| *m).max().unwrap_or(1);
+            tile_map.update_bomb_neighbors();
+        }
+        for (coordinates, written) in counts {
+            let actual = tile_map.bomb_count_at(coordinates);
+            if written != actual {
+                return Err(ParseMapError::WrongCount {
+                    coordinates,
+                    written,
+                    actual,
+                });
+            }
+        }
+        Ok(tile_map)
+    }
+
+    // getter for `width`
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    // getter for `height`
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    // getter for `bomb_count`, the tiles holding a bomb
+    pub fn bomb_count(&self) -> u32 {
+        self.bomb_count
+    }
+
+    // Mines of the board, the bombs of multi-mine boards counting each of their stacked mines
+    pub fn mine_count(&self) -> u32 {
+        self.tiles().map(|(_, t)| t.mines() as u32).sum()
+    }
+
+    // getter for `max_mines`
+    pub fn max_mines(&self) -> u8 {
+        self.max_mines
+    }
+
+    // Number of tiles of the board, holes excluded
+    pub fn tile_count(&self) -> usize {
+        self.iter().flatten().filter(|t| t.exists()).count()
+    }
+
+    // Tiles of the board with their coordinates, holes excluded
+    pub fn tiles(&self) -> impl Iterator<Item = (Coordinates, Tile)> + '_ {
+        self.iter().enumerate().flat_map(|(y, line)| {
+            line.iter()
+                .enumerate()
+                .filter(|(_, tile)| tile.exists())
+                .map(move |(x, tile)| {
+                    let coords = Coordinates {
+                        x: x as u16,
+                        y: y as u16,
+                    };
+                    (coords, *tile)
+                })
+        })
+    }
+
+    // Neighbors of a tile which are part of the board
+    pub fn neighbors(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.safe_square_at(coordinates)
+            .filter(move |c| self.exists(*c))
+    }
+
+    // Neighbors of a tile holding a bomb
+    pub fn bomb_neighbors(
+        &self,
+        coordinates: Coordinates,
+    ) -> impl Iterator<Item = Coordinates> + '_ {
+        self.neighbors(coordinates)
+            .filter(move |c| self.is_bomb_at(*c))
+    }
+
+    // Neighbors of a tile sharing an edge with it, which are part of the board
+    pub fn edge_neighbors(
+        &self,
+        coordinates: Coordinates,
+    ) -> impl Iterator<Item = Coordinates> + '_ {
+        self.offsets(coordinates, self.grid.edge_deltas(coordinates))
+            .filter(move |c| self.exists(*c))
+    }
+
+    // Is the tile within the map bounds? Holes are in bounds
+    pub fn in_bounds(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width && coordinates.y < self.height
+    }
+
+    // Is the tile in bounds and part of the board?
+    pub fn exists(&self, coordinates: Coordinates) -> bool {
+        self.in_bounds(coordinates)
+            && self.map[coordinates.y as usize][coordinates.x as usize].exists()
+    }
+
+    // Removes the tiles outside of the mask from an empty map
+    pub fn apply_mask(&mut self, mask: &BoardMask) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !mask.contains(Coordinates { x, y }) {
+                    self[y as usize][x as usize] = Tile::Void;
+                }
+            }
+        }
+    }
+
+    // getter for `grid`
+    pub fn grid(&self) -> GridKind {
+        self.grid
+    }
+
+    // getter for `kernel`
+    pub fn kernel(&self) -> &NeighborKernel {
+        &self.kernel
+    }
+
+    // Counts the numbers with `kernel`, the bombs already placed included
+    pub fn with_kernel(mut self, kernel: NeighborKernel) -> Self {
+        self.kernel = kernel;
+        self.update_bomb_neighbors();
+        self
+    }
+
+    // getter for `wrap`
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    // Places the next bombs with `placement`, the bombs already placed are kept
+    pub fn with_placement(mut self, placement: BombPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    // Joins the opposite edges of the map, a torus, the bombs already placed included
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self.update_bomb_neighbors();
+        self
+    }
+
+    // Tile at `delta` from a tile, across the edges of wrapped maps. `None` off the map edges
+    fn offset(&self, coordinates: Coordinates, (x, y): (i8, i8)) -> Option<Coordinates> {
+        if !self.wrap {
+            return Some(coordinates + (x, y)).filter(|c| self.in_bounds(*c));
+        }
+        let wrapped = |c: u16, d: i8, size: u16| {
+            (i32::from(c) + i32::from(d)).rem_euclid(i32::from(size).max(1)) as u16
+        };
+        Some(Coordinates {
+            x: wrapped(coordinates.x, x, self.width),
+            y: wrapped(coordinates.y, y, self.height),
+        })
+    }
+
+    // Tiles at `deltas` from a tile within the map bounds. A narrow wrapped map reaches a tile
+    // through several deltas, or the tile itself, they are only listed once
+    fn offsets<'a>(
+        &'a self,
+        coordinates: Coordinates,
+        deltas: &'a [(i8, i8)],
+    ) -> impl Iterator<Item = Coordinates> + 'a {
+        deltas.iter().enumerate().filter_map(move |(i, delta)| {
+            let c = self.offset(coordinates, *delta)?;
+            let repeated = self.wrap
+                && (c == coordinates
+                    || deltas[..i]
+                        .iter()
+                        .any(|d| self.offset(coordinates, *d) == Some(c)));
+            (!repeated).then_some(c)
+        })
+    }
+
+    // getter for `opening`
+    pub fn opening(&self) -> Option<Coordinates> {
+        self.opening
+    }
+
+    // setter for `opening`
+    pub fn set_opening(&mut self, opening: Option<Coordinates>) {
+        self.opening = opening;
+    }
+
+    // getter for `reveals`
+    pub fn reveals(&self) -> &[Coordinates] {
+        &self.reveals
+    }
+
+    // setter for `reveals`
+    pub fn set_reveals(&mut self, reveals: Vec<Coordinates>) {
+        self.reveals = reveals;
+    }
+
+    // Hash of the size, the tiling, the tiles and the starting tiles of the map. Tile maps
+    // generated from the same options and seed share it, on any platform and build
+    pub fn layout_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u16(self.width);
+        hasher.write_u16(self.height);
+        hasher.write_u8(match self.grid {
+            GridKind::Square => 0,
+            GridKind::Hex => 1,
+        });
+        hasher.write_u8(self.wrap as u8);
+        // the holes of shaped boards included
+        for tile in self.iter().flatten() {
+            hasher.write_u8(tile.text_output() as u8);
+        }
+        hasher.write_u8(self.opening.is_some() as u8);
+        for coords in self.opening.iter().chain(self.reveals.iter()) {
+            hasher.write_u16(coords.x);
+            hasher.write_u16(coords.y);
+        }
+        hasher.finish()
+    }
+
+    // getter for `payloads`
+    pub fn payloads(&self) -> &TileLayer<TilePayload> {
+        &self.payloads
+    }
+
+    pub fn payload_at(&self, coordinates: Coordinates) -> Option<TilePayload> {
+        self.payloads.get(coordinates).copied()
+    }
+
+    // Sets the payload of a tile, ignored for the tiles which aren't part of the board
+    pub fn set_payload(&mut self, coordinates: Coordinates, payload: Option<TilePayload>) {
+        match payload {
+            Some(payload) if self.exists(coordinates) => {
+                self.payloads.insert(coordinates, payload);
+            }
+            _ => {
+                self.payloads.remove(coordinates);
+            }
+        }
+    }
+
+    // Neighbors of a tile through the kernel within the map bounds, holes included. The neighbors
+    // off the edges are skipped, so the coordinates are always safe to index the map with
+    pub fn safe_square_at(
+        &self,
+        coordinates: Coordinates,
+    ) -> impl Iterator<Item = Coordinates> + '_ {
+        self.offsets(coordinates, self.kernel.deltas(self.grid, coordinates))
+    }
+
+    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
+        if !self.in_bounds(coordinates) {
+            return false;
+        };
+
+        self.map[coordinates.y as usize][coordinates.x as usize].is_bomb()
+    }
+
+    // Mines held by a tile, none out of bounds
+    pub fn mines_at(&self, coordinates: Coordinates) -> u8 {
+        match self.in_bounds(coordinates) {
+            true => self.map[coordinates.y as usize][coordinates.x as usize].mines(),
+            false => 0,
+        }
+    }
+
+    // Number of a safe tile: the mines of its neighbors, stacked ones included
+    pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
+        if self.is_bomb_at(coordinates) {
+            return 0;
+        }
+
+        self.neighbors(coordinates).map(|c| self.mines_at(c)).sum()
+    }
+
+    // Bechtel's Board Benchmark Value: minimum number of clicks needed to clear the board
+    pub fn three_bv(&self) -> u32 {
+        self.solved_three_bv(|_| true)
+    }
+
+    // Part of the 3BV cleared by the `revealed` tiles: the empty areas they opened, and the
+    // numbers they revealed outside of the empty areas
+    pub fn solved_three_bv(&self, revealed: impl Fn(Coordinates) -> bool) -> u32 {
+        let mut cleared = HashSet::new();
+        let mut three_bv = 0;
+
+        // every empty area, uncovered with its border in a single click
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coords = Coordinates { x, y };
+                if self[y as usize][x as usize] != Tile::Empty || cleared.contains(&coords) {
+                    continue;
+                }
+                // an empty area is uncovered at once
+                if revealed(coords) {
+                    three_bv += 1;
+                }
+                let mut queue = vec![coords];
+                while let Some(coords) = queue.pop() {
+                    if !cleared.insert(coords) {
+                        continue;
+                    }
+                    if self[coords.y as usize][coords.x as usize] == Tile::Empty {
+                        queue.extend(self.safe_square_at(coords));
+                    }
+                }
+            }
+        }
+
+        // every number outside of the empty areas
+        let numbers = self.iter().enumerate().flat_map(|(y, line)| {
+            line.iter()
+                .enumerate()
+                .filter_map(move |(x, tile)| match tile {
+                    Tile::BombNeighbor(_) => Some(Coordinates {
+                        x: x as u16,
+                        y: y as u16,
+                    }),
+                    _ => None,
+                })
+        });
+        three_bv
+            + numbers
+                .filter(|c| !cleared.contains(c) && revealed(*c))
+                .count() as u32
+    }
+
+    // Picks a random empty tile, used as a safe starting point
+    pub fn random_empty_tile(&self, rng: &mut (impl Rng + ?Sized)) -> Option<Coordinates> {
+        let empty_tiles: Vec<Coordinates> = self
+            .tiles()
+            .filter(|(_, tile)| *tile == Tile::Empty)
+            .map(|(c, _)| c)
+            .collect();
+        empty_tiles.choose(rng).copied()
+    }
+
+    // Most bombs fitting around a safe start: the bombs must leave out the smallest
+    // neighborhood of the board for the opening to be an empty tile
+    pub fn max_safe_start_bombs(&self) -> usize {
+        let smallest_neighborhood = self
+            .tiles()
+            .map(|(c, _)| 1 + self.neighbors(c).count())
+            .min()
+            .unwrap_or(0);
+        self.tile_count().saturating_sub(smallest_neighborhood)
+    }
+
+    // Checks that the bombs fit on the map, leaving room for a safe start if needed
+    pub fn check_bomb_count(&self, bomb_count: u32, safe_start: bool) -> Result<(), BoardError> {
+        if !self.kernel.is_valid() {
+            return Err(BoardError::InvalidKernel);
+        }
+        // the hexagon rows shift every other row, an odd count would misalign the wrapped ones
+        if self.wrap && self.grid == GridKind::Hex && self.height % 2 == 1 {
+            return Err(BoardError::OddWrappedHexRows);
+        }
+        let tile_count = self.tile_count();
+        if tile_count == 0 {
+            return Err(BoardError::EmptyMap);
+        }
+        if bomb_count as usize > tile_count {
+            return Err(BoardError::TooManyBombs {
+                bomb_count,
+                tile_count,
+            });
+        }
+        if !safe_start {
+            return Ok(());
+        }
+        let max_bomb_count = self.max_safe_start_bombs();
+        if bomb_count as usize > max_bomb_count {
+            return Err(BoardError::NoSafeStart {
+                bomb_count,
+                max_bomb_count,
+            });
+        }
+        Ok(())
+    }
+
+    // Places the bombs, failing instead of clamping when they don't fit on the map
+    pub fn try_set_bombs(
+        &mut self,
+        bomb_count: u32,
+        rng: &mut (impl Rng + ?Sized),
+    ) -> Result<(), BoardError> {
+        self.check_bomb_count(bomb_count, false)?;
+        self.set_bombs(bomb_count, rng);
+        Ok(())
+    }
+
+    // Places the bombs on random empty tiles, drawn with the placement of the map
+    pub fn set_bombs(&mut self, bomb_count: u32, rng: &mut (impl Rng + ?Sized)) {
+        let mut empty_tiles: Vec<Coordinates> = self
+            .tiles()
+            .filter(|(_, tile)| *tile == Tile::Empty)
+            .map(|(c, _)| c)
+            .collect();
+        // holes may leave less room than requested
+        let bomb_count = (bomb_count as usize).min(empty_tiles.len());
+        let bombs = match self.placement {
+            BombPlacement::Retry => retry_bomb_tiles(self, bomb_count, rng),
+            BombPlacement::Shuffle => empty_tiles.partial_shuffle(rng, bomb_count).0.to_vec(),
+        };
+        for coords in bombs {
+            self[coords.y as usize][coords.x as usize] = Tile::Bomb(1);
+        }
+        self.bomb_count = bomb_count as u32;
+
+        self.update_bomb_neighbors();
+    }
+
+    // Stacks from one to `max_mines` mines on each placed bomb, up to three. The numbers then
+    // count the stacked mines and may go above 8
+    pub fn stack_mines(&mut self, max_mines: u8, rng: &mut (impl Rng + ?Sized)) {
+        self.max_mines = max_mines.clamp(1, MOST_STACKED_MINES);
+        for row in self.map.iter_mut() {
+            for tile in row.iter_mut().filter(|t| t.is_bomb()) {
+                *tile = Tile::Bomb(rng.gen_range(1..=self.max_mines));
+            }
+        }
+        self.update_bomb_neighbors();
+    }
+
+    // Places bombs on the given tiles, for generators choosing their own layout
+    pub fn place_bombs(&mut self, bombs: impl IntoIterator<Item = Coordinates>) {
+        for coords in bombs {
+            if self.exists(coords) {
+                self[coords.y as usize][coords.x as usize] = Tile::Bomb(1);
+            }
+        }
+        self.bomb_count = self.iter().flatten().filter(|t| t.is_bomb()).count() as u32;
+        self.update_bomb_neighbors();
+    }
+
+    // Safe tiles split into the areas connected through shared edges
+    pub fn safe_areas(&self) -> Vec<Vec<Coordinates>> {
+        let mut visited = TileSet::new(self.width, self.height);
+        let mut areas = Vec::new();
+        for (coords, tile) in self.tiles() {
+            if tile.is_bomb() || !visited.insert(coords) {
+                continue;
+            }
+            let mut area = Vec::new();
+            let mut queue = vec![coords];
+            while let Some(coords) = queue.pop() {
+                area.push(coords);
+                for neighbor in self.edge_neighbors(coords) {
+                    if !self.is_bomb_at(neighbor) && visited.insert(neighbor) {
+                        queue.push(neighbor);
+                    }
+                }
+            }
+            areas.push(area);
+        }
+        areas
+    }
+
+    // Are the safe tiles all connected through shared edges, without pockets sealed off by
+    // bombs?
+    pub fn is_connected(&self) -> bool {
+        self.safe_areas().len() <= 1
+    }
+
+    // Bombs set off by the exploded `origins` in a chain reaction, each bomb blowing up the
+    // bombs sharing an edge with it. The bombs come with their step in the chain, starting at 1
+    // for the neighbors of the origins, in the order of the steps
+    pub fn chain_reaction(&self, origins: &[Coordinates]) -> Vec<(Coordinates, u32)> {
+        let mut visited = TileSet::new(self.width, self.height);
+        let mut queue: VecDeque<(Coordinates, u32)> = VecDeque::new();
+        for origin in origins {
+            if visited.insert(*origin) {
+                queue.push_back((*origin, 0));
+            }
+        }
+        let mut chain = Vec::new();
+        while let Some((coords, step)) = queue.pop_front() {
+            if step > 0 {
+                chain.push((coords, step));
+            }
+            for neighbor in self.edge_neighbors(coords) {
+                if self.is_bomb_at(neighbor) && visited.insert(neighbor) {
+                    queue.push_back((neighbor, step + 1));
+                }
+            }
+        }
+        chain
+    }
+
+    // Opens the pockets of safe tiles sealed off by bombs, keeping the bomb count: the shortest
+    // wall of bombs between each pocket and the other safe tiles is cleared, and the bombs are
+    // placed back on random safe tiles which don't split the safe area. Fails when a pocket is
+    // only sealed off by holes, the other pockets being opened anyway
+    pub fn connect_safe_tiles(&mut self, rng: &mut (impl Rng + ?Sized)) -> bool {
+        let areas = self.safe_areas();
+        // areas of the safe tiles, joined as the walls are cleared
+        let mut labels: HashMap<Coordinates, usize> = HashMap::new();
+        for (label, area) in areas.iter().enumerate() {
+            labels.extend(area.iter().map(|c| (*c, label)));
+        }
+        let mut joined = AreaUnion::new(areas.len());
+        let mut cleared = 0;
+        let mut connected = true;
+        for (label, area) in areas.iter().enumerate().skip(1) {
+            while joined.find(label) != joined.find(0) {
+                let wall = match self.shortest_wall(area, &labels, &mut joined) {
+                    Some(wall) => wall,
+                    None => {
+                        connected = false;
+                        break;
+                    }
+                };
+                for coords in wall {
+                    self[coords.y as usize][coords.x as usize] = Tile::Empty;
+                    labels.insert(coords, label);
+                    cleared += 1;
+                }
+            }
+        }
+
+        // every pass places at least a bomb, a connected area always has a tile to spare
+        while cleared > 0 {
+            let mut safe_tiles: Vec<Coordinates> = self
+                .tiles()
+                .filter(|(_, tile)| !tile.is_bomb())
+                .map(|(c, _)| c)
+                .collect();
+            safe_tiles.shuffle(rng);
+            for coords in safe_tiles {
+                if cleared == 0 {
+                    break;
+                }
+                if self.splits_safe_area(coords) {
+                    continue;
+                }
+                self[coords.y as usize][coords.x as usize] = Tile::Bomb(1);
+                cleared -= 1;
+            }
+        }
+        self.update_bomb_neighbors();
+        connected
+    }
+
+    // Short wall of bombs to clear to join the area to another one, found breadth first through
+    // the bombs and the safe tiles already joined to the area. The areas are joined on success
+    fn shortest_wall(
+        &self,
+        area: &[Coordinates],
+        labels: &HashMap<Coordinates, usize>,
+        joined: &mut AreaUnion,
+    ) -> Option<Vec<Coordinates>> {
+        let own = joined.find(labels[&area[0]]);
+        // reached tiles, with the last bomb of the wall leading to them
+        let mut reached: HashMap<Coordinates, Option<Coordinates>> =
+            area.iter().map(|c| (*c, None)).collect();
+        let mut queue: VecDeque<Coordinates> = area.iter().copied().collect();
+        while let Some(coords) = queue.pop_front() {
+            let wall = match self.is_bomb_at(coords) {
+                true => Some(coords),
+                false => None,
+            };
+            for neighbor in self.edge_neighbors(coords) {
+                if reached.contains_key(&neighbor) {
+                    continue;
+                }
+                if !self.is_bomb_at(neighbor) {
+                    let other = joined.find(labels[&neighbor]);
+                    if other != own {
+                        joined.join(own, other);
+                        let mut bombs = Vec::new();
+                        let mut next = wall;
+                        while let Some(bomb) = next {
+                            bombs.push(bomb);
+                            next = reached[&bomb];
+                        }
+                        return Some(bombs);
+                    }
+                    // a fresh start past the safe tiles of the area
+                    reached.insert(neighbor, None);
+                    queue.push_front(neighbor);
+                    continue;
+                }
+                reached.insert(neighbor, wall);
+                queue.push_back(neighbor);
+            }
+        }
+        None
+    }
+
+    // Would a bomb on the safe tile split its area? Its safe neighbors must stay connected
+    // around it, searched breadth first so the detour is found close by
+    fn splits_safe_area(&self, coordinates: Coordinates) -> bool {
+        let mut neighbors: Vec<Coordinates> = self
+            .edge_neighbors(coordinates)
+            .filter(|c| !self.is_bomb_at(*c))
+            .collect();
+        let start = match neighbors.pop() {
+            Some(c) => c,
+            None => return false,
+        };
+        let mut visited = HashSet::from([coordinates, start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(coords) = queue.pop_front() {
+            if neighbors.is_empty() {
+                return false;
+            }
+            for neighbor in self.edge_neighbors(coords) {
+                if self.is_bomb_at(neighbor) || !visited.insert(neighbor) {
+                    continue;
+                }
+                neighbors.retain(|c| *c != neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+        !neighbors.is_empty()
+    }
+
+    // Computes the bomb neighbor tiles from the placed bombs
+    fn update_bomb_neighbors(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coords = Coordinates { x, y };
+                if self.is_bomb_at(coords) || !self.exists(coords) {
+                    continue;
+                }
+
+                let tile = match self.bomb_count_at(coords) {
+                    0 => Tile::Empty,
+                    num => Tile::BombNeighbor(num),
+                };
+                self[y as usize][x as usize] = tile;
+            }
+        }
+    }
+}
+
+// Union-find of the safe areas joined by `TileMap::connect_safe_tiles`
+struct AreaUnion {
+    parents: Vec<usize>,
+}
+
+impl AreaUnion {
+    fn new(areas: usize) -> Self {
+        Self {
+            parents: (0..areas).collect(),
+        }
+    }
+
+    // Representative area of the areas joined with `area`
+    fn find(&mut self, mut area: usize) -> usize {
+        while self.parents[area] != area {
+            self.parents[area] = self.parents[self.parents[area]];
+            area = self.parents[area];
+        }
+        area
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a] = b;
+    }
+}
+
+// Draws random tiles until `bomb_count` distinct empty ones came up, as the first versions
+// placed the bombs
+fn retry_bomb_tiles(
+    tile_map: &TileMap,
+    bomb_count: usize,
+    rng: &mut (impl Rng + ?Sized),
+) -> Vec<Coordinates> {
+    let mut bombs = TileSet::new(tile_map.width, tile_map.height);
+    let mut drawn = Vec::with_capacity(bomb_count);
+    while drawn.len() < bomb_count {
+        let coords = Coordinates {
+            x: rng.gen_range(0..tile_map.width),
+            y: rng.gen_range(0..tile_map.height),
+        };
+        if tile_map[coords.y as usize][coords.x as usize] == Tile::Empty && bombs.insert(coords) {
+            drawn.push(coords);
+        }
+    }
+    drawn
+}
+
+// Single mine bombs of the tile maps saved without `max_mines`
+fn single_mine() -> u8 {
+    1
+}
+
+// Text rows in a frame of `-` and `|`, under a header line
+pub(crate) fn framed(header: &str, width: u16, rows: impl Iterator<Item = String>) -> String {
+    let line: String = (0..width + 2).map(|_| '-').collect();
+    let mut buffer = format!("{header}\n{line}\n");
+    for row in rows {
+        buffer = format!("{buffer}|{row}|\n");
+    }
+    format!("{buffer}{line}")
+}
+
+// Square text board, see `TileMap::from_text`
+impl FromStr for TileMap {
+    type Err = ParseMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_text(s, GridKind::Square)
+    }
+}
+
+impl Deref for TileMap {
+    type Target = Vec<Vec<Tile>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for TileMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
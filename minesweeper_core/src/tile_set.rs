@@ -0,0 +1,107 @@
+use crate::Coordinates;
+
+// Set of tiles of a tile map, stored as a bit per tile. Coordinates out of the map are never
+// contained
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TileSet {
+    width: u16,
+    height: u16,
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl TileSet {
+    // Empty set of a `width` x `height` tile map
+    pub fn new(width: u16, height: u16) -> Self {
+        let tiles = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            bits: vec![0; tiles.div_ceil(64)],
+            len: 0,
+        }
+    }
+
+    fn index(&self, coords: Coordinates) -> Option<usize> {
+        let in_bounds = coords.x < self.width && coords.y < self.height;
+        in_bounds.then(|| coords.y as usize * self.width as usize + coords.x as usize)
+    }
+
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        match self.index(coords) {
+            Some(i) => self.bits[i / 64] & (1 << (i % 64)) != 0,
+            None => false,
+        }
+    }
+
+    // Adds a tile, returning whether it was missing
+    pub fn insert(&mut self, coords: Coordinates) -> bool {
+        let i = match self.index(coords) {
+            Some(i) => i,
+            None => return false,
+        };
+        let (word, bit) = (&mut self.bits[i / 64], 1 << (i % 64));
+        let missing = *word & bit == 0;
+        *word |= bit;
+        self.len += missing as usize;
+        missing
+    }
+
+    // Removes a tile, returning whether it was present
+    pub fn remove(&mut self, coords: Coordinates) -> bool {
+        let i = match self.index(coords) {
+            Some(i) => i,
+            None => return false,
+        };
+        let (word, bit) = (&mut self.bits[i / 64], 1 << (i % 64));
+        let present = *word & bit != 0;
+        *word &= !bit;
+        self.len -= present as usize;
+        present
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Tiles of the set, row by row from the bottom, skipping the empty words
+    pub fn iter(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        let width = self.width as usize;
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| **word != 0)
+            .flat_map(move |(w, word)| {
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| {
+                        let i = w * 64 + bit;
+                        Coordinates {
+                            x: (i % width) as u16,
+                            y: (i / width) as u16,
+                        }
+                    })
+            })
+    }
+}
+
+impl Extend<Coordinates> for TileSet {
+    fn extend<I: IntoIterator<Item = Coordinates>>(&mut self, iter: I) {
+        for coords in iter {
+            self.insert(coords);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TileSet {
+    type Item = Coordinates;
+    type IntoIter = Box<dyn Iterator<Item = Coordinates> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
@@ -0,0 +1,777 @@
+// Invariants of the tile map generation and of the game flood fill and chords, over arbitrary
+// boards
+use minesweeper_core::solver::{BombOdds, Deduction};
+use minesweeper_core::{
+    Action, BoardMask, BombPlacement, Coordinates, Game, GameState, GridKind, Metrics,
+    NeighborKernel, ParseMapError, Tile, TileMap,
+};
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::{HashMap, VecDeque};
+use std::iter;
+
+const MAX_SIZE: u16 = 40;
+
+// Tile map of the given size and tiling, with `bomb_count` bombs placed from `seed`
+fn tile_map(width: u16, height: u16, grid: GridKind, bomb_count: u16, seed: u64) -> TileMap {
+    let mut tile_map = TileMap::empty_with_grid(width, height, grid);
+    tile_map.set_bombs(bomb_count.into(), &mut StdRng::seed_from_u64(seed));
+    tile_map
+}
+
+// Bombs around a tile, counted from the neighbor deltas of the tiling rather than through the
+// neighbor iterators of the tile map. Odd hexagonal rows are shifted to the right
+fn adjacent_bombs(tile_map: &TileMap, coords: Coordinates) -> usize {
+    let deltas: &[(i32, i32)] = match (tile_map.grid(), coords.y % 2) {
+        (GridKind::Square, _) => &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ],
+        (GridKind::Hex, 0) => &[(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)],
+        (GridKind::Hex, _) => &[(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)],
+    };
+    deltas
+        .iter()
+        .map(|(dx, dy)| (coords.x as i32 + dx, coords.y as i32 + dy))
+        .filter(|&(x, y)| x >= 0 && y >= 0)
+        .filter(|&(x, y)| {
+            tile_map.is_bomb_at(Coordinates {
+                x: x as u16,
+                y: y as u16,
+            })
+        })
+        .count()
+}
+
+fn grid_kind() -> impl Strategy<Value = GridKind> {
+    prop_oneof![Just(GridKind::Square), Just(GridKind::Hex)]
+}
+
+// Board size, bomb count up to twice the tile count and seed
+fn board() -> impl Strategy<Value = (u16, u16, u16, u64)> {
+    (1..=MAX_SIZE, 1..=MAX_SIZE).prop_flat_map(|(width, height)| {
+        let tiles = width * height;
+        (Just(width), Just(height), 0..=tiles * 2, any::<u64>())
+    })
+}
+
+// Moves of any kind on tiles of a board up to `MAX_SIZE` wide and high, some of them off the
+// boards smaller than that
+fn actions() -> impl Strategy<Value = Vec<Action>> {
+    let action = (0..4u8, 0..MAX_SIZE, 0..MAX_SIZE).prop_map(|(kind, x, y)| {
+        let coords = Coordinates { x, y };
+        match kind {
+            0 => Action::Uncover(coords),
+            1 => Action::Chord(coords),
+            2 => Action::ToggleFlag(coords),
+            _ => Action::Undo,
+        }
+    });
+    prop::collection::vec(action, 0..80)
+}
+
+proptest! {
+    #[test]
+    fn places_the_exact_bomb_count((width, height, bomb_count, seed) in board(), grid in grid_kind()) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let expected = bomb_count.min(width * height);
+        let bombs = tile_map.tiles().filter(|(_, t)| t.is_bomb()).count();
+        prop_assert_eq!(bombs, expected as usize);
+        prop_assert_eq!(tile_map.bomb_count(), expected as u32);
+    }
+
+    #[test]
+    fn numbers_match_the_adjacent_bombs((width, height, bomb_count, seed) in board(), grid in grid_kind()) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        for (coords, tile) in tile_map.tiles() {
+            let adjacent = adjacent_bombs(&tile_map, coords);
+            match tile {
+                Tile::Bomb(_) => (),
+                Tile::Empty => prop_assert_eq!(adjacent, 0, "empty tile {}", coords),
+                Tile::BombNeighbor(count) => {
+                    prop_assert!(count > 0);
+                    prop_assert_eq!(count as usize, adjacent, "number of {}", coords);
+                }
+                Tile::Void => prop_assert!(false, "hole on a rectangular board"),
+            }
+        }
+    }
+
+    #[test]
+    fn knight_numbers_count_the_bombs_a_knight_move_away(
+        (width, height, bomb_count, seed) in board(),
+        (x, y) in (0..MAX_SIZE, 0..MAX_SIZE),
+    ) {
+        let tile_map = tile_map(width, height, GridKind::Square, bomb_count, seed)
+            .with_kernel(NeighborKernel::Knight);
+        for (coords, tile) in tile_map.tiles().filter(|(_, t)| !t.is_bomb()) {
+            let (cx, cy) = (coords.x as i32, coords.y as i32);
+            let knights = [(1, 2), (2, 1)]
+                .into_iter()
+                .flat_map(|(dx, dy)| [(dx, dy), (-dx, dy), (dx, -dy), (-dx, -dy)])
+                .map(|(dx, dy)| (cx + dx, cy + dy))
+                .filter(|&(x, y)| x >= 0 && y >= 0)
+                .filter(|&(x, y)| tile_map.is_bomb_at(Coordinates { x: x as u16, y: y as u16 }))
+                .count();
+            let count = match tile {
+                Tile::BombNeighbor(count) => count as usize,
+                _ => 0,
+            };
+            prop_assert_eq!(count, knights, "number of {}", coords);
+        }
+
+        // the openings flood through the knight's moves
+        let start = Coordinates { x: x % width, y: y % height };
+        prop_assume!(!tile_map.is_bomb_at(start));
+        let mut game = Game::new(tile_map.clone());
+        let revealed = game.reveal(start).expect("covered tile").revealed.clone();
+        for coords in revealed.iter() {
+            prop_assert!(!tile_map.is_bomb_at(*coords), "revealed bomb {}", coords);
+        }
+    }
+
+    #[test]
+    fn wrapped_neighbors_cross_the_edges_both_ways(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        // the hexagon rows only wrap in even counts
+        let height = match grid {
+            GridKind::Hex => height + height % 2,
+            GridKind::Square => height,
+        };
+        let tile_map = tile_map(width, height, grid, bomb_count, seed).with_wrap(true);
+        prop_assert!(tile_map.check_bomb_count(0, false).is_ok());
+        for (coords, tile) in tile_map.tiles() {
+            let neighbors: Vec<Coordinates> = tile_map.neighbors(coords).collect();
+            prop_assert!(!neighbors.contains(&coords), "{} is its own neighbor", coords);
+            for neighbor in neighbors.iter() {
+                prop_assert!(tile_map.in_bounds(*neighbor), "neighbor {} of {}", neighbor, coords);
+                prop_assert!(tile_map.neighbors(*neighbor).any(|n| n == coords), "{} not back to {}", neighbor, coords);
+            }
+            // a tile away from the narrow boards has all its neighbors
+            if width >= 3 && height >= 3 {
+                prop_assert_eq!(neighbors.len(), grid.neighbor_deltas(coords).len());
+            }
+            if let Tile::BombNeighbor(count) = tile {
+                let bombs = neighbors.iter().filter(|n| tile_map.is_bomb_at(**n)).count();
+                prop_assert_eq!(count as usize, bombs, "number of {}", coords);
+            }
+        }
+    }
+
+    #[test]
+    fn flood_fill_never_reveals_a_bomb(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        (x, y) in (0..MAX_SIZE, 0..MAX_SIZE),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let start = Coordinates { x: x % width, y: y % height };
+        prop_assume!(!tile_map.is_bomb_at(start));
+
+        let mut game = Game::new(tile_map.clone());
+        let revealed = game.reveal(start).expect("covered tile").revealed.clone();
+        prop_assert_ne!(game.state(), GameState::Lost);
+        prop_assert!(revealed.contains(&start));
+        for coords in revealed.iter() {
+            prop_assert!(!tile_map.is_bomb_at(*coords), "revealed bomb {}", coords);
+            // the propagation goes through every neighbor of the empty tiles
+            if tile_map[coords.y as usize][coords.x as usize] == Tile::Empty {
+                for neighbor in tile_map.neighbors(*coords) {
+                    prop_assert!(!game.is_covered(neighbor), "covered neighbor {}", neighbor);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flood_fill_reveals_the_closest_tiles_first(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        (x, y) in (0..MAX_SIZE, 0..MAX_SIZE),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let start = Coordinates { x: x % width, y: y % height };
+        prop_assume!(!tile_map.is_bomb_at(start));
+
+        let mut game = Game::new(tile_map.clone());
+        let revealed = game.reveal(start).expect("covered tile").revealed.clone();
+        prop_assert_eq!(revealed[0], start);
+        // steps away from the start through the empty tiles, never going down along the reveals
+        let mut steps = HashMap::from([(start, 0)]);
+        for coords in revealed.iter().skip(1) {
+            let step = tile_map
+                .neighbors(*coords)
+                .filter(|c| tile_map[c.y as usize][c.x as usize] == Tile::Empty)
+                .filter_map(|c| steps.get(&c))
+                .min()
+                .map(|s| s + 1);
+            prop_assert!(step.is_some(), "{} revealed before its empty neighbors", coords);
+            steps.insert(*coords, step.unwrap());
+        }
+        let steps: Vec<usize> = revealed.iter().map(|c| steps[c]).collect();
+        prop_assert!(steps.windows(2).all(|w| w[0] <= w[1]), "steps {:?}", steps);
+    }
+
+    #[test]
+    fn chord_on_flagged_bombs_uncovers_the_safe_neighbors(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        pick in any::<usize>(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let numbers: Vec<Coordinates> = tile_map
+            .tiles()
+            .filter(|(_, t)| matches!(t, Tile::BombNeighbor(_)))
+            .map(|(c, _)| c)
+            .collect();
+        prop_assume!(!numbers.is_empty());
+        let start = numbers[pick % numbers.len()];
+
+        let mut game = Game::new(tile_map.clone());
+        game.reveal(start).expect("covered tile");
+        for neighbor in tile_map.bomb_neighbors(start) {
+            game.toggle_flag(neighbor);
+        }
+        let history = game.history().len();
+        let chorded = game.chord(start).is_some();
+        prop_assert_ne!(game.state(), GameState::Lost);
+        for neighbor in tile_map.neighbors(start) {
+            prop_assert_eq!(game.is_covered(neighbor), tile_map.is_bomb_at(neighbor), "neighbor {}", neighbor);
+        }
+        // a single move, undone at once
+        if chorded {
+            prop_assert_eq!(game.history().len(), history + 1);
+            game.undo();
+            prop_assert_eq!(game.history().len(), history);
+        }
+    }
+
+    #[test]
+    fn generation_terminates_or_fails(
+        (width, height) in (0..=MAX_SIZE, 0..=MAX_SIZE),
+        bomb_count in any::<u16>(),
+        seed in any::<u64>(),
+        masked in any::<bool>(),
+    ) {
+        let mut tile_map = TileMap::empty(width, height);
+        if masked {
+            tile_map.apply_mask(&BoardMask::donut(width, height));
+        }
+        let tile_count = tile_map.tile_count();
+        let result = tile_map.try_set_bombs(bomb_count.into(), &mut StdRng::seed_from_u64(seed));
+        match result {
+            Ok(()) => prop_assert_eq!(tile_map.bomb_count(), bomb_count as u32),
+            Err(_) => prop_assert!(tile_count == 0 || bomb_count as usize > tile_count),
+        }
+    }
+
+    #[test]
+    fn safe_start_validation_leaves_an_opening(
+        (width, height) in (1..=MAX_SIZE, 1..=MAX_SIZE),
+        grid in grid_kind(),
+    ) {
+        let mut tile_map = TileMap::empty_with_grid(width, height, grid);
+        let max_bomb_count = tile_map.max_safe_start_bombs() as u32;
+        prop_assert!(tile_map.check_bomb_count(max_bomb_count, true).is_ok());
+        prop_assert!(tile_map.check_bomb_count(max_bomb_count + 1, true).is_err());
+
+        // the most bombs still fit around the smallest neighborhood
+        let (opening, _) = tile_map
+            .tiles()
+            .min_by_key(|(c, _)| tile_map.neighbors(*c).count())
+            .unwrap();
+        let free: Vec<Coordinates> = iter::once(opening).chain(tile_map.neighbors(opening)).collect();
+        let bombs: Vec<Coordinates> = tile_map
+            .tiles()
+            .map(|(c, _)| c)
+            .filter(|c| !free.contains(c))
+            .collect();
+        tile_map.place_bombs(bombs);
+        prop_assert_eq!(tile_map.bomb_count(), max_bomb_count);
+        prop_assert_eq!(tile_map[opening.y as usize][opening.x as usize], Tile::Empty);
+    }
+
+    #[test]
+    fn text_output_reads_back((width, height, bomb_count, seed) in board(), grid in grid_kind()) {
+        let mut tile_map = TileMap::empty_with_grid(width, height, grid);
+        tile_map.apply_mask(&BoardMask::donut(width, height));
+        tile_map.set_bombs(bomb_count.into(), &mut StdRng::seed_from_u64(seed));
+        prop_assume!(tile_map.tile_count() > 0);
+
+        // the player view of the game text is left out
+        let text = Game::new(tile_map.clone()).text_output();
+        let parsed = TileMap::from_text(&text, grid).unwrap();
+        prop_assert_eq!(&*parsed, &*tile_map);
+        prop_assert_eq!(parsed.bomb_count(), tile_map.bomb_count());
+    }
+
+    #[test]
+    fn cleared_boards_solve_their_whole_three_bv(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let mut game = Game::new(tile_map.clone());
+        prop_assert_eq!(Metrics::new(&game, 0.).solved_three_bv, 0);
+
+        let safe: Vec<Coordinates> = tile_map
+            .tiles()
+            .map(|(c, _)| c)
+            .filter(|c| !tile_map.is_bomb_at(*c))
+            .collect();
+        for coords in safe {
+            game.reveal(coords);
+        }
+        let metrics = Metrics::new(&game, 1.);
+        prop_assert_eq!(game.state(), GameState::Won);
+        prop_assert_eq!(metrics.solved_three_bv, tile_map.three_bv());
+        // the tiles uncovered by a previous cascade waste their click
+        prop_assert_eq!(metrics.clicks - metrics.wasted_clicks, game.history().len() as u32);
+    }
+
+    #[test]
+    fn strict_games_never_flag_more_than_the_bombs(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let mut game = Game::new(tile_map.clone()).with_strict_flags(true);
+        // boards without a safe tile are won from the start
+        prop_assume!(game.state() == GameState::Playing);
+        let tiles: Vec<Coordinates> = tile_map.tiles().map(|(c, _)| c).collect();
+        for coords in tiles.iter() {
+            game.toggle_flag(*coords);
+        }
+        let expected = (tile_map.bomb_count() as usize).min(tiles.len());
+        prop_assert_eq!(game.flags().len(), expected);
+        prop_assert_eq!(game.mines_left(), 0);
+        // removing a flag makes room for another one
+        let flagged = game.flags().iter().next();
+        if let Some(flagged) = flagged {
+            prop_assert_eq!(game.toggle_flag(flagged), Some(false));
+            prop_assert_eq!(game.mines_left(), 1);
+        }
+    }
+
+    #[test]
+    fn connecting_opens_every_pocket_and_keeps_the_bombs(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let mut tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let bombs = tile_map.bomb_count();
+        let mut rng = StdRng::seed_from_u64(seed);
+        // rectangular boards have no pocket sealed off by holes
+        prop_assert!(tile_map.connect_safe_tiles(&mut rng));
+        prop_assert!(tile_map.is_connected());
+        prop_assert_eq!(tile_map.tiles().filter(|(_, t)| t.is_bomb()).count(), bombs as usize);
+        for (coords, tile) in tile_map.tiles() {
+            let expected = match tile_map.bomb_neighbors(coords).count() {
+                0 => Tile::Empty,
+                count => Tile::BombNeighbor(count as u8),
+            };
+            if !tile.is_bomb() {
+                prop_assert_eq!(tile, expected, "number of {}", coords);
+            }
+        }
+    }
+
+    #[test]
+    fn updated_odds_agree_with_the_deductions(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let mut game = Game::new(tile_map.clone());
+        let mut odds = BombOdds::new(&game.solver());
+        let mut safe: Vec<Coordinates> = tile_map
+            .tiles()
+            .map(|(c, _)| c)
+            .filter(|c| !tile_map.is_bomb_at(*c))
+            .collect();
+        safe.shuffle(&mut StdRng::seed_from_u64(seed));
+        for coords in safe.into_iter().take(5) {
+            let revealed = match game.reveal(coords) {
+                Some(delta) => delta.revealed.clone(),
+                None => continue,
+            };
+            let solver = game.solver();
+            odds.update(&solver, revealed);
+            for deduction in solver.single_tile_deductions() {
+                match deduction {
+                    Deduction::Safe(c) => prop_assert_eq!(odds.get(c), 0., "safe {}", c),
+                    Deduction::Bomb(c) => prop_assert_eq!(odds.get(c), 1., "bomb {}", c),
+                }
+            }
+            for (c, _) in tile_map.tiles().filter(|(c, _)| solver.is_unknown(*c)) {
+                prop_assert!((0. ..=1.).contains(&odds.get(c)), "odds of {}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn chain_reactions_follow_the_bombs_sharing_an_edge(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let origin = match tile_map.tiles().find(|(_, t)| t.is_bomb()) {
+            Some((c, _)) => c,
+            None => return Ok(()),
+        };
+        let chain = tile_map.chain_reaction(&[origin]);
+        let mut steps = HashMap::from([(origin, 0)]);
+        for (coords, step) in chain.iter().copied() {
+            prop_assert!(tile_map.is_bomb_at(coords), "chained {}", coords);
+            prop_assert!(steps.insert(coords, step).is_none(), "chained twice {}", coords);
+            // set off by a bomb of the previous step
+            let parent = tile_map
+                .edge_neighbors(coords)
+                .any(|n| steps.get(&n) == Some(&(step - 1)));
+            prop_assert!(parent, "step {} of {}", step, coords);
+        }
+        // every bomb next to the chain is part of it
+        for coords in steps.keys() {
+            for neighbor in tile_map.edge_neighbors(*coords) {
+                prop_assert!(!tile_map.is_bomb_at(neighbor) || steps.contains_key(&neighbor));
+            }
+        }
+    }
+
+    #[test]
+    fn stacked_mines_add_up_in_the_numbers_and_the_flags(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let mut tile_map = tile_map(width, height, grid, bomb_count, seed);
+        tile_map.stack_mines(3, &mut StdRng::seed_from_u64(seed));
+        prop_assert_eq!(tile_map.bomb_count() as usize, bomb_count.min(width * height) as usize);
+        for (coords, tile) in tile_map.tiles().filter(|(_, t)| !t.is_bomb()) {
+            let expected = match tile_map.neighbors(coords).map(|n| tile_map.mines_at(n)).sum() {
+                0 => Tile::Empty,
+                mines => Tile::BombNeighbor(mines),
+            };
+            prop_assert_eq!(tile, expected, "number of {}", coords);
+        }
+        let text = Game::new(tile_map.clone()).text_output();
+        let parsed = TileMap::from_text(&text, grid).unwrap();
+        prop_assert_eq!(&*parsed, &*tile_map);
+
+        // the flags of a tile stack up to three, then go away, and come back with the undos
+        let bomb = match tile_map.tiles().find(|(_, t)| t.is_bomb()) {
+            Some((c, _)) => c,
+            None => return Ok(()),
+        };
+        let mut game = Game::new(tile_map.clone());
+        prop_assume!(game.state() == GameState::Playing);
+        let mines = tile_map.mine_count() as i64;
+        for count in 1..=3 {
+            prop_assert_eq!(game.toggle_flag(bomb), Some(true));
+            prop_assert_eq!(game.flag_count(bomb), count);
+            prop_assert_eq!(game.mines_left(), mines - count as i64);
+        }
+        prop_assert_eq!(game.toggle_flag(bomb), Some(false));
+        prop_assert_eq!(game.mines_left(), mines);
+        for count in (0..=3).rev() {
+            game.undo();
+            prop_assert_eq!(game.flag_count(bomb), count);
+        }
+    }
+
+    #[test]
+    fn replaying_the_actions_gives_the_same_state(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        actions in actions(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let play = || {
+            let mut game = Game::new(tile_map.clone());
+            game.open();
+            let hashes: Vec<u64> = actions
+                .iter()
+                .filter(|a| a.coordinates().into_iter().all(|c| tile_map.exists(c)))
+                .map(|a| {
+                    game.apply(*a);
+                    game.state_hash()
+                })
+                .collect();
+            (game, hashes)
+        };
+        let (game, hashes) = play();
+        let (replayed, replayed_hashes) = play();
+        prop_assert_eq!(hashes, replayed_hashes);
+        prop_assert_eq!(game.text_output(), replayed.text_output());
+        prop_assert_eq!(game.state(), replayed.state());
+    }
+
+    #[test]
+    fn undoing_an_action_restores_the_state_hash(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        actions in actions(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let mut game = Game::new(tile_map.clone());
+        // undoing an undo doesn't play the move again
+        let moves = actions
+            .iter()
+            .filter(|a| a.coordinates().into_iter().any(|c| tile_map.exists(c)));
+        for action in moves {
+            let before = game.state_hash();
+            if game.apply(*action) {
+                let after = game.state_hash();
+                game.undo();
+                prop_assert_eq!(game.state_hash(), before, "undoing {:?}", action);
+                game.apply(*action);
+                prop_assert_eq!(game.state_hash(), after, "playing {:?} again", action);
+            }
+        }
+    }
+
+    #[test]
+    fn the_layout_hash_follows_the_seed(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+        other_seed in any::<u64>(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let same = self::tile_map(width, height, grid, bomb_count, seed);
+        prop_assert_eq!(tile_map.layout_hash(), same.layout_hash());
+        let other = self::tile_map(width, height, grid, bomb_count, other_seed);
+        prop_assert_eq!(
+            tile_map.layout_hash() == other.layout_hash(),
+            tile_map.text_output() == other.text_output()
+        );
+    }
+
+    #[test]
+    fn clearing_the_board_in_any_order_ends_in_the_same_state(
+        (width, height, bomb_count, seed) in board(),
+        grid in grid_kind(),
+    ) {
+        let tile_map = tile_map(width, height, grid, bomb_count, seed);
+        let safe: Vec<Coordinates> = tile_map
+            .tiles()
+            .filter(|(_, tile)| !tile.is_bomb())
+            .map(|(c, _)| c)
+            .collect();
+        let mut shuffled = safe.clone();
+        shuffled.shuffle(&mut StdRng::seed_from_u64(seed));
+        let clear = |order: &[Coordinates]| {
+            let mut game = Game::new(tile_map.clone());
+            for coords in order {
+                game.apply(Action::Uncover(*coords));
+            }
+            game
+        };
+        let (game, other) = (clear(&safe), clear(&shuffled));
+        prop_assert_eq!(game.state_hash(), other.state_hash());
+        prop_assert_eq!(game.state(), other.state());
+        if !safe.is_empty() {
+            prop_assert_eq!(game.state(), GameState::Won);
+        }
+    }
+}
+
+// The first placement deals the boards of the seeds saved before the shuffle
+#[test]
+fn retry_placement_keeps_the_boards_of_the_first_versions() {
+    let mut tile_map = TileMap::empty(9, 9).with_placement(BombPlacement::Retry);
+    tile_map.set_bombs(10, &mut StdRng::seed_from_u64(42));
+    let bombs: Vec<(u16, u16)> = tile_map
+        .tiles()
+        .filter(|(_, tile)| tile.is_bomb())
+        .map(|(c, _)| (c.x, c.y))
+        .collect();
+    let expected = [
+        (4, 0),
+        (8, 0),
+        (5, 1),
+        (5, 3),
+        (8, 3),
+        (1, 4),
+        (2, 4),
+        (7, 5),
+        (3, 6),
+        (1, 7),
+    ];
+    assert_eq!(bombs, expected);
+}
+
+// No-flag games refuse the player flags and the forced ones alike
+#[test]
+fn no_flag_games_refuse_every_flag() {
+    // the opening on the right forces the middle bomb, the 2 staying covered
+    let tile_map = TileMap::from_text("*2*1.", GridKind::Square).expect("text board");
+    let play = |no_flags: bool| {
+        let mut game = Game::new(tile_map.clone()).with_no_flags(no_flags);
+        game.reveal(Coordinates { x: 4, y: 0 });
+        game
+    };
+    let bomb = Coordinates { x: 0, y: 0 };
+
+    let mut game = play(true);
+    assert_eq!(game.toggle_flag(bomb), None);
+    assert!(!game.is_flagged(bomb));
+    assert!(game.flag_forced_bombs().is_empty());
+    assert_eq!(
+        play(false).flag_forced_bombs(),
+        [Coordinates { x: 2, y: 0 }]
+    );
+}
+
+// Forgives the bomb of two mines of a board after misplacing two flags, which use up the flags
+// of strict games, and checks that the bomb is fully marked without a click
+fn forgive_bomb(mut game: Game) {
+    // the bomb on the left, the two tiles on the right are safe
+    let bomb = Coordinates { x: 0, y: 0 };
+    game.toggle_flag(Coordinates { x: 2, y: 0 });
+    game.toggle_flag(Coordinates { x: 3, y: 0 });
+    game.reveal(bomb);
+    assert_eq!(game.state(), GameState::Lost);
+    game.undo();
+
+    let clicks = (game.clicks(), game.wasted_clicks());
+    assert!(game.mark_forgiven(bomb));
+    assert!(game.is_covered(bomb));
+    assert_eq!(game.flag_count(bomb), 2);
+    assert_eq!((game.clicks(), game.wasted_clicks()), clicks);
+    assert!(!game.mark_forgiven(bomb));
+    // undone like a move
+    game.undo();
+    assert_eq!(game.flag_count(bomb), 0);
+}
+
+fn forgiving_game() -> Game {
+    Game::new(TileMap::from_text("@2..", GridKind::Square).expect("text board"))
+}
+
+#[test]
+fn forgiven_bombs_are_marked() {
+    forgive_bomb(forgiving_game());
+}
+
+#[test]
+fn forgiven_bombs_are_marked_in_strict_games() {
+    forgive_bomb(forgiving_game().with_strict_flags(true));
+}
+
+#[test]
+fn forgiven_bombs_are_marked_in_no_flag_games() {
+    forgive_bomb(forgiving_game().with_no_flags(true));
+}
+
+// Plays a move along with the actions following from it as the board reducer does, every loss
+// being forgiven and the bombs set off by its chain reaction marked, logging the applied actions
+fn play_logged(game: &mut Game, log: &mut Vec<Action>, action: Action) {
+    let mut queue = VecDeque::from([action]);
+    while let Some(action) = queue.pop_front() {
+        // bombs of the losing move, taken back by the forgive
+        let bombs: Vec<Coordinates> = match action {
+            Action::Forgive => game
+                .history()
+                .last()
+                .into_iter()
+                .flat_map(|d| d.revealed.iter().copied())
+                .filter(|c| game.tile_map().is_bomb_at(*c))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if !game.apply(action) {
+            continue;
+        }
+        log.push(action);
+        match (action, game.state()) {
+            (Action::Uncover(_) | Action::Chord(_), GameState::Lost) => {
+                queue.push_back(Action::Forgive)
+            }
+            (Action::Uncover(_) | Action::Chord(_) | Action::Open, GameState::Playing) => {
+                queue.push_back(Action::FlagForced)
+            }
+            (Action::Forgive, _) => {
+                let chain = game.tile_map().chain_reaction(&bombs);
+                let marks = chain.iter().filter(|(c, _)| !game.is_flagged(*c));
+                queue.extend(marks.map(|(c, _)| Action::MarkForgiven(*c)));
+            }
+            _ => (),
+        }
+    }
+}
+
+// The action log of a game spending a life and flagging a chain reaction plays the same game
+// again, the forgiven mistake and the forced flags included
+#[test]
+fn action_logs_replay_forgiven_games() {
+    // the forced flag of the middle bomb leaves the left one to the chain reaction
+    let tile_map = TileMap::from_text(".***.....", GridKind::Square).expect("text board");
+    let mut game = Game::new(tile_map.clone());
+    let mut log = Vec::new();
+    play_logged(&mut game, &mut log, Action::Open);
+    for x in [8, 1, 0] {
+        play_logged(
+            &mut game,
+            &mut log,
+            Action::Uncover(Coordinates { x, y: 0 }),
+        );
+    }
+    let expected = [
+        Action::Uncover(Coordinates { x: 8, y: 0 }),
+        Action::FlagForced,
+        Action::Uncover(Coordinates { x: 1, y: 0 }),
+        Action::Forgive,
+        Action::MarkForgiven(Coordinates { x: 2, y: 0 }),
+        Action::Uncover(Coordinates { x: 0, y: 0 }),
+    ];
+    assert_eq!(log, expected);
+    assert_eq!(game.state(), GameState::Won);
+
+    let mut replayed = Game::new(tile_map);
+    for action in log.iter() {
+        assert!(replayed.apply(*action), "{action:?} refused");
+    }
+    assert_eq!(replayed.state_hash(), game.state_hash());
+    assert_eq!(replayed.history(), game.history());
+    assert_eq!(replayed.flags(), game.flags());
+    assert_eq!(
+        (replayed.clicks(), replayed.wasted_clicks()),
+        (game.clicks(), game.wasted_clicks())
+    );
+    // the player moves alone lose the game
+    let mut moves_only = Game::new(replayed.tile_map().clone());
+    for action in log.iter().filter(|a| a.is_move()) {
+        moves_only.apply(*action);
+    }
+    assert_eq!(moves_only.state(), GameState::Lost);
+}
+
+// Boards wider or taller than a tile map holds are refused, not truncated
+#[test]
+fn oversized_text_boards_are_refused() {
+    let wide = ".".repeat(usize::from(u16::MAX) + 1);
+    assert!(matches!(
+        TileMap::from_text(&wide, GridKind::Square),
+        Err(ParseMapError::TooLarge {
+            width: 65536,
+            height: 1
+        })
+    ));
+    let tall = ".\n".repeat(usize::from(u16::MAX) + 1);
+    assert!(matches!(
+        TileMap::from_text(&tall, GridKind::Square),
+        Err(ParseMapError::TooLarge {
+            width: 1,
+            height: 65536
+        })
+    ));
+}
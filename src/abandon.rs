@@ -0,0 +1,183 @@
+use crate::controls::RestartEvent;
+use crate::demo::Demo;
+use crate::locale::Locale;
+use crate::settings::Settings;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::resources::{Board, ReplayPlayback};
+use minesweeper_core::GameState;
+
+// Action waiting for the player to confirm abandoning the game. Must be used as a resource
+#[derive(Resource, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Abandon {
+    Quit,
+    Restart(RestartEvent),
+}
+
+#[derive(Component)]
+struct AbandonRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+enum AbandonButton {
+    Confirm,
+    Cancel,
+}
+
+// Quits on Escape, asking first when a game is in progress
+pub struct AbandonPlugin;
+
+impl Plugin for AbandonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, quit_on_escape)
+            .on_enter(AppState::Abandon, setup_abandon)
+            .on_update(AppState::Abandon, (abandon_buttons, abandon_keys))
+            .on_exit(AppState::Abandon, (cleanup::<AbandonRoot>, end_abandon));
+    }
+}
+
+// Is the player in the middle of a game? Finished boards, untouched boards, replays and demos
+// are left without asking
+pub fn in_progress(
+    settings: &Settings,
+    state: &AppState,
+    boards: &Query<&Board>,
+    watching: bool,
+) -> bool {
+    settings.confirm_abandon
+        && !watching
+        && matches!(state, AppState::InGame | AppState::Paused)
+        && boards
+            .iter()
+            .any(|b| b.game.state() == GameState::Playing && !b.game.history().is_empty())
+}
+
+// Asks before leaving a game in progress for `abandon`, returning whether it was asked
+pub fn ask_abandon(
+    cmds: &mut Commands,
+    state: &mut StateSwitch<AppState>,
+    abandon: Abandon,
+) -> bool {
+    if !state.push(AppState::Abandon) {
+        return false;
+    }
+    info!("Asking before abandoning the game: {abandon:?}");
+    cmds.insert_resource(abandon);
+    true
+}
+
+#[allow(clippy::too_many_arguments)]
+fn quit_on_escape(
+    mut cmds: Commands,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    boards: Query<&Board>,
+    playback: Option<Res<ReplayPlayback>>,
+    demo: Option<Res<Demo>>,
+    mut state: StateSwitch<AppState>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    // the dialog takes Escape as a cancel
+    if !keys.just_pressed(KeyCode::Escape) || *state.current() == AppState::Abandon {
+        return;
+    }
+    let watching = playback.is_some() || demo.is_some();
+    if in_progress(&settings, state.current(), &boards, watching)
+        && ask_abandon(&mut cmds, &mut state, Abandon::Quit)
+    {
+        // the press asking doesn't answer the dialog in the same frame
+        keys.reset(KeyCode::Escape);
+        return;
+    }
+    exit.write(AppExit::Success);
+}
+
+fn setup_abandon(mut cmds: Commands, ui_assets: Res<UiAssets>, locale: Res<Locale>) {
+    let font = &ui_assets.font;
+    // covers the pause screen the dialog may be pushed over
+    cmds.spawn((
+        screen_root(),
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+    ))
+    .insert(AbandonRoot)
+    .insert(Name::new("Abandon"))
+    .with_children(|parent| {
+        spawn_text(parent, font, locale.get("abandon.title"), 32.);
+        spawn_text(parent, font, locale.get("abandon.hint"), 12.);
+        let confirm = locale.get("abandon.confirm");
+        spawn_button(parent, font, confirm, AbandonButton::Confirm);
+        let cancel = locale.get("abandon.cancel");
+        spawn_button(parent, font, cancel, AbandonButton::Cancel);
+    });
+}
+
+// Plays the abandoned action, or goes back to the game
+fn answer(
+    confirmed: bool,
+    abandon: Abandon,
+    state: &mut StateSwitch<AppState>,
+    exit: &mut MessageWriter<AppExit>,
+    restart_ewr: &mut MessageWriter<RestartEvent>,
+) {
+    match (confirmed, abandon) {
+        (false, _) => {
+            info!("Game kept");
+            state.pop();
+        }
+        (true, Abandon::Quit) => {
+            exit.write(AppExit::Success);
+        }
+        // the restart leaves the dialog along with the game
+        (true, Abandon::Restart(event)) => {
+            restart_ewr.write(event);
+        }
+    }
+}
+
+fn abandon_buttons(
+    abandon: Option<Res<Abandon>>,
+    buttons: Query<(&Interaction, &AbandonButton), Changed<Interaction>>,
+    mut state: StateSwitch<AppState>,
+    mut exit: MessageWriter<AppExit>,
+    mut restart_ewr: MessageWriter<RestartEvent>,
+) {
+    let abandon = match abandon {
+        Some(a) => *a,
+        None => return,
+    };
+    let button = match buttons.iter().find(|(i, _)| **i == Interaction::Pressed) {
+        Some((_, b)) => *b,
+        None => return,
+    };
+    let confirmed = matches!(button, AbandonButton::Confirm);
+    answer(confirmed, abandon, &mut state, &mut exit, &mut restart_ewr);
+}
+
+// Enter abandons the game, Escape keeps it
+fn abandon_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    abandon: Option<Res<Abandon>>,
+    mut state: StateSwitch<AppState>,
+    mut exit: MessageWriter<AppExit>,
+    mut restart_ewr: MessageWriter<RestartEvent>,
+) {
+    // the dialog is asked for with commands, applied by the end of the frame
+    let abandon = match abandon {
+        Some(a) => *a,
+        None => return,
+    };
+    let confirmed = if keys.just_pressed(KeyCode::Enter) {
+        true
+    } else if keys.just_pressed(KeyCode::Escape) {
+        false
+    } else {
+        return;
+    };
+    answer(confirmed, abandon, &mut state, &mut exit, &mut restart_ewr);
+}
+
+fn end_abandon(mut cmds: Commands) {
+    cmds.remove_resource::<Abandon>();
+}
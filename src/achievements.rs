@@ -0,0 +1,339 @@
+use crate::coop::Coop;
+use crate::daily;
+use crate::demo::Demo;
+use crate::difficulty::Difficulty;
+use crate::leaderboard::is_ranked;
+use crate::locale::Locale;
+use crate::persistence::{self, Profile};
+use crate::protocol::Connection;
+use crate::race::Race;
+use crate::score_attack::ScoreAttack;
+use crate::stats::Stats;
+use crate::toast::ToastEvent;
+use crate::tutorial::Tutorial;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::resources::{Board, GameTimer, ReplayPlayback};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const ACHIEVEMENTS_FILE: &str = "achievements.ron";
+// Boards cleared in a row for the streak achievement
+const STREAK_WINS: u32 = 10;
+
+// Goals unlocked once per profile
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstWin,
+    // Beginner board won in less than 10 seconds
+    QuickBeginner,
+    // Expert board won in less than 100 seconds
+    ExpertSprint,
+    // Preset board won without placing a single flag
+    NoFlags,
+    // Expert board won without any assist
+    PureExpert,
+    // `STREAK_WINS` boards won without losing one in between
+    Streak,
+}
+
+// Won board the achievements are checked against
+#[derive(Debug, Copy, Clone)]
+pub struct Win {
+    // Difficulty the board ranks on, none for the boards off the leaderboards
+    pub difficulty: Option<Difficulty>,
+    // Completion time, assist penalties included
+    pub seconds: f32,
+    pub flags: usize,
+    pub assisted: bool,
+    // Boards won in a row, this one included
+    pub streak: u32,
+}
+
+impl Achievement {
+    pub const ALL: [Self; 6] = [
+        Self::FirstWin,
+        Self::QuickBeginner,
+        Self::ExpertSprint,
+        Self::NoFlags,
+        Self::PureExpert,
+        Self::Streak,
+    ];
+
+    // Locale key of the name, the description is under `<key>.description`
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::FirstWin => "achievement.first_win",
+            Self::QuickBeginner => "achievement.quick_beginner",
+            Self::ExpertSprint => "achievement.expert_sprint",
+            Self::NoFlags => "achievement.no_flags",
+            Self::PureExpert => "achievement.pure_expert",
+            Self::Streak => "achievement.streak",
+        }
+    }
+
+    pub fn is_earned(&self, win: &Win) -> bool {
+        match self {
+            Self::FirstWin => true,
+            Self::QuickBeginner => {
+                win.difficulty == Some(Difficulty::Beginner) && win.seconds < 10.
+            }
+            Self::ExpertSprint => win.difficulty == Some(Difficulty::Expert) && win.seconds < 100.,
+            Self::NoFlags => win.difficulty.is_some() && win.flags == 0,
+            Self::PureExpert => win.difficulty == Some(Difficulty::Expert) && !win.assisted,
+            Self::Streak => win.streak >= STREAK_WINS,
+        }
+    }
+}
+
+// Platform the achievements are unlocked on: the profile files, or a store keeping them with
+// the player account (Steamworks, itch). Days are counted since the Unix epoch in UTC
+pub trait AchievementBackend: Send + Sync + 'static {
+    // Day the achievement was unlocked, if it was
+    fn unlocked_on(&self, achievement: Achievement) -> Option<u32>;
+    // Unlocks the achievement for good
+    fn unlock(&mut self, achievement: Achievement, day: u32);
+}
+
+// Achievements unlocked in `achievements.ron` of the profile
+pub struct LocalFileBackend {
+    profile: Profile,
+    record: LocalRecord,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct LocalRecord {
+    // Day each achievement was unlocked
+    unlocked: HashMap<Achievement, u32>,
+    // Win streak of the first versions, moved to the stats. Read once, never written back
+    #[serde(skip_serializing)]
+    streak: u32,
+}
+
+// Takes the win streak out of the achievements file of the first versions, for the stats
+pub fn take_legacy_streak(profile: &Profile) -> Option<u32> {
+    let record: LocalRecord = persistence::load(profile, ACHIEVEMENTS_FILE);
+    if record.streak == 0 {
+        return None;
+    }
+    persistence::save(profile, ACHIEVEMENTS_FILE, &record);
+    Some(record.streak)
+}
+
+impl LocalFileBackend {
+    pub fn load(profile: &Profile) -> Self {
+        Self {
+            profile: profile.clone(),
+            record: persistence::load(profile, ACHIEVEMENTS_FILE),
+        }
+    }
+}
+
+impl AchievementBackend for LocalFileBackend {
+    fn unlocked_on(&self, achievement: Achievement) -> Option<u32> {
+        self.record.unlocked.get(&achievement).copied()
+    }
+
+    fn unlock(&mut self, achievement: Achievement, day: u32) {
+        self.record.unlocked.entry(achievement).or_insert(day);
+        persistence::save(&self.profile, ACHIEVEMENTS_FILE, &self.record);
+    }
+}
+
+// Achievements of the profile, unlocked on its backend. Must be used as a resource
+#[derive(Resource)]
+pub struct Achievements {
+    backend: Box<dyn AchievementBackend>,
+}
+
+impl Achievements {
+    pub fn new(backend: impl AchievementBackend) -> Self {
+        Self {
+            backend: Box::new(backend),
+        }
+    }
+
+    // Achievements of the profile files
+    pub fn load(profile: &Profile) -> Self {
+        Self::new(LocalFileBackend::load(profile))
+    }
+
+    pub fn unlocked_on(&self, achievement: Achievement) -> Option<u32> {
+        self.backend.unlocked_on(achievement)
+    }
+
+    pub fn unlocked_count(&self) -> usize {
+        Achievement::ALL
+            .into_iter()
+            .filter(|a| self.unlocked_on(*a).is_some())
+            .count()
+    }
+
+    // Records a won board, returning the achievements it unlocked
+    pub fn record_win(&mut self, win: &Win, day: u32) -> Vec<Achievement> {
+        let earned: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|a| self.unlocked_on(*a).is_none() && a.is_earned(win))
+            .collect();
+        for achievement in earned.iter() {
+            self.backend.unlock(*achievement, day);
+        }
+        earned
+    }
+}
+
+// Sent when an achievement is unlocked
+#[derive(Message, Debug, Copy, Clone)]
+pub struct AchievementUnlockedEvent(pub Achievement);
+
+#[derive(Component)]
+struct GalleryRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct BackButton;
+
+// Achievements unlocked from the won and lost boards of the player, shown in a toast on unlock
+// and listed in the gallery screen. Demos, replays, the tutorial and the multiplayer games
+// don't count
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<AchievementUnlockedEvent>()
+            // the summary is pushed over the game on the frame the board is won
+            .add_systems(Update, track_achievements)
+            .add_systems(Update, toast_unlocks)
+            .on_enter(AppState::Achievements, setup_gallery)
+            .on_update(AppState::Achievements, gallery_buttons)
+            .on_exit(AppState::Achievements, cleanup::<GalleryRoot>);
+    }
+}
+
+// Checks the won boards against the achievements, a lost board breaking the streak
+#[allow(clippy::too_many_arguments)]
+fn track_achievements(
+    boards: Query<&Board>,
+    timer: Res<GameTimer>,
+    difficulty: Res<Difficulty>,
+    playback: Option<Res<ReplayPlayback>>,
+    modes: (
+        Option<Res<Race>>,
+        Option<Res<Coop>>,
+        Option<Res<Connection>>,
+        Option<Res<Demo>>,
+        Option<Res<Tutorial>>,
+        Option<Res<ScoreAttack>>,
+    ),
+    mut achievements: ResMut<Achievements>,
+    mut stats: ResMut<Stats>,
+    profile: Res<Profile>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut unlocked_ewr: MessageWriter<AchievementUnlockedEvent>,
+) {
+    let (race, coop, connection, demo, tutorial, score_attack) = modes;
+    let ignored = playback.is_some()
+        || race.is_some()
+        || coop.is_some()
+        || connection.is_some()
+        || demo.is_some()
+        || tutorial.is_some();
+    let completed: Vec<Entity> = board_completed_evr.read().map(|e| e.board).collect();
+    let exploded = bomb_explosion_evr.read().count() > 0;
+    if ignored || (completed.is_empty() && !exploded) {
+        return;
+    }
+    // undone explosions break the streak as well
+    if exploded {
+        stats.win_streak = 0;
+    }
+    // zen boards are won without risk
+    for board in completed
+        .iter()
+        .filter_map(|b| boards.get(*b).ok())
+        .filter(|b| !b.options.relaxed)
+    {
+        // the difficulty achievements count the boards ranked on it
+        let ranked = score_attack.is_none() && is_ranked(&board.options, *difficulty);
+        stats.win_streak += 1;
+        let win = Win {
+            difficulty: ranked.then(|| *difficulty),
+            seconds: timer.elapsed() + board.options.scoring.time_penalty(&board.assists),
+            flags: board.game.flags().len(),
+            assisted: !board.assists.is_pure(),
+            streak: stats.win_streak,
+        };
+        for achievement in achievements.record_win(&win, daily::today()) {
+            info!("Achievement unlocked: {achievement:?}");
+            unlocked_ewr.write(AchievementUnlockedEvent(achievement));
+        }
+    }
+    stats.save(&profile);
+}
+
+// Notifies the unlocked achievements with a toast
+fn toast_unlocks(
+    locale: Res<Locale>,
+    mut unlocked_evr: MessageReader<AchievementUnlockedEvent>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    for event in unlocked_evr.read() {
+        let name = locale.get(event.0.key());
+        let message = locale.format("achievements.unlocked", &[("name", &name)]);
+        toast_ewr.write(ToastEvent::success(message));
+    }
+}
+
+// Lists every achievement with its description, and the day it was unlocked
+fn setup_gallery(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    achievements: Res<Achievements>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(GalleryRoot)
+        .insert(Name::new("Achievements"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("achievements.title"), 40.);
+            let unlocked = achievements.unlocked_count();
+            let total = Achievement::ALL.len();
+            let progress = locale.format(
+                "achievements.progress",
+                &[("unlocked", &unlocked), ("total", &total)],
+            );
+            spawn_text(parent, font, &progress, 16.);
+            for achievement in Achievement::ALL {
+                let key = achievement.key();
+                let status = match achievements.unlocked_on(achievement) {
+                    Some(day) => {
+                        locale.format("achievements.unlocked_on", &[("date", &daily::date(day))])
+                    }
+                    None => locale.get("achievements.locked").to_string(),
+                };
+                spawn_text(
+                    parent,
+                    font,
+                    &format!("{} - {status}", locale.get(key)),
+                    20.,
+                );
+                let description = locale.get(&format!("{key}.description")).to_string();
+                spawn_text(parent, font, &description, 12.);
+            }
+            spawn_button(parent, font, locale.get("common.back"), BackButton);
+        });
+}
+
+fn gallery_buttons(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Menu);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::persistence::data_dir;
+use crate::settings::Settings;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::events::{
+    Action, BoardCompletedEvent, BoardDiffEvent, BoardStartedEvent, BombExplosionEvent,
+};
+use board_plugin::resources::{Board, TileState};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+
+const ANNOUNCEMENTS_FILE: &str = "announcements.log";
+
+// Where the game events are announced, for screen reader and low-vision players
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Announcements {
+    Off,
+    // Printed on the standard output, read by the terminal screen readers
+    Stdout,
+    // Appended to `announcements.log` in the data directory
+    LogFile,
+    // Spoken by the system text to speech, with the `tts` feature
+    Speech,
+}
+
+impl Default for Announcements {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl Announcements {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Stdout, Self::LogFile, Self::Speech];
+
+    // Locale key of the label
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "announcements.off",
+            Self::Stdout => "announcements.console",
+            Self::LogFile => "announcements.log_file",
+            Self::Speech => "announcements.speech",
+        }
+    }
+
+    fn sink(&self) -> Option<Box<dyn AnnouncementSink>> {
+        match self {
+            Self::Off => None,
+            Self::Stdout => Some(Box::new(StdoutSink)),
+            Self::LogFile => match LogFileSink::open() {
+                Ok(sink) => Some(Box::new(sink)),
+                Err(e) => {
+                    error!("Failed to open the announcements log: {e}");
+                    None
+                }
+            },
+            #[cfg(feature = "tts")]
+            Self::Speech => match SpeechSink::new() {
+                Ok(sink) => Some(Box::new(sink)),
+                Err(e) => {
+                    error!("Failed to start the text to speech: {e}");
+                    None
+                }
+            },
+            #[cfg(not(feature = "tts"))]
+            Self::Speech => {
+                warn!("Built without the `tts` feature, announcing on the console");
+                Some(Box::new(StdoutSink))
+            }
+        }
+    }
+}
+
+// Destination of the announcements
+pub trait AnnouncementSink: Send + Sync + 'static {
+    fn announce(&mut self, message: &str);
+}
+
+pub struct StdoutSink;
+
+impl AnnouncementSink for StdoutSink {
+    fn announce(&mut self, message: &str) {
+        println!("{message}");
+    }
+}
+
+pub struct LogFileSink {
+    file: File,
+}
+
+impl LogFileSink {
+    fn open() -> std::io::Result<Self> {
+        let dir = data_dir();
+        fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(ANNOUNCEMENTS_FILE))?;
+        Ok(Self { file })
+    }
+}
+
+impl AnnouncementSink for LogFileSink {
+    fn announce(&mut self, message: &str) {
+        if let Err(e) = writeln!(self.file, "{message}") {
+            error!("Failed to write the announcements log: {e}");
+        }
+    }
+}
+
+// Speaks the announcements on its own thread, the speech doesn't block the frames
+#[cfg(feature = "tts")]
+pub struct SpeechSink {
+    sender: std::sync::Mutex<std::sync::mpsc::Sender<String>>,
+}
+
+#[cfg(feature = "tts")]
+impl SpeechSink {
+    fn new() -> Result<Self, tts::Error> {
+        let mut tts = tts::Tts::default()?;
+        let (sender, receiver) = std::sync::mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            for message in receiver {
+                // the latest event matters the most
+                if let Err(e) = tts.speak(message, true) {
+                    error!("Failed to speak: {e}");
+                }
+            }
+        });
+        Ok(Self {
+            sender: std::sync::Mutex::new(sender),
+        })
+    }
+}
+
+#[cfg(feature = "tts")]
+impl AnnouncementSink for SpeechSink {
+    fn announce(&mut self, message: &str) {
+        if let Ok(sender) = self.sender.lock() {
+            sender.send(message.to_string()).ok();
+        }
+    }
+}
+
+// Sinks of the announcements. Must be used as a resource
+#[derive(Resource, Default)]
+pub struct Announcer {
+    sinks: Vec<Box<dyn AnnouncementSink>>,
+}
+
+impl Announcer {
+    pub fn announce(&mut self, message: &str) {
+        for sink in self.sinks.iter_mut() {
+            sink.announce(message);
+        }
+    }
+}
+
+// Spreadsheet like name of a tile, the column letters then the row number: A1 is bottom left
+pub fn tile_name(coords: Coordinates) -> String {
+    let mut column = String::new();
+    let mut x = coords.x as u32 + 1;
+    while x > 0 {
+        let letter = (x - 1) % 26;
+        column.insert(0, (b'A' + letter as u8) as char);
+        x = (x - 1) / 26;
+    }
+    format!("{column}{}", coords.y as u32 + 1)
+}
+
+fn revealed_tile(board: Option<&Board>, coords: Coordinates) -> String {
+    let tile_map = match board {
+        Some(b) => b.tile_map(),
+        None => return format!("revealed {}", tile_name(coords)),
+    };
+    let content = match (tile_map.is_bomb_at(coords), tile_map.bomb_count_at(coords)) {
+        (true, _) => "a bomb".to_string(),
+        (false, 0) => "empty".to_string(),
+        (false, count) => count.to_string(),
+    };
+    format!("revealed {content} at {}", tile_name(coords))
+}
+
+pub struct AnnouncePlugin;
+
+impl Plugin for AnnouncePlugin {
+    fn build(&self, app: &mut App) {
+        // the last events of a board come along with the state change, so it always runs
+        app.init_resource::<Announcer>()
+            .add_systems(Update, apply_announcements)
+            .add_systems(Update, announce_board_events);
+    }
+}
+
+// Replaces the sink of the setting when the settings change
+fn apply_announcements(
+    settings: Res<Settings>,
+    mut announcer: ResMut<Announcer>,
+    mut applied: Local<Announcements>,
+) {
+    if !settings.is_changed() || settings.announcements == *applied {
+        return;
+    }
+    announcer.sinks = settings.announcements.sink().into_iter().collect();
+    *applied = settings.announcements;
+}
+
+// Announces the changes of the boards from the board events. The covered tiles are told apart
+// by the actions applied since the previous diff: the unflagged tiles and the undone moves both
+// cover tiles
+fn announce_board_events(
+    mut announcer: ResMut<Announcer>,
+    boards: Query<&Board>,
+    mut board_started_evr: MessageReader<BoardStartedEvent>,
+    mut board_diff_evr: MessageReader<BoardDiffEvent>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut announced_actions: Local<HashMap<Entity, usize>>,
+) {
+    let mut messages = Vec::new();
+    for event in board_started_evr.read() {
+        if let Ok(board) = boards.get(event.board) {
+            let tile_map = board.tile_map();
+            messages.push(format!(
+                "new board, {} by {}, {} mines",
+                tile_map.width(),
+                tile_map.height(),
+                tile_map.mine_count()
+            ));
+        }
+    }
+    for event in board_diff_evr.read() {
+        let board = boards.get(event.board).ok();
+        let changed = |state: TileState| {
+            event
+                .diff
+                .changes
+                .iter()
+                .filter(move |(_, s)| *s == state)
+                .map(|(c, _)| *c)
+        };
+        let actions = match board {
+            Some(board) => {
+                let announced = announced_actions.entry(event.board).or_default();
+                let actions = board.actions.get(*announced..).unwrap_or_default();
+                *announced = board.actions.len();
+                actions
+            }
+            None => &[],
+        };
+        let revealed: Vec<Coordinates> = changed(TileState::Revealed).collect();
+        let (unflagged, covered): (Vec<Coordinates>, Vec<Coordinates>) =
+            changed(TileState::Covered).partition(|c| actions.contains(&Action::ToggleFlag(*c)));
+        match revealed.as_slice() {
+            [] => (),
+            [coords] => messages.push(revealed_tile(board, *coords)),
+            tiles => messages.push(format!("revealed {} tiles", tiles.len())),
+        }
+        for coords in changed(TileState::Flagged) {
+            messages.push(format!("flag placed at {}", tile_name(coords)));
+        }
+        for coords in unflagged.iter() {
+            messages.push(format!("flag removed at {}", tile_name(*coords)));
+        }
+        // the other covered tiles are undone moves
+        match covered.as_slice() {
+            [] => (),
+            [coords] => messages.push(format!("covered {}", tile_name(*coords))),
+            tiles => messages.push(format!("covered {} tiles", tiles.len())),
+        }
+
+        let flags_changed = unflagged.len() + covered.len() + changed(TileState::Flagged).count();
+        if let Some(board) = board.filter(|_| flags_changed > 0) {
+            let remaining = board.game.mines_left();
+            messages.push(format!("{remaining} mines remaining"));
+        }
+    }
+    for _ in board_completed_evr.read() {
+        messages.push("board cleared".to_string());
+    }
+    for _ in bomb_explosion_evr.read() {
+        messages.push("bomb exploded".to_string());
+    }
+
+    for message in messages {
+        announcer.announce(&message);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::Profile;
+use crate::results::GameResult;
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent, SpawnBoardEvent};
+use board_plugin::resources::{Board, BoardOptions, GameTimer};
+
+// Length of a blitz run
+const BLITZ_SECONDS: f32 = 120.;
+// Points of a cleared board, on top of its revealed tiles
+const BOARD_POINTS: u32 = 50;
+
+// Blitz run in progress, counting down with the game timer. Must be used as a resource
+#[derive(Resource, Default)]
+pub struct Blitz {
+    boards_cleared: u32,
+    // Tiles revealed on the cleared boards
+    tiles_revealed: u32,
+}
+
+#[derive(Component)]
+struct BlitzHud;
+
+// Clears as many small boards as possible before the countdown ends, a fresh board replacing
+// every cleared one. Hitting a mine ends the run early
+pub struct BlitzPlugin;
+
+impl Plugin for BlitzPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_blitz_hud)
+            .on_update(AppState::InGame, blitz_run)
+            .on_exit(AppState::InGame, (cleanup::<BlitzHud>, end_blitz));
+    }
+}
+
+// Options of the boards of a blitz run, small and always opening safely
+pub fn blitz_options(settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        safe_start: true,
+        preview_rerolls: None,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        ..game_options(Difficulty::Beginner, settings)
+    }
+}
+
+fn setup_blitz_hud(mut cmds: Commands, ui_assets: Res<UiAssets>, blitz: Option<Res<Blitz>>) {
+    if blitz.is_none() {
+        return;
+    }
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(Name::new("Blitz HUD"))
+    .with_children(|parent| {
+        spawn_text(parent, &ui_assets.font, "", 16.).insert(BlitzHud);
+    });
+}
+
+// Counts down, replaces the cleared boards and ends the run
+#[allow(clippy::too_many_arguments)]
+fn blitz_run(
+    mut cmds: Commands,
+    blitz: Option<ResMut<Blitz>>,
+    timer: Res<GameTimer>,
+    settings: Res<Settings>,
+    boards: Query<&Board>,
+    mut huds: Query<&mut Text, With<BlitzHud>>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut spawn_board_ewr: MessageWriter<SpawnBoardEvent>,
+    mut stats: ResMut<Stats>,
+    profile: Res<Profile>,
+    locale: Res<Locale>,
+    mut state: StateSwitch<AppState>,
+) {
+    let mut blitz = match blitz {
+        Some(b) => b,
+        None => return,
+    };
+    let time_left = (BLITZ_SECONDS - timer.elapsed()).max(0.);
+
+    for event in board_completed_evr.read() {
+        if let Ok(board) = boards.get(event.board) {
+            blitz.boards_cleared += 1;
+            blitz.tiles_revealed += board.game.revealed_tiles().count() as u32;
+            cmds.entity(board.entity).despawn();
+            spawn_board_ewr.write(SpawnBoardEvent(blitz_options(&settings)));
+        }
+    }
+    for mut text in huds.iter_mut() {
+        text.0 = locale.format(
+            "blitz.hud",
+            &[
+                ("time", &time_left.ceil()),
+                ("boards", &blitz.boards_cleared),
+            ],
+        );
+    }
+
+    let exploded = bomb_explosion_evr.read().count() > 0;
+    if !exploded && time_left > 0. {
+        return;
+    }
+    // the tiles of the unfinished board count as well
+    let tiles_revealed = blitz.tiles_revealed
+        + boards
+            .iter()
+            .map(|b| b.game.revealed_tiles().count() as u32)
+            .sum::<u32>();
+    let score = blitz.boards_cleared * BOARD_POINTS + tiles_revealed;
+    info!("Blitz over, {score} points");
+    let best = score > stats.blitz_best;
+    if best {
+        stats.blitz_best = score;
+        stats.save(&profile);
+    }
+    cmds.insert_resource(GameResult {
+        headline: match best {
+            true => locale.format("blitz.best", &[("score", &score)]),
+            false => locale.format("blitz.score", &[("score", &score)]),
+        },
+        details: locale.format(
+            match exploded {
+                true => "blitz.details_exploded",
+                false => "blitz.details",
+            },
+            &[
+                ("boards", &blitz.boards_cleared),
+                ("tiles", &tiles_revealed),
+            ],
+        ),
+    });
+    state.set(AppState::Results);
+}
+
+fn end_blitz(mut cmds: Commands) {
+    cmds.remove_resource::<Blitz>();
+}
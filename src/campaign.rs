@@ -0,0 +1,248 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::{self, Profile};
+use crate::results::GameResult;
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::resources::{BoardOptions, BombCount};
+use serde::{Deserialize, Serialize};
+
+const CAMPAIGN_FILE: &str = "campaign.ron";
+const STARTING_LIVES: u8 = 3;
+// Points of a cleared level, multiplied by the level number
+const LEVEL_POINTS: u32 = 100;
+// Board size limits, reached by the late levels
+const MAX_WIDTH: u16 = 30;
+const MAX_HEIGHT: u16 = 16;
+const MAX_DENSITY: f32 = 0.22;
+
+// Board of a campaign level
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CampaignLevel {
+    pub map_size: (u16, u16),
+    pub bomb_count: u16,
+}
+
+impl CampaignLevel {
+    // Level definitions: the board starts tiny, grows by a tile per level and gets denser,
+    // up to Expert sizes where the campaign goes on endlessly
+    pub fn new(level: u32) -> Self {
+        let size = 5 + level.min(MAX_WIDTH as u32) as u16;
+        let map_size = (size.min(MAX_WIDTH), size.min(MAX_HEIGHT));
+        let density = (0.10 + 0.01 * level as f32).min(MAX_DENSITY);
+        let tiles = map_size.0 as f32 * map_size.1 as f32;
+        Self {
+            map_size,
+            bomb_count: ((tiles * density).round() as u16).max(1),
+        }
+    }
+
+    pub fn board_options(&self, settings: &Settings) -> BoardOptions {
+        BoardOptions {
+            map_size: self.map_size,
+            bomb_count: BombCount::Absolute(self.bomb_count),
+            safe_start: true,
+            preview_rerolls: None,
+            undos: 0,
+            lives: 0,
+            relaxed: false,
+            ..game_options(Difficulty::Beginner, settings)
+        }
+    }
+}
+
+// Campaign progression, saved between levels. Must be used as a resource while in the campaign
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CampaignSave {
+    // Level to play next, starting at 1
+    pub level: u32,
+    pub lives: u8,
+    pub score: u32,
+}
+
+impl Default for CampaignSave {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            lives: STARTING_LIVES,
+            score: 0,
+        }
+    }
+}
+
+impl CampaignSave {
+    pub fn load(profile: &Profile) -> Self {
+        persistence::load(profile, CAMPAIGN_FILE)
+    }
+
+    pub fn save(&self, profile: &Profile) {
+        persistence::save(profile, CAMPAIGN_FILE, self);
+    }
+}
+
+// Outcome of the last played level, shown by the transition screen. Must be used as a resource
+#[derive(Resource)]
+struct LevelOutcome(String);
+
+#[derive(Debug, Copy, Clone, Component)]
+enum CampaignButton {
+    Play,
+    Menu,
+}
+
+#[derive(Component)]
+struct CampaignRoot;
+
+// Levels of growing size and density, with lives carrying between levels. The campaign state
+// is the transition screen shown before every level
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Campaign, setup_transition)
+            .on_update(AppState::Campaign, transition_buttons)
+            .on_exit(
+                AppState::Campaign,
+                (cleanup::<CampaignRoot>, remove_outcome),
+            )
+            .on_update(AppState::InGame, level_over);
+    }
+}
+
+fn setup_transition(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    campaign: Res<CampaignSave>,
+    outcome: Option<Res<LevelOutcome>>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    let level = CampaignLevel::new(campaign.level);
+    cmds.spawn(screen_root())
+        .insert(CampaignRoot)
+        .insert(Name::new("Campaign"))
+        .with_children(|parent| {
+            if let Some(outcome) = outcome {
+                spawn_text(parent, font, &outcome.0, 20.);
+            }
+            let title = locale.format("campaign.level", &[("level", &campaign.level)]);
+            spawn_text(parent, font, &title, 40.);
+            let board = locale.format(
+                "campaign.board",
+                &[
+                    ("width", &level.map_size.0),
+                    ("height", &level.map_size.1),
+                    ("mines", &level.bomb_count),
+                ],
+            );
+            spawn_text(parent, font, &board, 16.);
+            let status = locale.format(
+                "campaign.status",
+                &[("lives", &campaign.lives), ("score", &campaign.score)],
+            );
+            spawn_text(parent, font, &status, 16.);
+            spawn_button(
+                parent,
+                font,
+                locale.get("campaign.play"),
+                CampaignButton::Play,
+            );
+            spawn_button(
+                parent,
+                font,
+                locale.get("campaign.menu"),
+                CampaignButton::Menu,
+            );
+        });
+}
+
+fn transition_buttons(
+    mut cmds: Commands,
+    campaign: Res<CampaignSave>,
+    settings: Res<Settings>,
+    buttons: Query<(&Interaction, &CampaignButton), Changed<Interaction>>,
+    mut state: StateSwitch<AppState>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            CampaignButton::Play => {
+                let level = CampaignLevel::new(campaign.level);
+                cmds.insert_resource(level.board_options(&settings));
+                state.set(AppState::InGame);
+            }
+            // the progression is saved, the campaign goes on from the menu
+            CampaignButton::Menu => {
+                cmds.remove_resource::<CampaignSave>();
+                state.set(AppState::Menu);
+            }
+        }
+    }
+}
+
+// Moves on to the next level on completion, and spends a life on explosion
+#[allow(clippy::too_many_arguments)]
+fn level_over(
+    mut cmds: Commands,
+    campaign: Option<ResMut<CampaignSave>>,
+    mut stats: ResMut<Stats>,
+    profile: Res<Profile>,
+    locale: Res<Locale>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    let mut campaign = match campaign {
+        Some(c) => c,
+        None => return,
+    };
+
+    if board_completed_evr.read().count() > 0 {
+        let points = campaign.level * LEVEL_POINTS;
+        let outcome = locale.format(
+            "campaign.cleared",
+            &[("level", &campaign.level), ("points", &points)],
+        );
+        campaign.level += 1;
+        campaign.score += points;
+        cmds.insert_resource(LevelOutcome(outcome));
+    } else if bomb_explosion_evr.read().count() > 0 {
+        campaign.lives = campaign.lives.saturating_sub(1);
+        if campaign.lives == 0 {
+            info!("Campaign over at level {}", campaign.level);
+            stats.campaign_best_level = stats.campaign_best_level.max(campaign.level);
+            stats.save(&profile);
+            cmds.insert_resource(GameResult {
+                headline: locale.get("campaign.over").to_string(),
+                details: locale.format(
+                    "campaign.reached",
+                    &[("level", &campaign.level), ("score", &campaign.score)],
+                ),
+            });
+            // the next campaign starts over
+            CampaignSave::default().save(&profile);
+            cmds.remove_resource::<CampaignSave>();
+            state.set(AppState::Results);
+            return;
+        }
+        let outcome = locale.format("campaign.boom", &[("lives", &campaign.lives)]);
+        cmds.insert_resource(LevelOutcome(outcome));
+    } else {
+        return;
+    }
+    campaign.save(&profile);
+    state.set(AppState::Campaign);
+}
+
+fn remove_outcome(mut cmds: Commands) {
+    cmds.remove_resource::<LevelOutcome>();
+}
@@ -0,0 +1,131 @@
+use crate::locale::Locale;
+use crate::persistence::Profile;
+use crate::settings::{self, SettingToggle, Settings};
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use serde::Deserialize;
+
+// Changelog embedded in the binary, newest version last
+const CHANGELOG: &str = include_str!("../assets/changelog.ron");
+
+// Version of the running game
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub highlights: Vec<String>,
+    // Settings introduced by the release, toggleable from the what's new screen
+    #[serde(default)]
+    pub options: Vec<SettingToggle>,
+}
+
+// Parses the embedded changelog
+pub fn changelog() -> Vec<ChangelogEntry> {
+    ron::from_str(CHANGELOG).expect("Invalid embedded changelog")
+}
+
+// Parses a `major.minor.patch` version for ordering
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version.split('.').map(|p| p.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+// Changelog entries the player hasn't seen yet
+pub fn unseen_entries(settings: &Settings) -> Vec<ChangelogEntry> {
+    let entries = changelog();
+    match &settings.version {
+        // first launch, only show the current release
+        None => entries
+            .into_iter()
+            .filter(|e| e.version == CURRENT_VERSION)
+            .collect(),
+        Some(seen) => entries
+            .into_iter()
+            .filter(|e| parse_version(&e.version) > parse_version(seen))
+            .collect(),
+    }
+}
+
+// Should the what's new screen be shown at startup?
+pub fn has_news(settings: &Settings) -> bool {
+    settings.version.as_deref() != Some(CURRENT_VERSION) && !unseen_entries(settings).is_empty()
+}
+
+#[derive(Component)]
+struct WhatsNewRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct ContinueButton;
+
+pub struct ChangelogPlugin;
+
+impl Plugin for ChangelogPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::WhatsNew, setup_whats_new)
+            .on_update(
+                AppState::WhatsNew,
+                (settings::toggle_settings, continue_button),
+            )
+            .on_exit(AppState::WhatsNew, (cleanup::<WhatsNewRoot>, mark_seen));
+    }
+}
+
+fn setup_whats_new(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    let mut entries = unseen_entries(&settings);
+    if entries.is_empty() {
+        // opened from the menu, show the latest release
+        entries = changelog().into_iter().rev().take(1).collect();
+    }
+
+    cmds.spawn(screen_root())
+        .insert(WhatsNewRoot)
+        .insert(Name::new("What's New"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("whats_new.title"), 40.);
+            for entry in entries.iter().rev() {
+                let version = locale.format("whats_new.version", &[("version", &entry.version)]);
+                spawn_text(parent, font, &version, 24.);
+                for highlight in entry.highlights.iter() {
+                    spawn_text(parent, font, &format!("- {highlight}"), 12.);
+                }
+                for option in entry.options.iter() {
+                    let label = option.button_label(&settings, &locale);
+                    spawn_button(parent, font, &label, *option);
+                }
+            }
+            spawn_button(
+                parent,
+                font,
+                locale.get("whats_new.continue"),
+                ContinueButton,
+            );
+        });
+}
+
+fn continue_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Menu);
+    }
+}
+
+// Records the current version as seen and persists the toggled options
+fn mark_seen(mut settings: ResMut<Settings>, profile: Res<Profile>) {
+    settings.version = Some(CURRENT_VERSION.to_string());
+    settings.save(&profile);
+}
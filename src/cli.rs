@@ -0,0 +1,155 @@
+use crate::difficulty::Difficulty;
+use crate::display::DisplayMode;
+use crate::menu::game_options;
+use crate::settings::Settings;
+use board_plugin::resources::{BoardError, BoardOptions, BombCount};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use minesweeper_core::Preset;
+
+// Look of the game picked on the command line
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Theme {
+    Default,
+    Classic,
+    HighContrast,
+}
+
+// Command line flags, overriding the board options and the window for scripted launches and
+// quick tests
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    // Bomb count, or density of the `--density` flag
+    pub bombs: Option<BombCount>,
+    pub seed: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub fullscreen: bool,
+    pub theme: Option<Theme>,
+}
+
+fn command() -> Command {
+    let tiles = |name: &'static str, help: &'static str| {
+        Arg::new(name)
+            .long(name)
+            .value_name("TILES")
+            .value_parser(value_parser!(u16).range(1..))
+            .help(help)
+    };
+    Command::new("minesweeper")
+        .about("Mine sweeper game. The board flags start a game right away")
+        .arg(tiles("width", "Board width, in tiles"))
+        .arg(tiles("height", "Board height, in tiles"))
+        .arg(
+            Arg::new("bombs")
+                .long("bombs")
+                .value_name("COUNT")
+                .value_parser(value_parser!(u16))
+                .help("Bomb count of the board"),
+        )
+        .arg(
+            Arg::new("density")
+                .long("density")
+                .value_name("PERCENT")
+                .value_parser(value_parser!(f32))
+                .conflicts_with("bombs")
+                .help("Bombs as a percentage of the tiles, scaling with the board size"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("SEED")
+                .value_parser(value_parser!(u64))
+                .help("Seed generating the board, the same seed deals the same board"),
+        )
+        .arg(
+            Arg::new("difficulty")
+                .long("difficulty")
+                .value_parser(Preset::ALL.map(|p| p.name()))
+                .help("Preset of the board, the other board flags override it"),
+        )
+        .arg(
+            Arg::new("fullscreen")
+                .long("fullscreen")
+                .action(ArgAction::SetTrue)
+                .help("Starts in borderless fullscreen"),
+        )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .value_parser(["default", "classic", "high-contrast"])
+                .help("Look of the tiles and the menus, instead of the profile settings"),
+        )
+}
+
+impl Cli {
+    // Parses the process arguments, exiting with the usage on invalid flags
+    pub fn parse() -> Self {
+        Self::from_matches(&command().get_matches())
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Self {
+        let name = |id: &str| matches.get_one::<String>(id).map(String::as_str);
+        Self {
+            width: matches.get_one("width").copied(),
+            height: matches.get_one("height").copied(),
+            bombs: matches
+                .get_one("bombs")
+                .copied()
+                .map(BombCount::Absolute)
+                .or_else(|| {
+                    let density = matches.get_one::<f32>("density")?;
+                    Some(BombCount::Density(density / 100.))
+                }),
+            seed: matches.get_one("seed").copied(),
+            difficulty: name("difficulty")
+                .and_then(Preset::from_name)
+                .map(Difficulty::from),
+            fullscreen: matches.get_flag("fullscreen"),
+            theme: name("theme").map(|t| match t {
+                "classic" => Theme::Classic,
+                "high-contrast" => Theme::HighContrast,
+                _ => Theme::Default,
+            }),
+        }
+    }
+
+    // Do the flags pick a board, skipping the menus?
+    pub fn starts_game(&self) -> bool {
+        self.width.is_some()
+            || self.height.is_some()
+            || self.bombs.is_some()
+            || self.seed.is_some()
+            || self.difficulty.is_some()
+    }
+
+    // Overrides the theme and display settings for the session, the profile keeping its own
+    pub fn apply_settings(&self, settings: &mut Settings) {
+        if self.fullscreen {
+            settings.override_display_mode(DisplayMode::Borderless);
+        }
+        if let Some(theme) = self.theme {
+            settings.override_theme(theme == Theme::Classic, theme == Theme::HighContrast);
+        }
+    }
+
+    // Options of the board picked by the flags, on top of the difficulty preset
+    pub fn board_options(&self, settings: &Settings) -> Result<BoardOptions, BoardError> {
+        let preset = game_options(self.difficulty(), settings);
+        let options = BoardOptions {
+            map_size: (
+                self.width.unwrap_or(preset.map_size.0),
+                self.height.unwrap_or(preset.map_size.1),
+            ),
+            bomb_count: self.bombs.unwrap_or(preset.bomb_count),
+            seed: self.seed,
+            ..preset
+        };
+        options.validate()?;
+        Ok(options)
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty.unwrap_or(Difficulty::Beginner)
+    }
+}
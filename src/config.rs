@@ -0,0 +1,44 @@
+use crate::overlay::OverlayConfig;
+use crate::persistence;
+use bevy::prelude::Resource;
+use serde::Deserialize;
+
+// Machine wide configuration file, never written by the game
+pub const CONFIG_FILE: &str = "config.ron";
+
+// Configuration only editable by changing the config file, shared by every profile. Must be used
+// as a resource
+#[derive(Resource, Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Parental / streamer safe mode, disables network features, chat and external integrations
+    pub safe_mode: bool,
+    // Streaming overlay over the games
+    pub stream_overlay: OverlayConfig,
+    // REST endpoint of the online daily leaderboard, the daily times stay local without it
+    pub daily_leaderboard_url: Option<String>,
+    // Application id of the Discord Rich Presence, the activity isn't published without it
+    pub discord_client_id: Option<String>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        persistence::load_global(CONFIG_FILE)
+    }
+
+    // Can the game connect to the network (online play, leaderboard submission)?
+    pub fn network_allowed(&self) -> bool {
+        !self.safe_mode
+    }
+
+    // Can players exchange messages? No mode has a chat yet
+    #[allow(dead_code)]
+    pub fn chat_allowed(&self) -> bool {
+        !self.safe_mode
+    }
+
+    // Can the game talk to external applications (e.g. Discord presence)?
+    pub fn integrations_allowed(&self) -> bool {
+        !self.safe_mode
+    }
+}
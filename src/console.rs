@@ -0,0 +1,286 @@
+use crate::AppState;
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+use board_plugin::components::{Coordinates, PlayerId};
+use board_plugin::engine::StateSwitch;
+use board_plugin::events::{Action, BoardActionEvent};
+use board_plugin::generation::{BoardGenerators, FixedGenerator};
+use board_plugin::resources::{tile_map::TileMap, Board, BoardOptions, BombCount};
+use std::fs;
+
+// Lines kept in the console log
+const LOG_LINES: usize = 100;
+
+const HELP: &str = "commands: reveal <x> <y>, flag <x> <y>, seed, regen <width> <height> <bombs>, \
+                    load <file>, win, lose, help";
+
+// Generator name of the boards loaded from text files
+const LOADED_BOARD: &str = "console_board";
+
+// Debug console, opened with the grave key. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    log: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: String) {
+        info!("{line}");
+        self.log.push(line);
+        if self.log.len() > LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+}
+
+// Command typed in the console
+#[derive(Message, Debug, Clone)]
+struct ConsoleCommand(String);
+
+// Debug console running test commands on the first board, to reach the end-game flows
+// without playing full boards
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_message::<ConsoleCommand>()
+            // the typed keys don't reach the game bindings
+            .add_systems(PreUpdate, toggle_console.after(InputSystems))
+            .add_systems(EguiPrimaryContextPass, console_window)
+            .add_systems(Update, run_commands);
+    }
+}
+
+fn toggle_console(mut console: ResMut<Console>, mut keys: ResMut<ButtonInput<KeyCode>>) {
+    if keys.just_pressed(KeyCode::Backquote) {
+        console.open = !console.open;
+    }
+    if console.open {
+        keys.clear();
+    }
+}
+
+fn console_window(
+    mut egui_contexts: EguiContexts,
+    mut console: ResMut<Console>,
+    mut command_ewr: MessageWriter<ConsoleCommand>,
+) {
+    if !console.open {
+        return;
+    }
+    let ctx = match egui_contexts.ctx_mut() {
+        Ok(ctx) => ctx,
+        Err(_) => return,
+    };
+    let console = &mut *console;
+    let mut submitted = false;
+    egui::Window::new("Console").show(ctx, |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(200.)
+            .show(ui, |ui| {
+                for line in console.log.iter() {
+                    ui.label(line);
+                }
+            });
+        let response = ui.text_edit_singleline(&mut console.input);
+        // the grave key only toggles the console
+        console.input.retain(|c| c != '`');
+        submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        response.request_focus();
+    });
+    if submitted && !console.input.trim().is_empty() {
+        let command = console.input.trim().to_string();
+        console.print(format!("> {command}"));
+        command_ewr.write(ConsoleCommand(command));
+        console.input.clear();
+    }
+}
+
+// Coordinates of the `x y` arguments of a command
+fn coordinates(args: &[&str]) -> Option<Coordinates> {
+    match args {
+        [x, y] => Some(Coordinates {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_commands(
+    mut console: ResMut<Console>,
+    mut command_evr: MessageReader<ConsoleCommand>,
+    boards: Query<&Board>,
+    board_options: Option<Res<BoardOptions>>,
+    mut state: StateSwitch<AppState>,
+    mut cmds: Commands,
+    mut board_action_ewr: MessageWriter<BoardActionEvent>,
+    mut generators: ResMut<BoardGenerators>,
+) {
+    for ConsoleCommand(command) in command_evr.read() {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let (name, args) = match words.split_first() {
+            Some((name, args)) => (*name, args),
+            None => continue,
+        };
+        let board = boards.iter().next();
+        let reply = match (name, board) {
+            ("help", _) => HELP.to_string(),
+            ("regen", _) => regen(args, board_options.as_deref(), &mut cmds, &mut state),
+            ("load", _) => load(
+                args,
+                board_options.as_deref(),
+                &mut generators,
+                &mut cmds,
+                &mut state,
+            ),
+            (_, None) => "no board in play".to_string(),
+            ("reveal", Some(board)) => match coordinates(args) {
+                Some(coordinates) => {
+                    board_action_ewr.write(BoardActionEvent {
+                        board: board.entity,
+                        action: Action::Uncover(coordinates),
+                        player: PlayerId::One,
+                    });
+                    format!("revealing {coordinates}")
+                }
+                None => "usage: reveal <x> <y>".to_string(),
+            },
+            ("flag", Some(board)) => match coordinates(args) {
+                Some(coordinates) => {
+                    board_action_ewr.write(BoardActionEvent {
+                        board: board.entity,
+                        action: Action::ToggleFlag(coordinates),
+                        player: PlayerId::One,
+                    });
+                    format!("flagging {coordinates}")
+                }
+                None => "usage: flag <x> <y>".to_string(),
+            },
+            ("seed", Some(board)) => format!("seed {}", board.seed),
+            ("win", Some(board)) => {
+                // the cascades of the first tiles reveal some of the next ones
+                let tile_map = board.tile_map();
+                let safe: Vec<Coordinates> = tile_map
+                    .tiles()
+                    .map(|(c, _)| c)
+                    .filter(|c| board.game.is_covered(*c) && !tile_map.is_bomb_at(*c))
+                    .collect();
+                for coordinates in safe.iter().copied() {
+                    board_action_ewr.write(BoardActionEvent {
+                        board: board.entity,
+                        action: Action::Uncover(coordinates),
+                        player: PlayerId::One,
+                    });
+                }
+                format!("revealing {} safe tiles", safe.len())
+            }
+            ("lose", Some(board)) => {
+                let tile_map = board.tile_map();
+                let bomb = tile_map
+                    .tiles()
+                    .map(|(c, _)| c)
+                    .find(|c| tile_map.is_bomb_at(*c) && !board.game.is_flagged(*c));
+                match bomb {
+                    Some(coordinates) => {
+                        board_action_ewr.write(BoardActionEvent {
+                            board: board.entity,
+                            action: Action::Uncover(coordinates),
+                            player: PlayerId::One,
+                        });
+                        format!("revealing the bomb at {coordinates}")
+                    }
+                    None => "every bomb is flagged".to_string(),
+                }
+            }
+            _ => format!("unknown command `{name}`, {HELP}"),
+        };
+        console.print(reply);
+    }
+}
+
+// Plays a new board of the given size and bomb count, with the other current options
+fn regen(
+    args: &[&str],
+    board_options: Option<&BoardOptions>,
+    cmds: &mut Commands,
+    state: &mut StateSwitch<AppState>,
+) -> String {
+    let parsed = match args {
+        [width, height, bombs] => width
+            .parse()
+            .ok()
+            .zip(height.parse().ok())
+            .zip(bombs.parse().ok()),
+        _ => None,
+    };
+    let ((width, height), bomb_count) = match parsed {
+        Some(p) => p,
+        None => return "usage: regen <width> <height> <bombs>".to_string(),
+    };
+    let options = BoardOptions {
+        map_size: (width, height),
+        bomb_count: BombCount::Absolute(bomb_count),
+        mask: None,
+        seed: None,
+        ..board_options.cloned().unwrap_or_default()
+    };
+    if let Err(e) = options.validate() {
+        return format!("invalid board: {e}");
+    }
+    match play(options, cmds, state) {
+        Ok(()) => format!("new {width}x{height} board with {bomb_count} bombs"),
+        Err(e) => e,
+    }
+}
+
+// Plays the board of a text file, see `TileMap::from_text`, on the grid of the current options
+fn load(
+    args: &[&str],
+    board_options: Option<&BoardOptions>,
+    generators: &mut BoardGenerators,
+    cmds: &mut Commands,
+    state: &mut StateSwitch<AppState>,
+) -> String {
+    let path = match args {
+        [path] => path,
+        _ => return "usage: load <file>".to_string(),
+    };
+    let base = board_options.cloned().unwrap_or_default();
+    let tile_map = match fs::read_to_string(path) {
+        Ok(text) => TileMap::from_text(&text, base.grid),
+        Err(e) => return format!("can't read {path}: {e}"),
+    };
+    let generator = match tile_map {
+        Ok(tile_map) => FixedGenerator(tile_map),
+        Err(e) => return format!("invalid board: {e}"),
+    };
+    let options = generator.options(LOADED_BOARD, base);
+    generators.register(LOADED_BOARD, generator);
+    match play(options, cmds, state) {
+        Ok(()) => format!("playing the board of {path}"),
+        Err(e) => e,
+    }
+}
+
+// Starts a new game with the options
+fn play(
+    options: BoardOptions,
+    cmds: &mut Commands,
+    state: &mut StateSwitch<AppState>,
+) -> Result<(), String> {
+    cmds.insert_resource(options);
+    let next = match state.current() {
+        AppState::InGame => AppState::Restarting,
+        _ => AppState::InGame,
+    };
+    match state.set(next) {
+        true => Ok(()),
+        false => Err("can't start the board: a state switch is already pending".to_string()),
+    }
+}
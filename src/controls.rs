@@ -0,0 +1,543 @@
+use crate::abandon::{ask_abandon, in_progress, Abandon};
+use crate::blitz::Blitz;
+use crate::campaign::CampaignSave;
+use crate::daily::DailyChallenge;
+use crate::demo::Demo;
+use crate::locale::Locale;
+use crate::menu::PREVIEW_REROLLS;
+use crate::persistence::Profile;
+use crate::protocol::Connection;
+use crate::race::Race;
+use crate::settings::Settings;
+use crate::tutorial::Tutorial;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::resources::{
+    Binding, Board, BoardOptions, InputAction, InputMap, InputPreset, ReplayPlayback,
+};
+
+// Hold durations of the long press mode cycled through by its button, in seconds
+const LONG_PRESS_DURATIONS: [Option<f32>; 4] = [None, Some(0.3), Some(0.5), Some(0.8)];
+
+// Locale key of the name of an action
+fn action_label(action: InputAction) -> &'static str {
+    match action {
+        InputAction::Uncover => "action.uncover",
+        InputAction::Flag => "action.flag",
+        InputAction::Chord => "action.chord",
+        InputAction::Hint => "action.hint",
+        InputAction::FlagMode => "action.flag_mode",
+        InputAction::Heatmap => "action.heatmap",
+        InputAction::Undo => "action.undo",
+        InputAction::Restart => "action.restart",
+        InputAction::Retry => "action.retry",
+        InputAction::Pause => "action.pause",
+        InputAction::Export => "action.export",
+        InputAction::Fullscreen => "action.fullscreen",
+        InputAction::Overlay => "action.overlay",
+    }
+}
+
+// Name of the mouse button or key of a binding
+pub fn binding_label(binding: Binding, locale: &Locale) -> String {
+    match binding {
+        Binding::Mouse(MouseButton::Left) => locale.get("binding.mouse_left").to_string(),
+        Binding::Mouse(MouseButton::Right) => locale.get("binding.mouse_right").to_string(),
+        Binding::Mouse(MouseButton::Middle) => locale.get("binding.mouse_middle").to_string(),
+        Binding::Mouse(MouseButton::Other(button)) => {
+            locale.format("binding.mouse_other", &[("button", &button)])
+        }
+        Binding::Mouse(button @ (MouseButton::Back | MouseButton::Forward)) => {
+            format!("{button:?}")
+        }
+        Binding::Key(key) => format!("{key:?}"),
+    }
+}
+
+fn binding_button_label(action: InputAction, input_map: &InputMap, locale: &Locale) -> String {
+    let binding = binding_label(input_map.get(action), locale);
+    let action = locale.get(action_label(action));
+    locale.format(
+        "controls.binding",
+        &[("action", &action), ("binding", &binding)],
+    )
+}
+
+fn preset_label(input_map: &InputMap, locale: &Locale) -> String {
+    let preset = match input_map.preset() {
+        Some(InputPreset::RightHanded) => locale.get("controls.right_handed"),
+        Some(InputPreset::LeftHanded) => locale.get("controls.left_handed"),
+        None => locale.get("controls.custom"),
+    };
+    locale.format("controls.preset", &[("preset", &preset)])
+}
+
+fn long_press_label(long_press: Option<f32>, locale: &Locale) -> String {
+    let duration = match long_press {
+        Some(seconds) => locale.format("controls.seconds", &[("seconds", &seconds)]),
+        None => locale.get("common.off").to_string(),
+    };
+    locale.format("controls.long_press", &[("duration", &duration)])
+}
+
+fn wheel_marks_label(wheel_marks: bool, locale: &Locale) -> String {
+    let check = if wheel_marks { "x" } else { " " };
+    format!("[{check}] {}", locale.get("controls.wheel_marks"))
+}
+
+#[derive(Component)]
+struct ControlsRoot;
+
+// Rebinds its action to the next pressed mouse button or key
+#[derive(Debug, Copy, Clone, Component)]
+struct BindingButton(InputAction);
+
+// Binding button waiting for a mouse button or key
+#[derive(Debug, Copy, Clone, Component)]
+struct Capturing;
+
+// Cycles through the binding presets
+#[derive(Debug, Copy, Clone, Component)]
+struct PresetButton;
+
+// Cycles through the hold durations of the long press mode
+#[derive(Debug, Copy, Clone, Component)]
+struct LongPressButton;
+
+// Turns the wheel marks on or off
+#[derive(Debug, Copy, Clone, Component)]
+struct WheelMarksButton;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct ResetButton;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct BackButton;
+
+#[derive(Component)]
+struct PauseRoot;
+
+// Sent to leave the game for another board of the options of the played board
+#[derive(Message, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RestartEvent {
+    // Same layout again, generated from the seed of the board
+    SameBoard,
+    NewBoard,
+}
+
+// Controls screen, and the pause and restart actions of the games
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<RestartEvent>()
+            .add_systems(Update, apply_input_map)
+            .add_systems(Update, restart_game)
+            .on_enter(AppState::Controls, setup_controls)
+            .on_update(
+                AppState::Controls,
+                (
+                    binding_buttons,
+                    preset_button,
+                    long_press_button,
+                    wheel_marks_button,
+                    reset_button,
+                    back_button,
+                ),
+            )
+            .on_exit(AppState::Controls, (cleanup::<ControlsRoot>, save_controls))
+            .on_update(AppState::InGame, game_actions)
+            .on_enter(AppState::Paused, setup_pause)
+            .on_update(AppState::Paused, unpause)
+            .on_exit(AppState::Paused, cleanup::<PauseRoot>)
+            .on_enter(AppState::Restarting, restart);
+    }
+}
+
+// Applies the bindings of the settings to the boards
+fn apply_input_map(settings: Res<Settings>, mut input_map: ResMut<InputMap>) {
+    if settings.is_changed() && settings.input_map != *input_map {
+        *input_map = settings.input_map;
+    }
+}
+
+fn setup_controls(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(ControlsRoot)
+        .insert(Name::new("Controls"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("controls.title"), 40.);
+            spawn_text(parent, font, locale.get("controls.hint"), 12.);
+            let preset = preset_label(&settings.input_map, &locale);
+            spawn_button(parent, font, &preset, PresetButton);
+            // two bindings per row, the list would overflow the window
+            for actions in InputAction::ALL.chunks(2) {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for action in actions {
+                            let label = binding_button_label(*action, &settings.input_map, &locale);
+                            spawn_button(parent, font, &label, BindingButton(*action));
+                        }
+                    });
+            }
+            let long_press = long_press_label(settings.input_map.long_press, &locale);
+            spawn_button(parent, font, &long_press, LongPressButton);
+            let wheel_marks = wheel_marks_label(settings.input_map.wheel_marks, &locale);
+            spawn_button(parent, font, &wheel_marks, WheelMarksButton);
+            spawn_button(parent, font, locale.get("controls.reset"), ResetButton);
+            spawn_button(parent, font, locale.get("common.back"), BackButton);
+        });
+}
+
+// Captures the next mouse button or key for the clicked binding buttons. The captured binding
+// is taken from the action previously bound to it, which gets the replaced binding
+#[allow(clippy::too_many_arguments)]
+fn binding_buttons(
+    mut cmds: Commands,
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    clicked: Query<(Entity, &Interaction, &BindingButton, &Children), Changed<Interaction>>,
+    capturing: Query<(Entity, &BindingButton), With<Capturing>>,
+    buttons: Query<(&BindingButton, &Children)>,
+    presets: Query<&Children, With<PresetButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    // the press ending a capture doesn't click the button under the cursor
+    if let Some((entity, button)) = capturing.iter().next() {
+        let binding = match Binding::just_pressed_any(&mouse, &keys) {
+            Some(b) => b,
+            None => return,
+        };
+        let input_map = &mut settings.input_map;
+        let replaced = input_map.get(button.0);
+        if let Some(other) = InputAction::ALL
+            .into_iter()
+            .find(|a| *a != button.0 && input_map.get(*a) == binding)
+        {
+            input_map.set(other, replaced);
+        }
+        input_map.set(button.0, binding);
+        cmds.entity(entity).remove::<Capturing>();
+        for (button, children) in buttons.iter() {
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(child) {
+                    text.0 = binding_button_label(button.0, &settings.input_map, &locale);
+                }
+            }
+        }
+        // rebinding a preset makes custom bindings
+        for children in presets.iter() {
+            for child in children.iter() {
+                if let Ok(mut text) = texts.get_mut(child) {
+                    text.0 = preset_label(&settings.input_map, &locale);
+                }
+            }
+        }
+        return;
+    }
+
+    for (entity, interaction, button, children) in clicked.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        cmds.entity(entity).insert(Capturing);
+        let action = locale.get(action_label(button.0));
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = locale.format("controls.press", &[("action", &action)]);
+            }
+        }
+        // one capture at a time
+        break;
+    }
+}
+
+// Binds the actions to the next preset, custom bindings going back to the first one
+fn preset_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    clicked: Query<&Interaction, (Changed<Interaction>, With<PresetButton>)>,
+    presets: Query<&Children, With<PresetButton>>,
+    buttons: Query<(&BindingButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !clicked.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    let all = InputPreset::ALL;
+    let index = all
+        .iter()
+        .position(|p| Some(*p) == settings.input_map.preset());
+    let preset = all[index.map_or(0, |i| (i + 1) % all.len())];
+    settings.input_map = preset.apply(&settings.input_map);
+    let labels = buttons
+        .iter()
+        .map(|(button, children)| {
+            let label = binding_button_label(button.0, &settings.input_map, &locale);
+            (children, label)
+        })
+        .chain(presets.iter().map(|children| {
+            let label = preset_label(&settings.input_map, &locale);
+            (children, label)
+        }));
+    for (children, label) in labels {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
+
+fn long_press_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<LongPressButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // custom durations of the settings file go back to the mode off
+        let all = LONG_PRESS_DURATIONS;
+        let index = all.iter().position(|d| *d == settings.input_map.long_press);
+        settings.input_map.long_press = all[index.map_or(0, |i| (i + 1) % all.len())];
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = long_press_label(settings.input_map.long_press, &locale);
+            }
+        }
+    }
+}
+
+fn wheel_marks_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<WheelMarksButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        settings.input_map.wheel_marks = !settings.input_map.wheel_marks;
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = wheel_marks_label(settings.input_map.wheel_marks, &locale);
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn reset_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    reset: Query<&Interaction, (Changed<Interaction>, With<ResetButton>)>,
+    buttons: Query<(&BindingButton, &Children), Without<Capturing>>,
+    long_press: Query<&Children, With<LongPressButton>>,
+    wheel_marks: Query<&Children, With<WheelMarksButton>>,
+    presets: Query<&Children, With<PresetButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    if !reset.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    settings.input_map = InputMap::default();
+    let labels = buttons
+        .iter()
+        .map(|(button, children)| {
+            let label = binding_button_label(button.0, &settings.input_map, &locale);
+            (children, label)
+        })
+        .chain(long_press.iter().map(|children| {
+            let label = long_press_label(settings.input_map.long_press, &locale);
+            (children, label)
+        }))
+        .chain(wheel_marks.iter().map(|children| {
+            let label = wheel_marks_label(settings.input_map.wheel_marks, &locale);
+            (children, label)
+        }))
+        .chain(presets.iter().map(|children| {
+            let label = preset_label(&settings.input_map, &locale);
+            (children, label)
+        }));
+    for (children, label) in labels {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
+
+fn back_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Settings);
+    }
+}
+
+fn save_controls(settings: Res<Settings>, profile: Res<Profile>) {
+    settings.save(&profile);
+}
+
+// Pauses, restarts or retries the game on the bindings of the actions. Races go on for every
+// player, demos leave on any key, and only the plain games restart, the other modes keep track
+// of their boards. Restarting a game in progress asks first
+#[allow(clippy::too_many_arguments)]
+fn game_actions(
+    mut cmds: Commands,
+    settings: Res<Settings>,
+    boards: Query<&Board>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    shared: (
+        Option<Res<Race>>,
+        Option<Res<Connection>>,
+        Option<Res<Demo>>,
+    ),
+    modes: (
+        Option<Res<Blitz>>,
+        Option<Res<CampaignSave>>,
+        Option<Res<DailyChallenge>>,
+        Option<Res<ReplayPlayback>>,
+        Option<Res<Tutorial>>,
+    ),
+    mut state: StateSwitch<AppState>,
+    mut restart_ewr: MessageWriter<RestartEvent>,
+) {
+    if shared.0.is_some() || shared.1.is_some() || shared.2.is_some() {
+        return;
+    }
+    if input_map.pause.just_pressed(&mouse, &keys) {
+        info!("Game paused");
+        state.push(AppState::Paused);
+        return;
+    }
+    let (blitz, campaign, daily, playback, tutorial) = modes;
+    if blitz.is_some()
+        || campaign.is_some()
+        || daily.is_some()
+        || playback.is_some()
+        || tutorial.is_some()
+    {
+        return;
+    }
+    if let Some(event) = restart_binding(&input_map, &mouse, &keys) {
+        if in_progress(&settings, state.current(), &boards, false)
+            && ask_abandon(&mut cmds, &mut state, Abandon::Restart(event))
+        {
+            return;
+        }
+        restart_ewr.write(event);
+    }
+}
+
+// Restart event of the pressed restart or retry binding
+pub fn restart_binding(
+    input_map: &InputMap,
+    mouse: &ButtonInput<MouseButton>,
+    keys: &ButtonInput<KeyCode>,
+) -> Option<RestartEvent> {
+    if input_map.retry.just_pressed(mouse, keys) {
+        Some(RestartEvent::SameBoard)
+    } else if input_map.restart.just_pressed(mouse, keys) {
+        Some(RestartEvent::NewBoard)
+    } else {
+        None
+    }
+}
+
+// Leaves the game, along with the states pushed over it, for the board of the restart
+fn restart_game(
+    mut cmds: Commands,
+    settings: Res<Settings>,
+    boards: Query<&Board>,
+    mut restart_evr: MessageReader<RestartEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    let event = match restart_evr.read().last() {
+        Some(e) => *e,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    let options = match event {
+        RestartEvent::SameBoard => {
+            info!("Retrying the board of seed {}", board.seed);
+            // the board is already known
+            BoardOptions {
+                seed: Some(board.seed),
+                preview_rerolls: None,
+                ..board.options.clone()
+            }
+        }
+        RestartEvent::NewBoard => {
+            info!("Restarting the game");
+            BoardOptions {
+                seed: None,
+                preview_rerolls: settings.board_preview.then_some(PREVIEW_REROLLS),
+                ..board.options.clone()
+            }
+        }
+    };
+    cmds.insert_resource(options);
+    state.replace(AppState::Restarting);
+}
+
+fn setup_pause(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    input_map: Res<InputMap>,
+) {
+    let font = &ui_assets.font;
+    let binding = binding_label(input_map.pause, &locale);
+    // hides the board, the paused game can't be studied
+    cmds.spawn((screen_root(), BackgroundColor(Color::srgb(0.1, 0.1, 0.1))))
+        .insert(PauseRoot)
+        .insert(Name::new("Pause"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("pause.title"), 40.);
+            let hint = locale.format("pause.hint", &[("binding", &binding)]);
+            spawn_text(parent, font, &hint, 12.);
+        });
+}
+
+fn unpause(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut state: StateSwitch<AppState>,
+) {
+    if input_map.pause.just_pressed(&mouse, &keys) {
+        info!("Game resumed");
+        state.pop();
+    }
+}
+
+// Goes back in game, on a new board of the same options
+fn restart(mut state: StateSwitch<AppState>) {
+    state.set(AppState::InGame);
+}
@@ -0,0 +1,192 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::results::GameResult;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::components::PlayerId;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{Action, BoardActionEvent, BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::resources::{BoardControls, BoardOptions, GameTimer};
+
+// Moves of a player on the shared board
+#[derive(Debug, Copy, Clone, Default)]
+struct Moves {
+    // Uncovers and chords
+    reveals: u32,
+    // Flags placed or removed
+    flags: u32,
+}
+
+// Local cooperative game in progress, both players sharing one board. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct Coop {
+    moves: [Moves; 2],
+}
+
+impl Coop {
+    fn moves(&self, player: PlayerId) -> Moves {
+        self.moves[player as usize]
+    }
+
+    fn moves_mut(&mut self, player: PlayerId) -> &mut Moves {
+        &mut self.moves[player as usize]
+    }
+}
+
+fn player_name(player: PlayerId, locale: &Locale) -> &str {
+    locale.get(match player {
+        PlayerId::One => "race.player_one",
+        PlayerId::Two => "race.player_two",
+    })
+}
+
+fn moves_label(coop: &Coop, player: PlayerId, locale: &Locale) -> String {
+    let moves = coop.moves(player);
+    locale.format(
+        "coop.moves",
+        &[
+            ("player", &player_name(player, locale)),
+            ("reveals", &moves.reveals),
+            ("flags", &moves.flags),
+        ],
+    )
+}
+
+#[derive(Component)]
+struct CoopRoot;
+
+// Text of the moves of a player
+#[derive(Component)]
+struct MovesText(PlayerId);
+
+// One board played by two players at once, player one with the mouse and player two with the
+// tile cursor. The flags, the time and the outcome of the board are shared
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_coop)
+            .on_update(AppState::InGame, (count_moves, coop_over))
+            .on_exit(AppState::InGame, cleanup::<CoopRoot>)
+            .on_exit(AppState::Results, end_coop);
+    }
+}
+
+// Options of the shared board. A bomb ends the game for both players, so there is no undo and no
+// life
+pub fn coop_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        controls: BoardControls::Shared,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        // the board is locked in with the mouse, player two would have no say
+        preview_rerolls: None,
+        ..game_options(difficulty, settings)
+    }
+}
+
+// Resets the moves of a new board and spawns the controls reminder
+fn setup_coop(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    coop: Option<ResMut<Coop>>,
+    locale: Res<Locale>,
+) {
+    let mut coop = match coop {
+        Some(c) => c,
+        None => return,
+    };
+    *coop = Coop::default();
+
+    let font = &ui_assets.font;
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(CoopRoot)
+    .insert(Name::new("Coop Controls"))
+    .with_children(|parent| {
+        spawn_text(parent, font, locale.get("race.controls_one"), 12.);
+        spawn_text(parent, font, locale.get("race.controls_two"), 12.);
+        for player in PlayerId::ALL {
+            let label = moves_label(&coop, player, &locale);
+            spawn_text(parent, font, &label, 12.).insert(MovesText(player));
+        }
+    });
+}
+
+// Counts the moves of each player
+fn count_moves(
+    coop: Option<ResMut<Coop>>,
+    locale: Res<Locale>,
+    mut texts: Query<(&MovesText, &mut Text)>,
+    mut board_action_evr: MessageReader<BoardActionEvent>,
+) {
+    let mut coop = match coop {
+        Some(c) => c,
+        None => return,
+    };
+    for event in board_action_evr.read() {
+        let moves = coop.moves_mut(event.player);
+        match event.action {
+            Action::Uncover(_) | Action::Chord(_) => moves.reveals += 1,
+            Action::ToggleFlag(_) => moves.flags += 1,
+            // undone moves stay counted
+            Action::Undo => (),
+            Action::Open | Action::Forgive | Action::MarkForgiven(_) | Action::FlagForced => (),
+        }
+    }
+    if !coop.is_changed() {
+        return;
+    }
+    for (MovesText(player), mut text) in texts.iter_mut() {
+        text.0 = moves_label(&coop, *player, &locale);
+    }
+}
+
+// Ends the game for both players when the board is cleared or a bomb goes off
+fn coop_over(
+    mut cmds: Commands,
+    coop: Option<Res<Coop>>,
+    timer: Res<GameTimer>,
+    locale: Res<Locale>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    if coop.is_none() {
+        return;
+    }
+    let time = format!("{:.2}", timer.elapsed());
+    let result = if board_completed_evr.read().next().is_some() {
+        GameResult {
+            headline: locale.get("coop.cleared").to_string(),
+            details: locale.format("result.cleared", &[("time", &time)]),
+        }
+    } else if let Some(event) = bomb_explosion_evr.read().next() {
+        let player = player_name(event.player, &locale);
+        GameResult {
+            headline: locale.get("coop.lost").to_string(),
+            details: locale.format("race.exploded", &[("player", &player)]),
+        }
+    } else {
+        return;
+    };
+    info!("Co-op game over: {}", result.details);
+    cmds.insert_resource(result);
+    state.set(AppState::Results);
+}
+
+fn end_coop(mut cmds: Commands) {
+    cmds.remove_resource::<Coop>();
+}
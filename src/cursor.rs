@@ -0,0 +1,121 @@
+use crate::settings::Settings;
+use crate::ui::cleanup;
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy::window::{CursorOptions, PrimaryWindow};
+use board_plugin::engine::{self, PrimaryWindowQuery, StateSystems};
+use board_plugin::resources::{PointerMode, PointerTool};
+
+// Side of the cursor sprites, in pixels
+const CURSOR_SIZE: f32 = 32.;
+
+// Sprites of the tools. Must be used as a resource
+#[derive(Resource)]
+struct CursorAssets {
+    shovel: Handle<Image>,
+    flag: Handle<Image>,
+    magnifier: Handle<Image>,
+}
+
+impl FromWorld for CursorAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        Self {
+            shovel: asset_server.load("sprites/shovel.png"),
+            flag: asset_server.load("sprites/flag.png"),
+            magnifier: asset_server.load("sprites/magnifier.png"),
+        }
+    }
+}
+
+impl CursorAssets {
+    // Sprite of a tool, with the pixel pointing at the tiles from its top left corner
+    fn sprite(&self, tool: PointerTool) -> (&Handle<Image>, Vec2) {
+        match tool {
+            PointerTool::Shovel => (&self.shovel, Vec2::new(1., 1.)),
+            PointerTool::Flag => (&self.flag, Vec2::new(16., 16.)),
+            PointerTool::Magnifier => (&self.magnifier, Vec2::new(12., 12.)),
+        }
+    }
+}
+
+// Sprite drawn in place of the system cursor
+#[derive(Component)]
+struct ToolCursor;
+
+// Cursor showing the tool of the next click over the boards: a shovel uncovering, a flag in flag
+// mode and a magnifier once the hint is armed. The system cursor is kept with its setting
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorAssets>()
+            .on_enter(AppState::InGame, spawn_cursor)
+            // every state, the system cursor coming back once the game is paused or left
+            .add_systems(Update, follow_pointer)
+            .on_exit(AppState::InGame, cleanup::<ToolCursor>);
+    }
+}
+
+fn spawn_cursor(mut cmds: Commands, cursor_assets: Res<CursorAssets>) {
+    cmds.spawn((
+        ImageNode::new(cursor_assets.shovel.clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(CURSOR_SIZE),
+            height: Val::Px(CURSOR_SIZE),
+            ..Default::default()
+        },
+        // the clicks go to the buttons under it
+        FocusPolicy::Pass,
+        Visibility::Hidden,
+    ))
+    .insert(ToolCursor)
+    .insert(Name::new("Tool cursor"));
+}
+
+// Moves the tool sprite to the pointer over the boards, hiding the system cursor under it
+#[allow(clippy::too_many_arguments)]
+fn follow_pointer(
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    mode: Res<PointerMode>,
+    cursor_assets: Res<CursorAssets>,
+    windows: PrimaryWindowQuery,
+    mut cursor_options: Query<&mut CursorOptions, With<PrimaryWindow>>,
+    mut cursors: Query<(&mut Node, &mut ImageNode, &mut Visibility), With<ToolCursor>>,
+    mut system_cursor: Local<Option<bool>>,
+) {
+    let (window, mut options) = match (windows.single(), cursor_options.single_mut()) {
+        (Ok(w), Ok(o)) => (w, o),
+        _ => return,
+    };
+    let position = engine::cursor_position(window);
+    let tool = mode
+        .hovered
+        .filter(|_| *state.get() == AppState::InGame && !settings.system_cursor)
+        .zip(position);
+
+    let visible = tool.is_none();
+    if *system_cursor != Some(visible) {
+        options.visible = visible;
+        *system_cursor = Some(visible);
+    }
+    for (mut node, mut image, mut visibility) in cursors.iter_mut() {
+        *visibility = match tool {
+            Some(_) => Visibility::Inherited,
+            None => Visibility::Hidden,
+        };
+        let (tool, position) = match tool {
+            Some(t) => t,
+            None => continue,
+        };
+        let (sprite, hotspot) = cursor_assets.sprite(tool);
+        node.left = Val::Px(position.x - hotspot.x);
+        node.top = Val::Px(position.y - hotspot.y);
+        if image.image != *sprite {
+            image.image = sprite.clone();
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::Profile;
+use crate::settings::Settings;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets, NORMAL_BUTTON};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::resources::{BoardOptions, BombCount};
+use serde::{Deserialize, Serialize};
+
+// Largest width and height of a custom board
+const MAX_SIDE: u16 = 200;
+// Steps of the buttons around every value, the small steps next to the value
+const STEPS: [i32; 4] = [-10, -1, 1, 10];
+
+// Board of the custom board dialog, saved with the profile settings
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomBoard {
+    pub width: u16,
+    pub height: u16,
+    // Bomb count, or density keeping the board as hard when resized
+    pub bomb_count: BombCount,
+}
+
+impl Default for CustomBoard {
+    fn default() -> Self {
+        let options = Difficulty::Intermediate.board_options();
+        Self {
+            width: options.map_size.0,
+            height: options.map_size.1,
+            bomb_count: options.bomb_count,
+        }
+    }
+}
+
+impl CustomBoard {
+    fn tile_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    // Moves a value by `step`, the sides staying within 1 and `MAX_SIDE`. Densities move by
+    // `step` percents
+    fn step(&mut self, field: Field, step: i32) {
+        let (value, min, max): (&mut u16, u16, u16) = match field {
+            Field::Width => (&mut self.width, 1, MAX_SIDE),
+            Field::Height => (&mut self.height, 1, MAX_SIDE),
+            Field::BombCount => match &mut self.bomb_count {
+                BombCount::Absolute(count) => (count, 0, u16::MAX),
+                BombCount::Density(density) => {
+                    let percent = (*density * 100.).round() as i32 + step;
+                    *density = percent.clamp(0, 100) as f32 / 100.;
+                    return;
+                }
+            },
+        };
+        *value = (i32::from(*value) + step).clamp(min.into(), max.into()) as u16;
+    }
+
+    // Switches between a bomb count and the closest density in whole percents
+    fn toggle_density(&mut self) {
+        let bombs = self.bomb_count.resolve(self.tile_count());
+        self.bomb_count = match self.bomb_count {
+            BombCount::Absolute(_) => {
+                let percent = (bombs as f32 * 100. / self.tile_count() as f32).round();
+                BombCount::Density(percent / 100.)
+            }
+            BombCount::Density(_) => BombCount::Absolute(bombs.min(u16::MAX.into()) as u16),
+        };
+    }
+
+    // Options of the custom board, the other options following the settings
+    pub fn board_options(&self, difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+        BoardOptions {
+            map_size: (self.width, self.height),
+            bomb_count: self.bomb_count,
+            seed: None,
+            ..game_options(difficulty, settings)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Field {
+    Width,
+    Height,
+    BombCount,
+}
+
+impl Field {
+    const ALL: [Self; 3] = [Self::Width, Self::Height, Self::BombCount];
+
+    fn label(&self, custom: &CustomBoard, locale: &Locale) -> String {
+        match (self, custom.bomb_count) {
+            (Self::Width, _) => locale.format("custom.width", &[("value", &custom.width)]),
+            (Self::Height, _) => locale.format("custom.height", &[("value", &custom.height)]),
+            (Self::BombCount, BombCount::Absolute(count)) => {
+                locale.format("custom.bombs", &[("value", &count)])
+            }
+            (Self::BombCount, BombCount::Density(density)) => {
+                let percent = (density * 100.).round();
+                let bombs = custom.bomb_count.resolve(custom.tile_count());
+                locale.format(
+                    "custom.density",
+                    &[("percent", &percent), ("bombs", &bombs)],
+                )
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct CustomRoot;
+
+// Text of the value of a field
+#[derive(Component)]
+struct FieldText(Field);
+
+// Text of the validation error of the board, empty for valid boards
+#[derive(Component)]
+struct ErrorText;
+
+#[derive(Debug, Copy, Clone, Component)]
+enum CustomButton {
+    Step(Field, i32),
+    // Switches the bombs between a count and a density
+    Density,
+    Play,
+    Back,
+}
+
+// Custom board dialog of the settings, picking the size and the bomb count of a board and
+// starting a game on it
+pub struct CustomBoardPlugin;
+
+impl Plugin for CustomBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::CustomBoard, setup_custom_board)
+            .on_update(AppState::CustomBoard, custom_buttons)
+            .on_exit(
+                AppState::CustomBoard,
+                (cleanup::<CustomRoot>, save_custom_board),
+            );
+    }
+}
+
+// Message of the validation error of the custom board, if any
+fn error_message(
+    custom: &CustomBoard,
+    difficulty: Difficulty,
+    settings: &Settings,
+    locale: &Locale,
+) -> String {
+    match custom.board_options(difficulty, settings).validate() {
+        Ok(()) => String::new(),
+        Err(e) => locale.format("custom.invalid", &[("error", &e)]),
+    }
+}
+
+fn setup_custom_board(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    settings: Res<Settings>,
+    difficulty: Res<Difficulty>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    let custom = &settings.custom_board;
+    cmds.spawn(screen_root())
+        .insert(CustomRoot)
+        .insert(Name::new("Custom Board"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("custom.title"), 40.);
+            for field in Field::ALL {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        let (down, up) = STEPS.split_at(STEPS.len() / 2);
+                        for step in down {
+                            spawn_step_button(parent, font, field, *step);
+                        }
+                        let label = field.label(custom, &locale);
+                        spawn_text(parent, font, &label, 20.).insert(FieldText(field));
+                        for step in up {
+                            spawn_step_button(parent, font, field, *step);
+                        }
+                    });
+            }
+            let error = error_message(custom, *difficulty, &settings, &locale);
+            spawn_text(parent, font, &error, 12.).insert(ErrorText);
+            let density = locale.get("custom.toggle_density");
+            spawn_button(parent, font, density, CustomButton::Density);
+            spawn_button(parent, font, locale.get("custom.play"), CustomButton::Play);
+            spawn_button(parent, font, locale.get("common.back"), CustomButton::Back);
+        });
+}
+
+// Small button moving a value by its step
+fn spawn_step_button(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    field: Field,
+    step: i32,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(50.),
+                height: Val::Px(40.),
+                margin: UiRect::all(Val::Px(4.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+        ))
+        .insert(CustomButton::Step(field, step))
+        .with_children(|parent| {
+            spawn_text(parent, font, &format!("{step:+}"), 12.);
+        });
+}
+
+// Steps the values and switches the bombs between a count and a density, validating the board on
+// every change, and starts the game on a valid board
+#[allow(clippy::too_many_arguments)]
+fn custom_buttons(
+    mut cmds: Commands,
+    mut settings: ResMut<Settings>,
+    difficulty: Res<Difficulty>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &CustomButton), Changed<Interaction>>,
+    mut fields: Query<(&FieldText, &mut Text)>,
+    mut errors: Query<&mut Text, (With<ErrorText>, Without<FieldText>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            CustomButton::Step(field, step) => settings.custom_board.step(*field, *step),
+            CustomButton::Density => settings.custom_board.toggle_density(),
+            CustomButton::Play => {
+                let options = settings.custom_board.board_options(*difficulty, &settings);
+                match options.validate() {
+                    Ok(()) => {
+                        let (width, height) = options.map_size;
+                        info!(
+                            "Custom {width}x{height} board with {} bombs",
+                            options.bombs()
+                        );
+                        cmds.insert_resource(options);
+                        state.set(AppState::InGame);
+                    }
+                    Err(e) => warn!("Invalid custom board: {e}"),
+                }
+                return;
+            }
+            CustomButton::Back => {
+                state.set(AppState::Settings);
+                return;
+            }
+        }
+        // the size changes the bombs of a density as well
+        let custom = &settings.custom_board;
+        for (FieldText(field), mut text) in fields.iter_mut() {
+            text.0 = field.label(custom, &locale);
+        }
+        let error = error_message(custom, *difficulty, &settings, &locale);
+        for mut text in errors.iter_mut() {
+            text.0 = error.clone();
+        }
+    }
+}
+
+fn save_custom_board(settings: Res<Settings>, profile: Res<Profile>) {
+    settings.save(&profile);
+}
@@ -0,0 +1,114 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::Profile;
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::toast::ToastEvent;
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::events::BoardCompletedEvent;
+use board_plugin::grid::{GridKind, NeighborKernel};
+use board_plugin::resources::{BoardOptions, Generation};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Difficulty of every daily challenge
+pub const DAILY_DIFFICULTY: Difficulty = Difficulty::Intermediate;
+// Mixed into the date, so daily seeds don't match the seeds of other games
+const DAILY_SALT: u64 = 0x6d69_6e65_7377_6565;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Daily challenge being played, of the given day. Must be used as a resource
+#[derive(Resource)]
+pub struct DailyChallenge {
+    pub day: u32,
+}
+
+// Records the completed daily challenges in the player stats
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, complete_daily)
+            .on_exit(AppState::InGame, end_daily);
+    }
+}
+
+// Current UTC day, counted since the Unix epoch
+pub fn today() -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (now.as_secs() / SECONDS_PER_DAY) as u32
+}
+
+// `YYYY-MM-DD` date of a day since the Unix epoch
+pub fn date(day: u32) -> String {
+    // civil from days, by Howard Hinnant
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+// Seed of the challenge of a day, the same for every player on every platform
+pub fn daily_seed(day: u32) -> u64 {
+    // splitmix64 finalizer
+    let mut z = DAILY_SALT ^ u64::from(day);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Options of the challenge of a day, only the assists follow the player settings
+pub fn daily_options(day: u32, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        seed: Some(daily_seed(day)),
+        grid: GridKind::Square,
+        kernel: NeighborKernel::Grid,
+        wrap: false,
+        max_mines: 1,
+        safe_start: true,
+        generation: Generation::Uniform,
+        preview_rerolls: None,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        ..game_options(DAILY_DIFFICULTY, settings)
+    }
+}
+
+fn complete_daily(
+    daily: Option<Res<DailyChallenge>>,
+    mut stats: ResMut<Stats>,
+    profile: Res<Profile>,
+    locale: Res<Locale>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    let daily = match daily {
+        Some(d) => d,
+        None => return,
+    };
+    if board_completed_evr.read().count() > 0 {
+        stats.daily.complete(daily.day);
+        info!(
+            "Daily challenge completed, {} days streak",
+            stats.daily.streak
+        );
+        stats.save(&profile);
+        let message = locale.format("toast.daily_done", &[("days", &stats.daily.streak)]);
+        toast_ewr.write(ToastEvent::success(message));
+    }
+}
+
+fn end_daily(mut cmds: Commands) {
+    cmds.remove_resource::<DailyChallenge>();
+}
@@ -0,0 +1,310 @@
+use crate::config::Config;
+use crate::daily::{self, DailyChallenge};
+use crate::locale::Locale;
+use crate::persistence::{self, Profile};
+use crate::settings::Settings;
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, Task};
+use board_plugin::engine::StateSystems;
+use board_plugin::events::BoardCompletedEvent;
+use board_plugin::resources::{Board, GameTimer};
+use futures_lite::future;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+const QUEUE_FILE: &str = "daily_submissions.ron";
+// Online entries shown on the leaderboard
+const TOP_ENTRIES: usize = 10;
+// Seconds before retrying a failed submission, doubled after every failure in a row
+const FIRST_RETRY: f32 = 5.;
+const MAX_RETRY: f32 = 300.;
+
+// Daily challenge time, as sent to the endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySubmission {
+    pub day: u32,
+    pub name: String,
+    // Completion time in seconds, including the assist penalties
+    pub time: f32,
+}
+
+// Entry of the online daily leaderboard, as fetched from the endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnlineEntry {
+    pub name: String,
+    pub time: f32,
+}
+
+// Times waiting for the endpoint, kept across sessions while offline. Must be used as a resource
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct SubmissionQueue {
+    pending: Vec<DailySubmission>,
+}
+
+impl SubmissionQueue {
+    fn load() -> Self {
+        persistence::load_global(QUEUE_FILE)
+    }
+
+    fn save(&self) {
+        persistence::save_global(QUEUE_FILE, self);
+    }
+}
+
+// Submissions which didn't go through
+#[derive(Debug)]
+pub enum SubmitError {
+    // The endpoint refused the submission with a client error, sending it again won't help
+    #[cfg_attr(not(feature = "online_leaderboard"), allow(dead_code))]
+    Rejected(String),
+    // The endpoint couldn't be reached or failed on its side, the submission is sent again
+    Failed(String),
+}
+
+impl Display for SubmitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(e) => write!(f, "rejected: {e}"),
+            Self::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+// Submission in flight, one at a time in the queue order. Must be used as a resource
+#[derive(Resource, Default)]
+struct Uploader {
+    task: Option<Task<Result<(), SubmitError>>>,
+    // Seconds left before the next try
+    wait: f32,
+    // Wait after the next failure
+    backoff: f32,
+}
+
+// Top entries of the day being fetched for the leaderboard screen. Must be used as a resource
+#[derive(Resource)]
+struct PendingTopEntries(Task<Result<Vec<OnlineEntry>, String>>);
+
+// Text of the online daily rankings on the leaderboard screen
+#[derive(Component)]
+pub struct OnlineDailyText;
+
+// Submits the daily challenge times to the REST endpoint of the config file, and shows the top
+// times of the day on the leaderboard screen. Players opt in from the settings, and builds
+// without the `online_leaderboard` feature keep the times queued without sending them
+pub struct DailyOnlinePlugin;
+
+impl Plugin for DailyOnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SubmissionQueue::load())
+            .insert_resource(Uploader {
+                backoff: FIRST_RETRY,
+                ..Default::default()
+            })
+            .add_systems(Update, upload_submissions)
+            .on_update(AppState::InGame, queue_daily_time)
+            .on_enter(AppState::Leaderboard, fetch_top_entries)
+            .on_update(AppState::Leaderboard, show_top_entries);
+    }
+}
+
+// Endpoint of the online daily leaderboard, if the player opted in and the network is allowed
+pub fn endpoint<'a>(settings: &Settings, config: &'a Config) -> Option<&'a str> {
+    let url = config.daily_leaderboard_url.as_deref()?;
+    (settings.submit_daily && config.network_allowed()).then_some(url)
+}
+
+// Queues the time of a completed daily challenge
+#[allow(clippy::too_many_arguments)]
+fn queue_daily_time(
+    daily: Option<Res<DailyChallenge>>,
+    settings: Res<Settings>,
+    config: Res<Config>,
+    profile: Res<Profile>,
+    timer: Res<GameTimer>,
+    boards: Query<&Board>,
+    mut queue: ResMut<SubmissionQueue>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+) {
+    let daily = match daily {
+        Some(d) => d,
+        None => return,
+    };
+    let board = match board_completed_evr.read().last() {
+        Some(event) => match boards.get(event.board) {
+            Ok(b) => b,
+            Err(_) => return,
+        },
+        None => return,
+    };
+    if endpoint(&settings, &config).is_none() {
+        return;
+    }
+    // ranked like the local leaderboard, assist penalties included
+    let time = timer.elapsed() + board.options.scoring.time_penalty(&board.assists);
+    info!("Queuing the daily challenge time {time:.2}s for submission");
+    queue.pending.push(DailySubmission {
+        day: daily.day,
+        name: profile.name().to_string(),
+        time,
+    });
+    queue.save();
+}
+
+// Sends the queued times in order, waiting longer after every failure in a row. The times
+// rejected by the endpoint are dropped instead, so they don't hold the queue up
+fn upload_submissions(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    config: Res<Config>,
+    mut queue: ResMut<SubmissionQueue>,
+    mut uploader: ResMut<Uploader>,
+) {
+    if let Some(task) = uploader.task.as_mut() {
+        let result = match future::block_on(future::poll_once(task)) {
+            Some(r) => r,
+            None => return,
+        };
+        uploader.task = None;
+        match result {
+            Ok(()) => {
+                info!("Daily challenge time submitted");
+                queue.pending.remove(0);
+                queue.save();
+                uploader.backoff = FIRST_RETRY;
+            }
+            Err(SubmitError::Rejected(e)) => {
+                warn!("Daily challenge time rejected by the endpoint, dropping it: {e}");
+                queue.pending.remove(0);
+                queue.save();
+                uploader.backoff = FIRST_RETRY;
+            }
+            Err(SubmitError::Failed(e)) => {
+                warn!(
+                    "Failed to submit the daily challenge time, retrying in {}s: {e}",
+                    uploader.backoff
+                );
+                uploader.wait = uploader.backoff;
+                uploader.backoff = (uploader.backoff * 2.).min(MAX_RETRY);
+            }
+        }
+        return;
+    }
+
+    uploader.wait = (uploader.wait - time.delta_secs()).max(0.);
+    let url = match endpoint(&settings, &config) {
+        Some(url) if cfg!(feature = "online_leaderboard") => url.to_string(),
+        _ => return,
+    };
+    let submission = match queue.pending.first() {
+        Some(s) if uploader.wait <= 0. => s.clone(),
+        _ => return,
+    };
+    uploader.task = Some(IoTaskPool::get().spawn(async move { http::submit(&url, &submission) }));
+}
+
+// Fetches the top times of today's challenge
+fn fetch_top_entries(mut cmds: Commands, settings: Res<Settings>, config: Res<Config>) {
+    let url = match endpoint(&settings, &config) {
+        Some(url) => url.to_string(),
+        None => return,
+    };
+    let day = daily::today();
+    let task = IoTaskPool::get().spawn(async move { http::top_entries(&url, day) });
+    cmds.insert_resource(PendingTopEntries(task));
+}
+
+// Lists the fetched times, or the failure, on the leaderboard screen
+fn show_top_entries(
+    mut cmds: Commands,
+    pending: Option<ResMut<PendingTopEntries>>,
+    locale: Res<Locale>,
+    mut texts: Query<&mut Text, With<OnlineDailyText>>,
+) {
+    let mut pending = match pending {
+        Some(p) => p,
+        None => return,
+    };
+    let result = match future::block_on(future::poll_once(&mut pending.0)) {
+        Some(r) => r,
+        None => return,
+    };
+    cmds.remove_resource::<PendingTopEntries>();
+    let lines = match result {
+        Ok(entries) if entries.is_empty() => locale.get("leaderboard.online_empty").to_string(),
+        Ok(entries) => entries
+            .iter()
+            .take(TOP_ENTRIES)
+            .enumerate()
+            .map(|(rank, entry)| {
+                locale.format(
+                    "leaderboard.entry",
+                    &[
+                        ("rank", &(rank + 1)),
+                        ("name", &entry.name),
+                        ("time", &format!("{:.2}", entry.time)),
+                    ],
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(e) => {
+            warn!("Failed to fetch the online daily leaderboard: {e}");
+            locale.get("leaderboard.online_offline").to_string()
+        }
+    };
+    for mut text in texts.iter_mut() {
+        text.0 = lines.clone();
+    }
+}
+
+// REST calls of the endpoint: `POST <url>/daily/<day>` with a `DailySubmission` as JSON, and
+// `GET <url>/daily/<day>?limit=<count>` answering the fastest `OnlineEntry` list
+#[cfg(feature = "online_leaderboard")]
+mod http {
+    use super::{DailySubmission, OnlineEntry, SubmitError, TOP_ENTRIES};
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn agent() -> ureq::Agent {
+        ureq::AgentBuilder::new().timeout(TIMEOUT).build()
+    }
+
+    // The 4xx answers reject the submission, the 5xx ones and the transport errors fail it
+    pub fn submit(url: &str, submission: &DailySubmission) -> Result<(), SubmitError> {
+        agent()
+            .post(&format!("{url}/daily/{}", submission.day))
+            .send_json(submission)
+            .map(|_| ())
+            .map_err(|e| match e {
+                ureq::Error::Status(400..=499, _) => SubmitError::Rejected(e.to_string()),
+                _ => SubmitError::Failed(e.to_string()),
+            })
+    }
+
+    pub fn top_entries(url: &str, day: u32) -> Result<Vec<OnlineEntry>, String> {
+        agent()
+            .get(&format!("{url}/daily/{day}"))
+            .query("limit", &TOP_ENTRIES.to_string())
+            .call()
+            .map_err(|e| e.to_string())?
+            .into_json()
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(feature = "online_leaderboard"))]
+mod http {
+    use super::{DailySubmission, OnlineEntry, SubmitError};
+
+    const DISABLED: &str = "built without the `online_leaderboard` feature";
+
+    pub fn submit(_url: &str, _submission: &DailySubmission) -> Result<(), SubmitError> {
+        Err(SubmitError::Failed(DISABLED.to_string()))
+    }
+
+    pub fn top_entries(_url: &str, _day: u32) -> Result<Vec<OnlineEntry>, String> {
+        Err(DISABLED.to_string())
+    }
+}
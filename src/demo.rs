@@ -0,0 +1,205 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{
+    BoardCommand, BoardCompletedEvent, BombExplosionEvent, SpawnBoardEvent,
+};
+use board_plugin::resources::{Board, BoardOptions};
+use board_plugin::solver::Deduction;
+use minesweeper_core::GameState;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+// Seconds between two moves, drawn at random so the play looks human
+const MOVE_SECONDS: (f32, f32) = (0.3, 0.9);
+// Seconds a finished board stays on screen before the next one
+const NEXT_BOARD_SECONDS: f32 = 3.;
+
+// Demo in progress, the solver playing board after board. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct Demo {
+    // Seconds until the next move, or the next board once the board is finished
+    cooldown: f32,
+    finished: bool,
+}
+
+#[derive(Component)]
+struct DemoHud;
+
+// Attract mode playing boards with the solver through the board commands: the safe tiles it
+// proves are uncovered, the bombs flagged, and the least likely tile is guessed when stuck.
+// Any key or click goes back to the menu
+pub struct DemoPlugin;
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_demo_hud)
+            .on_update(AppState::InGame, auto_play)
+            .on_exit(AppState::InGame, (cleanup::<DemoHud>, end_demo));
+    }
+}
+
+// Options of the demo boards, opening safely so the demo never loses on its first move
+pub fn demo_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        safe_start: true,
+        // the demo flags the bombs it finds
+        no_flags: false,
+        preview_rerolls: None,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        confirm_last_guess: false,
+        ..game_options(difficulty, settings)
+    }
+}
+
+fn setup_demo_hud(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    demo: Option<Res<Demo>>,
+    locale: Res<Locale>,
+) {
+    if demo.is_none() {
+        return;
+    }
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(DemoHud)
+    .insert(Name::new("Demo HUD"))
+    .with_children(|parent| {
+        spawn_text(parent, &ui_assets.font, locale.get("demo.hud"), 16.);
+    });
+}
+
+// Plays the next move once the cooldown is over, and replaces the finished boards
+#[allow(clippy::too_many_arguments)]
+fn auto_play(
+    mut cmds: Commands,
+    demo: Option<ResMut<Demo>>,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    settings: Res<Settings>,
+    difficulty: Res<Difficulty>,
+    boards: Query<&Board>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut board_command_ewr: MessageWriter<BoardCommand>,
+    mut spawn_board_ewr: MessageWriter<SpawnBoardEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    let mut demo = match demo {
+        Some(d) => d,
+        None => return,
+    };
+    if keys.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some() {
+        info!("Demo over");
+        state.set(AppState::Menu);
+        return;
+    }
+    let ended = board_completed_evr.read().count() + bomb_explosion_evr.read().count() > 0;
+    if ended && !demo.finished {
+        demo.finished = true;
+        demo.cooldown = NEXT_BOARD_SECONDS;
+    }
+    demo.cooldown -= time.delta_secs();
+    if demo.cooldown > 0. {
+        return;
+    }
+    // a replaced board may not be spawned yet
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+
+    if demo.finished {
+        demo.finished = false;
+        cmds.entity(board.entity).despawn();
+        spawn_board_ewr.write(SpawnBoardEvent(demo_options(*difficulty, &settings)));
+        return;
+    }
+    if board.game.state() != GameState::Playing {
+        return;
+    }
+    let mut rng = thread_rng();
+    demo.cooldown = rng.gen_range(MOVE_SECONDS.0..MOVE_SECONDS.1);
+    let command = match board.game.solver().hint(board.game.flags()) {
+        Some(Deduction::Safe(coordinates)) => BoardCommand::UncoverTile {
+            board: board.entity,
+            coordinates,
+        },
+        Some(Deduction::Bomb(coordinates)) => BoardCommand::ToggleFlag {
+            board: board.entity,
+            coordinates,
+        },
+        None => match guess(board, &mut rng) {
+            Some(coordinates) => {
+                info!("Demo guessing {coordinates}");
+                BoardCommand::UncoverTile {
+                    board: board.entity,
+                    coordinates,
+                }
+            }
+            None => return,
+        },
+    };
+    board_command_ewr.write(command);
+}
+
+// Covered tile the least likely to hold a bomb: the exact odds on endgames, else the odds
+// given by the revealed numbers around the tile, or the density of the bombs left away from
+// them
+fn guess(board: &Board, rng: &mut impl Rng) -> Option<Coordinates> {
+    let game = &board.game;
+    let tile_map = board.tile_map();
+    let odds: Vec<(Coordinates, f32)> = match game.solver().endgame_odds() {
+        Some(odds) => odds,
+        None => {
+            let mut unknown: Vec<Coordinates> = tile_map
+                .tiles()
+                .map(|(c, _)| c)
+                .filter(|c| game.is_covered(*c) && !game.is_flagged(*c))
+                .collect();
+            // even odds are broken at random
+            unknown.shuffle(rng);
+            let density = game.mines_left().max(0) as f32 / unknown.len().max(1) as f32;
+            unknown
+                .into_iter()
+                .map(|c| {
+                    let local = tile_map
+                        .neighbors(c)
+                        .filter(|n| game.is_revealed(*n))
+                        .map(|n| {
+                            let flagged = game.flagged_neighbors(n);
+                            let bombs = tile_map.bomb_count_at(n) as usize;
+                            let unknown = game.covered_neighbors(n).count() - flagged;
+                            bombs.saturating_sub(flagged) as f32 / unknown.max(1) as f32
+                        })
+                        .reduce(f32::max);
+                    (c, local.unwrap_or(density))
+                })
+                .collect()
+        }
+    };
+    odds.into_iter()
+        .filter(|(c, _)| !game.is_flagged(*c))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(c, _)| c)
+}
+
+fn end_demo(mut cmds: Commands) {
+    cmds.remove_resource::<Demo>();
+}
@@ -0,0 +1,65 @@
+use crate::locale::Locale;
+use bevy::prelude::Resource;
+use board_plugin::resources::{BoardOptions, BombCount};
+use minesweeper_core::Preset;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+// Classic board presets
+#[derive(
+    Resource, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    // Board size and bomb count of the preset, shared with the terminal front-end
+    pub fn preset(&self) -> Preset {
+        match self {
+            Self::Beginner => Preset::Beginner,
+            Self::Intermediate => Preset::Intermediate,
+            Self::Expert => Preset::Expert,
+        }
+    }
+
+    // Board options matching the preset
+    pub fn board_options(&self) -> BoardOptions {
+        let preset = self.preset();
+        BoardOptions {
+            map_size: preset.map_size(),
+            bomb_count: BombCount::Absolute(preset.bomb_count()),
+            tile_padding: 3.0,
+            ..Default::default()
+        }
+    }
+
+    // Name of the preset in the player language
+    pub fn label<'a>(&self, locale: &'a Locale) -> &'a str {
+        locale.get(match self {
+            Self::Beginner => "difficulty.beginner",
+            Self::Intermediate => "difficulty.intermediate",
+            Self::Expert => "difficulty.expert",
+        })
+    }
+}
+
+impl From<Preset> for Difficulty {
+    fn from(preset: Preset) -> Self {
+        match preset {
+            Preset::Beginner => Self::Beginner,
+            Preset::Intermediate => Self::Intermediate,
+            Preset::Expert => Self::Expert,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
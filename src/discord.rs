@@ -0,0 +1,225 @@
+use crate::config::Config;
+use crate::daily;
+use crate::demo::Demo;
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::persistence::Profile;
+use crate::stats::Stats;
+use crate::AppState;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::events::BoardCompletedEvent;
+use board_plugin::resources::{GameTimer, ReplayPlayback};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Activity shown on the Discord profile of the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    // First line, what the player is doing
+    pub details: String,
+    // Second line, the boards cleared today
+    pub state: String,
+    // Unix time in seconds of the game start, Discord counting the elapsed time from it
+    pub start: Option<i64>,
+}
+
+// Connection to the Discord client, and the activity it shows. Must be used as a resource
+#[derive(Resource, Default)]
+struct Presence {
+    client: Option<rpc::Client>,
+    published: Option<Activity>,
+}
+
+// Publishes the current activity of the player (difficulty, elapsed time and boards cleared
+// today) to the Discord Rich Presence, with the `discord` feature and the application id of the
+// config file. The activity is updated on the state transitions and cleared on exit
+pub struct DiscordPlugin;
+
+impl Plugin for DiscordPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, count_cleared_boards);
+        if cfg!(not(feature = "discord")) {
+            info!("Built without the `discord` feature, the Discord activity isn't published");
+            return;
+        }
+        app.init_resource::<Presence>()
+            .add_systems(Update, publish_activity)
+            // after the exit requests of the frame
+            .add_systems(Last, clear_activity);
+    }
+}
+
+// Records the boards cleared by the player, replays and demos don't count
+fn count_cleared_boards(
+    playback: Option<Res<ReplayPlayback>>,
+    demo: Option<Res<Demo>>,
+    profile: Res<Profile>,
+    mut stats: ResMut<Stats>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+) {
+    let cleared = board_completed_evr.read().count();
+    if cleared == 0 || playback.is_some() || demo.is_some() {
+        return;
+    }
+    for _ in 0..cleared {
+        stats.cleared.record(daily::today());
+    }
+    stats.save(&profile);
+}
+
+// Activity of the current state
+fn current_activity(
+    state: &AppState,
+    difficulty: &Difficulty,
+    timer: &GameTimer,
+    stats: &Stats,
+    locale: &Locale,
+) -> Activity {
+    let difficulty = difficulty.label(locale);
+    let (details, start) = match state {
+        AppState::InGame | AppState::Abandon => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            let details = locale.format("discord.playing", &[("difficulty", &difficulty)]);
+            (details, Some(now - timer.elapsed() as i64))
+        }
+        // the timer is stopped
+        AppState::Paused => {
+            let details = locale.format("discord.paused", &[("difficulty", &difficulty)]);
+            (details, None)
+        }
+        _ => (locale.get("discord.menu").to_string(), None),
+    };
+    let cleared = stats.cleared.on(daily::today());
+    Activity {
+        details,
+        state: locale.format("discord.cleared_today", &[("count", &cleared)]),
+        start,
+    }
+}
+
+// Publishes the activity on the state transitions and once a board is cleared. Discord not
+// running is retried on the next change
+fn publish_activity(
+    state: Res<State<AppState>>,
+    difficulty: Res<Difficulty>,
+    timer: Res<GameTimer>,
+    stats: Res<Stats>,
+    locale: Res<Locale>,
+    config: Res<Config>,
+    mut presence: ResMut<Presence>,
+) {
+    let client_id = match config.discord_client_id.as_deref() {
+        Some(id) if config.integrations_allowed() => id,
+        _ => return,
+    };
+    // left right away for the new board
+    if *state.get() == AppState::Restarting {
+        return;
+    }
+    if !state.is_changed() && !stats.is_changed() && presence.published.is_some() {
+        return;
+    }
+    let activity = current_activity(state.get(), &difficulty, &timer, &stats, &locale);
+    // the start time only moves with the state, not with the rounding of the timer
+    let unchanged = presence.published.as_ref().is_some_and(|published| {
+        *published == activity
+            || (!state.is_changed()
+                && published.details == activity.details
+                && published.state == activity.state)
+    });
+    if unchanged {
+        return;
+    }
+
+    if presence.client.is_none() {
+        match rpc::Client::connect(client_id) {
+            Ok(client) => presence.client = Some(client),
+            Err(e) => {
+                warn!("Failed to connect to Discord: {e}");
+                // not tried again before the next change
+                presence.published = Some(activity);
+                return;
+            }
+        }
+    }
+    let client = presence.client.as_mut().unwrap();
+    match client.publish(&activity) {
+        Ok(()) => presence.published = Some(activity),
+        Err(e) => {
+            warn!("Failed to publish the Discord activity: {e}");
+            presence.client = None;
+            presence.published = Some(activity);
+        }
+    }
+}
+
+// Clears the activity and closes the connection once the game exits
+fn clear_activity(mut presence: ResMut<Presence>, mut app_exit_evr: MessageReader<AppExit>) {
+    if app_exit_evr.read().count() == 0 {
+        return;
+    }
+    if let Some(mut client) = presence.client.take() {
+        if let Err(e) = client.clear() {
+            warn!("Failed to clear the Discord activity: {e}");
+        }
+    }
+}
+
+// IPC connection to the local Discord client
+#[cfg(feature = "discord")]
+mod rpc {
+    use super::Activity;
+    use discord_rich_presence::activity::{self, Timestamps};
+    use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+    pub struct Client(DiscordIpcClient);
+
+    impl Client {
+        pub fn connect(client_id: &str) -> Result<Self, String> {
+            let mut client = DiscordIpcClient::new(client_id);
+            client.connect().map_err(|e| e.to_string())?;
+            Ok(Self(client))
+        }
+
+        pub fn publish(&mut self, activity: &Activity) -> Result<(), String> {
+            let mut payload = activity::Activity::new()
+                .details(activity.details.as_str())
+                .state(activity.state.as_str());
+            if let Some(start) = activity.start {
+                payload = payload.timestamps(Timestamps::new().start(start));
+            }
+            self.0.set_activity(payload).map_err(|e| e.to_string())
+        }
+
+        pub fn clear(&mut self) -> Result<(), String> {
+            self.0.clear_activity().map_err(|e| e.to_string())?;
+            self.0.close().map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(not(feature = "discord"))]
+mod rpc {
+    use super::Activity;
+
+    const DISABLED: &str = "built without the `discord` feature";
+
+    pub struct Client;
+
+    impl Client {
+        pub fn connect(_client_id: &str) -> Result<Self, String> {
+            Err(DISABLED.to_string())
+        }
+
+        pub fn publish(&mut self, _activity: &Activity) -> Result<(), String> {
+            Err(DISABLED.to_string())
+        }
+
+        pub fn clear(&mut self) -> Result<(), String> {
+            Err(DISABLED.to_string())
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::locale::Locale;
+use crate::persistence::Profile;
+use crate::settings::Settings;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PrimaryWindow, VideoModeSelection, WindowMode};
+use board_plugin::engine::{self, StateSwitch, StateSystems};
+use board_plugin::resources::InputMap;
+use serde::{Deserialize, Serialize};
+
+// Window sizes cycled through by the resolution button, the first one is the default
+const RESOLUTIONS: [(u32, u32); 5] = [
+    (700, 800),
+    (1024, 768),
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    Windowed,
+    // Window covering the screen at the desktop resolution
+    Borderless,
+    // Exclusive fullscreen
+    Fullscreen,
+}
+
+impl DisplayMode {
+    const ALL: [Self; 3] = [Self::Windowed, Self::Borderless, Self::Fullscreen];
+
+    fn window_mode(&self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+            Self::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+
+    // Locale key of the name of the mode
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Windowed => "display.windowed",
+            Self::Borderless => "display.borderless",
+            Self::Fullscreen => "display.fullscreen",
+        }
+    }
+}
+
+// Window settings, saved with the profile settings
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+    pub vsync: bool,
+    // Size of the window in windowed mode
+    pub resolution: (u32, u32),
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            vsync: true,
+            resolution: RESOLUTIONS[0],
+        }
+    }
+}
+
+impl DisplaySettings {
+    // Sets up the window created on startup
+    pub fn apply_to(&self, window: &mut Window) {
+        engine::set_window_size(window, self.resolution);
+        window.mode = self.mode.window_mode();
+        engine::set_vsync(window, self.vsync);
+    }
+}
+
+fn mode_label(display: &DisplaySettings, locale: &Locale) -> String {
+    let mode = locale.get(display.mode.label());
+    locale.format("display.mode", &[("mode", &mode)])
+}
+
+fn vsync_label(display: &DisplaySettings, locale: &Locale) -> String {
+    let check = if display.vsync { "x" } else { " " };
+    format!("[{check}] {}", locale.get("display.vsync"))
+}
+
+fn resolution_label(display: &DisplaySettings, locale: &Locale) -> String {
+    let (width, height) = display.resolution;
+    locale.format(
+        "display.resolution",
+        &[("width", &width), ("height", &height)],
+    )
+}
+
+#[derive(Component)]
+struct DisplayRoot;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+enum DisplayButton {
+    Mode,
+    Vsync,
+    Resolution,
+    Back,
+}
+
+// Display screen of the settings, and the fullscreen binding toggling the window mode anywhere
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_display)
+            .add_systems(Update, toggle_fullscreen)
+            .on_enter(AppState::Display, setup_display)
+            .on_update(AppState::Display, display_buttons)
+            .on_exit(AppState::Display, (cleanup::<DisplayRoot>, save_display));
+    }
+}
+
+// Updates the window when the display settings change
+fn apply_display(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let mut window = match windows.single_mut() {
+        Ok(w) => w,
+        Err(_) => return,
+    };
+    let display = &settings.display;
+    let mode = display.mode.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    if engine::vsync(&window) != display.vsync {
+        engine::set_vsync(&mut window, display.vsync);
+    }
+    let (width, height) = display.resolution;
+    if engine::window_size(&window) != Vec2::new(width as f32, height as f32) {
+        engine::set_window_size(&mut window, display.resolution);
+    }
+}
+
+// Switches between the windowed mode and the borderless fullscreen, saving the choice
+fn toggle_fullscreen(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if !input_map.fullscreen.just_pressed(&mouse, &keys) {
+        return;
+    }
+    settings.display.mode = match settings.display.mode {
+        DisplayMode::Windowed => DisplayMode::Borderless,
+        DisplayMode::Borderless | DisplayMode::Fullscreen => DisplayMode::Windowed,
+    };
+    settings.save(&profile);
+}
+
+fn setup_display(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    let display = &settings.display;
+    cmds.spawn(screen_root())
+        .insert(DisplayRoot)
+        .insert(Name::new("Display"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("display.title"), 40.);
+            let buttons = [
+                (mode_label(display, &locale), DisplayButton::Mode),
+                (vsync_label(display, &locale), DisplayButton::Vsync),
+                (
+                    resolution_label(display, &locale),
+                    DisplayButton::Resolution,
+                ),
+                (locale.get("common.back").to_string(), DisplayButton::Back),
+            ];
+            for (label, button) in buttons {
+                spawn_button(parent, font, &label, button);
+            }
+        });
+}
+
+fn display_buttons(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &DisplayButton, &Children), Changed<Interaction>>,
+    mut texts: Query<&mut Text>,
+    mut state: StateSwitch<AppState>,
+) {
+    for (interaction, button, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let display = &mut settings.display;
+        let label = match button {
+            DisplayButton::Mode => {
+                let all = DisplayMode::ALL;
+                let index = all.iter().position(|m| *m == display.mode).unwrap_or(0);
+                display.mode = all[(index + 1) % all.len()];
+                mode_label(display, &locale)
+            }
+            DisplayButton::Vsync => {
+                display.vsync = !display.vsync;
+                vsync_label(display, &locale)
+            }
+            DisplayButton::Resolution => {
+                // custom sizes of the settings file go back to the first one
+                let index = RESOLUTIONS.iter().position(|r| *r == display.resolution);
+                display.resolution = RESOLUTIONS[index.map_or(0, |i| (i + 1) % RESOLUTIONS.len())];
+                resolution_label(display, &locale)
+            }
+            DisplayButton::Back => {
+                state.set(AppState::Settings);
+                return;
+            }
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
+
+fn save_display(settings: Res<Settings>, profile: Res<Profile>) {
+    settings.save(&profile);
+}
@@ -0,0 +1,106 @@
+use crate::locale::Locale;
+use crate::mswp::{self, MswpContent};
+use crate::persistence::data_dir;
+use crate::thumbnail::picture;
+use crate::toast::ToastEvent;
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::resources::{Board, BoardOptions, InputMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Saves a picture and the text state of the boards in play, and their `.mswp` board files
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, export_boards);
+    }
+}
+
+fn export_boards(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    locale: Res<Locale>,
+    boards: Query<&Board>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    if !input_map.export.just_pressed(&mouse, &keys) {
+        return;
+    }
+    let dir = data_dir().join("exports");
+    if let Err(e) = fs::create_dir_all(&dir) {
+        error!("Failed to create {}: {e}", dir.display());
+        toast_ewr.write(ToastEvent::error(locale.get("toast.export_failed")));
+        return;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut exported = true;
+    for (i, board) in boards.iter().enumerate() {
+        let name = match i {
+            0 => format!("board-{timestamp}"),
+            _ => format!("board-{timestamp}-{i}"),
+        };
+        exported &= export_board(board, dir.join(name));
+    }
+    match exported {
+        true => {
+            toast_ewr.write(ToastEvent::info(locale.get("toast.exported")));
+        }
+        false => {
+            toast_ewr.write(ToastEvent::error(locale.get("toast.export_failed")));
+        }
+    }
+}
+
+// Writes the `.txt` and `.png` files of a board next to `path`, and its board file in the
+// shared directory, returning whether all were written
+fn export_board(board: &Board, path: PathBuf) -> bool {
+    // the seed generates the same board on import
+    let options = BoardOptions {
+        seed: Some(board.seed),
+        preview_rerolls: None,
+        ..board.options.clone()
+    };
+    let shared = match mswp::export(&MswpContent::Board(options), "board") {
+        Ok(path) => {
+            info!("Board file exported to {}", path.display());
+            true
+        }
+        Err(e) => {
+            error!("Failed to write the board file: {e}");
+            false
+        }
+    };
+
+    let text = format!("Seed {}\n{}\n", board.seed, board.game.text_output());
+    let text_path = path.with_extension("txt");
+    let text_written = match fs::write(&text_path, text) {
+        Ok(()) => {
+            info!("Board state exported to {}", text_path.display());
+            true
+        }
+        Err(e) => {
+            error!("Failed to write {}: {e}", text_path.display());
+            false
+        }
+    };
+
+    let png_path = path.with_extension("png");
+    let saved = picture(&board.game).and_then(|p| p.save(&png_path).map_err(|e| e.to_string()));
+    match saved {
+        Ok(()) => {
+            info!("Board picture exported to {}", png_path.display());
+            text_written && shared
+        }
+        Err(e) => {
+            error!("Failed to write {}: {e}", png_path.display());
+            false
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::coop::Coop;
+use crate::demo::Demo;
+use crate::persistence::{self, Profile};
+use crate::protocol::Connection;
+use crate::race::Race;
+use crate::settings::Settings;
+use crate::tutorial::Tutorial;
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::events::BoardCompletedEvent;
+use board_plugin::resources::{
+    Board, BoardPreview, GameTimer, Ghost, Replay, ReplayPlayback, ReplayRecorder,
+};
+use serde::{Deserialize, Serialize};
+
+// Fastest clear of a board, raced in the time trials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BestRun {
+    time: f32,
+    replay: Replay,
+}
+
+// Modes playing the board for someone else than the player alone
+type SharedModes<'w> = (
+    Option<Res<'w, ReplayPlayback>>,
+    Option<Res<'w, Demo>>,
+    Option<Res<'w, Tutorial>>,
+    Option<Res<'w, Race>>,
+    Option<Res<'w, Coop>>,
+    Option<Res<'w, Connection>>,
+);
+
+// Time trials on the seeded boards: the fastest clear of the board without assists is kept, and
+// raced as a ghost the next times, with the time trial setting
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, (start_ghost, save_best_run))
+            .on_exit(AppState::InGame, end_ghost);
+    }
+}
+
+// Best run file of a board, named after its layout so that any seeded board of the same options
+// finds it
+fn best_run_file(board: &Board) -> String {
+    format!("ghost-{:016x}.ron", board.tile_map().layout_hash())
+}
+
+// Is the seeded board played by the player alone?
+fn is_time_trial(board: &Board, board_count: usize, modes: &SharedModes) -> bool {
+    let (playback, demo, tutorial, race, coop, connection) = modes;
+    board.options.seed.is_some()
+        && board_count == 1
+        && playback.is_none()
+        && demo.is_none()
+        && tutorial.is_none()
+        && race.is_none()
+        && coop.is_none()
+        && connection.is_none()
+}
+
+// Loads the best run of a new time trial board as its ghost
+fn start_ghost(
+    mut cmds: Commands,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+    modes: SharedModes,
+    new_boards: Query<&Board, (Added<Board>, Without<BoardPreview>)>,
+    boards: Query<&Board>,
+) {
+    let board = match new_boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    if !settings.time_trial || !is_time_trial(board, boards.iter().count(), &modes) {
+        return;
+    }
+    let best: Option<BestRun> = persistence::load(&profile, &best_run_file(board));
+    if let Some(best) = best {
+        info!("Racing the ghost of a {:.2}s run", best.time);
+        cmds.insert_resource(Ghost::new(best.replay));
+    }
+}
+
+// Keeps the run clearing a time trial board if it's the fastest, whether the ghost is raced or
+// not. Assisted and relaxed runs don't count
+fn save_best_run(
+    profile: Res<Profile>,
+    timer: Res<GameTimer>,
+    modes: SharedModes,
+    boards: Query<(&Board, Option<&ReplayRecorder>)>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+) {
+    if board_completed_evr.read().count() == 0 {
+        return;
+    }
+    let (board, recorder) = match boards.iter().next() {
+        Some((board, Some(recorder))) => (board, recorder),
+        _ => return,
+    };
+    let pure = board.assists.is_pure() && !board.options.relaxed;
+    if !pure || !is_time_trial(board, boards.iter().count(), &modes) {
+        return;
+    }
+    let file = best_run_file(board);
+    let best: Option<BestRun> = persistence::load(&profile, &file);
+    let time = timer.elapsed();
+    if best.is_some_and(|b| b.time <= time) {
+        return;
+    }
+    info!("New best run of the board: {time:.2}s");
+    let best = BestRun {
+        time,
+        replay: recorder.replay.clone(),
+    };
+    persistence::save(&profile, &file, &Some(best));
+}
+
+fn end_ghost(mut cmds: Commands) {
+    cmds.remove_resource::<Ghost>();
+}
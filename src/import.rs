@@ -0,0 +1,98 @@
+use crate::locale::Locale;
+use crate::mswp::{self, MswpContent, MswpError};
+use crate::toast::ToastEvent;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::resources::ReplayPlayback;
+use std::path::PathBuf;
+
+// Files listed on the import screen, the most recently modified ones
+const LISTED_FILES: usize = 8;
+
+#[derive(Debug, Clone, Component)]
+enum ImportButton {
+    Open(PathBuf),
+    Back,
+}
+
+#[derive(Component)]
+struct ImportRoot;
+
+// Import screen, listing the `.mswp` files of the shared directory to pick one: a replay is
+// played back, a board is played
+pub struct ImportPlugin;
+
+impl Plugin for ImportPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Import, setup_import)
+            .on_update(AppState::Import, import_buttons)
+            .on_exit(AppState::Import, cleanup::<ImportRoot>);
+    }
+}
+
+fn setup_import(mut cmds: Commands, ui_assets: Res<UiAssets>, locale: Res<Locale>) {
+    let font = &ui_assets.font;
+    let files = mswp::shared_files();
+    cmds.spawn(screen_root())
+        .insert(ImportRoot)
+        .insert(Name::new("Import"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("import.title"), 40.);
+            if files.is_empty() {
+                let dir = mswp::shared_dir().display().to_string();
+                let message = locale.format("import.none", &[("dir", &dir)]);
+                spawn_text(parent, font, &message, 16.);
+            }
+            for path in files.into_iter().take(LISTED_FILES) {
+                let name = path
+                    .file_name()
+                    .map_or(String::new(), |n| n.to_string_lossy().to_string());
+                spawn_button(parent, font, &name, ImportButton::Open(path));
+            }
+            spawn_button(parent, font, locale.get("common.back"), ImportButton::Back);
+        });
+}
+
+fn import_buttons(
+    mut cmds: Commands,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &ImportButton), Changed<Interaction>>,
+    mut state: StateSwitch<AppState>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let path = match button {
+            ImportButton::Open(path) => path,
+            ImportButton::Back => {
+                state.set(AppState::Menu);
+                continue;
+            }
+        };
+        match mswp::import(path) {
+            Ok(MswpContent::Replay(replay)) => {
+                cmds.insert_resource(replay.options.clone());
+                cmds.insert_resource(ReplayPlayback::new(replay));
+                state.set(AppState::InGame);
+            }
+            Ok(MswpContent::Board(options)) => {
+                cmds.insert_resource(options);
+                state.set(AppState::InGame);
+            }
+            Err(e) => {
+                warn!("Failed to import {}: {e}", path.display());
+                let message = match e {
+                    MswpError::UnknownVersion(version) => {
+                        locale.format("toast.import_unknown_version", &[("version", &version)])
+                    }
+                    _ => locale.get("toast.import_failed").to_string(),
+                };
+                toast_ewr.write(ToastEvent::error(message));
+            }
+        }
+    }
+}
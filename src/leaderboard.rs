@@ -0,0 +1,437 @@
+use crate::config::Config;
+use crate::daily;
+use crate::daily_online::{self, OnlineDailyText};
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::persistence::{self, Profile};
+use crate::settings::Settings;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, TextInput, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::grid::NeighborKernel;
+use board_plugin::resources::{AssistUsage, BoardOptions, ChainReaction};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const LEADERBOARD_FILE: &str = "leaderboard.ron";
+// Number of entries kept per difficulty
+pub const LEADERBOARD_SIZE: usize = 10;
+const MAX_NAME_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    // Completion time in seconds, including the assist penalties
+    pub time: f32,
+    // Seconds of assist penalties included in `time`
+    #[serde(default)]
+    pub penalty: f32,
+    #[serde(default)]
+    pub assists: AssistUsage,
+}
+
+// Score attack entry, ranking by score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub score: u32,
+}
+
+// Is a board of the options ranked on the difficulty? Custom boards, from the command line or the
+// console, treasure hunts, zen games, knight's move numbers, wrapped boards, multi-mine boards and
+// scored chains don't rank. Achievements only count the ranked boards too
+pub fn is_ranked(options: &BoardOptions, difficulty: Difficulty) -> bool {
+    let preset = difficulty.board_options();
+    options.map_size == preset.map_size
+        && options.bombs() == preset.bombs()
+        && options.treasures == 0
+        && !options.relaxed
+        && options.kernel == NeighborKernel::Grid
+        && !options.wrap
+        && options.max_mines == 1
+        && options.chain_reaction != ChainReaction::Scored
+}
+
+// Leaderboard category of a game, the no-flag games and the competitive games ranking apart
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Category {
+    Standard,
+    NoFlags,
+    // Games refusing every assist, never mixed with assisted times
+    Competitive,
+}
+
+impl Category {
+    pub const ALL: [Self; 3] = [Self::Standard, Self::NoFlags, Self::Competitive];
+
+    // Category of the games played with the options, no-flag competitive games being
+    // competitive
+    pub fn of(options: &BoardOptions) -> Self {
+        match (options.competitive, options.no_flags) {
+            (true, _) => Self::Competitive,
+            (false, true) => Self::NoFlags,
+            (false, false) => Self::Standard,
+        }
+    }
+
+    // Locale key of the label
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "leaderboard.standard",
+            Self::NoFlags => "leaderboard.no_flags",
+            Self::Competitive => "leaderboard.competitive",
+        }
+    }
+}
+
+// Local best times for every difficulty and category. Must be used as a resource
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    entries: BTreeMap<Difficulty, Vec<LeaderboardEntry>>,
+    // Entries of the no-flag games
+    #[serde(default)]
+    no_flags: BTreeMap<Difficulty, Vec<LeaderboardEntry>>,
+    // Entries of the competitive games
+    #[serde(default)]
+    competitive: BTreeMap<Difficulty, Vec<LeaderboardEntry>>,
+    // Entries of the score attack games
+    #[serde(default)]
+    scores: BTreeMap<Difficulty, Vec<ScoreEntry>>,
+}
+
+impl Leaderboard {
+    pub fn load(profile: &Profile) -> Self {
+        persistence::load(profile, LEADERBOARD_FILE)
+    }
+
+    pub fn save(&self, profile: &Profile) {
+        persistence::save(profile, LEADERBOARD_FILE, self);
+    }
+
+    fn table_mut(
+        &mut self,
+        category: Category,
+    ) -> &mut BTreeMap<Difficulty, Vec<LeaderboardEntry>> {
+        match category {
+            Category::Standard => &mut self.entries,
+            Category::NoFlags => &mut self.no_flags,
+            Category::Competitive => &mut self.competitive,
+        }
+    }
+
+    // Entries of a difficulty in a category, fastest first
+    pub fn entries(&self, difficulty: Difficulty, category: Category) -> &[LeaderboardEntry] {
+        let table = match category {
+            Category::Standard => &self.entries,
+            Category::NoFlags => &self.no_flags,
+            Category::Competitive => &self.competitive,
+        };
+        table
+            .get(&difficulty)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Would the time enter the leaderboard?
+    pub fn qualifies(&self, difficulty: Difficulty, category: Category, time: f32) -> bool {
+        let entries = self.entries(difficulty, category);
+        entries.len() < LEADERBOARD_SIZE || entries.last().is_none_or(|e| time < e.time)
+    }
+
+    // Inserts an entry at its rank, dropping the entries past the leaderboard size
+    pub fn insert(&mut self, difficulty: Difficulty, category: Category, entry: LeaderboardEntry) {
+        let entries = self.table_mut(category).entry(difficulty).or_default();
+        let rank = entries
+            .iter()
+            .position(|e| entry.time < e.time)
+            .unwrap_or(entries.len());
+        entries.insert(rank, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+    }
+
+    // Score attack entries of a difficulty, highest first
+    pub fn score_entries(&self, difficulty: Difficulty) -> &[ScoreEntry] {
+        self.scores
+            .get(&difficulty)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    // Would the score enter the score attack leaderboard?
+    pub fn score_qualifies(&self, difficulty: Difficulty, score: u32) -> bool {
+        let entries = self.score_entries(difficulty);
+        entries.len() < LEADERBOARD_SIZE || entries.last().is_none_or(|e| score > e.score)
+    }
+
+    // Inserts a score attack entry at its rank, dropping the entries past the leaderboard size
+    pub fn insert_score(&mut self, difficulty: Difficulty, entry: ScoreEntry) {
+        let entries = self.scores.entry(difficulty).or_default();
+        let rank = entries
+            .iter()
+            .position(|e| entry.score > e.score)
+            .unwrap_or(entries.len());
+        entries.insert(rank, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+// Winning time waiting for the player name. Must be used as a resource
+#[derive(Resource, Debug, Clone)]
+pub struct PendingScore {
+    pub difficulty: Difficulty,
+    pub category: Category,
+    // Completion time in seconds, including the assist penalties
+    pub time: f32,
+    pub penalty: f32,
+    pub assists: AssistUsage,
+    // Score of a score attack game, ranking on its own leaderboard instead of the time
+    pub score: Option<u32>,
+}
+
+#[derive(Component)]
+struct NameEntryRoot;
+
+#[derive(Component)]
+struct LeaderboardRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct BackButton;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::NameEntry, setup_name_entry)
+            .on_update(AppState::NameEntry, name_input)
+            .on_exit(AppState::NameEntry, cleanup::<NameEntryRoot>)
+            .on_enter(AppState::Leaderboard, setup_leaderboard)
+            .on_update(AppState::Leaderboard, leaderboard_buttons)
+            .on_exit(AppState::Leaderboard, cleanup::<LeaderboardRoot>);
+    }
+}
+
+fn setup_name_entry(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    pending: Res<PendingScore>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(NameEntryRoot)
+        .insert(Name::new("Name Entry"))
+        .with_children(|parent| {
+            let (headline, score) = match pending.score {
+                Some(score) => (
+                    "leaderboard.new_high_score",
+                    locale.format(
+                        "leaderboard.score_attack_score",
+                        &[
+                            ("difficulty", &pending.difficulty.label(&locale)),
+                            ("score", &score),
+                        ],
+                    ),
+                ),
+                None => (
+                    "leaderboard.new_best",
+                    locale.format(
+                        "leaderboard.score",
+                        &[
+                            (
+                                "difficulty",
+                                &ranking_label(pending.difficulty, pending.category, &locale),
+                            ),
+                            ("time", &format!("{:.2}", pending.time)),
+                        ],
+                    ),
+                ),
+            };
+            spawn_text(parent, font, locale.get(headline), 40.);
+            spawn_text(parent, font, &score, 24.);
+            if !pending.assists.is_pure() {
+                let penalty = format!("{:.0}", pending.penalty);
+                let penalties = locale.format("leaderboard.penalties", &[("penalty", &penalty)]);
+                spawn_text(parent, font, &penalties, 12.);
+            }
+            spawn_text(parent, font, locale.get("leaderboard.enter_name"), 20.);
+            spawn_text(parent, font, "_", 28.).insert(TextInput {
+                max_length: MAX_NAME_LENGTH,
+                ..Default::default()
+            });
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn name_input(
+    mut cmds: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    pending: Res<PendingScore>,
+    mut leaderboard: ResMut<Leaderboard>,
+    profile: Res<Profile>,
+    mut state: StateSwitch<AppState>,
+    inputs: Query<&TextInput>,
+    locale: Res<Locale>,
+) {
+    if !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    let name = match inputs.iter().next().map(|i| i.value.trim()) {
+        None | Some("") => locale.get("leaderboard.anonymous").to_string(),
+        Some(name) => name.to_string(),
+    };
+    match pending.score {
+        Some(score) => leaderboard.insert_score(pending.difficulty, ScoreEntry { name, score }),
+        None => leaderboard.insert(
+            pending.difficulty,
+            pending.category,
+            LeaderboardEntry {
+                name,
+                time: pending.time,
+                penalty: pending.penalty,
+                assists: pending.assists,
+            },
+        ),
+    }
+    leaderboard.save(&profile);
+    cmds.remove_resource::<PendingScore>();
+    state.set(AppState::Leaderboard);
+}
+
+// Difficulty label of a ranking, with the category of the no-flag and competitive games
+fn ranking_label(difficulty: Difficulty, category: Category, locale: &Locale) -> String {
+    let key = match category {
+        Category::Standard => return difficulty.label(locale).to_string(),
+        Category::NoFlags => "leaderboard.no_flags_ranking",
+        Category::Competitive => "leaderboard.competitive_ranking",
+    };
+    locale.format(key, &[("difficulty", &difficulty.label(locale))])
+}
+
+fn setup_leaderboard(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    leaderboard: Res<Leaderboard>,
+    settings: Res<Settings>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(LeaderboardRoot)
+        .insert(Name::new("Leaderboard"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("leaderboard.title"), 40.);
+            for category in Category::ALL {
+                spawn_text(parent, font, locale.get(category.label()), 24.);
+                spawn_rankings(parent, font, &locale, |difficulty| {
+                    time_lines(&leaderboard, difficulty, category, &locale)
+                });
+            }
+            spawn_text(parent, font, locale.get("leaderboard.score_attack"), 24.);
+            spawn_rankings(parent, font, &locale, |difficulty| {
+                score_lines(&leaderboard, difficulty, &locale)
+            });
+            // filled once fetched
+            if daily_online::endpoint(&settings, &config).is_some() {
+                let date = daily::date(daily::today());
+                let title = locale.format("leaderboard.online_daily", &[("date", &date)]);
+                spawn_text(parent, font, &title, 24.);
+                spawn_text(parent, font, locale.get("leaderboard.online_loading"), 12.)
+                    .insert(OnlineDailyText);
+            }
+            spawn_text(parent, font, locale.get("leaderboard.assisted"), 10.);
+            spawn_button(parent, font, locale.get("common.back"), BackButton);
+        });
+}
+
+// Ranked times of a difficulty in a category
+fn time_lines(
+    leaderboard: &Leaderboard,
+    difficulty: Difficulty,
+    category: Category,
+    locale: &Locale,
+) -> Vec<String> {
+    let entries = leaderboard.entries(difficulty, category);
+    entries
+        .iter()
+        .enumerate()
+        .map(|(rank, entry)| {
+            // assisted runs are marked with a star
+            let assisted = match entry.assists.is_pure() {
+                true => "",
+                false => "*",
+            };
+            let line = locale.format(
+                "leaderboard.entry",
+                &[
+                    ("rank", &(rank + 1)),
+                    ("name", &entry.name),
+                    ("time", &format!("{:.2}", entry.time)),
+                ],
+            );
+            format!("{line}{assisted}")
+        })
+        .collect()
+}
+
+// Ranked score attack scores of a difficulty
+fn score_lines(leaderboard: &Leaderboard, difficulty: Difficulty, locale: &Locale) -> Vec<String> {
+    let entries = leaderboard.score_entries(difficulty);
+    entries
+        .iter()
+        .enumerate()
+        .map(|(rank, entry)| {
+            locale.format(
+                "leaderboard.score_entry",
+                &[
+                    ("rank", &(rank + 1)),
+                    ("name", &entry.name),
+                    ("score", &entry.score),
+                ],
+            )
+        })
+        .collect()
+}
+
+// Rankings of a leaderboard, one column per difficulty
+fn spawn_rankings(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    locale: &Locale,
+    lines: impl Fn(Difficulty) -> Vec<String>,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::FlexStart,
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for difficulty in Difficulty::ALL {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(10.)),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        spawn_text(parent, font, difficulty.label(locale), 20.);
+                        for line in lines(difficulty) {
+                            spawn_text(parent, font, &line, 12.);
+                        }
+                    });
+            }
+        });
+}
+
+fn leaderboard_buttons(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Menu);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::ui::{cleanup, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::resources::Board;
+
+// Side of the hearts, in pixels
+const HEART_SIZE: f32 = 24.;
+// Tint of the hearts of the spent lives
+const SPENT_HEART: Color = Color::srgba(0.3, 0.3, 0.3, 0.6);
+
+#[derive(Component)]
+struct HeartsRoot;
+
+// Hearts of the lives of the board, the spent lives dimmed. Boards without lives show none
+pub struct LivesPlugin;
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, show_hearts)
+            .on_exit(AppState::InGame, cleanup::<HeartsRoot>);
+    }
+}
+
+// Respawns the hearts when a life is granted or spent
+fn show_hearts(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    boards: Query<&Board>,
+    roots: Query<Entity, With<HeartsRoot>>,
+    // lives left and spent of the shown hearts
+    mut shown: Local<(u8, u32)>,
+) {
+    let lives = match boards.iter().next() {
+        Some(b) => (b.lives, b.assists.forgiven_mistakes),
+        None => return,
+    };
+    let root = roots.iter().next();
+    if lives == *shown && root.is_some() {
+        return;
+    }
+    if let Some(root) = root {
+        cmds.entity(root).despawn();
+    }
+    *shown = lives;
+    let (left, spent) = lives;
+    if left == 0 && spent == 0 {
+        return;
+    }
+
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            left: Val::Px(10.),
+            padding: UiRect::all(Val::Px(4.)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(HeartsRoot)
+    .insert(Name::new("Hearts"))
+    .with_children(|parent| {
+        let hearts = (0..left)
+            .map(|_| Color::WHITE)
+            .chain((0..spent).map(|_| SPENT_HEART));
+        for color in hearts {
+            parent.spawn((
+                ImageNode::new(ui_assets.heart.clone()),
+                Node {
+                    width: Val::Px(HEART_SIZE),
+                    height: Val::Px(HEART_SIZE),
+                    margin: UiRect::all(Val::Px(2.)),
+                    ..Default::default()
+                },
+                BackgroundColor(color),
+            ));
+        }
+    });
+}
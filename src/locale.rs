@@ -0,0 +1,96 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+
+// English strings embedded in the binary, the fallback of every missing translation
+const ENGLISH: &str = include_str!("../assets/locales/en.ron");
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// Directory of the `<language>.ron` locale files, translations are added without code changes
+fn locales_dir() -> PathBuf {
+    FileAssetReader::get_base_path()
+        .join("assets")
+        .join("locales")
+}
+
+fn parse(content: &str) -> Result<HashMap<String, String>, ron::Error> {
+    ron::from_str(content)
+}
+
+// User facing strings of the current language. Must be used as a resource
+#[derive(Resource, Debug, Clone)]
+pub struct Locale {
+    language: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    // Loads a language, falling back to English when its file is missing or invalid
+    pub fn load(language: &str) -> Self {
+        let fallback = parse(ENGLISH).expect("Invalid embedded English locale");
+        let strings = match language {
+            DEFAULT_LANGUAGE => HashMap::new(),
+            _ => {
+                let path = locales_dir().join(format!("{language}.ron"));
+                match fs::read_to_string(&path).map(|content| parse(&content)) {
+                    Ok(Ok(strings)) => strings,
+                    Ok(Err(e)) => {
+                        error!("Invalid locale file {}: {e}", path.display());
+                        HashMap::new()
+                    }
+                    Err(e) => {
+                        warn!("Failed to read the locale file {}: {e}", path.display());
+                        HashMap::new()
+                    }
+                }
+            }
+        };
+        Self {
+            language: language.to_string(),
+            strings,
+            fallback,
+        }
+    }
+
+    // Languages of the locale files, English first
+    pub fn languages() -> Vec<String> {
+        let mut languages: Vec<String> = fs::read_dir(locales_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let language = path.file_stem()?.to_str()?;
+                (path.extension()? == "ron" && language != DEFAULT_LANGUAGE)
+                    .then(|| language.to_string())
+            })
+            .collect();
+        languages.sort();
+        languages.insert(0, DEFAULT_LANGUAGE.to_string());
+        languages
+    }
+
+    // getter for `language`
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    // String of a key, in English when untranslated, or the key itself when unknown
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, |s| s.as_str())
+    }
+
+    // String of a key with its `{name}` placeholders replaced by the named values
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+}
@@ -1,45 +1,427 @@
-use bevy::{input::system::exit_on_esc_system, prelude::*};
-use board_plugin::{resources::BoardOptions, BoardPlugin};
+// Defaults are written as `impl Default` blocks, and the system queries spell out their filters
+#![allow(clippy::derivable_impls, clippy::type_complexity)]
 
+mod abandon;
+mod achievements;
+mod announce;
+mod blitz;
+mod campaign;
+mod changelog;
+mod cli;
+mod config;
 #[cfg(feature = "debug")]
-use bevy_inspector_egui::WorldInspectorPlugin;
+mod console;
+mod controls;
+mod coop;
+mod cursor;
+mod custom;
+mod daily;
+mod daily_online;
+mod demo;
+mod difficulty;
+mod discord;
+mod display;
+mod export;
+mod ghost;
+mod import;
+mod leaderboard;
+mod lives;
+mod locale;
+mod menu;
+mod minimap;
+mod mswp;
+mod online;
+mod overlay;
+mod persistence;
+mod pregen;
+mod preview;
+mod profile;
+mod protocol;
+mod puzzle;
+mod race;
+mod replay;
+mod results;
+mod score_attack;
+mod settings;
+mod stats;
+mod summary;
+mod thumbnail;
+mod toast;
+mod treasure;
+mod tutorial;
+mod ui;
+
+use abandon::AbandonPlugin;
+use achievements::{Achievements, AchievementsPlugin};
+use announce::AnnouncePlugin;
+use bevy::{prelude::*, ui::UiSystems};
+use blitz::{Blitz, BlitzPlugin};
+use board_plugin::{
+    events::{BoardCompletedEvent, BombExplosionEvent},
+    resources::{Board, GameTimer, ReplayPlayback},
+    BoardPlugin, EXPLOSION_DURATION,
+};
+use campaign::{CampaignPlugin, CampaignSave};
+use changelog::ChangelogPlugin;
+use cli::Cli;
+use config::Config;
+use controls::ControlsPlugin;
+use coop::{Coop, CoopPlugin};
+use cursor::CursorPlugin;
+use custom::CustomBoardPlugin;
+use daily::DailyPlugin;
+use daily_online::DailyOnlinePlugin;
+use demo::{Demo, DemoPlugin};
+use difficulty::Difficulty;
+use discord::DiscordPlugin;
+use display::DisplayPlugin;
+use export::ExportPlugin;
+use ghost::GhostPlugin;
+use import::ImportPlugin;
+use leaderboard::{is_ranked, Category, Leaderboard, LeaderboardPlugin, PendingScore};
+use lives::LivesPlugin;
+use locale::Locale;
+use menu::MenuPlugin;
+use minimap::MinimapPlugin;
+use online::OnlinePlugin;
+use overlay::StreamOverlayPlugin;
+use pregen::PregenPlugin;
+use preview::PreviewPlugin;
+use profile::{ProfilePlugin, Profiles};
+use protocol::Connection;
+use puzzle::{PuzzlePlugin, PuzzleRun};
+use race::{Race, RacePlugin};
+use replay::ReplayPlugin;
+use results::ResultsPlugin;
+use score_attack::{ScoreAttack, ScoreAttackPlugin};
+use settings::{Settings, SettingsPlugin};
+use stats::Stats;
+use summary::SummaryPlugin;
+use thumbnail::ThumbnailPlugin;
+use toast::{ToastEvent, ToastPlugin};
+use treasure::TreasurePlugin;
+use tutorial::{Tutorial, TutorialPlugin};
+use ui::{UiAssets, UiTheme};
+
+#[cfg(feature = "debug")]
+use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
+use board_plugin::engine::{self, StateSwitch, StateSystems};
+#[cfg(feature = "debug")]
+use console::ConsolePlugin;
+
+#[derive(States, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum AppState {
+    Menu,
+    InGame,
+    NameEntry,
+    Leaderboard,
+    Achievements,
+    WhatsNew,
+    ProfileSelect,
+    Settings,
+    Results,
+    // Pushed over `InGame` once a plain game is won or lost
+    Summary,
+    Online,
+    Campaign,
+    Puzzles,
+    Controls,
+    Display,
+    CustomBoard,
+    Import,
+    // Pushed over `InGame`, the game is resumed by popping it
+    Paused,
+    // Left right away for a new `InGame` board
+    Restarting,
+    // Pushed over `InGame` or `Paused`, asking before quitting or restarting a game in progress
+    Abandon,
+}
+
+// Delay before leaving a lost game, letting the explosion play out. Must be used as a resource
+#[derive(Resource)]
+struct LeaveGame(Timer);
+
+impl LeaveGame {
+    // Leaves once the explosion and the chain reaction of `board` are over
+    fn after_explosion(board: &Board) -> Self {
+        let delay = EXPLOSION_DURATION + board.chain_duration();
+        Self(Timer::from_seconds(delay, TimerMode::Once))
+    }
+}
 
 fn main() {
+    let cli = Cli::parse();
     let mut app = App::new();
 
+    // load the last used profile, letting the player pick one on shared computers
+    let mut profiles = Profiles::load();
+    let profile = profiles.startup_profile();
+    let mut settings = Settings::load(&profile);
+    cli.apply_settings(&mut settings);
+    let locale = Locale::load(&settings.language);
+
+    // the board flags skip the menus
+    let board_options = match cli.starts_game() {
+        true => match cli.board_options(&settings) {
+            Ok(options) => Some(options),
+            Err(e) => {
+                eprintln!("Invalid board: {e}");
+                std::process::exit(2);
+            }
+        },
+        false => None,
+    };
+
     // window setup
-    app.insert_resource(WindowDescriptor {
-        title: "Mine Sweeper!".to_string(),
-        width: 700.,
-        height: 800.,
+    let mut window = Window {
+        title: locale.get("window.title").to_string(),
         ..Default::default()
-    });
-
-    app.add_plugins(DefaultPlugins); // Bevy default plugins
+    };
+    settings.display.apply_to(&mut window);
 
-    app.add_startup_system(camera_setup); // setup cameras
+    app.add_plugins(DefaultPlugins.set(engine::window_plugin(window))); // Bevy default plugins
 
-    app.add_system(exit_on_esc_system); // exit when escape key is pressed
+    app.add_systems(Startup, camera_setup); // setup cameras
 
     // Debug hierarchy inspector
     #[cfg(feature = "debug")]
-    app.add_plugin(WorldInspectorPlugin::new());
+    app.add_plugins((EguiPlugin::default(), WorldInspectorPlugin::new()));
+    // Debug console, after the inspector setting egui up
+    #[cfg(feature = "debug")]
+    app.add_plugins(ConsolePlugin);
 
-    app.add_plugin(BoardPlugin);
+    // the tutorial greets the new players
+    let tutorial =
+        board_options.is_none() && profiles.names.len() <= 1 && tutorial::is_pending(&settings);
+    app.insert_stacked_state(if board_options.is_some() {
+        AppState::InGame
+    } else if profiles.names.len() > 1 {
+        AppState::ProfileSelect
+    } else if tutorial {
+        AppState::InGame
+    } else if changelog::has_news(&settings) {
+        // show the changelog once per new version
+        AppState::WhatsNew
+    } else {
+        AppState::Menu
+    });
+    app.insert_resource(Leaderboard::load(&profile));
+    app.insert_resource(Stats::load(&profile));
+    app.insert_resource(Achievements::load(&profile));
+    if tutorial {
+        app.insert_resource(tutorial::tutorial_options(&settings));
+        app.insert_resource(Tutorial::default());
+    }
+    app.insert_resource(settings);
+    app.insert_resource(locale);
+    app.insert_resource(profile);
+    app.insert_resource(profiles);
+    app.insert_resource(cli.difficulty());
+    if let Some(options) = board_options {
+        app.insert_resource(options);
+    }
+    app.insert_resource(Config::load());
+    app.init_resource::<UiAssets>();
+    app.init_resource::<UiTheme>();
+    app.add_systems(Update, ui::button_colors);
+    // themed before the new nodes are laid out
+    app.add_systems(PostUpdate, ui::apply_theme.before(UiSystems::Prepare));
+    app.add_systems(Update, ui::text_input);
 
-    // Board plugin options
-    app.insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 40,
-        tile_padding: 3.0,
-        ..Default::default()
+    app.add_plugins(BoardPlugin {
+        running_state: AppState::InGame,
     });
+    app.add_plugins(ToastPlugin);
+    // exit when escape key is pressed, asking first in the middle of a game
+    app.add_plugins(AbandonPlugin);
+    app.add_plugins(MenuPlugin);
+    app.add_plugins(LeaderboardPlugin);
+    app.add_plugins(AchievementsPlugin);
+    app.add_plugins(ChangelogPlugin);
+    app.add_plugins(ProfilePlugin);
+    app.add_plugins(ReplayPlugin);
+    app.add_plugins(GhostPlugin);
+    app.add_plugins(SettingsPlugin);
+    app.add_plugins(CursorPlugin);
+    app.add_plugins(PreviewPlugin);
+    app.add_plugins(ThumbnailPlugin);
+    app.add_plugins(MinimapPlugin);
+    app.add_plugins(PregenPlugin);
+    app.add_plugins(ResultsPlugin);
+    app.add_plugins(SummaryPlugin);
+    app.add_plugins(RacePlugin);
+    app.add_plugins(CoopPlugin);
+    app.add_plugins(TreasurePlugin);
+    app.add_plugins(ScoreAttackPlugin);
+    app.add_plugins(LivesPlugin);
+    app.add_plugins(OnlinePlugin);
+    app.add_plugins(DailyPlugin);
+    app.add_plugins(DailyOnlinePlugin);
+    app.add_plugins(BlitzPlugin);
+    app.add_plugins(DemoPlugin);
+    app.add_plugins(TutorialPlugin);
+    app.add_plugins(CampaignPlugin);
+    app.add_plugins(PuzzlePlugin);
+    app.add_plugins(AnnouncePlugin);
+    app.add_plugins(DiscordPlugin);
+    app.add_plugins(ControlsPlugin);
+    app.add_plugins(DisplayPlugin);
+    app.add_plugins(CustomBoardPlugin);
+    app.add_plugins(ExportPlugin);
+    app.add_plugins(ImportPlugin);
+    app.add_plugins(StreamOverlayPlugin);
+
+    // Game session
+    app.on_update(AppState::InGame, (game_over, leave_game, give_up));
+    app.on_exit(AppState::InGame, end_game);
 
     // run the game
     app.run();
 }
 
 fn camera_setup(mut cmds: Commands) {
-    // 2d orthographic camera
-    cmds.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // 2d orthographic camera, drawing the UI as well
+    engine::spawn_2d_camera(&mut cmds);
+}
+
+fn end_game(mut cmds: Commands) {
+    cmds.remove_resource::<LeaveGame>();
+}
+
+// Leaves the game on win or loss for its summary, keeping a new best time for the name entry
+#[allow(clippy::too_many_arguments)]
+fn game_over(
+    mut cmds: Commands,
+    timer: Res<GameTimer>,
+    boards: Query<&Board>,
+    difficulty: Res<Difficulty>,
+    leaderboard: Res<Leaderboard>,
+    locale: Res<Locale>,
+    playback: Option<Res<ReplayPlayback>>,
+    score_attack: Option<Res<ScoreAttack>>,
+    modes: (
+        Option<Res<Race>>,
+        Option<Res<Coop>>,
+        Option<Res<Connection>>,
+        Option<Res<Blitz>>,
+        Option<Res<CampaignSave>>,
+        Option<Res<PuzzleRun>>,
+        Option<Res<Demo>>,
+        Option<Res<Tutorial>>,
+    ),
+    mut state: StateSwitch<AppState>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    // races, co-op games, blitz runs, campaign levels, puzzles, demos and the tutorial are ended
+    // by their own plugins
+    let (race, coop, connection, blitz, campaign, puzzle, demo, tutorial) = modes;
+    if race.is_some()
+        || coop.is_some()
+        || connection.is_some()
+        || blitz.is_some()
+        || campaign.is_some()
+        || puzzle.is_some()
+        || demo.is_some()
+        || tutorial.is_some()
+    {
+        return;
+    }
+    if let Some(playback) = playback {
+        // replays don't count, and go on after explosions if the player undid them
+        let explosion = bomb_explosion_evr
+            .read()
+            .last()
+            .filter(|_| playback.is_finished());
+        if board_completed_evr.read().count() > 0 {
+            info!("Replay over");
+            state.set(AppState::Menu);
+        } else if let Some(board) = explosion.and_then(|e| boards.get(e.board).ok()) {
+            info!("Replay over");
+            cmds.insert_resource(LeaveGame::after_explosion(board));
+        }
+    } else if let Some(event) = bomb_explosion_evr.read().last() {
+        let board = match boards.get(event.board) {
+            Ok(b) => b,
+            Err(_) => return,
+        };
+        if board.can_undo(board.options.undos) {
+            info!("Boom! Undo the last move, or press Enter to give up");
+        } else {
+            info!("Game lost");
+            cmds.insert_resource(LeaveGame::after_explosion(board));
+        }
+    } else if let Some(event) = board_completed_evr.read().last() {
+        // the board can't be completed before it is locked in
+        let board = match boards.get(event.board) {
+            Ok(b) if timer.is_started() => b,
+            _ => return,
+        };
+        // assists add time penalties, so assisted and pure runs rank consistently
+        let penalty = board.options.scoring.time_penalty(&board.assists);
+        let elapsed = timer.elapsed() + penalty;
+        info!(
+            "Game won in {elapsed:.2}s ({penalty}s of penalties, {:?})",
+            board.assists
+        );
+        // score attack games rank by score
+        let ranked = score_attack.is_none() && is_ranked(&board.options, *difficulty);
+        // no-flag and competitive games rank apart
+        let category = Category::of(&board.options);
+        let best = leaderboard.entries(*difficulty, category).first();
+        if ranked && best.is_none_or(|b| elapsed < b.time) {
+            let message = locale.format("toast.best_time", &[("time", &format!("{elapsed:.2}"))]);
+            toast_ewr.write(ToastEvent::success(message));
+        }
+        if ranked && leaderboard.qualifies(*difficulty, category, elapsed) {
+            cmds.insert_resource(PendingScore {
+                difficulty: *difficulty,
+                category,
+                time: elapsed,
+                penalty,
+                assists: board.assists,
+                score: None,
+            });
+        }
+        state.push(AppState::Summary);
+    }
+}
+
+// Shows the summary of a lost game, replays go back to the menu
+fn leave_lost_game(playback: bool, state: &mut StateSwitch<AppState>) {
+    // the player may have given up in the meantime, leaving the switch undone
+    match playback {
+        true => state.set(AppState::Menu),
+        false => state.push(AppState::Summary),
+    };
+}
+
+// Leaves the game once the delay of a lost game is over
+fn leave_game(
+    time: Res<Time>,
+    leave: Option<ResMut<LeaveGame>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut state: StateSwitch<AppState>,
+) {
+    let mut leave = match leave {
+        Some(l) => l,
+        None => return,
+    };
+    if leave.0.tick(time.delta()).just_finished() {
+        leave_lost_game(playback.is_some(), &mut state);
+    }
+}
+
+// Leaves an exploded board on Enter instead of undoing the last move
+fn give_up(
+    keys: Res<ButtonInput<KeyCode>>,
+    boards: Query<&Board>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut state: StateSwitch<AppState>,
+) {
+    if boards.iter().any(|b| b.game.is_exploded()) && keys.just_pressed(KeyCode::Enter) {
+        info!("Game lost");
+        leave_lost_game(playback.is_some(), &mut state);
+    }
 }
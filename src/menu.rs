@@ -0,0 +1,396 @@
+use crate::blitz::{self, Blitz};
+use crate::campaign::CampaignSave;
+use crate::config::Config;
+use crate::coop::{self, Coop};
+use crate::daily::{self, DailyChallenge, DAILY_DIFFICULTY};
+use crate::demo::{self, Demo};
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::mswp::{self, MswpContent};
+use crate::persistence::Profile;
+use crate::pregen::BoardCache;
+use crate::race;
+use crate::replay;
+use crate::score_attack::{self, ScoreAttack};
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::toast::ToastEvent;
+use crate::treasure::{self, TreasureHunt};
+use crate::tutorial::{self, Tutorial};
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use board_plugin::engine::{self, PrimaryWindowQuery, StateSwitch, StateSystems};
+use board_plugin::grid::{GridKind, NeighborKernel};
+use board_plugin::resources::{
+    Animations, BoardOptions, ChainReaction, ExplosionEffects, Generation, ReplayPlayback,
+};
+use rand::{thread_rng, Rng};
+
+// Seed rerolls allowed by the board preview
+pub const PREVIEW_REROLLS: u8 = 3;
+// Undos allowed per game in casual mode
+const CASUAL_UNDOS: u8 = 3;
+// Most mines stacked on a bomb of the multi-mine boards
+const MULTI_MINES: u8 = 3;
+
+// Main menu buttons
+#[derive(Debug, Copy, Clone, Component)]
+pub enum MenuButton {
+    Play(Difficulty),
+    Daily,
+    Blitz,
+    Campaign,
+    Puzzles,
+    Race,
+    Coop,
+    Treasure,
+    ScoreAttack,
+    Demo,
+    Tutorial,
+    Online,
+    Leaderboard,
+    Achievements,
+    Replay,
+    ExportReplay,
+    Import,
+    WhatsNew,
+    Settings,
+    Profile,
+    Quit,
+}
+
+#[derive(Component)]
+struct MenuRoot;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Menu, setup_menu)
+            .on_update(AppState::Menu, menu_buttons)
+            .on_exit(AppState::Menu, cleanup::<MenuRoot>);
+    }
+}
+
+fn setup_menu(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    profile: Res<Profile>,
+    difficulty: Res<Difficulty>,
+    config: Res<Config>,
+    stats: Res<Stats>,
+    locale: Res<Locale>,
+) {
+    let today = daily::today();
+    let daily_label = match stats.daily.is_completed(today) {
+        true => locale.format("menu.daily_done", &[("days", &stats.daily.streak)]),
+        false => match stats.daily.current_streak(today) {
+            0 => locale.get("menu.daily").to_string(),
+            streak => locale.format("menu.daily_streak", &[("days", &streak)]),
+        },
+    };
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(MenuRoot)
+        .insert(Name::new("Main Menu"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("menu.title"), 40.);
+            for difficulty in Difficulty::ALL {
+                spawn_button(
+                    parent,
+                    font,
+                    difficulty.label(&locale),
+                    MenuButton::Play(difficulty),
+                );
+            }
+            spawn_button(parent, font, &daily_label, MenuButton::Daily);
+            spawn_button(parent, font, locale.get("menu.blitz"), MenuButton::Blitz);
+            let campaign = CampaignSave::load(&profile);
+            let campaign_label = match campaign.level {
+                1 => locale.get("menu.campaign").to_string(),
+                level => locale.format("menu.campaign_level", &[("level", &level)]),
+            };
+            spawn_button(parent, font, &campaign_label, MenuButton::Campaign);
+            spawn_button(
+                parent,
+                font,
+                locale.get("menu.puzzles"),
+                MenuButton::Puzzles,
+            );
+            // races, co-op games, treasure hunts, score attacks and demos use the difficulty of the
+            // last game
+            let race_label =
+                locale.format("menu.race", &[("difficulty", &difficulty.label(&locale))]);
+            spawn_button(parent, font, &race_label, MenuButton::Race);
+            let coop_label =
+                locale.format("menu.coop", &[("difficulty", &difficulty.label(&locale))]);
+            spawn_button(parent, font, &coop_label, MenuButton::Coop);
+            let treasure_label = locale.format(
+                "menu.treasure",
+                &[("difficulty", &difficulty.label(&locale))],
+            );
+            spawn_button(parent, font, &treasure_label, MenuButton::Treasure);
+            let score_attack_label = locale.format(
+                "menu.score_attack",
+                &[("difficulty", &difficulty.label(&locale))],
+            );
+            spawn_button(parent, font, &score_attack_label, MenuButton::ScoreAttack);
+            let demo_label =
+                locale.format("menu.demo", &[("difficulty", &difficulty.label(&locale))]);
+            spawn_button(parent, font, &demo_label, MenuButton::Demo);
+            if config.network_allowed() {
+                spawn_button(parent, font, locale.get("menu.online"), MenuButton::Online);
+            }
+            let buttons = [
+                ("menu.tutorial", MenuButton::Tutorial),
+                ("menu.leaderboard", MenuButton::Leaderboard),
+                ("menu.achievements", MenuButton::Achievements),
+                ("menu.replay", MenuButton::Replay),
+                ("menu.export_replay", MenuButton::ExportReplay),
+                ("menu.import", MenuButton::Import),
+                ("menu.whats_new", MenuButton::WhatsNew),
+                ("menu.settings", MenuButton::Settings),
+            ];
+            for (key, button) in buttons {
+                spawn_button(parent, font, locale.get(key), button);
+            }
+            let profile_label = locale.format("menu.profile", &[("name", &profile.name())]);
+            spawn_button(parent, font, &profile_label, MenuButton::Profile);
+            spawn_button(parent, font, locale.get("menu.quit"), MenuButton::Quit);
+        });
+}
+
+// Options of a new game of the given difficulty
+pub fn game_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        grid: match settings.hex_grid {
+            true => GridKind::Hex,
+            false => GridKind::Square,
+        },
+        kernel: match settings.knight_moves {
+            true => NeighborKernel::Knight,
+            false => NeighborKernel::Grid,
+        },
+        // the hexagon rows only wrap in even counts
+        wrap: settings.wrap_edges
+            && (!settings.hex_grid || difficulty.board_options().map_size.1.is_multiple_of(2)),
+        max_mines: match settings.multi_mines {
+            true => MULTI_MINES,
+            false => 1,
+        },
+        safe_start: settings.safe_start,
+        // no guess boards come first, their layout can't be rearranged. The solver only finds
+        // the safe tiles of multi-mine boards, too few to clear them
+        generation: match (
+            settings.no_guess && !settings.multi_mines,
+            settings.connected,
+        ) {
+            (true, _) => Generation::NoGuess,
+            (false, true) => Generation::Connected,
+            (false, false) => Generation::Uniform,
+        },
+        auto_flag: settings.auto_flag,
+        strict_flags: settings.strict_flags,
+        no_flags: settings.no_flags,
+        preview_rerolls: settings.board_preview.then_some(PREVIEW_REROLLS),
+        undos: if settings.casual { CASUAL_UNDOS } else { 0 },
+        // zen games flag every bomb, the lives would go unused, and competitive games forgive
+        // no mistake
+        lives: if settings.casual && !settings.zen && !settings.competitive {
+            settings.casual_lives
+        } else {
+            0
+        },
+        relaxed: settings.zen && !settings.competitive,
+        // the board refuses the other assists of competitive games
+        competitive: settings.competitive,
+        confirm_last_guess: settings.confirm_last_guess,
+        explosion: match (
+            settings.explosion_effects && !settings.zen,
+            settings.reduced_motion,
+        ) {
+            (true, false) => ExplosionEffects::default(),
+            // the burst without the shake
+            (true, true) => ExplosionEffects {
+                shake: 0.,
+                ..Default::default()
+            },
+            (false, _) => ExplosionEffects::NONE,
+        },
+        // the scoring only counts with the chains on
+        chain_reaction: match (settings.chain_reaction, settings.chain_scoring) {
+            (false, _) => ChainReaction::Off,
+            (true, false) => ChainReaction::Ripple,
+            (true, true) => ChainReaction::Scored,
+        },
+        animations: match settings.reduced_motion {
+            true => Animations::REDUCED,
+            false => Animations::default(),
+        },
+        ..difficulty.board_options()
+    }
+}
+
+// Options of a versus game: a shared seed, and no assist changing the board or the rules
+pub fn versus_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        // the same seed generates the same board, opening included
+        seed: Some(thread_rng().gen()),
+        preview_rerolls: None,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        confirm_last_guess: false,
+        ..game_options(difficulty, settings)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn menu_buttons(
+    mut cmds: Commands,
+    settings: Res<Settings>,
+    difficulty: Res<Difficulty>,
+    windows: PrimaryWindowQuery,
+    mut board_cache: ResMut<BoardCache>,
+    profile: Res<Profile>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut state: StateSwitch<AppState>,
+    mut exit: MessageWriter<AppExit>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Play(difficulty) => {
+                let options = game_options(*difficulty, &settings);
+                if let Some(board) = board_cache.take(&options) {
+                    cmds.insert_resource(board);
+                }
+                cmds.insert_resource(options);
+                cmds.insert_resource(*difficulty);
+                state.set(AppState::InGame);
+            }
+            MenuButton::Daily => {
+                // replaying a completed challenge doesn't extend the streak
+                let day = daily::today();
+                info!("Daily challenge of {}", daily::date(day));
+                cmds.insert_resource(daily::daily_options(day, &settings));
+                cmds.insert_resource(DailyChallenge { day });
+                cmds.insert_resource(DAILY_DIFFICULTY);
+                state.set(AppState::InGame);
+            }
+            MenuButton::Blitz => {
+                cmds.insert_resource(blitz::blitz_options(&settings));
+                cmds.insert_resource(Blitz::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::Campaign => {
+                cmds.insert_resource(CampaignSave::load(&profile));
+                state.set(AppState::Campaign);
+            }
+            MenuButton::Puzzles => {
+                state.set(AppState::Puzzles);
+            }
+            MenuButton::Race => {
+                // the window size of the settings stands in for a missing window
+                let (width, height) = settings.display.resolution;
+                let window = windows
+                    .single()
+                    .map_or(Vec2::new(width as f32, height as f32), engine::window_size);
+                let (options, race) = race::race_options(*difficulty, &settings, window);
+                cmds.insert_resource(options);
+                cmds.insert_resource(race);
+                state.set(AppState::InGame);
+            }
+            MenuButton::Coop => {
+                cmds.insert_resource(coop::coop_options(*difficulty, &settings));
+                cmds.insert_resource(Coop::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::Treasure => {
+                cmds.insert_resource(treasure::treasure_options(*difficulty, &settings));
+                cmds.insert_resource(TreasureHunt::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::ScoreAttack => {
+                let options = score_attack::score_attack_options(*difficulty, &settings);
+                cmds.insert_resource(options);
+                cmds.insert_resource(ScoreAttack::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::Demo => {
+                cmds.insert_resource(demo::demo_options(*difficulty, &settings));
+                cmds.insert_resource(Demo::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::Tutorial => {
+                cmds.insert_resource(tutorial::tutorial_options(&settings));
+                cmds.insert_resource(Tutorial::default());
+                state.set(AppState::InGame);
+            }
+            MenuButton::Online => {
+                state.set(AppState::Online);
+            }
+            MenuButton::Leaderboard => {
+                state.set(AppState::Leaderboard);
+            }
+            MenuButton::Achievements => {
+                state.set(AppState::Achievements);
+            }
+            MenuButton::Replay => match replay::load_last_replay(&profile) {
+                Some(replay) => {
+                    // the replay options regenerate the same board
+                    cmds.insert_resource(replay.options.clone());
+                    cmds.insert_resource(ReplayPlayback::new(replay));
+                    state.set(AppState::InGame);
+                }
+                None => {
+                    warn!("No replay to watch");
+                    toast_ewr.write(ToastEvent::error(locale.get("toast.no_replay")));
+                }
+            },
+            MenuButton::ExportReplay => {
+                let replay = match replay::load_last_replay(&profile) {
+                    Some(r) => r,
+                    None => {
+                        warn!("No replay to export");
+                        toast_ewr.write(ToastEvent::error(locale.get("toast.no_replay")));
+                        continue;
+                    }
+                };
+                match mswp::export(&MswpContent::Replay(replay), "replay") {
+                    Ok(path) => {
+                        info!("Replay exported to {}", path.display());
+                        let path = path.display().to_string();
+                        let message = locale.format("toast.replay_exported", &[("path", &path)]);
+                        toast_ewr.write(ToastEvent::info(message));
+                    }
+                    Err(e) => {
+                        error!("Failed to export the replay: {e}");
+                        toast_ewr.write(ToastEvent::error(locale.get("toast.export_failed")));
+                    }
+                }
+            }
+            MenuButton::Import => {
+                state.set(AppState::Import);
+            }
+            MenuButton::WhatsNew => {
+                state.set(AppState::WhatsNew);
+            }
+            MenuButton::Settings => {
+                state.set(AppState::Settings);
+            }
+            MenuButton::Profile => {
+                state.set(AppState::ProfileSelect);
+            }
+            MenuButton::Quit => {
+                exit.write(AppExit::Success);
+            }
+        }
+    }
+}
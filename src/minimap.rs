@@ -0,0 +1,312 @@
+use crate::ui::cleanup;
+use crate::AppState;
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::{ButtonState, InputSystems};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use board_plugin::components::CameraFit;
+use board_plugin::engine::{self, Is2dCamera, PrimaryWindowQuery, StateSystems};
+use board_plugin::resources::{Board, BoardAssets, TileState};
+use board_plugin::screen_space::ScreenSpace;
+use board_plugin::ScreenShake;
+
+// Minimap length along the longest side of the board, in pixels
+const MINIMAP_SIZE: f32 = 160.;
+// Camera zoom of a key press
+const ZOOM_STEP: f32 = 1.25;
+// Closest camera zoom, as the scale of its projection
+const MIN_CAMERA_SCALE: f32 = 0.2;
+
+// 2D cameras, with their view
+type Cameras<'w, 's> = Query<'w, 's, (&'static GlobalTransform, &'static Projection), Is2dCamera>;
+
+// Minimap of a board, a pixel per tile
+#[derive(Component)]
+struct Minimap {
+    board: Entity,
+    image: Handle<Image>,
+}
+
+// Part of the board seen by the camera, child of the minimap
+#[derive(Component)]
+struct MinimapViewport;
+
+// Corner minimap of the boards overflowing the view, once zoomed in or too large for the
+// window. The `=` and `-` keys zoom the camera, and clicking or dragging on the minimap moves it
+// over the board
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(
+            AppState::InGame,
+            (zoom_camera, show_minimap, paint_minimap, move_viewport),
+        )
+        // before the boards see the clicks
+        .add_systems(PreUpdate, jump_to_click.after(InputSystems))
+        .on_exit(AppState::InGame, (cleanup::<Minimap>, reset_camera));
+    }
+}
+
+// World area seen by the 2D camera, its bottom left and top right corners
+fn camera_view(windows: &PrimaryWindowQuery, cameras: &Cameras) -> Option<(Vec2, Vec2)> {
+    let screen = ScreenSpace::from_window(windows.single().ok()?);
+    let camera = cameras
+        .iter()
+        .find_map(|(t, p)| Some((t, engine::orthographic(p)?)));
+    let screen = match camera {
+        Some((transform, projection)) => screen.with_camera(transform, projection),
+        None => screen,
+    };
+    // the window corners are its top left and bottom right ones
+    let (top_left, bottom_right) = (
+        screen.window_to_world(Vec2::ZERO),
+        screen.window_to_world(screen.window_size),
+    );
+    Some((top_left.min(bottom_right), top_left.max(bottom_right)))
+}
+
+// Zooms the 2D camera in and out, back to a pixel per world unit at most
+fn zoom_camera(keys: Res<ButtonInput<KeyCode>>, mut cameras: Query<&mut Projection, Is2dCamera>) {
+    let zoom = if keys.just_pressed(KeyCode::Equal) {
+        1. / ZOOM_STEP
+    } else if keys.just_pressed(KeyCode::Minus) {
+        ZOOM_STEP
+    } else {
+        return;
+    };
+    for mut projection in cameras.iter_mut() {
+        let scale = (engine::camera_zoom(&projection) * zoom).clamp(MIN_CAMERA_SCALE, 1.);
+        engine::set_camera_zoom(&mut projection, scale);
+    }
+}
+
+// Moves the 2D camera back over the boards, unzoomed
+fn reset_camera(mut cameras: Query<(&mut Transform, &mut Projection), Is2dCamera>) {
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        transform.translation = Vec3::new(0., 0., transform.translation.z);
+        engine::set_camera_zoom(&mut projection, 1.);
+    }
+}
+
+// Shows the minimap of the first board overflowing the view, the board fitting the view again
+// hides it. Left alone while the camera frames a new board
+#[allow(clippy::too_many_arguments)]
+fn show_minimap(
+    mut cmds: Commands,
+    windows: PrimaryWindowQuery,
+    mut images: ResMut<Assets<Image>>,
+    board_assets: Res<BoardAssets>,
+    cameras: Cameras,
+    fits: Query<(), With<CameraFit>>,
+    boards: Query<&Board>,
+    minimaps: Query<(Entity, &Minimap)>,
+) {
+    if fits.iter().next().is_some() {
+        return;
+    }
+    let (view_min, view_max) = match camera_view(&windows, &cameras) {
+        Some(v) => v,
+        None => return,
+    };
+    // a pixel of leeway, for the boards filling the window
+    let overflowing = boards.iter().find(|b| {
+        let (min, max) = (b.bounds.position, b.bounds.position + b.bounds.size);
+        min.cmplt(view_min - Vec2::ONE).any() || max.cmpgt(view_max + Vec2::ONE).any()
+    });
+    let shown = minimaps.iter().next();
+    if shown.map(|(_, m)| m.board) == overflowing.map(|b| b.entity) {
+        return;
+    }
+    if let Some((entity, _)) = shown {
+        cmds.entity(entity).despawn();
+    }
+    if let Some(board) = overflowing {
+        spawn_minimap(&mut cmds, board, &mut images, &board_assets);
+    }
+}
+
+fn spawn_minimap(
+    cmds: &mut Commands,
+    board: &Board,
+    images: &mut Assets<Image>,
+    board_assets: &BoardAssets,
+) {
+    let tile_map = board.tile_map();
+    let (width, height) = (tile_map.width(), tile_map.height());
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // a sharp square per tile
+    image.sampler = ImageSampler::nearest();
+    paint(&mut image, board, board_assets);
+    let image = images.add(image);
+
+    let scale = MINIMAP_SIZE / width.max(height) as f32;
+    cmds.spawn((
+        ImageNode::new(image.clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.),
+            right: Val::Px(10.),
+            width: Val::Px(width as f32 * scale),
+            height: Val::Px(height as f32 * scale),
+            ..Default::default()
+        },
+    ))
+    .insert(Minimap {
+        board: board.entity,
+        image,
+    })
+    .insert(Name::new("Minimap"))
+    .with_children(|parent| {
+        parent
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    ..Default::default()
+                },
+                BackgroundColor(Color::srgba(1., 1., 1., 0.3)),
+            ))
+            .insert(MinimapViewport);
+    });
+}
+
+// Paints a pixel per tile from its visible state, the holes left transparent
+fn paint(image: &mut Image, board: &Board, board_assets: &BoardAssets) {
+    let colors = board_assets.tile_colors;
+    let tile_map = board.tile_map();
+    let (width, height) = (tile_map.width() as usize, tile_map.height() as usize);
+    for (coords, tile) in tile_map.tiles() {
+        let color = match board.tile_state(coords) {
+            Some(TileState::Revealed) if tile.is_bomb() => colors.bomb,
+            Some(TileState::Revealed) => colors.revealed,
+            Some(TileState::Flagged) => Color::srgb(1., 0., 0.),
+            _ => colors.covered,
+        };
+        // image rows go down while the board rows go up
+        let row = height - 1 - coords.y as usize;
+        let i = (row * width + coords.x as usize) * 4;
+        if let Some(data) = image.data.as_mut() {
+            data[i..i + 4].copy_from_slice(&color.to_srgba().to_u8_array());
+        }
+    }
+}
+
+// Repaints the minimap after the moves on its board
+fn paint_minimap(
+    mut images: ResMut<Assets<Image>>,
+    board_assets: Res<BoardAssets>,
+    boards: Query<&Board, Changed<Board>>,
+    minimaps: Query<&Minimap>,
+) {
+    for minimap in minimaps.iter() {
+        let board = match boards.get(minimap.board) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        if let Some(image) = images.get_mut(&minimap.image) {
+            paint(image, board, &board_assets);
+        }
+    }
+}
+
+// Frames the part of the board seen by the camera on the minimap
+fn move_viewport(
+    windows: PrimaryWindowQuery,
+    cameras: Cameras,
+    boards: Query<&Board>,
+    minimaps: Query<&Minimap>,
+    mut viewports: Query<&mut Node, With<MinimapViewport>>,
+) {
+    let board = match minimaps.iter().next().map(|m| boards.get(m.board)) {
+        Some(Ok(b)) => b,
+        _ => return,
+    };
+    let (view_min, view_max) = match camera_view(&windows, &cameras) {
+        Some(v) => v,
+        None => return,
+    };
+    // shares of the board, the view may go past its edges
+    let share = |world: Vec2| {
+        ((world - board.bounds.position) / board.bounds.size).clamp(Vec2::ZERO, Vec2::ONE) * 100.
+    };
+    let (min, max) = (share(view_min), share(view_max));
+    let (left, bottom) = (Val::Percent(min.x), Val::Percent(min.y));
+    let (width, height) = (Val::Percent(max.x - min.x), Val::Percent(max.y - min.y));
+    for mut node in viewports.iter_mut() {
+        // the layout is only computed again on changes
+        if (node.left, node.bottom, node.width, node.height) != (left, bottom, width, height) {
+            node.left = left;
+            node.bottom = bottom;
+            node.width = width;
+            node.height = height;
+        }
+    }
+}
+
+// Centers the 2D camera on the board point clicked on the minimap, following the cursor until
+// the button is released. The presses on the minimap are taken from the boards under it, and
+// the camera keeps the offset of a screen shake
+#[allow(clippy::too_many_arguments)]
+fn jump_to_click(
+    windows: PrimaryWindowQuery,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut mouse_button_evr: MessageReader<MouseButtonInput>,
+    boards: Query<&Board>,
+    shake: Res<ScreenShake>,
+    minimaps: Query<(&Minimap, &ComputedNode, &UiGlobalTransform)>,
+    mut cameras: Query<&mut Transform, Is2dCamera>,
+    mut dragging: Local<bool>,
+) {
+    // the release is read from the events, the press being reset out of the input
+    let released = mouse_button_evr
+        .read()
+        .any(|e| e.button == MouseButton::Left && e.state == ButtonState::Released);
+    let cursor = windows.single().ok().and_then(engine::cursor_position);
+    let ((minimap, node, transform), cursor) = match (minimaps.iter().next(), cursor) {
+        (Some(minimap), Some(cursor)) => (minimap, cursor),
+        _ => {
+            *dragging = false;
+            return;
+        }
+    };
+    // share of the minimap under the cursor from its bottom left corner, the UI nodes being
+    // centered on their transform and laid out in physical pixels
+    let centered = match node.normalize_point(*transform, cursor / node.inverse_scale_factor()) {
+        Some(p) => p,
+        None => return,
+    };
+    let share = Vec2::new(centered.x + 0.5, 0.5 - centered.y);
+    let inside = share.cmpge(Vec2::ZERO).all() && share.cmple(Vec2::ONE).all();
+    if inside && mouse.get_just_pressed().next().is_some() {
+        *dragging = mouse.just_pressed(MouseButton::Left);
+        let pressed: Vec<MouseButton> = mouse.get_just_pressed().copied().collect();
+        for button in pressed {
+            mouse.reset(button);
+        }
+    }
+    if !*dragging {
+        return;
+    }
+    *dragging = !released;
+
+    let board = match boards.get(minimap.board) {
+        Ok(b) => b,
+        Err(_) => return,
+    };
+    let target = board.bounds.position + share.clamp(Vec2::ZERO, Vec2::ONE) * board.bounds.size;
+    for mut transform in cameras.iter_mut() {
+        transform.translation = target.extend(transform.translation.z) + shake.offset();
+    }
+}
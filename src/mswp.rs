@@ -0,0 +1,215 @@
+use crate::persistence::data_dir;
+use board_plugin::resources::{BoardOptions, Replay};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Extension of the shared replay and board files
+pub const EXTENSION: &str = "mswp";
+// First bytes of every file, followed by a space, the schema version and a newline
+const MAGIC: &str = "MSWP";
+// Schema version written by this build. A new version keeps reading the older ones, migrating
+// their content, so the files shared today stay loadable
+const VERSION: u16 = 1;
+// Longest side of the shared boards, as on the custom board screen. The files come from other
+// players, their boards are bounded before any tile map is allocated
+const MAX_SIDE: u16 = 200;
+
+// Content of a shared file, written as RON after the header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MswpContent {
+    // Recorded game, played back on import
+    Replay(Replay),
+    // Board options with their seed, played on import
+    Board(BoardOptions),
+}
+
+// Files which can't be imported
+#[derive(Debug, Clone, PartialEq)]
+pub enum MswpError {
+    // The file can't be read
+    Io(String),
+    // The file doesn't start with the magic bytes
+    NotMswp,
+    // The schema version is unknown, written by a newer build
+    UnknownVersion(u16),
+    // The content doesn't match the schema of its version, or isn't a playable board
+    Invalid(String),
+}
+
+impl Display for MswpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "the file can't be read: {e}"),
+            Self::NotMswp => write!(f, "not a .{EXTENSION} file"),
+            Self::UnknownVersion(version) => write!(
+                f,
+                "unknown version {version} of the file format, up to {VERSION} is supported"
+            ),
+            Self::Invalid(e) => write!(f, "invalid content: {e}"),
+        }
+    }
+}
+
+// Writes the content with the header of the current version
+pub fn encode(content: &MswpContent) -> Result<String, String> {
+    let body =
+        ron::ser::to_string_pretty(content, PrettyConfig::default()).map_err(|e| e.to_string())?;
+    Ok(format!("{MAGIC} {VERSION}\n{body}\n"))
+}
+
+// Reads the content of a file of any known version
+pub fn decode(text: &str) -> Result<MswpContent, MswpError> {
+    let (header, body) = text.split_once('\n').ok_or(MswpError::NotMswp)?;
+    let version = match header.trim_end().split_once(' ') {
+        Some((MAGIC, version)) => version.parse::<u16>().map_err(|_| MswpError::NotMswp)?,
+        _ => return Err(MswpError::NotMswp),
+    };
+    let content = match version {
+        1 => ron::from_str(body).map_err(|e| MswpError::Invalid(e.to_string()))?,
+        _ => return Err(MswpError::UnknownVersion(version)),
+    };
+    validate(&content)?;
+    Ok(content)
+}
+
+// Checks the board of the content, and that the replay actions stay on it
+fn validate(content: &MswpContent) -> Result<(), MswpError> {
+    let options = match content {
+        MswpContent::Replay(replay) => &replay.options,
+        MswpContent::Board(options) => options,
+    };
+    options
+        .validate_within(MAX_SIDE)
+        .map_err(|e| MswpError::Invalid(e.to_string()))?;
+    if let MswpContent::Replay(replay) = content {
+        let (width, height) = options.map_size;
+        let off_board = replay
+            .events
+            .iter()
+            .find(|e| e.coordinates.x >= width || e.coordinates.y >= height);
+        if let Some(event) = off_board {
+            return Err(MswpError::Invalid(format!(
+                "the action on {} is off the board",
+                event.coordinates
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Directory of the exported files, and of the files to import
+pub fn shared_dir() -> PathBuf {
+    data_dir().join("shared")
+}
+
+// Writes the content in the shared directory, returning the path of the new file
+pub fn export(content: &MswpContent, name: &str) -> Result<PathBuf, String> {
+    let dir = shared_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = dir.join(format!("{name}-{timestamp}.{EXTENSION}"));
+    write(content, &path)?;
+    Ok(path)
+}
+
+// Writes the content to a file
+pub fn write(content: &MswpContent, path: &Path) -> Result<(), String> {
+    fs::write(path, encode(content)?).map_err(|e| e.to_string())
+}
+
+// Files of the shared directory, the most recently modified first
+pub fn shared_files() -> Vec<PathBuf> {
+    let entries = match fs::read_dir(shared_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == EXTENSION))
+        .collect();
+    paths.sort_by_key(|path| std::cmp::Reverse(fs::metadata(path).and_then(|m| m.modified()).ok()));
+    paths
+}
+
+// Reads a shared file
+pub fn import(path: &Path) -> Result<MswpContent, MswpError> {
+    let text = fs::read_to_string(path).map_err(|e| MswpError::Io(e.to_string()))?;
+    decode(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board_plugin::components::Coordinates;
+    use board_plugin::resources::{ReplayAction, ReplayEvent};
+
+    fn replay() -> Replay {
+        Replay {
+            options: BoardOptions {
+                seed: Some(42),
+                ..Default::default()
+            },
+            events: vec![ReplayEvent {
+                coordinates: Coordinates { x: 3, y: 4 },
+                action: ReplayAction::Uncover,
+                time: 1.5,
+                hash: Some(7),
+            }],
+        }
+    }
+
+    #[test]
+    fn encoded_files_decode_to_their_content() {
+        let text = encode(&MswpContent::Replay(replay())).unwrap();
+        assert!(text.starts_with("MSWP 1\n"));
+        match decode(&text).unwrap() {
+            MswpContent::Replay(decoded) => {
+                assert_eq!(decoded.options.seed, Some(42));
+                assert_eq!(decoded.events.len(), 1);
+                assert_eq!(decoded.events[0].coordinates, Coordinates { x: 3, y: 4 });
+                assert_eq!(decoded.events[0].hash, Some(7));
+            }
+            MswpContent::Board(_) => panic!("a replay decoded as a board"),
+        }
+    }
+
+    #[test]
+    fn files_without_the_magic_bytes_are_refused() {
+        let text = encode(&MswpContent::Replay(replay())).unwrap();
+        let renamed = text.replacen(MAGIC, "MSWQ", 1);
+        assert_eq!(decode(&renamed).unwrap_err(), MswpError::NotMswp);
+        assert_eq!(decode("").unwrap_err(), MswpError::NotMswp);
+    }
+
+    #[test]
+    fn files_of_a_newer_version_are_refused() {
+        let text = encode(&MswpContent::Replay(replay())).unwrap();
+        let newer = text.replacen("MSWP 1", "MSWP 2", 1);
+        assert_eq!(decode(&newer).unwrap_err(), MswpError::UnknownVersion(2));
+    }
+
+    #[test]
+    fn oversized_boards_are_refused() {
+        let options = BoardOptions {
+            map_size: (MAX_SIDE + 1, 10),
+            ..Default::default()
+        };
+        let text = encode(&MswpContent::Board(options)).unwrap();
+        assert!(matches!(decode(&text), Err(MswpError::Invalid(_))));
+    }
+
+    #[test]
+    fn replay_actions_off_the_board_are_refused() {
+        let mut replay = replay();
+        replay.events[0].coordinates = Coordinates { x: 500, y: 0 };
+        let text = encode(&MswpContent::Replay(replay)).unwrap();
+        assert!(matches!(decode(&text), Err(MswpError::Invalid(_))));
+    }
+}
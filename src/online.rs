@@ -0,0 +1,338 @@
+use crate::config::Config;
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::versus_options;
+use crate::persistence::Profile;
+use crate::protocol::{Connection, NetEvent, NetMessage, Outcome, DEFAULT_PORT, PROTOCOL_VERSION};
+use crate::results::GameResult;
+use crate::settings::Settings;
+use crate::thumbnail::OpponentBoard;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, TextInput, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{
+    BoardCompletedEvent, BoardDiffEvent, BoardStartedEvent, BombExplosionEvent,
+};
+use board_plugin::resources::{Board, BoardOptions, BoardSilhouette, GameTimer};
+
+const MAX_ADDRESS_LENGTH: usize = 40;
+
+// Online lobby buttons
+#[derive(Debug, Copy, Clone, Component)]
+enum LobbyButton {
+    Host,
+    Join,
+    Back,
+}
+
+// Role of this client, the host picks the board. Must be used as a resource with the `Connection`
+#[derive(Resource, Debug, Copy, Clone, Eq, PartialEq)]
+enum Role {
+    Host,
+    Guest,
+}
+
+// State hashes of the new boards of both clients, compared by the host. Must be used as a
+// resource during the race
+#[derive(Resource, Debug, Default)]
+struct BoardCheck {
+    // Hash of the board of this client, sent to the host by the guest
+    local: Option<u64>,
+    // Hash received from the guest
+    guest: Option<u64>,
+}
+
+#[derive(Component)]
+struct LobbyRoot;
+
+#[derive(Component)]
+struct LobbyStatus;
+
+// Race against another client over TCP: both play the board of a shared seed, the opponent
+// progress is shown by the thumbnail and the first to clear (or last to survive) wins
+pub struct OnlinePlugin;
+
+impl Plugin for OnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Online, setup_lobby)
+            .on_update(AppState::Online, (lobby_buttons, lobby_connection))
+            .on_exit(AppState::Online, cleanup::<LobbyRoot>)
+            .on_update(AppState::InGame, online_race)
+            .on_exit(AppState::Results, disconnect);
+    }
+}
+
+fn setup_lobby(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    difficulty: Res<Difficulty>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(LobbyRoot)
+        .insert(Name::new("Online Lobby"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("online.title"), 40.);
+            let difficulty = difficulty.label(&locale);
+            let help = locale.format("online.help", &[("difficulty", &difficulty)]);
+            spawn_text(parent, font, &help, 12.);
+            spawn_text(parent, font, "_", 20.).insert(TextInput {
+                max_length: MAX_ADDRESS_LENGTH,
+                ..Default::default()
+            });
+            spawn_button(parent, font, locale.get("online.host"), LobbyButton::Host);
+            spawn_button(parent, font, locale.get("online.join"), LobbyButton::Join);
+            spawn_button(parent, font, locale.get("common.back"), LobbyButton::Back);
+            spawn_text(parent, font, "", 12.).insert(LobbyStatus);
+        });
+}
+
+fn set_status(statuses: &mut Query<&mut Text, With<LobbyStatus>>, status: &str) {
+    info!("{status}");
+    for mut text in statuses.iter_mut() {
+        text.0 = status.to_string();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lobby_buttons(
+    mut cmds: Commands,
+    connection: Option<Res<Connection>>,
+    config: Res<Config>,
+    buttons: Query<(&Interaction, &LobbyButton), Changed<Interaction>>,
+    inputs: Query<&TextInput>,
+    mut statuses: Query<&mut Text, With<LobbyStatus>>,
+    mut state: StateSwitch<AppState>,
+    locale: Res<Locale>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            // one connection attempt at a time
+            LobbyButton::Host | LobbyButton::Join if connection.is_some() => (),
+            LobbyButton::Host => {
+                cmds.insert_resource(Connection::host(DEFAULT_PORT, &config));
+                cmds.insert_resource(Role::Host);
+                let status = locale.format("online.waiting", &[("port", &DEFAULT_PORT)]);
+                set_status(&mut statuses, &status);
+            }
+            LobbyButton::Join => match inputs.iter().next().map(|i| i.value.trim()) {
+                None | Some("") => set_status(&mut statuses, locale.get("online.no_address")),
+                Some(address) => {
+                    cmds.insert_resource(Connection::join(address, &config));
+                    cmds.insert_resource(Role::Guest);
+                    let status = locale.format("online.connecting", &[("address", &address)]);
+                    set_status(&mut statuses, &status);
+                }
+            },
+            LobbyButton::Back => {
+                cmds.remove_resource::<Connection>();
+                cmds.remove_resource::<Role>();
+                state.set(AppState::Menu);
+            }
+        }
+    }
+}
+
+// Greets the opponent, then starts the race once the host sent the board options
+#[allow(clippy::too_many_arguments)]
+fn lobby_connection(
+    mut cmds: Commands,
+    connection: Option<Res<Connection>>,
+    role: Option<Res<Role>>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    difficulty: Res<Difficulty>,
+    mut statuses: Query<&mut Text, With<LobbyStatus>>,
+    mut state: StateSwitch<AppState>,
+    locale: Res<Locale>,
+    mut opponent_name: Local<Option<String>>,
+) {
+    let (connection, role) = match (connection, role) {
+        (Some(c), Some(r)) => (c, *r),
+        _ => return,
+    };
+    let mut start = |cmds: &mut Commands, options: BoardOptions, name: Option<String>| {
+        // the opponent diffs only apply to the race board agreed here
+        let (width, height) = options.map_size;
+        cmds.insert_resource(OpponentBoard {
+            name: name.unwrap_or_else(|| locale.get("online.opponent").to_string()),
+            silhouette: BoardSilhouette::covered(width, height),
+        });
+        cmds.insert_resource(options);
+        cmds.insert_resource(BoardCheck::default());
+        state.set(AppState::InGame);
+    };
+
+    for event in connection.poll() {
+        match event {
+            NetEvent::Connected => {
+                *opponent_name = None;
+                set_status(&mut statuses, locale.get("online.connected"));
+                connection.send(NetMessage::Hello {
+                    name: profile.name().to_string(),
+                    version: PROTOCOL_VERSION,
+                });
+            }
+            NetEvent::Message(NetMessage::Hello { version, .. }) if version != PROTOCOL_VERSION => {
+                set_status(&mut statuses, locale.get("online.incompatible"));
+                cmds.remove_resource::<Connection>();
+                return;
+            }
+            NetEvent::Message(NetMessage::Hello { name, .. }) => {
+                if role == Role::Host {
+                    let options = versus_options(*difficulty, &settings);
+                    connection.send(NetMessage::Start {
+                        options: options.clone(),
+                    });
+                    start(&mut cmds, options, Some(name));
+                    return;
+                }
+                *opponent_name = Some(name);
+            }
+            // the options were checked by the connection, the host may run another build
+            NetEvent::Message(NetMessage::Start { options }) if role == Role::Guest => {
+                start(&mut cmds, options, opponent_name.take());
+                return;
+            }
+            NetEvent::Message(_) => (),
+            NetEvent::Disconnected(reason) => {
+                let status = locale.format("online.failed", &[("reason", &reason)]);
+                set_status(&mut statuses, &status);
+                cmds.remove_resource::<Connection>();
+                return;
+            }
+        }
+    }
+}
+
+// Streams the board progress to the opponent and ends the race with the first finished game.
+// The host calls the race off when the boards of the seed differ, from builds generating them
+// another way
+#[allow(clippy::too_many_arguments)]
+fn online_race(
+    mut cmds: Commands,
+    connection: Option<Res<Connection>>,
+    role: Option<Res<Role>>,
+    timer: Res<GameTimer>,
+    mut opponent: Option<ResMut<OpponentBoard>>,
+    boards: Query<&Board>,
+    mut check: Option<ResMut<BoardCheck>>,
+    mut board_started_evr: MessageReader<BoardStartedEvent>,
+    mut board_diff_evr: MessageReader<BoardDiffEvent>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut state: StateSwitch<AppState>,
+    locale: Res<Locale>,
+) {
+    let connection = match connection {
+        Some(c) => c,
+        None => return,
+    };
+    let host = role.as_deref() == Some(&Role::Host);
+    // hashed once the board is opened, before any move
+    let started = board_started_evr
+        .read()
+        .filter_map(|e| boards.get(e.board).ok());
+    if let (Some(check), Some(board)) = (check.as_mut(), started.last()) {
+        if check.local.is_none() {
+            let hash = board.state_hash();
+            check.local = Some(hash);
+            if !host {
+                connection.send(NetMessage::Ready { hash });
+            }
+        }
+    }
+    for event in board_diff_evr.read() {
+        connection.send(NetMessage::Progress(event.diff.clone()));
+    }
+
+    let mut result = None;
+    if board_completed_evr.read().count() > 0 {
+        let elapsed = timer.elapsed();
+        connection.send(NetMessage::Finished(Outcome::Cleared(elapsed)));
+        let time = format!("{elapsed:.2}");
+        let details = locale.format("result.cleared", &[("time", &time)]);
+        result = Some(("result.win", details));
+    } else if bomb_explosion_evr.read().count() > 0 {
+        connection.send(NetMessage::Finished(Outcome::Exploded));
+        let details = locale.get("result.exploded").to_string();
+        result = Some(("result.lose", details));
+    }
+
+    let name = opponent
+        .as_ref()
+        .map_or(locale.get("online.opponent").to_string(), |o| {
+            o.name.clone()
+        });
+    for event in connection.poll() {
+        match event {
+            NetEvent::Message(NetMessage::Progress(diff)) => {
+                if let Some(opponent) = opponent.as_mut() {
+                    if !opponent.silhouette.apply(&diff) {
+                        warn!("Dropped an opponent diff of a {:?} board", diff.size);
+                    }
+                }
+            }
+            NetEvent::Message(NetMessage::Finished(Outcome::Cleared(time))) => {
+                let time = format!("{time:.2}");
+                let details = locale.format(
+                    "online.opponent_cleared",
+                    &[("name", &name), ("time", &time)],
+                );
+                result.get_or_insert(("result.lose", details));
+            }
+            NetEvent::Message(NetMessage::Ready { hash }) => {
+                if let Some(check) = check.as_mut() {
+                    check.guest = Some(hash);
+                }
+            }
+            NetEvent::Message(NetMessage::Mismatch) => {
+                let details = locale.get("online.mismatch_details").to_string();
+                result.get_or_insert(("online.mismatch", details));
+            }
+            NetEvent::Message(NetMessage::Finished(Outcome::Exploded)) => {
+                let details = locale.format("online.opponent_exploded", &[("name", &name)]);
+                result.get_or_insert(("result.win", details));
+            }
+            NetEvent::Disconnected(reason) => {
+                let details = locale.format(
+                    "online.opponent_left",
+                    &[("name", &name), ("reason", &reason)],
+                );
+                result.get_or_insert(("result.win", details));
+            }
+            NetEvent::Connected | NetEvent::Message(_) => (),
+        }
+    }
+    let mismatch = check.as_ref().is_some_and(|c| match (c.local, c.guest) {
+        (Some(local), Some(guest)) => local != guest,
+        _ => false,
+    });
+    if host && mismatch {
+        warn!("The boards of the race differ, calling it off");
+        connection.send(NetMessage::Mismatch);
+        let details = locale.get("online.mismatch_details").to_string();
+        result = Some(("online.mismatch", details));
+    }
+
+    if let Some((headline, details)) = result {
+        info!("Online race over: {details}");
+        cmds.insert_resource(GameResult {
+            headline: locale.get(headline).to_string(),
+            details,
+        });
+        state.set(AppState::Results);
+    }
+}
+
+fn disconnect(mut cmds: Commands) {
+    cmds.remove_resource::<Connection>();
+    cmds.remove_resource::<Role>();
+    cmds.remove_resource::<OpponentBoard>();
+    cmds.remove_resource::<BoardCheck>();
+}
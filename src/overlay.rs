@@ -0,0 +1,190 @@
+use crate::config::Config;
+use crate::locale::Locale;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::resources::{Board, BoardAssets, GameTimer, InputMap};
+use minesweeper_core::Metrics;
+use serde::Deserialize;
+
+// Corner of the window holding the overlay
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// Streaming overlay of the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    // Overlay shown on startup, the overlay binding toggles it
+    pub enabled: bool,
+    pub corner: OverlayCorner,
+    // Distance to the edges of the window, in pixels
+    pub margin: f32,
+    // Hides the highlight of the hovered tile while the overlay is shown
+    pub hide_hover: bool,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: OverlayCorner::TopRight,
+            margin: 10.,
+            hide_hover: false,
+        }
+    }
+}
+
+impl OverlayConfig {
+    // Node placed in the corner of the overlay
+    fn node(&self) -> Node {
+        let margin = Val::Px(self.margin);
+        let (top, left) = match self.corner {
+            OverlayCorner::TopLeft => (true, true),
+            OverlayCorner::TopRight => (true, false),
+            OverlayCorner::BottomLeft => (false, true),
+            OverlayCorner::BottomRight => (false, false),
+        };
+        let mut node = Node {
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        };
+        match top {
+            true => node.top = margin,
+            false => node.bottom = margin,
+        }
+        match left {
+            true => node.left = margin,
+            false => node.right = margin,
+        }
+        node
+    }
+}
+
+// Whether the streaming overlay is shown. Must be used as a resource
+#[derive(Resource)]
+pub struct StreamOverlay {
+    pub shown: bool,
+}
+
+#[derive(Component)]
+struct OverlayRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+enum OverlayText {
+    Timer,
+    Mines,
+    Rate,
+}
+
+// Large timer, mines left and live 3BV per second over the games, for capturing the gameplay
+pub struct StreamOverlayPlugin;
+
+impl Plugin for StreamOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_stream_overlay)
+            .add_systems(Update, toggle_overlay)
+            .add_systems(Update, hide_hover)
+            .on_update(AppState::InGame, (show_overlay, update_overlay))
+            .on_exit(AppState::InGame, cleanup::<OverlayRoot>);
+    }
+}
+
+fn setup_stream_overlay(mut cmds: Commands, config: Res<Config>) {
+    cmds.insert_resource(StreamOverlay {
+        shown: config.stream_overlay.enabled,
+    });
+}
+
+fn toggle_overlay(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut overlay: ResMut<StreamOverlay>,
+) {
+    if input_map.overlay.just_pressed(&mouse, &keys) {
+        overlay.shown = !overlay.shown;
+        info!("Stream overlay shown: {}", overlay.shown);
+    }
+}
+
+// Turns the hover highlight off while the overlay is shown, if the config asks for it
+fn hide_hover(
+    config: Res<Config>,
+    overlay: Res<StreamOverlay>,
+    mut board_assets: ResMut<BoardAssets>,
+) {
+    let highlight = !(overlay.shown && config.stream_overlay.hide_hover);
+    // the board plugin redraws the feedback on every change
+    if board_assets.hover_highlight != highlight {
+        board_assets.hover_highlight = highlight;
+    }
+}
+
+// Spawns or despawns the overlay following the toggle
+fn show_overlay(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    config: Res<Config>,
+    overlay: Res<StreamOverlay>,
+    roots: Query<Entity, With<OverlayRoot>>,
+) {
+    match (overlay.shown, roots.iter().next()) {
+        (true, None) => (),
+        (false, Some(root)) => {
+            cmds.entity(root).despawn();
+            return;
+        }
+        _ => return,
+    }
+    let font = &ui_assets.font;
+    cmds.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(8.)),
+            ..config.stream_overlay.node()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(OverlayRoot)
+    .insert(Name::new("Stream Overlay"))
+    .with_children(|parent| {
+        spawn_text(parent, font, "", 64.).insert(OverlayText::Timer);
+        spawn_text(parent, font, "", 24.).insert(OverlayText::Mines);
+        spawn_text(parent, font, "", 24.).insert(OverlayText::Rate);
+    });
+}
+
+fn update_overlay(
+    timer: Res<GameTimer>,
+    boards: Query<&Board>,
+    locale: Res<Locale>,
+    mut texts: Query<(&OverlayText, &mut Text)>,
+) {
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    let elapsed = timer.elapsed();
+    let mines = board.game.mines_left();
+    for (overlay_text, mut text) in texts.iter_mut() {
+        text.0 = match overlay_text {
+            // zen games hide the timer, and the rate following from it
+            OverlayText::Timer | OverlayText::Rate if board.options.relaxed => String::new(),
+            OverlayText::Timer => format!("{elapsed:.1}"),
+            OverlayText::Mines => locale.format("overlay.mines", &[("mines", &mines)]),
+            OverlayText::Rate => {
+                let rate = Metrics::new(&board.game, elapsed).three_bv_per_second();
+                let rate = format!("{rate:.2}");
+                locale.format("overlay.rate", &[("rate", &rate)])
+            }
+        };
+    }
+}
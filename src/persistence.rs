@@ -0,0 +1,92 @@
+use bevy::log;
+use bevy::prelude::Resource;
+use ron::ser::PrettyConfig;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Local player profile, every profile data is stored in its own directory. Must be used as a
+// resource
+#[derive(Resource, Debug, Clone, Eq, PartialEq)]
+pub enum Profile {
+    Named(String),
+    // Shared computer guest, nothing is written to disk
+    Guest,
+}
+
+impl Profile {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Named(name) => name,
+            Self::Guest => "Guest",
+        }
+    }
+
+    // Directory holding the profile data
+    fn dir(&self) -> Option<PathBuf> {
+        match self {
+            Self::Named(name) => Some(data_dir().join("profiles").join(name)),
+            Self::Guest => None,
+        }
+    }
+}
+
+// Directory holding every persisted file
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("minesweeper")
+}
+
+// Loads a RON file from the profile directory, falling back to the default value
+pub fn load<T: DeserializeOwned + Default>(profile: &Profile, file: &str) -> T {
+    match profile.dir() {
+        Some(dir) => read(&dir.join(file)),
+        None => T::default(),
+    }
+}
+
+// Saves a value as a RON file in the profile directory
+pub fn save<T: Serialize>(profile: &Profile, file: &str, value: &T) {
+    if let Some(dir) = profile.dir() {
+        write(&dir, file, value);
+    }
+}
+
+// Loads a RON file shared by every profile
+pub fn load_global<T: DeserializeOwned + Default>(file: &str) -> T {
+    read(&data_dir().join(file))
+}
+
+// Saves a RON file shared by every profile
+pub fn save_global<T: Serialize>(file: &str, value: &T) {
+    write(&data_dir(), file, value);
+}
+
+fn read<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return T::default(),
+    };
+    match ron::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("Failed to parse {}: {e}", path.display());
+            T::default()
+        }
+    }
+}
+
+fn write<T: Serialize>(dir: &Path, file: &str, value: &T) {
+    let path = dir.join(file);
+    let content = match ron::ser::to_string_pretty(value, PrettyConfig::default()) {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to serialize {}: {e}", path.display());
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&path, content)) {
+        log::error!("Failed to write {}: {e}", path.display());
+    }
+}
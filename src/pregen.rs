@@ -0,0 +1,130 @@
+use crate::difficulty::Difficulty;
+use crate::menu::game_options;
+use crate::persistence;
+use crate::settings::Settings;
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use board_plugin::generation::{generate_tile_map, BoardGenerators, PregeneratedBoard};
+use board_plugin::resources::{BoardOptions, Generation};
+use futures_lite::future;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+const CACHE_FILE: &str = "pregenerated_boards.ron";
+
+// Boards generated in the background for the next games, kept across sessions.
+// Must be used as a resource
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct BoardCache {
+    boards: Vec<PregeneratedBoard>,
+}
+
+impl BoardCache {
+    pub fn load() -> Self {
+        persistence::load_global(CACHE_FILE)
+    }
+
+    fn save(&self) {
+        persistence::save_global(CACHE_FILE, self);
+    }
+
+    // Takes the cached board generated for the options, if any
+    pub fn take(&mut self, options: &BoardOptions) -> Option<PregeneratedBoard> {
+        let index = self
+            .boards
+            .iter()
+            .position(|b| b.options.generates_like(options))?;
+        let board = self.boards.remove(index);
+        self.save();
+        Some(board)
+    }
+}
+
+// Board being generated in the background. Must be used as a resource
+#[derive(Resource)]
+struct PendingBoard(Task<PregeneratedBoard>);
+
+pub struct PregenPlugin;
+
+impl Plugin for PregenPlugin {
+    fn build(&self, app: &mut App) {
+        // the cache from the last session makes the first game instant
+        app.insert_resource(BoardCache::load())
+            .add_systems(Update, pregenerate_boards)
+            .add_systems(Update, collect_pregenerated_board);
+    }
+}
+
+// Uniform boards are generated instantly, only the others are worth generating ahead
+fn is_expensive(options: &BoardOptions) -> bool {
+    options.generation != Generation::Uniform
+}
+
+// Generates the missing boards of the menu difficulties one at a time, outside of games
+fn pregenerate_boards(
+    mut cmds: Commands,
+    mut cache: ResMut<BoardCache>,
+    settings: Res<Settings>,
+    generators: Res<BoardGenerators>,
+    state: Res<State<AppState>>,
+    pending: Option<Res<PendingBoard>>,
+) {
+    let wanted: Vec<BoardOptions> = Difficulty::ALL
+        .iter()
+        .map(|d| game_options(*d, &settings))
+        .filter(is_expensive)
+        .collect();
+
+    // boards generated for previous settings won't be played
+    let cached = cache.boards.len();
+    cache
+        .boards
+        .retain(|b| wanted.iter().any(|o| o.generates_like(&b.options)));
+    if cache.boards.len() != cached {
+        cache.save();
+    }
+
+    if pending.is_some() || *state.get() == AppState::InGame {
+        return;
+    }
+    let options = match wanted
+        .into_iter()
+        .find(|o| !cache.boards.iter().any(|b| b.options.generates_like(o)))
+    {
+        Some(o) => o,
+        None => return,
+    };
+
+    info!(
+        "Pregenerating a {}x{} board",
+        options.map_size.0, options.map_size.1
+    );
+    let generator = generators.generator(&options.generation);
+    let seed = thread_rng().gen();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let tile_map = generate_tile_map(generator.as_ref(), &options, seed);
+        PregeneratedBoard {
+            options,
+            seed,
+            tile_map,
+        }
+    });
+    cmds.insert_resource(PendingBoard(task));
+}
+
+fn collect_pregenerated_board(
+    mut cmds: Commands,
+    mut cache: ResMut<BoardCache>,
+    pending: Option<ResMut<PendingBoard>>,
+) {
+    let mut pending = match pending {
+        Some(p) => p,
+        None => return,
+    };
+    if let Some(board) = future::block_on(future::poll_once(&mut pending.0)) {
+        cmds.remove_resource::<PendingBoard>();
+        cache.boards.push(board);
+        cache.save();
+    }
+}
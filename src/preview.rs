@@ -0,0 +1,87 @@
+use crate::locale::Locale;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::resources::{Board, BoardPreview};
+
+#[derive(Component)]
+struct PreviewRoot;
+
+// Shows the previewed board details until it is locked in
+pub struct PreviewPlugin;
+
+impl Plugin for PreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, preview_overlay)
+            .on_exit(AppState::InGame, cleanup::<PreviewRoot>);
+    }
+}
+
+// 3BV range shown in the preview, the exact value is left for the end of the game
+fn three_bv_range(three_bv: u32) -> (u32, u32) {
+    let low = three_bv / 10 * 10;
+    (low, low + 9)
+}
+
+fn preview_overlay(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    locale: Res<Locale>,
+    previews: Query<(&Board, &BoardPreview)>,
+    changed: Query<
+        (),
+        (
+            With<BoardPreview>,
+            Or<(Changed<Board>, Changed<BoardPreview>)>,
+        ),
+    >,
+    overlays: Query<Entity, With<PreviewRoot>>,
+) {
+    let (board, preview) = match previews.iter().next() {
+        Some(v) => v,
+        None => {
+            // locked in
+            for entity in overlays.iter() {
+                cmds.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+    if changed.iter().next().is_none() {
+        return;
+    }
+    for entity in overlays.iter() {
+        cmds.entity(entity).despawn();
+    }
+
+    let font = &ui_assets.font;
+    let tile_map = board.tile_map();
+    let (low, high) = three_bv_range(tile_map.three_bv());
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(PreviewRoot)
+    .insert(Name::new("Board Preview"))
+    .with_children(|parent| {
+        let size = locale.format(
+            "preview.size",
+            &[("width", &tile_map.width()), ("height", &tile_map.height())],
+        );
+        spawn_text(parent, font, &size, 16.);
+        let mines = locale.format("preview.mines", &[("mines", &tile_map.mine_count())]);
+        spawn_text(parent, font, &mines, 16.);
+        let three_bv = locale.format("preview.three_bv", &[("low", &low), ("high", &high)]);
+        spawn_text(parent, font, &three_bv, 16.);
+        let reroll = locale.format("preview.reroll", &[("rerolls", &preview.rerolls_left)]);
+        spawn_text(parent, font, &reroll, 12.);
+        spawn_text(parent, font, locale.get("preview.start"), 12.);
+    });
+}
@@ -0,0 +1,175 @@
+use crate::achievements::Achievements;
+use crate::changelog;
+use crate::leaderboard::Leaderboard;
+use crate::locale::Locale;
+use crate::persistence::{self, Profile};
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::tutorial::{self, Tutorial};
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, TextInput, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use serde::{Deserialize, Serialize};
+
+const PROFILES_FILE: &str = "profiles.ron";
+const DEFAULT_PROFILE: &str = "Player";
+const MAX_NAME_LENGTH: usize = 12;
+
+// Local profiles index, shared by every profile. Must be used as a resource
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub names: Vec<String>,
+    pub last_used: Option<String>,
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        persistence::load_global(PROFILES_FILE)
+    }
+
+    pub fn save(&self) {
+        persistence::save_global(PROFILES_FILE, self);
+    }
+
+    // Profile selected at startup, creating the default profile on first launch
+    pub fn startup_profile(&mut self) -> Profile {
+        if let Some(name) = self.last_used.as_ref().filter(|n| self.names.contains(n)) {
+            return Profile::Named(name.clone());
+        }
+        if self.names.is_empty() {
+            self.names.push(DEFAULT_PROFILE.to_string());
+            self.save();
+        }
+        Profile::Named(self.names[0].clone())
+    }
+
+    // Registers a new profile, returning `None` for invalid or existing names
+    pub fn create(&mut self, name: &str) -> Option<Profile> {
+        // the name is used as a directory name
+        let name: String = name
+            .trim()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+            .collect();
+        if name.is_empty() || self.names.contains(&name) {
+            return None;
+        }
+        self.names.push(name.clone());
+        Some(Profile::Named(name))
+    }
+}
+
+// Replaces the per profile resources with the data of `profile`, returning its settings
+pub fn switch_profile(cmds: &mut Commands, profiles: &mut Profiles, profile: Profile) -> Settings {
+    info!("Switching to profile {}", profile.name());
+    if let Profile::Named(name) = &profile {
+        profiles.last_used = Some(name.clone());
+        profiles.save();
+    }
+    let settings = Settings::load(&profile);
+    cmds.insert_resource(settings.clone());
+    cmds.insert_resource(Leaderboard::load(&profile));
+    cmds.insert_resource(Stats::load(&profile));
+    cmds.insert_resource(Achievements::load(&profile));
+    cmds.insert_resource(profile);
+    settings
+}
+
+#[derive(Debug, Clone, Component)]
+enum ProfileButton {
+    Select(String),
+    Guest,
+    Create,
+}
+
+#[derive(Component)]
+struct ProfileSelectRoot;
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::ProfileSelect, setup_profile_select)
+            .on_update(AppState::ProfileSelect, profile_buttons)
+            .on_exit(AppState::ProfileSelect, cleanup::<ProfileSelectRoot>);
+    }
+}
+
+fn setup_profile_select(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    profiles: Res<Profiles>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(ProfileSelectRoot)
+        .insert(Name::new("Profile Select"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("profile.title"), 40.);
+            for name in profiles.names.iter() {
+                spawn_button(parent, font, name, ProfileButton::Select(name.clone()));
+            }
+            spawn_button(
+                parent,
+                font,
+                locale.get("profile.guest"),
+                ProfileButton::Guest,
+            );
+            spawn_text(parent, font, locale.get("profile.new_name"), 16.);
+            spawn_text(parent, font, "_", 20.).insert(TextInput {
+                max_length: MAX_NAME_LENGTH,
+                ..Default::default()
+            });
+            spawn_button(
+                parent,
+                font,
+                locale.get("profile.create"),
+                ProfileButton::Create,
+            );
+        });
+}
+
+fn profile_buttons(
+    mut cmds: Commands,
+    mut profiles: ResMut<Profiles>,
+    mut state: StateSwitch<AppState>,
+    buttons: Query<(&Interaction, &ProfileButton), Changed<Interaction>>,
+    inputs: Query<&TextInput>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let profile = match button {
+            ProfileButton::Select(name) => Profile::Named(name.clone()),
+            ProfileButton::Guest => Profile::Guest,
+            ProfileButton::Create => {
+                let name = inputs.iter().next().map(|i| i.value.as_str()).unwrap_or("");
+                match profiles.create(name) {
+                    Some(profile) => profile,
+                    None => {
+                        warn!("Invalid or existing profile name {name:?}");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let settings = switch_profile(&mut cmds, &mut profiles, profile);
+        // new profiles start with the tutorial
+        let next = if tutorial::is_pending(&settings) {
+            cmds.insert_resource(tutorial::tutorial_options(&settings));
+            cmds.insert_resource(Tutorial::default());
+            AppState::InGame
+        } else if changelog::has_news(&settings) {
+            AppState::WhatsNew
+        } else {
+            AppState::Menu
+        };
+        state.set(next);
+        return;
+    }
+}
@@ -0,0 +1,331 @@
+use crate::config::Config;
+use bevy::prelude::Resource;
+use board_plugin::resources::{BoardDiff, BoardOptions};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// Bumped on every incompatible message change
+pub const PROTOCOL_VERSION: u32 = 2;
+// Port listened to by hosts, and joined when the address has none
+pub const DEFAULT_PORT: u16 = 7878;
+// Longest side of the race boards, the largest custom board. The options and diffs of larger
+// boards are refused
+const MAX_RACE_SIDE: u16 = 200;
+// Longest message line read from the opponent, with room for a full diff of the largest race
+// board. Longer lines drop the connection
+const MAX_LINE: u64 = 4 * 1024 * 1024;
+// Longest opponent name, as the profile names
+const MAX_NAME_LENGTH: usize = 12;
+
+// How a player's game ended
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Outcome {
+    // Board cleared in the given seconds
+    Cleared(f32),
+    // Mine hit
+    Exploded,
+}
+
+// Messages exchanged by the two clients of an online race, one RON message per line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NetMessage {
+    // First message of both clients
+    Hello { name: String, version: u32 },
+    // Sent by the host, both players race on the board generated from these options
+    Start { options: BoardOptions },
+    // Sent by the guest once its board started, with the state hash of the board. The host
+    // checks it against its own board, generated from the same seed
+    Ready { hash: u64 },
+    // Sent by the host when the boards of both clients differ, calling the race off
+    Mismatch,
+    // Visible changes of the sender's board
+    Progress(BoardDiff),
+    // End of the sender's game
+    Finished(Outcome),
+}
+
+impl NetMessage {
+    // Checks the values sent by the opponent, the messages failing it drop the connection
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Hello { name, .. } => match name.chars().count() > MAX_NAME_LENGTH {
+                true => Err(format!("name over {MAX_NAME_LENGTH} characters")),
+                false => Ok(()),
+            },
+            Self::Start { options } => options
+                .validate_within(MAX_RACE_SIDE)
+                .map_err(|e| e.to_string()),
+            Self::Progress(diff) => {
+                let (width, height) = diff.size;
+                if width > MAX_RACE_SIDE || height > MAX_RACE_SIDE {
+                    return Err(format!("{width}x{height} board diff"));
+                }
+                match diff.changes.len() > width as usize * height as usize {
+                    true => Err(format!(
+                        "{} changes of a {width}x{height} board",
+                        diff.changes.len()
+                    )),
+                    false => Ok(()),
+                }
+            }
+            Self::Finished(Outcome::Cleared(time)) => match time.is_finite() && *time >= 0. {
+                true => Ok(()),
+                false => Err(format!("{time} seconds clear time")),
+            },
+            Self::Ready { .. } | Self::Mismatch | Self::Finished(Outcome::Exploded) => Ok(()),
+        }
+    }
+}
+
+// Connection state changes and received messages
+#[derive(Debug, Clone)]
+pub enum NetEvent {
+    Connected,
+    Message(NetMessage),
+    Disconnected(String),
+}
+
+// Connection to the opponent, run by background threads. Must be used as a resource,
+// dropping it closes the connection
+#[derive(Resource)]
+pub struct Connection {
+    events: Mutex<Receiver<NetEvent>>,
+    outgoing: Mutex<Sender<NetMessage>>,
+}
+
+impl Connection {
+    // Waits for an opponent on the given port, unless the config keeps the game off the network
+    pub fn host(port: u16, config: &Config) -> Self {
+        Self::spawn(config, move |outgoing| {
+            let listener = TcpListener::bind(("0.0.0.0", port))?;
+            // the connection may be dropped before anyone joins
+            listener.set_nonblocking(true)?;
+            loop {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        stream.set_nonblocking(false)?;
+                        return Ok(Some(stream));
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        if let Err(TryRecvError::Disconnected) = outgoing.try_recv() {
+                            return Ok(None);
+                        }
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+
+    // Joins a host, `address` being `host` or `host:port`, unless the config keeps the game off
+    // the network
+    pub fn join(address: &str, config: &Config) -> Self {
+        let address = match address.contains(':') {
+            true => address.to_string(),
+            false => format!("{address}:{DEFAULT_PORT}"),
+        };
+        Self::spawn(config, move |_| TcpStream::connect(&address).map(Some))
+    }
+
+    // Runs `connect` in a background thread, then streams the messages both ways. Without
+    // network access the connection is closed at once, without opening any socket
+    fn spawn<F>(config: &Config, connect: F) -> Self
+    where
+        F: FnOnce(&Receiver<NetMessage>) -> std::io::Result<Option<TcpStream>> + Send + 'static,
+    {
+        let (events_tx, events_rx) = channel();
+        let (outgoing_tx, outgoing_rx) = channel();
+        let allowed = config.network_allowed();
+        thread::spawn(move || {
+            if !allowed {
+                let reason = "the network is disabled by the safe mode".to_string();
+                events_tx.send(NetEvent::Disconnected(reason)).ok();
+                return;
+            }
+            let stream = match connect(&outgoing_rx) {
+                Ok(Some(s)) => s,
+                Ok(None) => return,
+                Err(e) => {
+                    events_tx.send(NetEvent::Disconnected(e.to_string())).ok();
+                    return;
+                }
+            };
+            events_tx.send(NetEvent::Connected).ok();
+            let reader = match stream.try_clone() {
+                Ok(s) => s,
+                Err(e) => {
+                    events_tx.send(NetEvent::Disconnected(e.to_string())).ok();
+                    return;
+                }
+            };
+            thread::spawn(move || read_messages(reader, events_tx));
+            write_messages(stream, outgoing_rx);
+        });
+        Self {
+            events: Mutex::new(events_rx),
+            outgoing: Mutex::new(outgoing_tx),
+        }
+    }
+
+    pub fn send(&self, message: NetMessage) {
+        if let Ok(outgoing) = self.outgoing.lock() {
+            outgoing.send(message).ok();
+        }
+    }
+
+    // Events received since the last poll
+    pub fn poll(&self) -> Vec<NetEvent> {
+        match self.events.lock() {
+            Ok(events) => events.try_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+// Reads a message line without its line break, or None at the end of the stream. Lines over
+// `MAX_LINE` bytes are refused without being read to their end
+fn read_line(reader: &mut impl BufRead) -> Result<Option<String>, String> {
+    let mut line = Vec::new();
+    let read = reader
+        .by_ref()
+        .take(MAX_LINE + 1)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| e.to_string())?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.last() == Some(&b'\n') {
+        line.pop();
+    } else if read as u64 > MAX_LINE {
+        return Err(format!("Message over {MAX_LINE} bytes"));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|e| format!("Invalid message: {e}"))
+}
+
+fn parse(line: &str) -> Result<NetMessage, String> {
+    let message: NetMessage = ron::from_str(line).map_err(|e| format!("Invalid message: {e}"))?;
+    message
+        .validate()
+        .map_err(|e| format!("Invalid message: {e}"))?;
+    Ok(message)
+}
+
+fn read_messages(stream: TcpStream, events: Sender<NetEvent>) {
+    let mut reader = BufReader::new(stream);
+    loop {
+        let event = match read_line(&mut reader).and_then(|l| l.map(|l| parse(&l)).transpose()) {
+            Ok(Some(message)) => NetEvent::Message(message),
+            Ok(None) => NetEvent::Disconnected("Connection closed".to_string()),
+            Err(e) => {
+                // the rest of the stream isn't read
+                reader.get_ref().shutdown(Shutdown::Both).ok();
+                NetEvent::Disconnected(e)
+            }
+        };
+        let disconnected = matches!(event, NetEvent::Disconnected(_));
+        if events.send(event).is_err() || disconnected {
+            return;
+        }
+    }
+}
+
+// Writes the outgoing messages until the connection is dropped
+fn write_messages(mut stream: TcpStream, outgoing: Receiver<NetMessage>) {
+    for message in outgoing.iter() {
+        let line = match ron::to_string(&message) {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        if writeln!(stream, "{line}").is_err() {
+            break;
+        }
+    }
+    // unblocks the reader thread
+    stream.shutdown(Shutdown::Both).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board_plugin::components::Coordinates;
+    use board_plugin::resources::TileState;
+    use std::io::Cursor;
+
+    fn line(message: &NetMessage) -> String {
+        ron::to_string(message).unwrap()
+    }
+
+    #[test]
+    fn lines_over_the_limit_are_refused() {
+        let mut long = vec![b'a'; MAX_LINE as usize + 1];
+        long.push(b'\n');
+        let mut reader = Cursor::new(long);
+        assert!(read_line(&mut reader).is_err());
+        // refused without reading the whole line
+        assert!(reader.position() <= MAX_LINE + 1);
+
+        let mut reader = Cursor::new(b"Mismatch\nReady(hash:3)".to_vec());
+        assert_eq!(read_line(&mut reader), Ok(Some("Mismatch".to_string())));
+        assert_eq!(
+            read_line(&mut reader),
+            Ok(Some("Ready(hash:3)".to_string()))
+        );
+        assert_eq!(read_line(&mut reader), Ok(None));
+    }
+
+    #[test]
+    fn messages_out_of_bounds_are_refused() {
+        let name = "a name longer than a profile name".to_string();
+        assert!(parse(&line(&NetMessage::Hello { name, version: 2 })).is_err());
+        let options = BoardOptions {
+            map_size: (MAX_RACE_SIDE + 1, 10),
+            ..Default::default()
+        };
+        assert!(parse(&line(&NetMessage::Start { options })).is_err());
+        let huge = BoardDiff {
+            size: (u16::MAX, u16::MAX),
+            changes: Vec::new(),
+        };
+        assert!(parse(&line(&NetMessage::Progress(huge))).is_err());
+        let repeated = BoardDiff {
+            size: (1, 1),
+            changes: vec![(Coordinates { x: 0, y: 0 }, TileState::Revealed); 2],
+        };
+        assert!(parse(&line(&NetMessage::Progress(repeated))).is_err());
+        let time = NetMessage::Finished(Outcome::Cleared(-1.));
+        assert!(parse(&line(&time)).is_err());
+    }
+
+    #[test]
+    fn race_messages_are_read() {
+        let diff = BoardDiff {
+            size: (9, 9),
+            changes: vec![(Coordinates { x: 8, y: 8 }, TileState::Flagged)],
+        };
+        let messages = [
+            NetMessage::Hello {
+                name: "Ada".to_string(),
+                version: PROTOCOL_VERSION,
+            },
+            NetMessage::Start {
+                options: BoardOptions::default(),
+            },
+            NetMessage::Ready { hash: 7 },
+            NetMessage::Progress(diff),
+            NetMessage::Finished(Outcome::Cleared(12.5)),
+            NetMessage::Finished(Outcome::Exploded),
+            NetMessage::Mismatch,
+        ];
+        for message in messages.iter() {
+            assert!(parse(&line(message)).is_ok(), "{message:?} refused");
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::Profile;
+use crate::settings::Settings;
+use crate::stats::Stats;
+use crate::thumbnail::{self, PREVIEW_SIZE};
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets, NORMAL_BUTTON};
+use crate::AppState;
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::generation::{FixedGenerator, RegisterBoardGenerator};
+use board_plugin::grid::GridKind;
+use board_plugin::resources::{tile_map::TileMap, BoardOptions, GameTimer};
+use minesweeper_core::Game;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Puzzles per row of the selection grid
+const GRID_COLUMNS: usize = 4;
+
+// Directory of the `.ron` puzzle files, listed in file name order
+fn puzzles_dir() -> PathBuf {
+    FileAssetReader::get_base_path()
+        .join("assets")
+        .join("puzzles")
+}
+
+// Content of a puzzle file
+#[derive(Debug, Deserialize)]
+struct PuzzleFile {
+    name: String,
+    // Text board, see `TileMap::from_text`
+    board: String,
+    #[serde(default)]
+    grid: GridKind,
+    // Tiles uncovered on start
+    #[serde(default)]
+    reveals: Vec<Coordinates>,
+}
+
+// Hand made board with a fixed layout
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    // File name without extension, the best times are saved under it
+    pub id: String,
+    pub name: String,
+    pub tile_map: TileMap,
+}
+
+impl Puzzle {
+    fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: PuzzleFile = ron::from_str(&content).map_err(|e| e.to_string())?;
+        let mut tile_map = TileMap::from_text(&file.board, file.grid).map_err(|e| e.to_string())?;
+        let unsafe_reveal = file
+            .reveals
+            .iter()
+            .find(|c| !tile_map.exists(**c) || tile_map.is_bomb_at(**c));
+        if let Some(coordinates) = unsafe_reveal {
+            return Err(format!(
+                "the starting reveal {coordinates} isn't a safe tile"
+            ));
+        }
+        tile_map.set_reveals(file.reveals);
+        let id = path
+            .file_stem()
+            .map_or(String::new(), |s| s.to_string_lossy().to_string());
+        Ok(Self {
+            id,
+            name: file.name,
+            tile_map,
+        })
+    }
+
+    // Name of the generator dealing the puzzle board
+    fn generator(&self) -> String {
+        format!("puzzle:{}", self.id)
+    }
+
+    // Starting board of the puzzle, its reveals uncovered
+    fn start(&self) -> Game {
+        let mut game = Game::new(self.tile_map.clone());
+        game.open();
+        game
+    }
+
+    // Options of the puzzle board, without assist changing the board or the rules
+    pub fn board_options(&self, settings: &Settings) -> BoardOptions {
+        let base = BoardOptions {
+            preview_rerolls: None,
+            undos: 0,
+            lives: 0,
+            relaxed: false,
+            ..game_options(Difficulty::Beginner, settings)
+        };
+        FixedGenerator(self.tile_map.clone()).options(&self.generator(), base)
+    }
+}
+
+// Puzzles of the `assets/puzzles` directory. Must be used as a resource
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Puzzles(pub Vec<Puzzle>);
+
+impl Puzzles {
+    // Loads the valid puzzle files, skipping the invalid ones
+    pub fn load() -> Self {
+        let dir = puzzles_dir();
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "ron"))
+                .collect(),
+            Err(e) => {
+                warn!("No puzzles in {}: {e}", dir.display());
+                return Self::default();
+            }
+        };
+        paths.sort();
+        let puzzles = paths
+            .iter()
+            .filter_map(|path| match Puzzle::load(path) {
+                Ok(puzzle) => Some(puzzle),
+                Err(e) => {
+                    error!("Invalid puzzle {}: {e}", path.display());
+                    None
+                }
+            })
+            .collect();
+        Self(puzzles)
+    }
+}
+
+// Puzzle being played, by index in `Puzzles`. Must be used as a resource
+#[derive(Resource)]
+pub struct PuzzleRun(pub usize);
+
+// Outcome of the last played puzzle, shown above the selection grid. Must be used as a resource
+#[derive(Resource)]
+struct PuzzleOutcome(String);
+
+#[derive(Debug, Copy, Clone, Component)]
+enum PuzzleButton {
+    Play(usize),
+    Back,
+}
+
+#[derive(Component)]
+struct PuzzlesRoot;
+
+// Hand made boards with fixed layouts and starting reveals. The puzzles state is the
+// selection grid, showing the best time of every solved puzzle
+pub struct PuzzlePlugin;
+
+impl Plugin for PuzzlePlugin {
+    fn build(&self, app: &mut App) {
+        let puzzles = Puzzles::load();
+        info!("Loaded {} puzzles", puzzles.0.len());
+        // the replays of the puzzles generate their boards again
+        for puzzle in puzzles.0.iter() {
+            app.register_board_generator(
+                &puzzle.generator(),
+                FixedGenerator(puzzle.tile_map.clone()),
+            );
+        }
+        app.insert_resource(puzzles)
+            .on_enter(AppState::Puzzles, setup_puzzles)
+            .on_update(AppState::Puzzles, puzzle_buttons)
+            .on_exit(AppState::Puzzles, (cleanup::<PuzzlesRoot>, remove_outcome))
+            .on_update(AppState::InGame, puzzle_over);
+    }
+}
+
+fn setup_puzzles(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    puzzles: Res<Puzzles>,
+    stats: Res<Stats>,
+    outcome: Option<Res<PuzzleOutcome>>,
+    locale: Res<Locale>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(PuzzlesRoot)
+        .insert(Name::new("Puzzles"))
+        .with_children(|parent| {
+            if let Some(outcome) = outcome {
+                spawn_text(parent, font, &outcome.0, 20.);
+            }
+            spawn_text(parent, font, locale.get("puzzles.title"), 40.);
+            if puzzles.0.is_empty() {
+                spawn_text(parent, font, locale.get("puzzles.none"), 16.);
+            }
+            let indexed: Vec<(usize, &Puzzle)> = puzzles.0.iter().enumerate().collect();
+            for row in indexed.chunks(GRID_COLUMNS) {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for (index, puzzle) in row {
+                            let status = match stats.puzzle_times.get(&puzzle.id) {
+                                Some(time) => locale
+                                    .format("puzzles.best", &[("time", &format!("{time:.1}"))]),
+                                None => locale.get("puzzles.unsolved").to_string(),
+                            };
+                            let title = format!("{}. {}", index + 1, puzzle.name);
+                            let preview = puzzle_preview(puzzle, &mut images);
+                            spawn_puzzle_button(parent, font, &title, &status, preview, *index);
+                        }
+                    });
+            }
+            spawn_button(parent, font, locale.get("common.back"), PuzzleButton::Back);
+        });
+}
+
+// Thumbnail of the starting board of a puzzle, with its size
+fn puzzle_preview(puzzle: &Puzzle, images: &mut Assets<Image>) -> Option<(Handle<Image>, Vec2)> {
+    match thumbnail::preview(&puzzle.start()) {
+        Ok(preview) => {
+            let size = Vec2::new(preview.width() as f32, preview.height() as f32);
+            Some((images.add(thumbnail::texture(&preview)), size))
+        }
+        Err(e) => {
+            error!(
+                "Failed to draw the thumbnail of the {} puzzle: {e}",
+                puzzle.name
+            );
+            None
+        }
+    }
+}
+
+// Cell of the selection grid, with the puzzle thumbnail over its name and status
+fn spawn_puzzle_button(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    title: &str,
+    status: &str,
+    preview: Option<(Handle<Image>, Vec2)>,
+    index: usize,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(150.),
+                height: Val::Px(PREVIEW_SIZE as f32 + 80.),
+                margin: UiRect::all(Val::Px(6.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+        ))
+        .insert(PuzzleButton::Play(index))
+        .with_children(|parent| {
+            if let Some((image, size)) = preview {
+                parent.spawn((
+                    ImageNode::new(image),
+                    Node {
+                        width: Val::Px(size.x),
+                        height: Val::Px(size.y),
+                        margin: UiRect::all(Val::Px(4.)),
+                        ..Default::default()
+                    },
+                ));
+            }
+            spawn_text(parent, font, title, 12.);
+            spawn_text(parent, font, status, 10.);
+        });
+}
+
+fn puzzle_buttons(
+    mut cmds: Commands,
+    puzzles: Res<Puzzles>,
+    settings: Res<Settings>,
+    buttons: Query<(&Interaction, &PuzzleButton), Changed<Interaction>>,
+    mut state: StateSwitch<AppState>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PuzzleButton::Play(index) => {
+                let puzzle = &puzzles.0[*index];
+                info!("Playing the {} puzzle", puzzle.name);
+                cmds.insert_resource(puzzle.board_options(&settings));
+                cmds.insert_resource(PuzzleRun(*index));
+                state.set(AppState::InGame);
+            }
+            PuzzleButton::Back => {
+                state.set(AppState::Menu);
+            }
+        }
+    }
+}
+
+// Records the time of a solved puzzle, and goes back to the selection grid once the puzzle is
+// solved or lost
+#[allow(clippy::too_many_arguments)]
+fn puzzle_over(
+    mut cmds: Commands,
+    run: Option<Res<PuzzleRun>>,
+    puzzles: Res<Puzzles>,
+    timer: Res<GameTimer>,
+    mut stats: ResMut<Stats>,
+    profile: Res<Profile>,
+    locale: Res<Locale>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    let puzzle = match run {
+        Some(run) => &puzzles.0[run.0],
+        None => return,
+    };
+
+    let outcome = if board_completed_evr.read().count() > 0 {
+        let elapsed = timer.elapsed();
+        let best = stats.puzzle_times.get(&puzzle.id).copied();
+        let seconds = format!("{elapsed:.1}");
+        let args: [(&str, &dyn Display); 2] = [("name", &puzzle.name), ("time", &seconds)];
+        let outcome = match best {
+            Some(best) if best <= elapsed => locale.format("puzzles.solved", &args),
+            _ => locale.format("puzzles.record", &args),
+        };
+        info!("Puzzle {} solved in {elapsed:.2}s", puzzle.name);
+        let best = best.map_or(elapsed, |b| b.min(elapsed));
+        stats.puzzle_times.insert(puzzle.id.clone(), best);
+        stats.save(&profile);
+        outcome
+    } else if bomb_explosion_evr.read().count() > 0 {
+        locale.format("puzzles.boom", &[("name", &puzzle.name)])
+    } else {
+        return;
+    };
+    cmds.insert_resource(PuzzleOutcome(outcome));
+    cmds.remove_resource::<PuzzleRun>();
+    state.set(AppState::Puzzles);
+}
+
+fn remove_outcome(mut cmds: Commands) {
+    cmds.remove_resource::<PuzzleOutcome>();
+}
@@ -0,0 +1,180 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::versus_options;
+use crate::results::GameResult;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent, SpawnBoardEvent};
+use board_plugin::grid::GridLayout;
+use board_plugin::resources::{
+    Board, BoardControls, BoardOptions, BoardPosition, GameTimer, TileSize,
+};
+
+// Window space kept around each half of the split screen
+const RACE_MARGIN: f32 = 40.;
+
+// Race players, player one plays with the mouse and player two with the keyboard or a gamepad
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    // Player of a race board
+    fn of(board: &Board) -> Self {
+        match board.options.controls {
+            BoardControls::Mouse | BoardControls::Shared => Self::One,
+            BoardControls::Keyboard => Self::Two,
+        }
+    }
+
+    fn opponent(self) -> Self {
+        match self {
+            Self::One => Self::Two,
+            Self::Two => Self::One,
+        }
+    }
+
+    fn name<'a>(&self, locale: &'a Locale) -> &'a str {
+        locale.get(match self {
+            Self::One => "race.player_one",
+            Self::Two => "race.player_two",
+        })
+    }
+}
+
+// Local split screen race in progress. Must be used as a resource
+#[derive(Resource)]
+pub struct Race {
+    // Options of the player two board, spawned next to the board of the `BoardOptions` resource
+    second_board: BoardOptions,
+}
+
+#[derive(Component)]
+struct RaceRoot;
+
+// Two boards generated from the same seed side by side, first to clear (or last to survive) wins
+pub struct RacePlugin;
+
+impl Plugin for RacePlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_race)
+            .on_update(AppState::InGame, race_over)
+            .on_exit(AppState::InGame, cleanup::<RaceRoot>)
+            .on_exit(AppState::Results, end_race);
+    }
+}
+
+// Options of the player one board, and the race spawning the player two board.
+// Each board fits half of the window
+pub fn race_options(
+    difficulty: Difficulty,
+    settings: &Settings,
+    window: Vec2,
+) -> (BoardOptions, Race) {
+    let options = versus_options(difficulty, settings);
+    let (width, height) = options.map_size;
+    let unit = options.grid.grid().board_size(width, height, 1.);
+    let tile_size = ((window.x / 2. - RACE_MARGIN) / unit.x)
+        .min((window.y - RACE_MARGIN) / unit.y)
+        .clamp(10., 50.);
+    let half = |offset: f32, controls: BoardControls| BoardOptions {
+        position: BoardPosition::Centered {
+            offset: Vec3::new(offset, 0., 0.),
+        },
+        tile_size: TileSize::Fixed(tile_size as u32),
+        controls,
+        ..options.clone()
+    };
+    (
+        half(-window.x / 4., BoardControls::Mouse),
+        Race {
+            second_board: half(window.x / 4., BoardControls::Keyboard),
+        },
+    )
+}
+
+// Spawns the player two board and the controls reminder
+fn setup_race(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    race: Option<Res<Race>>,
+    locale: Res<Locale>,
+    mut spawn_board_ewr: MessageWriter<SpawnBoardEvent>,
+) {
+    let race = match race {
+        Some(r) => r,
+        None => return,
+    };
+    spawn_board_ewr.write(SpawnBoardEvent(race.second_board.clone()));
+
+    let font = &ui_assets.font;
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(RaceRoot)
+    .insert(Name::new("Race Controls"))
+    .with_children(|parent| {
+        spawn_text(parent, font, locale.get("race.controls_one"), 12.);
+        spawn_text(parent, font, locale.get("race.controls_two"), 12.);
+    });
+}
+
+// Ends the race when a board is cleared or a player hits a mine
+#[allow(clippy::too_many_arguments)]
+fn race_over(
+    mut cmds: Commands,
+    race: Option<Res<Race>>,
+    timer: Res<GameTimer>,
+    boards: Query<&Board>,
+    locale: Res<Locale>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    if race.is_none() {
+        return;
+    }
+    let cleared = board_completed_evr
+        .read()
+        .find_map(|e| boards.get(e.board).ok())
+        .map(Player::of);
+    let exploded = bomb_explosion_evr
+        .read()
+        .find_map(|e| boards.get(e.board).ok())
+        .map(Player::of);
+
+    let (winner, details) = match (cleared, exploded) {
+        (Some(winner), _) => {
+            let elapsed = timer.elapsed();
+            let time = format!("{elapsed:.2}");
+            (winner, locale.format("result.cleared", &[("time", &time)]))
+        }
+        (None, Some(loser)) => {
+            let details = locale.format("race.exploded", &[("player", &loser.name(&locale))]);
+            (loser.opponent(), details)
+        }
+        (None, None) => return,
+    };
+    info!("{winner:?} wins the race: {details}");
+    cmds.insert_resource(GameResult {
+        headline: locale.format("race.wins", &[("player", &winner.name(&locale))]),
+        details,
+    });
+    state.set(AppState::Results);
+}
+
+fn end_race(mut cmds: Commands) {
+    cmds.remove_resource::<Race>();
+}
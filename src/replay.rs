@@ -0,0 +1,102 @@
+use crate::blitz::Blitz;
+use crate::coop::Coop;
+use crate::demo::Demo;
+use crate::locale::Locale;
+use crate::persistence::{self, Profile};
+use crate::protocol::Connection;
+use crate::race::Race;
+use crate::toast::ToastEvent;
+use crate::tutorial::Tutorial;
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::resources::{Replay, ReplayPlayback, ReplayRecorder};
+
+const REPLAY_FILE: &str = "last_replay.ron";
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 16.;
+
+// Loads the replay of the last played game
+pub fn load_last_replay(profile: &Profile) -> Option<Replay> {
+    persistence::load(profile, REPLAY_FILE)
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, (replay_speed, warn_desync))
+            .on_exit(AppState::InGame, (save_replay, end_playback));
+    }
+}
+
+// Up and Down arrows change the playback speed
+fn replay_speed(keys: Res<ButtonInput<KeyCode>>, playback: Option<ResMut<ReplayPlayback>>) {
+    let mut playback = match playback {
+        Some(p) => p,
+        None => return,
+    };
+    let speed = if keys.just_pressed(KeyCode::ArrowUp) {
+        playback.speed * 2.
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        playback.speed / 2.
+    } else {
+        return;
+    };
+    playback.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    info!("Replay speed: x{}", playback.speed);
+}
+
+// Tells the player once when the played back boards stop matching the recorded state hashes,
+// the replay then shows another game than the recorded one
+fn warn_desync(
+    playback: Option<Res<ReplayPlayback>>,
+    locale: Res<Locale>,
+    mut warned: Local<bool>,
+    mut toast_ewr: MessageWriter<ToastEvent>,
+) {
+    let playback = match playback {
+        Some(p) => p,
+        None => return,
+    };
+    if playback.is_added() {
+        *warned = false;
+    }
+    if playback.desync.is_some() && !*warned {
+        *warned = true;
+        toast_ewr.write(ToastEvent::error(locale.get("toast.replay_desync")));
+    }
+}
+
+fn save_replay(
+    recorders: Query<&ReplayRecorder>,
+    modes: (
+        Option<Res<Race>>,
+        Option<Res<Coop>>,
+        Option<Res<Connection>>,
+        Option<Res<Blitz>>,
+        Option<Res<Demo>>,
+        Option<Res<Tutorial>>,
+    ),
+    profile: Res<Profile>,
+) {
+    // replays are single player, on a single board, and demos or the tutorial would replace the
+    // player's
+    let (race, coop, connection, blitz, demo, tutorial) = modes;
+    if race.is_some()
+        || coop.is_some()
+        || connection.is_some()
+        || blitz.is_some()
+        || demo.is_some()
+        || tutorial.is_some()
+    {
+        return;
+    }
+    if let Some(recorder) = recorders.iter().find(|r| !r.replay.events.is_empty()) {
+        persistence::save(&profile, REPLAY_FILE, &Some(recorder.replay.clone()));
+    }
+}
+
+fn end_playback(mut cmds: Commands) {
+    cmds.remove_resource::<ReplayPlayback>();
+}
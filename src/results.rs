@@ -0,0 +1,62 @@
+use crate::locale::Locale;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+
+// Outcome of a game mode without leaderboard, such as races or blitz runs.
+// Must be used as a resource
+#[derive(Resource)]
+pub struct GameResult {
+    // Winner or score announcement
+    pub headline: String,
+    // How the game was decided
+    pub details: String,
+}
+
+#[derive(Component)]
+struct ResultsRoot;
+
+#[derive(Component)]
+struct BackButton;
+
+// Results screen of the `GameResult` resource
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Results, setup_results)
+            .on_update(AppState::Results, results_buttons)
+            .on_exit(AppState::Results, (cleanup::<ResultsRoot>, remove_result));
+    }
+}
+
+fn setup_results(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    result: Res<GameResult>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(ResultsRoot)
+        .insert(Name::new("Results"))
+        .with_children(|parent| {
+            spawn_text(parent, font, &result.headline, 40.);
+            spawn_text(parent, font, &result.details, 16.);
+            spawn_button(parent, font, locale.get("common.back"), BackButton);
+        });
+}
+
+fn results_buttons(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Menu);
+    }
+}
+
+fn remove_result(mut cmds: Commands) {
+    cmds.remove_resource::<GameResult>();
+}
@@ -0,0 +1,207 @@
+use crate::difficulty::Difficulty;
+use crate::leaderboard::{is_ranked, Category, Leaderboard, PendingScore};
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::grid::{GridKind, NeighborKernel};
+use board_plugin::resources::{Board, BoardOptions, ChainReaction, GameTimer, Generation};
+use minesweeper_core::Game;
+
+// Points of a revealed tile, before the combo multiplier
+const TILE_POINTS: u32 = 10;
+// Seconds between two reveals keeping the combo going
+const COMBO_WINDOW: f32 = 1.5;
+// Highest combo multiplier
+const MAX_COMBO: u32 = 8;
+// Points taken off for every flag on a safe tile
+const WRONG_FLAG_PENALTY: u32 = 50;
+
+// Score of a finished score attack game
+#[derive(Debug, Copy, Clone)]
+pub struct FinalScore {
+    // Points of the reveals, combos included
+    pub points: u32,
+    // Multiplier of the flags on bombs, from 1 without any to 2 with every bomb flagged
+    pub accuracy: f32,
+    pub wrong_flags: u32,
+    pub score: u32,
+}
+
+// Score attack game in progress. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct ScoreAttack {
+    points: u32,
+    combo: u32,
+    // Game time of the last reveal
+    last_reveal: Option<f32>,
+    // Moves of the game already scored
+    moves: usize,
+    // Set once the game is over
+    pub result: Option<FinalScore>,
+}
+
+impl ScoreAttack {
+    // Scores the moves played since the last call. Reveals following each other quickly raise
+    // the combo multiplier, a slower one starts it over
+    fn score_moves(&mut self, game: &Game, now: f32) {
+        for delta in game.history().iter().skip(self.moves) {
+            if delta.revealed.is_empty() {
+                continue;
+            }
+            self.combo = match self.last_reveal {
+                Some(last) if now - last <= COMBO_WINDOW => (self.combo + 1).min(MAX_COMBO),
+                _ => 1,
+            };
+            self.points += delta.revealed.len() as u32 * TILE_POINTS * self.combo;
+            self.last_reveal = Some(now);
+        }
+        self.moves = game.history().len();
+    }
+
+    // Final score of the game. The flags are only judged once the game is over, a penalty
+    // during the game would give the bombs away
+    fn final_score(&self, game: &Game) -> FinalScore {
+        let tile_map = game.tile_map();
+        let (right, wrong): (Vec<_>, Vec<_>) =
+            game.flags().iter().partition(|c| tile_map.is_bomb_at(*c));
+        let accuracy = 1. + right.len() as f32 / tile_map.bomb_count().max(1) as f32;
+        let wrong_flags = wrong.len() as u32;
+        let score = (self.points as f32 * accuracy) as u32;
+        FinalScore {
+            points: self.points,
+            accuracy,
+            wrong_flags,
+            score: score.saturating_sub(wrong_flags * WRONG_FLAG_PENALTY),
+        }
+    }
+}
+
+#[derive(Component)]
+struct ScoreAttackRoot;
+
+#[derive(Component)]
+struct ScoreAttackHud;
+
+// Arcade mode scoring the revealed tiles, with combos for fast reveals and a final multiplier
+// for the flags on bombs. The score shows on the summary and ranks on its own leaderboard
+pub struct ScoreAttackPlugin;
+
+impl Plugin for ScoreAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_score_attack_hud)
+            .on_update(AppState::InGame, score_attack)
+            .on_exit(AppState::InGame, cleanup::<ScoreAttackRoot>)
+            // kept through the summary and its restarts
+            .on_enter(AppState::Menu, end_score_attack);
+    }
+}
+
+// Options of a score attack board, every move counting. The variants are turned off, the scores
+// of every difficulty ranking on the standard boards only
+pub fn score_attack_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        grid: GridKind::Square,
+        kernel: NeighborKernel::Grid,
+        wrap: false,
+        max_mines: 1,
+        generation: Generation::Uniform,
+        chain_reaction: ChainReaction::Off,
+        auto_flag: false,
+        no_flags: false,
+        treasures: 0,
+        undos: 0,
+        lives: 0,
+        relaxed: false,
+        ..game_options(difficulty, settings)
+    }
+}
+
+// Starts the score over for every board, restarts included
+fn setup_score_attack_hud(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    attack: Option<ResMut<ScoreAttack>>,
+) {
+    let mut attack = match attack {
+        Some(a) => a,
+        None => return,
+    };
+    *attack = ScoreAttack::default();
+
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(ScoreAttackRoot)
+    .insert(Name::new("Score Attack HUD"))
+    .with_children(|parent| {
+        spawn_text(parent, &ui_assets.font, "", 16.).insert(ScoreAttackHud);
+    });
+}
+
+// Scores the moves, and keeps the final score of a finished game for the summary and the
+// leaderboard. Assisted games and empty scores don't rank
+#[allow(clippy::too_many_arguments)]
+fn score_attack(
+    mut cmds: Commands,
+    attack: Option<ResMut<ScoreAttack>>,
+    timer: Res<GameTimer>,
+    difficulty: Res<Difficulty>,
+    leaderboard: Res<Leaderboard>,
+    boards: Query<&Board>,
+    mut huds: Query<&mut Text, With<ScoreAttackHud>>,
+    mut board_completed_evr: MessageReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: MessageReader<BombExplosionEvent>,
+    locale: Res<Locale>,
+) {
+    let mut attack = match attack {
+        Some(a) => a,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    attack.score_moves(&board.game, timer.elapsed());
+    for mut text in huds.iter_mut() {
+        text.0 = locale.format(
+            "score_attack.hud",
+            &[("score", &attack.points), ("combo", &attack.combo.max(1))],
+        );
+    }
+
+    let won = board_completed_evr.read().count() > 0;
+    let exploded = bomb_explosion_evr.read().count() > 0;
+    if !won && !exploded {
+        return;
+    }
+    let result = attack.final_score(&board.game);
+    info!("Score attack over, {} points", result.score);
+    attack.result = Some(result);
+    let ranked =
+        result.score > 0 && board.assists.is_pure() && is_ranked(&board.options, *difficulty);
+    if ranked && leaderboard.score_qualifies(*difficulty, result.score) {
+        cmds.insert_resource(PendingScore {
+            difficulty: *difficulty,
+            category: Category::Standard,
+            time: timer.elapsed(),
+            penalty: 0.,
+            assists: board.assists,
+            score: Some(result.score),
+        });
+    }
+}
+
+fn end_score_attack(mut cmds: Commands) {
+    cmds.remove_resource::<ScoreAttack>();
+}
@@ -0,0 +1,720 @@
+use crate::announce::Announcements;
+use crate::config::{Config, CONFIG_FILE};
+use crate::custom::CustomBoard;
+use crate::display::{DisplayMode, DisplaySettings};
+use crate::locale::{Locale, DEFAULT_LANGUAGE};
+use crate::persistence::{self, data_dir, Profile};
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets, UiTheme};
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use board_plugin::engine::{Is2dCamera, StateSwitch, StateSystems};
+use board_plugin::resources::{
+    BoardAssets, BoardDecorations, InputMap, NumberPalette, TileColors, TileSheet,
+};
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_FILE: &str = "settings.ron";
+// UI scales cycled through by the settings button
+const UI_SCALES: [f32; 4] = [1., 1.25, 1.5, 2.];
+// Casual lives cycled through by the settings button
+const CASUAL_LIVES: [u8; 5] = [0, 1, 2, 3, 5];
+
+// Persisted player settings. Must be used as a resource
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Last game version the player has seen the changelog of
+    pub version: Option<String>,
+    // Was the tutorial cleared or skipped?
+    pub tutorial_done: bool,
+    // Uncover a random empty tile when a game starts
+    pub safe_start: bool,
+    // Generate boards solvable without guessing
+    pub no_guess: bool,
+    // Generate boards without pockets of safe tiles sealed off by bombs
+    pub connected: bool,
+    // Automatically flag trivially forced bombs
+    pub auto_flag: bool,
+    // Refuse flags beyond the bomb count
+    pub strict_flags: bool,
+    // No-flag play, ranked apart
+    pub no_flags: bool,
+    // Preview the board, with a few rerolls, before playing it
+    pub board_preview: bool,
+    // Allow undoing moves, and surviving bombs, for a time penalty
+    pub casual: bool,
+    // Bombs survived per game in casual mode, each one flagged instead of ending the game
+    pub casual_lives: u8,
+    // Relaxed play: no timer, unlimited undos, bombs flagged instead of lost and calm colors,
+    // without ranking
+    pub zen: bool,
+    // Competitive play: no hints, odds, undos, lives or auto flags, ranked apart
+    pub competitive: bool,
+    // Race the ghost of the best run on seeded boards
+    pub time_trial: bool,
+    // Play on hexagonal tiles
+    pub hex_grid: bool,
+    // Numbers counting the bombs a knight's move away instead of the adjacent ones
+    pub knight_moves: bool,
+    // Wrap-around boards, whose opposite edges touch
+    pub wrap_edges: bool,
+    // Bombs stacking up to three mines, with numbers above 8 and stacked flags
+    pub multi_mines: bool,
+    // Ask for a second click on the last guess of a game
+    pub confirm_last_guess: bool,
+    // Ask before quitting or restarting a game in progress
+    pub confirm_abandon: bool,
+    // Send the daily challenge times to the online leaderboard of the config file
+    pub submit_daily: bool,
+    // Draw the square tiles from the classic sprite sheet
+    pub classic_tiles: bool,
+    // Chiseled covers, grid lines and a frame around the board
+    pub decorations: bool,
+    // Crisp sprites on whole pixels, without anti-aliasing
+    pub pixel_art: bool,
+    // Burst particles and shake the screen on explosions
+    pub explosion_effects: bool,
+    // Blow up the bombs next to an exploded bomb in a chain before the game over
+    pub chain_reaction: bool,
+    // Chain reactions flag the bombs next to the forgiven mistakes, lowering their penalty
+    pub chain_scoring: bool,
+    // No camera shake, cascading reveals, growing flags or sliding notifications
+    pub reduced_motion: bool,
+    // Color the bomb counts with a color-blind safe palette
+    pub color_blind_numbers: bool,
+    // Light tile covers on black tiles, and black menus
+    pub high_contrast: bool,
+    // Keep the system cursor over the boards instead of the tool sprites
+    pub system_cursor: bool,
+    // Multiplier of the menu, HUD and bomb counter font sizes
+    pub ui_scale: f32,
+    // Where the game events are announced
+    pub announcements: Announcements,
+    // Language of the locale file of the user facing strings
+    pub language: String,
+    // Mouse buttons and keys of the player actions
+    pub input_map: InputMap,
+    // Window mode, vsync and resolution
+    pub display: DisplaySettings,
+    // Last board of the custom board dialog
+    pub custom_board: CustomBoard,
+    // Settings replaced by the command line for this session only
+    #[serde(skip)]
+    pub session: SessionOverrides,
+}
+
+// Settings replaced for the session by the command line flags, with the profile values they
+// replaced. The profile values are saved in their place until the player changes the setting
+#[derive(Debug, Clone, Default)]
+pub struct SessionOverrides {
+    // Window mode of the session, then of the profile
+    display_mode: Option<(DisplayMode, DisplayMode)>,
+    // Classic tiles and high contrast of the session, then of the profile
+    theme: Option<((bool, bool), (bool, bool))>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: None,
+            tutorial_done: false,
+            safe_start: true,
+            no_guess: false,
+            connected: false,
+            auto_flag: false,
+            strict_flags: false,
+            no_flags: false,
+            board_preview: false,
+            casual: false,
+            casual_lives: 3,
+            zen: false,
+            competitive: false,
+            time_trial: false,
+            hex_grid: false,
+            knight_moves: false,
+            wrap_edges: false,
+            multi_mines: false,
+            confirm_last_guess: false,
+            confirm_abandon: true,
+            submit_daily: false,
+            classic_tiles: false,
+            decorations: false,
+            pixel_art: false,
+            explosion_effects: true,
+            chain_reaction: false,
+            chain_scoring: false,
+            reduced_motion: false,
+            color_blind_numbers: false,
+            high_contrast: false,
+            system_cursor: false,
+            ui_scale: 1.,
+            announcements: Announcements::Off,
+            language: DEFAULT_LANGUAGE.to_string(),
+            input_map: InputMap::default(),
+            display: DisplaySettings::default(),
+            custom_board: CustomBoard::default(),
+            session: SessionOverrides::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(profile: &Profile) -> Self {
+        persistence::load(profile, SETTINGS_FILE)
+    }
+
+    pub fn save(&self, profile: &Profile) {
+        let mut saved = self.clone();
+        if let Some((session, kept)) = self.session.display_mode {
+            if saved.display.mode == session {
+                saved.display.mode = kept;
+            }
+        }
+        if let Some((session, kept)) = self.session.theme {
+            if (saved.classic_tiles, saved.high_contrast) == session {
+                (saved.classic_tiles, saved.high_contrast) = kept;
+            }
+        }
+        persistence::save(profile, SETTINGS_FILE, &saved);
+    }
+
+    // Window mode of this session only
+    pub fn override_display_mode(&mut self, mode: DisplayMode) {
+        self.session.display_mode = Some((mode, self.display.mode));
+        self.display.mode = mode;
+    }
+
+    // Tile theme of this session only
+    pub fn override_theme(&mut self, classic_tiles: bool, high_contrast: bool) {
+        let kept = (self.classic_tiles, self.high_contrast);
+        self.session.theme = Some(((classic_tiles, high_contrast), kept));
+        self.classic_tiles = classic_tiles;
+        self.high_contrast = high_contrast;
+    }
+}
+
+// Boolean setting, toggled by the button carrying it
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Component)]
+pub enum SettingToggle {
+    SafeStart,
+    NoGuess,
+    Connected,
+    AutoFlag,
+    StrictFlags,
+    NoFlags,
+    BoardPreview,
+    Casual,
+    Zen,
+    Competitive,
+    TimeTrial,
+    HexGrid,
+    KnightMoves,
+    WrapEdges,
+    MultiMines,
+    ConfirmLastGuess,
+    ConfirmAbandon,
+    SubmitDaily,
+    ClassicTiles,
+    Decorations,
+    PixelArt,
+    ExplosionEffects,
+    ChainReaction,
+    ChainScoring,
+    ReducedMotion,
+    ColorBlindNumbers,
+    HighContrast,
+    SystemCursor,
+}
+
+impl SettingToggle {
+    pub const ALL: [Self; 28] = [
+        Self::SafeStart,
+        Self::NoGuess,
+        Self::Connected,
+        Self::AutoFlag,
+        Self::StrictFlags,
+        Self::NoFlags,
+        Self::BoardPreview,
+        Self::Casual,
+        Self::Zen,
+        Self::Competitive,
+        Self::TimeTrial,
+        Self::HexGrid,
+        Self::KnightMoves,
+        Self::WrapEdges,
+        Self::MultiMines,
+        Self::ConfirmLastGuess,
+        Self::ConfirmAbandon,
+        Self::SubmitDaily,
+        Self::ClassicTiles,
+        Self::Decorations,
+        Self::PixelArt,
+        Self::ExplosionEffects,
+        Self::ChainReaction,
+        Self::ChainScoring,
+        Self::ReducedMotion,
+        Self::ColorBlindNumbers,
+        Self::HighContrast,
+        Self::SystemCursor,
+    ];
+
+    // Locale key of the label
+    fn label(&self) -> &'static str {
+        match self {
+            Self::SafeStart => "settings.safe_start",
+            Self::NoGuess => "settings.no_guess",
+            Self::Connected => "settings.connected",
+            Self::AutoFlag => "settings.auto_flag",
+            Self::StrictFlags => "settings.strict_flags",
+            Self::NoFlags => "settings.no_flags",
+            Self::BoardPreview => "settings.board_preview",
+            Self::Casual => "settings.casual",
+            Self::Zen => "settings.zen",
+            Self::Competitive => "settings.competitive",
+            Self::TimeTrial => "settings.time_trial",
+            Self::HexGrid => "settings.hex_grid",
+            Self::KnightMoves => "settings.knight_moves",
+            Self::WrapEdges => "settings.wrap_edges",
+            Self::MultiMines => "settings.multi_mines",
+            Self::ConfirmLastGuess => "settings.confirm_last_guess",
+            Self::ConfirmAbandon => "settings.confirm_abandon",
+            Self::SubmitDaily => "settings.submit_daily",
+            Self::ClassicTiles => "settings.classic_tiles",
+            Self::Decorations => "settings.decorations",
+            Self::PixelArt => "settings.pixel_art",
+            Self::ExplosionEffects => "settings.explosion_effects",
+            Self::ChainReaction => "settings.chain_reaction",
+            Self::ChainScoring => "settings.chain_scoring",
+            Self::ReducedMotion => "settings.reduced_motion",
+            Self::ColorBlindNumbers => "settings.color_blind_numbers",
+            Self::HighContrast => "settings.high_contrast",
+            Self::SystemCursor => "settings.system_cursor",
+        }
+    }
+
+    fn get(&self, settings: &Settings) -> bool {
+        match self {
+            Self::SafeStart => settings.safe_start,
+            Self::NoGuess => settings.no_guess,
+            Self::Connected => settings.connected,
+            Self::AutoFlag => settings.auto_flag,
+            Self::StrictFlags => settings.strict_flags,
+            Self::NoFlags => settings.no_flags,
+            Self::BoardPreview => settings.board_preview,
+            Self::Casual => settings.casual,
+            Self::Zen => settings.zen,
+            Self::Competitive => settings.competitive,
+            Self::TimeTrial => settings.time_trial,
+            Self::HexGrid => settings.hex_grid,
+            Self::KnightMoves => settings.knight_moves,
+            Self::WrapEdges => settings.wrap_edges,
+            Self::MultiMines => settings.multi_mines,
+            Self::ConfirmLastGuess => settings.confirm_last_guess,
+            Self::ConfirmAbandon => settings.confirm_abandon,
+            Self::SubmitDaily => settings.submit_daily,
+            Self::ClassicTiles => settings.classic_tiles,
+            Self::Decorations => settings.decorations,
+            Self::PixelArt => settings.pixel_art,
+            Self::ExplosionEffects => settings.explosion_effects,
+            Self::ChainReaction => settings.chain_reaction,
+            Self::ChainScoring => settings.chain_scoring,
+            Self::ReducedMotion => settings.reduced_motion,
+            Self::ColorBlindNumbers => settings.color_blind_numbers,
+            Self::HighContrast => settings.high_contrast,
+            Self::SystemCursor => settings.system_cursor,
+        }
+    }
+
+    fn toggle(&self, settings: &mut Settings) {
+        match self {
+            Self::SafeStart => settings.safe_start = !settings.safe_start,
+            Self::NoGuess => settings.no_guess = !settings.no_guess,
+            Self::Connected => settings.connected = !settings.connected,
+            Self::AutoFlag => settings.auto_flag = !settings.auto_flag,
+            Self::StrictFlags => settings.strict_flags = !settings.strict_flags,
+            Self::NoFlags => settings.no_flags = !settings.no_flags,
+            Self::BoardPreview => settings.board_preview = !settings.board_preview,
+            Self::Casual => settings.casual = !settings.casual,
+            Self::Zen => settings.zen = !settings.zen,
+            Self::Competitive => settings.competitive = !settings.competitive,
+            Self::TimeTrial => settings.time_trial = !settings.time_trial,
+            Self::HexGrid => settings.hex_grid = !settings.hex_grid,
+            Self::KnightMoves => settings.knight_moves = !settings.knight_moves,
+            Self::WrapEdges => settings.wrap_edges = !settings.wrap_edges,
+            Self::MultiMines => settings.multi_mines = !settings.multi_mines,
+            Self::ConfirmLastGuess => settings.confirm_last_guess = !settings.confirm_last_guess,
+            Self::ConfirmAbandon => settings.confirm_abandon = !settings.confirm_abandon,
+            Self::SubmitDaily => settings.submit_daily = !settings.submit_daily,
+            Self::ClassicTiles => settings.classic_tiles = !settings.classic_tiles,
+            Self::Decorations => settings.decorations = !settings.decorations,
+            Self::PixelArt => settings.pixel_art = !settings.pixel_art,
+            Self::ExplosionEffects => settings.explosion_effects = !settings.explosion_effects,
+            Self::ChainReaction => settings.chain_reaction = !settings.chain_reaction,
+            Self::ChainScoring => settings.chain_scoring = !settings.chain_scoring,
+            Self::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            Self::ColorBlindNumbers => settings.color_blind_numbers = !settings.color_blind_numbers,
+            Self::HighContrast => settings.high_contrast = !settings.high_contrast,
+            Self::SystemCursor => settings.system_cursor = !settings.system_cursor,
+        }
+    }
+
+    pub fn button_label(&self, settings: &Settings, locale: &Locale) -> String {
+        let check = if self.get(settings) { "x" } else { " " };
+        format!("[{check}] {}", locale.get(self.label()))
+    }
+}
+
+// Toggles the setting of clicked `SettingToggle` buttons
+pub fn toggle_settings(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &SettingToggle, &Children), Changed<Interaction>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, toggle, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        toggle.toggle(&mut settings);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = toggle.button_label(&settings, &locale);
+            }
+        }
+    }
+}
+
+// Cycles through the UI scales
+#[derive(Debug, Copy, Clone, Component)]
+struct UiScaleButton;
+
+fn ui_scale_label(scale: f32, locale: &Locale) -> String {
+    locale.format("settings.ui_scale", &[("percent", &(scale * 100.).round())])
+}
+
+fn ui_scale_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<UiScaleButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // custom scales of the settings file go back to the first one
+        settings.ui_scale = UI_SCALES
+            .iter()
+            .copied()
+            .find(|s| *s > settings.ui_scale)
+            .unwrap_or(UI_SCALES[0]);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = ui_scale_label(settings.ui_scale, &locale);
+            }
+        }
+    }
+}
+
+// Cycles through the lives of the casual mode
+#[derive(Debug, Copy, Clone, Component)]
+struct CasualLivesButton;
+
+fn casual_lives_label(lives: u8, locale: &Locale) -> String {
+    locale.format("settings.casual_lives", &[("lives", &lives)])
+}
+
+fn casual_lives_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<CasualLivesButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // custom counts of the settings file go back to the first one
+        settings.casual_lives = CASUAL_LIVES
+            .iter()
+            .copied()
+            .find(|l| *l > settings.casual_lives)
+            .unwrap_or(CASUAL_LIVES[0]);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = casual_lives_label(settings.casual_lives, &locale);
+            }
+        }
+    }
+}
+
+// Cycles through the announcement outputs
+#[derive(Debug, Copy, Clone, Component)]
+struct AnnouncementsButton;
+
+fn announcements_label(announcements: Announcements, locale: &Locale) -> String {
+    let output = locale.get(announcements.label());
+    locale.format("settings.announcements", &[("output", &output)])
+}
+
+fn announcements_button(
+    mut settings: ResMut<Settings>,
+    locale: Res<Locale>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<AnnouncementsButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let all = Announcements::ALL;
+        let index = all.iter().position(|a| *a == settings.announcements);
+        settings.announcements = all[index.map_or(0, |i| (i + 1) % all.len())];
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = announcements_label(settings.announcements, &locale);
+            }
+        }
+    }
+}
+
+// Cycles through the languages of the locale files
+#[derive(Debug, Copy, Clone, Component)]
+struct LanguageButton;
+
+fn language_label(locale: &Locale) -> String {
+    let language = locale.get("language.name");
+    locale.format("settings.language", &[("language", &language)])
+}
+
+fn language_button(
+    mut settings: ResMut<Settings>,
+    buttons: Query<(&Interaction, &Children), (Changed<Interaction>, With<LanguageButton>)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, children) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let languages = Locale::languages();
+        let index = languages.iter().position(|l| *l == settings.language);
+        settings.language = languages[index.map_or(0, |i| (i + 1) % languages.len())].clone();
+        // the other labels follow on the next screens
+        let locale = Locale::load(&settings.language);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = language_label(&locale);
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct ControlsButton;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct DisplayButton;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct CustomBoardButton;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct BackButton;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_board_assets)
+            .add_systems(Update, apply_ui_theme)
+            .add_systems(Update, apply_language)
+            .on_enter(AppState::Settings, setup_settings)
+            .on_update(
+                AppState::Settings,
+                (
+                    toggle_settings,
+                    casual_lives_button,
+                    ui_scale_button,
+                    announcements_button,
+                    language_button,
+                    controls_button,
+                    display_button,
+                    custom_board_button,
+                    back_button,
+                ),
+            )
+            .on_exit(AppState::Settings, (cleanup::<SettingsRoot>, save_settings));
+    }
+}
+
+fn setup_settings(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    settings: Res<Settings>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+) {
+    let font = &ui_assets.font;
+    cmds.spawn(screen_root())
+        .insert(SettingsRoot)
+        .insert(Name::new("Settings"))
+        .with_children(|parent| {
+            spawn_text(parent, font, locale.get("settings.title"), 40.);
+            spawn_text(parent, font, locale.get("settings.game"), 24.);
+            for toggle in SettingToggle::ALL {
+                let label = toggle.button_label(&settings, &locale);
+                spawn_button(parent, font, &label, toggle);
+            }
+            let lives = casual_lives_label(settings.casual_lives, &locale);
+            spawn_button(parent, font, &lives, CasualLivesButton);
+            let scale = ui_scale_label(settings.ui_scale, &locale);
+            spawn_button(parent, font, &scale, UiScaleButton);
+            let announcements = announcements_label(settings.announcements, &locale);
+            spawn_button(parent, font, &announcements, AnnouncementsButton);
+            spawn_button(parent, font, &language_label(&locale), LanguageButton);
+            spawn_button(
+                parent,
+                font,
+                locale.get("settings.controls"),
+                ControlsButton,
+            );
+            spawn_button(parent, font, locale.get("settings.display"), DisplayButton);
+            spawn_button(
+                parent,
+                font,
+                locale.get("settings.custom_board"),
+                CustomBoardButton,
+            );
+
+            // locked section, only editable through the config file
+            spawn_text(parent, font, locale.get("settings.safe_mode"), 24.);
+            let status = match config.safe_mode {
+                true => locale.get("settings.safe_mode_on"),
+                false => locale.get("common.off"),
+            };
+            spawn_text(parent, font, status, 12.);
+            let path = data_dir().join(CONFIG_FILE);
+            let hint = locale.format("settings.safe_mode_hint", &[("path", &path.display())]);
+            spawn_text(parent, font, &hint, 10.);
+
+            spawn_button(parent, font, locale.get("common.back"), BackButton);
+        });
+}
+
+fn controls_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Controls);
+    }
+}
+
+fn display_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<DisplayButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Display);
+    }
+}
+
+fn custom_board_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<CustomBoardButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::CustomBoard);
+    }
+}
+
+fn back_button(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        state.set(AppState::Menu);
+    }
+}
+
+// Applies the rendering settings to the next boards
+fn apply_board_assets(
+    settings: Res<Settings>,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlasLayout>>,
+    mut board_assets: ResMut<BoardAssets>,
+    mut cameras: Query<&mut Msaa, Is2dCamera>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    if settings.classic_tiles != board_assets.tile_sheet.is_some() {
+        board_assets.tile_sheet = settings
+            .classic_tiles
+            .then(|| TileSheet::classic(&asset_server, &mut atlases));
+    }
+    board_assets.number_palette = match settings.color_blind_numbers {
+        true => NumberPalette::ColorBlind,
+        false => NumberPalette::Classic,
+    };
+    board_assets.tile_colors = match (settings.high_contrast, settings.zen) {
+        (true, _) => TileColors::HIGH_CONTRAST,
+        (false, true) => TileColors::CALM,
+        (false, false) => TileColors::CLASSIC,
+    };
+    board_assets.decorations = match settings.decorations {
+        true => BoardDecorations::CLASSIC,
+        false => BoardDecorations::NONE,
+    };
+    board_assets.text_scale = settings.ui_scale;
+    board_assets.pixel_perfect = settings.pixel_art;
+    // pixel art edges stay sharp without multisampling
+    for mut msaa in cameras.iter_mut() {
+        *msaa = match settings.pixel_art {
+            true => Msaa::Off,
+            false => Msaa::Sample4,
+        };
+    }
+}
+
+// Applies the UI settings to the menus and the HUD
+fn apply_ui_theme(
+    settings: Res<Settings>,
+    mut theme: ResMut<UiTheme>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let new_theme = UiTheme {
+        scale: settings.ui_scale,
+        high_contrast: settings.high_contrast,
+    };
+    if *theme != new_theme {
+        *theme = new_theme;
+        *clear_color = theme.clear_color();
+    }
+}
+
+// Loads the language of the settings, and translates the window title
+fn apply_language(
+    settings: Res<Settings>,
+    mut locale: ResMut<Locale>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() || locale.language() == settings.language {
+        return;
+    }
+    *locale = Locale::load(&settings.language);
+    if let Ok(mut window) = windows.single_mut() {
+        window.title = locale.get("window.title").to_string();
+    }
+}
+
+fn save_settings(settings: Res<Settings>, profile: Res<Profile>) {
+    settings.save(&profile);
+}
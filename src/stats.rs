@@ -0,0 +1,102 @@
+use crate::achievements;
+use crate::persistence::{self, Profile};
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const STATS_FILE: &str = "stats.ron";
+
+// Daily challenge completions, days are counted since the Unix epoch in UTC
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyStats {
+    // Day of the last completed challenge
+    pub last_completed: Option<u32>,
+    // Consecutive days of completed challenges, up to `last_completed`
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl DailyStats {
+    pub fn is_completed(&self, day: u32) -> bool {
+        self.last_completed == Some(day)
+    }
+
+    // Streak still running on `today`, it breaks once a day is missed
+    pub fn current_streak(&self, today: u32) -> u32 {
+        match self.last_completed {
+            Some(day) if day + 1 >= today => self.streak,
+            _ => 0,
+        }
+    }
+
+    // Records the challenge of `day` as completed, extending the streak
+    pub fn complete(&mut self, day: u32) {
+        // challenges completed past midnight don't count twice
+        if self.last_completed.is_some_and(|d| d >= day) {
+            return;
+        }
+        self.streak = match self.last_completed {
+            Some(last) if last + 1 == day => self.streak + 1,
+            _ => 1,
+        };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_completed = Some(day);
+    }
+}
+
+// Boards cleared during a day, days are counted since the Unix epoch in UTC
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClearedBoards {
+    pub day: u32,
+    pub count: u32,
+}
+
+impl ClearedBoards {
+    // Boards cleared on `today`, none once the day is over
+    pub fn on(&self, today: u32) -> u32 {
+        if self.day == today {
+            self.count
+        } else {
+            0
+        }
+    }
+
+    pub fn record(&mut self, today: u32) {
+        self.count = self.on(today) + 1;
+        self.day = today;
+    }
+}
+
+// Player statistics. Must be used as a resource
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub daily: DailyStats,
+    // Boards won since the last lost one
+    pub win_streak: u32,
+    pub cleared: ClearedBoards,
+    // Best score of a blitz run
+    pub blitz_best: u32,
+    // Furthest level reached by a finished campaign
+    pub campaign_best_level: u32,
+    // Best time in seconds of every solved puzzle, by puzzle id
+    pub puzzle_times: HashMap<String, f32>,
+}
+
+impl Stats {
+    pub fn load(profile: &Profile) -> Self {
+        let mut stats: Self = persistence::load(profile, STATS_FILE);
+        // the streak was kept with the achievements before
+        if let Some(streak) = achievements::take_legacy_streak(profile) {
+            stats.win_streak = streak;
+            stats.save(profile);
+        }
+        stats
+    }
+
+    pub fn save(&self, profile: &Profile) {
+        persistence::save(profile, STATS_FILE, self);
+    }
+}
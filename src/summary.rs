@@ -0,0 +1,204 @@
+use crate::controls::{binding_label, restart_binding, RestartEvent};
+use crate::leaderboard::PendingScore;
+use crate::locale::Locale;
+use crate::score_attack::ScoreAttack;
+use crate::ui::{cleanup, screen_root, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::resources::{Board, GameTimer, InputMap};
+use minesweeper_core::{GameState, Metrics};
+
+#[derive(Component)]
+struct SummaryRoot;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+enum SummaryButton {
+    // Same board again, generated from its seed
+    PlayAgain,
+    NewBoard,
+    // Name entry of a new best time, the other buttons drop it
+    SaveRecord,
+    Menu,
+}
+
+// Summary of a finished plain game. The summary state is pushed over the game, leaving the
+// board visible under it, and the restart and retry bindings leave it for the next board
+pub struct SummaryPlugin;
+
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::Summary, setup_summary)
+            .on_update(AppState::Summary, (summary_buttons, summary_actions))
+            .on_exit(AppState::Summary, cleanup::<SummaryRoot>);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn setup_summary(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    boards: Query<&Board>,
+    timer: Res<GameTimer>,
+    pending: Option<Res<PendingScore>>,
+    score_attack: Option<Res<ScoreAttack>>,
+    input_map: Res<InputMap>,
+    locale: Res<Locale>,
+) {
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    let game = &board.game;
+    let won = game.state() == GameState::Won;
+    let final_score = score_attack.and_then(|a| a.result);
+    // score attack records are high scores
+    let score_record = pending.as_ref().is_some_and(|p| p.score.is_some());
+    let metrics = Metrics::new(game, timer.elapsed());
+    let font = &ui_assets.font;
+    // dims the board without hiding it
+    cmds.spawn((
+        screen_root(),
+        BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.8)),
+    ))
+    .insert(SummaryRoot)
+    .insert(Name::new("Summary"))
+    .with_children(|parent| {
+        let headline = match won {
+            true => locale.get("result.win"),
+            false => locale.get("result.lose"),
+        };
+        spawn_text(parent, font, headline, 40.);
+        if let Some(result) = final_score {
+            let score = locale.format("summary.score", &[("score", &result.score)]);
+            spawn_text(parent, font, &score, 28.);
+            let details = locale.format(
+                "summary.score_details",
+                &[
+                    ("points", &result.points),
+                    ("accuracy", &format!("{:.2}", result.accuracy)),
+                    ("wrong", &result.wrong_flags),
+                ],
+            );
+            spawn_text(parent, font, &details, 14.);
+        }
+        if pending.is_some() {
+            let record = match score_record {
+                true => "leaderboard.new_high_score",
+                false => "leaderboard.new_best",
+            };
+            spawn_text(parent, font, locale.get(record), 20.);
+        }
+        let time = format!("{:.2}", metrics.time);
+        let three_bv = match won {
+            true => locale.format("summary.three_bv", &[("three_bv", &metrics.three_bv)]),
+            false => locale.format(
+                "summary.three_bv_solved",
+                &[
+                    ("solved", &metrics.solved_three_bv),
+                    ("three_bv", &metrics.three_bv),
+                ],
+            ),
+        };
+        let rate = format!("{:.2}", metrics.three_bv_per_second());
+        // zen games hide the timer, and the rate following from it
+        let mut lines = match board.options.relaxed {
+            true => vec![three_bv],
+            false => vec![
+                locale.format("summary.time", &[("time", &time)]),
+                three_bv,
+                locale.format("summary.three_bv_per_second", &[("rate", &rate)]),
+            ],
+        };
+        lines.push(locale.format(
+            "summary.clicks",
+            &[
+                ("clicks", &metrics.clicks),
+                ("wasted", &metrics.wasted_clicks),
+            ],
+        ));
+        if let Some(efficiency) = metrics.efficiency() {
+            let efficiency = format!("{efficiency:.0}");
+            lines.push(locale.format("summary.efficiency", &[("efficiency", &efficiency)]));
+        }
+        lines.push(locale.format(
+            "summary.flags",
+            &[
+                ("flags", &game.flags().len()),
+                ("bombs", &board.tile_map().bomb_count()),
+            ],
+        ));
+        for line in lines {
+            spawn_text(parent, font, &line, 16.);
+        }
+
+        let mut buttons = vec![
+            (locale.get("summary.play_again"), SummaryButton::PlayAgain),
+            (locale.get("summary.new_board"), SummaryButton::NewBoard),
+        ];
+        if pending.is_some() {
+            let save = match score_record {
+                true => "summary.save_score",
+                false => "summary.save_record",
+            };
+            buttons.push((locale.get(save), SummaryButton::SaveRecord));
+        }
+        buttons.push((locale.get("summary.menu"), SummaryButton::Menu));
+        for (label, button) in buttons {
+            spawn_button(parent, font, label, button);
+        }
+        let hint = locale.format(
+            "summary.hint",
+            &[
+                ("retry", &binding_label(input_map.retry, &locale)),
+                ("restart", &binding_label(input_map.restart, &locale)),
+            ],
+        );
+        spawn_text(parent, font, &hint, 12.);
+    });
+}
+
+// Leaves the game for the picked board or screen, replacing the whole state stack
+fn summary_buttons(
+    mut cmds: Commands,
+    buttons: Query<(&Interaction, &SummaryButton), Changed<Interaction>>,
+    mut restart_ewr: MessageWriter<RestartEvent>,
+    mut state: StateSwitch<AppState>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if *button != SummaryButton::SaveRecord {
+            cmds.remove_resource::<PendingScore>();
+        }
+        match button {
+            SummaryButton::PlayAgain => {
+                restart_ewr.write(RestartEvent::SameBoard);
+            }
+            SummaryButton::NewBoard => {
+                restart_ewr.write(RestartEvent::NewBoard);
+            }
+            SummaryButton::SaveRecord => {
+                state.replace(AppState::NameEntry);
+            }
+            SummaryButton::Menu => {
+                state.replace(AppState::Menu);
+            }
+        }
+        return;
+    }
+}
+
+fn summary_actions(
+    mut cmds: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut restart_ewr: MessageWriter<RestartEvent>,
+) {
+    if let Some(event) = restart_binding(&input_map, &mouse, &keys) {
+        cmds.remove_resource::<PendingScore>();
+        restart_ewr.write(event);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::persistence::data_dir;
+use crate::ui::{cleanup, UiAssets};
+use crate::AppState;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use board_plugin::components::Coordinates;
+use board_plugin::engine::StateSystems;
+use board_plugin::grid::GridLayout;
+use board_plugin::resources::{BoardSilhouette, TileState};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use minesweeper_core::{Game, Tile};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+
+// Thumbnail width in pixels, whatever the board size
+const THUMBNAIL_WIDTH: f32 = 150.;
+
+// Classic tile sheet the pictures are drawn with, Bevy can't read back the rendered frames
+const TILE_SHEET: &[u8] = include_bytes!("../assets/sprites/tiles.png");
+
+// Width of the frames of the tile sheet, and of the tiles of the pictures
+const FRAME: u32 = 16;
+
+// Longest side of the board previews, in pixels
+pub const PREVIEW_SIZE: u32 = 96;
+
+// Version of the drawing, hashed with the cached previews so the ones drawn by older builds are
+// drawn again
+const PREVIEW_VERSION: u32 = 2;
+
+// Highest count of the tile sheet, the higher counts of multi-mine boards are drawn as digits
+const SHEET_MAX_COUNT: u8 = 8;
+
+// Digits of the counts past the tile sheet, 3x5 pixels, a row per byte from the top
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+// Silhouette of the opponent board in online versus, sized to the race board agreed in the
+// lobby and updated from its diffs. Must be used as a resource
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OpponentBoard {
+    pub name: String,
+    pub silhouette: BoardSilhouette,
+}
+
+#[derive(Component)]
+struct ThumbnailRoot;
+
+// Live miniature of the opponent board, shown while an `OpponentBoard` resource exists
+pub struct ThumbnailPlugin;
+
+impl Plugin for ThumbnailPlugin {
+    fn build(&self, app: &mut App) {
+        app.on_update(AppState::InGame, opponent_thumbnail)
+            .on_exit(AppState::InGame, cleanup::<ThumbnailRoot>);
+    }
+}
+
+fn tile_color(state: TileState) -> Color {
+    match state {
+        TileState::Covered => Color::srgb(0.25, 0.25, 0.25),
+        TileState::Revealed => Color::srgb(0.5, 0.5, 0.5),
+        TileState::Flagged => Color::srgb(1., 0., 0.),
+    }
+}
+
+// Respawns the thumbnail on every opponent board change
+fn opponent_thumbnail(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    opponent: Option<Res<OpponentBoard>>,
+    thumbnails: Query<Entity, With<ThumbnailRoot>>,
+) {
+    let opponent = match opponent {
+        Some(o) if o.is_changed() => o,
+        Some(_) => return,
+        None => {
+            for entity in thumbnails.iter() {
+                cmds.entity(entity).despawn();
+            }
+            return;
+        }
+    };
+    for entity in thumbnails.iter() {
+        cmds.entity(entity).despawn();
+    }
+
+    let silhouette = &opponent.silhouette;
+    let tile_size = THUMBNAIL_WIDTH / silhouette.width().max(1) as f32;
+    let label = format!(
+        "{} {:.0}% ({} flags)",
+        opponent.name,
+        silhouette.progress() * 100.,
+        silhouette.flags()
+    );
+    cmds.spawn(Node {
+        position_type: PositionType::Absolute,
+        top: Val::Px(10.),
+        right: Val::Px(10.),
+        flex_direction: FlexDirection::Column,
+        ..Default::default()
+    })
+    .insert(ThumbnailRoot)
+    .insert(Name::new("Opponent Thumbnail"))
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(label),
+            TextFont {
+                font: ui_assets.font.clone(),
+                font_size: 12.,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+        ));
+        // top row first
+        for y in (0..silhouette.height()).rev() {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    ..Default::default()
+                })
+                .with_children(|row| {
+                    for x in 0..silhouette.width() {
+                        let state = silhouette
+                            .get(Coordinates { x, y })
+                            .unwrap_or(TileState::Covered);
+                        row.spawn((
+                            Node {
+                                width: Val::Px(tile_size),
+                                height: Val::Px(tile_size),
+                                ..Default::default()
+                            },
+                            BackgroundColor(tile_color(state)),
+                        ));
+                    }
+                });
+        }
+    });
+}
+
+// Frame of the classic tile sheet showing a tile as the player sees it
+fn frame(game: &Game, coords: Coordinates, tile: Tile) -> u32 {
+    match tile {
+        _ if game.is_flagged(coords) => 1,
+        _ if game.is_covered(coords) => 0,
+        // the only revealed bomb ended the game
+        Tile::Bomb(_) => 12,
+        // the higher counts are drawn over a revealed tile
+        Tile::BombNeighbor(count) if count > SHEET_MAX_COUNT => 2,
+        Tile::BombNeighbor(count) => 2 + (count as u32).max(1),
+        Tile::Empty | Tile::Void => 2,
+    }
+}
+
+// Draws a count in the middle of a tile sprite, the digits doubled in size while they fit
+fn draw_count(sprite: &mut RgbaImage, count: u8) {
+    let digits: Vec<usize> = count
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let columns = digits.len() as u32 * 4 - 1;
+    let scale = if columns * 2 <= FRAME { 2 } else { 1 };
+    let left = (FRAME - columns * scale) / 2;
+    let top = (FRAME - 5 * scale) / 2;
+    let color = image::Rgba([0, 0, 0, 255]);
+    for (i, digit) in digits.iter().enumerate() {
+        for (row, bits) in DIGITS[*digit].iter().enumerate() {
+            for column in (0..3).filter(|c| bits & (0b100 >> c) != 0) {
+                let x = left + (i as u32 * 4 + column) * scale;
+                let y = top + row as u32 * scale;
+                for (dx, dy) in (0..scale).flat_map(|dx| (0..scale).map(move |dy| (dx, dy))) {
+                    sprite.put_pixel(x + dx, y + dy, color);
+                }
+            }
+        }
+    }
+}
+
+// Draws the board of a game from its state, with the tile layout of its grid
+pub fn picture(game: &Game) -> Result<RgbaImage, String> {
+    let sheet = image::load_from_memory(TILE_SHEET)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let tile_map = game.tile_map();
+    let grid = tile_map.grid().grid();
+    let size = grid.board_size(tile_map.width(), tile_map.height(), FRAME as f32);
+    let height = size.y.ceil() as u32;
+    let mut picture = RgbaImage::new(size.x.ceil() as u32, height);
+    let half = FRAME as f32 / 2.;
+    for (coords, tile) in tile_map.tiles() {
+        if !tile.exists() {
+            continue;
+        }
+        let mut sprite =
+            imageops::crop_imm(&sheet, frame(game, coords, tile) * FRAME, 0, FRAME, FRAME)
+                .to_image();
+        match tile {
+            Tile::BombNeighbor(count) if count > SHEET_MAX_COUNT && game.is_revealed(coords) => {
+                draw_count(&mut sprite, count)
+            }
+            _ => (),
+        }
+        // board rows go up from the bottom, picture rows down from the top
+        let center = grid.tile_center(coords, FRAME as f32);
+        let x = (center.x - half).round() as u32;
+        let y = height.saturating_sub((center.y + half).round() as u32);
+        imageops::overlay(&mut picture, &sprite, x, y);
+    }
+    Ok(picture)
+}
+
+// Small picture of a game, drawn once and cached in the data directory. The cached files are
+// named after the state of the game, a changed board is drawn again
+pub fn preview(game: &Game) -> Result<RgbaImage, String> {
+    let mut hasher = DefaultHasher::new();
+    PREVIEW_VERSION.hash(&mut hasher);
+    format!("{:?}", game.tile_map().grid()).hash(&mut hasher);
+    game.text_output().hash(&mut hasher);
+    let dir = data_dir().join("thumbnails");
+    let path = dir.join(format!("{:016x}.png", hasher.finish()));
+    if let Ok(cached) = image::open(&path) {
+        return Ok(cached.to_rgba8());
+    }
+
+    let picture = picture(game)?;
+    let scale = PREVIEW_SIZE as f32 / picture.width().max(picture.height()).max(1) as f32;
+    let width = ((picture.width() as f32 * scale).round() as u32).max(1);
+    let height = ((picture.height() as f32 * scale).round() as u32).max(1);
+    let preview = imageops::resize(&picture, width, height, FilterType::Triangle);
+    let saved = fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| preview.save(&path).map_err(|e| e.to_string()));
+    if let Err(e) = saved {
+        warn!("Failed to cache the thumbnail {}: {e}", path.display());
+    }
+    Ok(preview)
+}
+
+// Texture of a picture, for the UI images
+pub fn texture(picture: &RgbaImage) -> Image {
+    Image::new(
+        Extent3d {
+            width: picture.width(),
+            height: picture.height(),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        picture.as_raw().clone(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
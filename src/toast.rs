@@ -0,0 +1,164 @@
+use crate::settings::Settings;
+use crate::ui::{spawn_text, UiAssets};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+// Seconds a toast stays on screen, sliding in and out included
+const TOAST_SECONDS: f32 = 4.;
+// Seconds of the slide in and out
+const SLIDE_SECONDS: f32 = 0.3;
+const TOAST_WIDTH: f32 = 360.;
+// Height of a toast with its spacing, the toasts stack up from the bottom right corner
+const TOAST_HEIGHT: f32 = 44.;
+const MARGIN: f32 = 10.;
+// Toasts on screen at once, the next ones wait in the queue
+const MAX_TOASTS: usize = 3;
+
+// Tint of a toast
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Error,
+}
+
+impl ToastKind {
+    fn color(&self) -> Color {
+        match self {
+            Self::Info => Color::srgba(0., 0., 0., 0.8),
+            Self::Success => Color::srgba(0.05, 0.3, 0.1, 0.8),
+            Self::Error => Color::srgba(0.45, 0.05, 0.05, 0.8),
+        }
+    }
+}
+
+// Sent by any plugin to show a notification, from the menus or during a game
+#[derive(Message, Debug, Clone)]
+pub struct ToastEvent {
+    pub message: String,
+    pub kind: ToastKind,
+}
+
+impl ToastEvent {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            kind: ToastKind::Info,
+        }
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            kind: ToastKind::Success,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            kind: ToastKind::Error,
+        }
+    }
+}
+
+// Toasts waiting for a free spot on screen
+#[derive(Resource, Debug, Default)]
+struct ToastQueue(VecDeque<ToastEvent>);
+
+// Toast on screen, despawned once its timer is over. The toasts are stacked in their order
+#[derive(Component)]
+struct Toast {
+    timer: Timer,
+    order: u64,
+}
+
+// Queue of timed notification banners sliding in from the bottom right corner of the window,
+// whatever the state of the app
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ToastEvent>()
+            .init_resource::<ToastQueue>()
+            .add_systems(Update, show_toasts)
+            .add_systems(Update, animate_toasts);
+    }
+}
+
+// Queues the sent toasts, and shows the queued ones while there is room on screen
+fn show_toasts(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    mut queue: ResMut<ToastQueue>,
+    toasts: Query<&Toast>,
+    mut next_order: Local<u64>,
+    mut toast_evr: MessageReader<ToastEvent>,
+) {
+    queue.0.extend(toast_evr.read().cloned());
+    let mut shown = toasts.iter().count();
+    while shown < MAX_TOASTS {
+        let toast = match queue.0.pop_front() {
+            Some(t) => t,
+            None => break,
+        };
+        info!("Toast: {}", toast.message);
+        cmds.spawn((
+            // off screen until animated
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(MARGIN + shown as f32 * TOAST_HEIGHT),
+                right: Val::Px(-TOAST_WIDTH),
+                width: Val::Px(TOAST_WIDTH),
+                height: Val::Auto,
+                padding: UiRect::all(Val::Px(8.)),
+                ..Default::default()
+            },
+            BackgroundColor(toast.kind.color()),
+        ))
+        .insert(Toast {
+            timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+            order: *next_order,
+        })
+        .insert(Name::new("Toast"))
+        .with_children(|parent| {
+            spawn_text(parent, &ui_assets.font, &toast.message, 16.);
+        });
+        *next_order += 1;
+        shown += 1;
+    }
+}
+
+// Slides the toasts in and out, moving the stack down as the older toasts expire
+fn animate_toasts(
+    mut cmds: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut toasts: Query<(Entity, &mut Toast, &mut Node)>,
+) {
+    let mut stack: Vec<(u64, Entity)> = Vec::new();
+    for (entity, mut toast, mut node) in toasts.iter_mut() {
+        toast.timer.tick(time.delta());
+        if toast.timer.is_finished() {
+            cmds.entity(entity).despawn();
+            continue;
+        }
+        let elapsed = toast.timer.elapsed_secs();
+        let left = TOAST_SECONDS - elapsed;
+        // reduced motion shows the toasts in place
+        let shown = match settings.reduced_motion {
+            true => 1.,
+            false => (elapsed.min(left) / SLIDE_SECONDS).min(1.),
+        };
+        // eased out
+        let offset = (1. - shown).powi(2);
+        node.right = Val::Px(MARGIN - offset * (TOAST_WIDTH + MARGIN));
+        stack.push((toast.order, entity));
+    }
+    stack.sort_unstable();
+    for (slot, (_, entity)) in stack.into_iter().enumerate() {
+        if let Ok((_, _, mut node)) = toasts.get_mut(entity) {
+            node.bottom = Val::Px(MARGIN + slot as f32 * TOAST_HEIGHT);
+        }
+    }
+}
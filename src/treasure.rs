@@ -0,0 +1,124 @@
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::engine::StateSystems;
+use board_plugin::events::TreasureFoundEvent;
+use board_plugin::resources::{Board, BoardOptions, Treasure};
+
+// Treasures hidden on a treasure hunt board
+const TREASURES: u16 = 6;
+
+// Treasure hunt in progress. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct TreasureHunt {
+    found: u16,
+    // Last treasure found, shown until the next one
+    last: Option<Treasure>,
+}
+
+#[derive(Component)]
+struct TreasureRoot;
+
+#[derive(Debug, Copy, Clone, Component)]
+enum TreasureText {
+    Found,
+    Last,
+}
+
+// Variant mode hiding treasures under safe tiles: time bonuses and extra lives sparing the
+// player from a bomb. The game otherwise ends like a classic game, without ranking
+pub struct TreasurePlugin;
+
+impl Plugin for TreasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.on_enter(AppState::InGame, setup_treasure_hud)
+            .on_update(AppState::InGame, update_treasure_hud)
+            .on_exit(AppState::InGame, cleanup::<TreasureRoot>)
+            // kept through the summary and its restarts
+            .on_enter(AppState::Menu, end_treasure_hunt);
+    }
+}
+
+// Options of a treasure hunt board
+pub fn treasure_options(difficulty: Difficulty, settings: &Settings) -> BoardOptions {
+    BoardOptions {
+        treasures: TREASURES,
+        ..game_options(difficulty, settings)
+    }
+}
+
+fn setup_treasure_hud(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    hunt: Option<ResMut<TreasureHunt>>,
+) {
+    let mut hunt = match hunt {
+        Some(h) => h,
+        None => return,
+    };
+    *hunt = TreasureHunt::default();
+
+    let font = &ui_assets.font;
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(TreasureRoot)
+    .insert(Name::new("Treasure HUD"))
+    .with_children(|parent| {
+        for text in [TreasureText::Found, TreasureText::Last] {
+            spawn_text(parent, font, "", 16.).insert(text);
+        }
+    });
+}
+
+// Counts the treasures found, the lives show with the hearts
+fn update_treasure_hud(
+    hunt: Option<ResMut<TreasureHunt>>,
+    boards: Query<&Board>,
+    locale: Res<Locale>,
+    mut treasure_found_evr: MessageReader<TreasureFoundEvent>,
+    mut texts: Query<(&TreasureText, &mut Text)>,
+) {
+    let mut hunt = match hunt {
+        Some(h) => h,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    for event in treasure_found_evr.read() {
+        hunt.found += 1;
+        hunt.last = Some(event.treasure);
+    }
+    for (text_kind, mut text) in texts.iter_mut() {
+        text.0 = match text_kind {
+            TreasureText::Found => locale.format(
+                "treasure.found",
+                &[("found", &hunt.found), ("total", &board.options.treasures)],
+            ),
+            TreasureText::Last => match hunt.last {
+                Some(Treasure::TimeBonus(seconds)) => {
+                    locale.format("treasure.time_bonus", &[("seconds", &seconds)])
+                }
+                Some(Treasure::ExtraLife) => locale.get("treasure.extra_life").to_string(),
+                None => String::new(),
+            },
+        };
+    }
+}
+
+fn end_treasure_hunt(mut cmds: Commands) {
+    cmds.remove_resource::<TreasureHunt>();
+}
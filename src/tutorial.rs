@@ -0,0 +1,330 @@
+use crate::changelog;
+use crate::difficulty::Difficulty;
+use crate::locale::Locale;
+use crate::menu::game_options;
+use crate::persistence::Profile;
+use crate::settings::Settings;
+use crate::ui::{cleanup, spawn_button, spawn_text, UiAssets};
+use crate::AppState;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::engine::{StateSwitch, StateSystems};
+use board_plugin::events::BoardCommand;
+use board_plugin::generation::{FixedGenerator, RegisterBoardGenerator};
+use board_plugin::grid::GridKind;
+use board_plugin::resources::{
+    tile_map::TileMap, Board, BoardOptions, ExplosionEffects, InputGate,
+};
+use minesweeper_core::GameState;
+
+// Scripted board of the tutorial, opened from its top left corner
+const BOARD: &str = "
+    ...1**
+    ...13*
+    ..1121
+    ..1*1.
+";
+// Name of the generator dealing the tutorial board
+const GENERATOR: &str = "tutorial";
+// Seconds the last prompt stays on screen once the board is cleared
+const DONE_SECONDS: f32 = 3.;
+// Tint of the number a step explains, and of the tile it expects a move on
+const NUMBER_HIGHLIGHT: Color = Color::srgba(0.2, 0.5, 1., 0.4);
+const TARGET_HIGHLIGHT: Color = Color::srgba(1., 0.9, 0., 0.4);
+
+// Move expected by a tutorial step
+#[derive(Debug, Copy, Clone)]
+enum Expected {
+    Uncover(Coordinates),
+    Flag(Coordinates),
+    Chord(Coordinates),
+    // Any move clearing the board
+    Clear,
+}
+
+// Prompt of the tutorial, with the number it explains and the move letting the player through
+struct Step {
+    prompt: &'static str,
+    number: Option<Coordinates>,
+    expected: Expected,
+}
+
+const STEPS: [Step; 4] = [
+    Step {
+        prompt: "tutorial.uncover",
+        number: None,
+        expected: Expected::Uncover(Coordinates { x: 0, y: 3 }),
+    },
+    Step {
+        prompt: "tutorial.flag",
+        number: Some(Coordinates { x: 2, y: 0 }),
+        expected: Expected::Flag(Coordinates { x: 3, y: 0 }),
+    },
+    Step {
+        prompt: "tutorial.chord",
+        number: None,
+        expected: Expected::Chord(Coordinates { x: 3, y: 1 }),
+    },
+    Step {
+        prompt: "tutorial.clear",
+        number: None,
+        expected: Expected::Clear,
+    },
+];
+
+impl Step {
+    // Has the board moved past the step?
+    fn is_done(&self, board: &Board) -> bool {
+        let game = &board.game;
+        match self.expected {
+            Expected::Uncover(c) => game.is_revealed(c),
+            Expected::Flag(c) => game.is_flagged(c),
+            Expected::Chord(c) => board
+                .tile_map()
+                .neighbors(c)
+                .all(|n| game.is_revealed(n) || game.is_flagged(n)),
+            Expected::Clear => game.state() == GameState::Won,
+        }
+    }
+
+    // Only move the input gate lets through, `None` for the free play
+    fn command(&self, board: Entity) -> Option<BoardCommand> {
+        match self.expected {
+            Expected::Uncover(coordinates) => {
+                Some(BoardCommand::UncoverTile { board, coordinates })
+            }
+            Expected::Flag(coordinates) => Some(BoardCommand::ToggleFlag { board, coordinates }),
+            Expected::Chord(coordinates) => Some(BoardCommand::ChordTile { board, coordinates }),
+            Expected::Clear => None,
+        }
+    }
+
+    fn target(&self) -> Option<Coordinates> {
+        match self.expected {
+            Expected::Uncover(c) | Expected::Flag(c) | Expected::Chord(c) => Some(c),
+            Expected::Clear => None,
+        }
+    }
+}
+
+// Tutorial in progress. Must be used as a resource
+#[derive(Resource, Debug, Default)]
+pub struct Tutorial {
+    // Index in `STEPS`, past the last step once the board is cleared
+    step: usize,
+    // Step shown by the prompt and the highlights
+    shown: Option<usize>,
+    // Seconds left on the last prompt
+    cooldown: f32,
+}
+
+#[derive(Component)]
+struct TutorialRoot;
+
+#[derive(Component)]
+struct TutorialText;
+
+// Highlight of a tile the prompt talks about, child of the tile
+#[derive(Component)]
+struct TutorialHighlight;
+
+#[derive(Debug, Copy, Clone, Component)]
+struct SkipButton;
+
+// Interactive tutorial on a small scripted board: every step prompts for a move and only lets
+// that move through, until the board is free to clear. It starts on the first launch of a
+// profile, and can be played again from the menu
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_board_generator(GENERATOR, FixedGenerator(tutorial_map()))
+            .on_enter(AppState::InGame, setup_tutorial_hud)
+            .on_update(AppState::InGame, (follow_steps, skip_button))
+            .on_exit(AppState::InGame, (cleanup::<TutorialRoot>, end_tutorial));
+    }
+}
+
+fn tutorial_map() -> TileMap {
+    TileMap::from_text(BOARD, GridKind::Square).expect("Invalid tutorial board")
+}
+
+// Has the profile never been through the tutorial? The players updating from a release without
+// the tutorial have seen a changelog already
+pub fn is_pending(settings: &Settings) -> bool {
+    !settings.tutorial_done && settings.version.is_none()
+}
+
+// Options of the tutorial board, without any assist or effect getting in the way of the steps.
+// Its bombs are flagged instead of lost
+pub fn tutorial_options(settings: &Settings) -> BoardOptions {
+    let base = BoardOptions {
+        grid: GridKind::Square,
+        auto_flag: false,
+        strict_flags: false,
+        no_flags: false,
+        preview_rerolls: None,
+        undos: 0,
+        lives: 0,
+        relaxed: true,
+        confirm_last_guess: false,
+        explosion: ExplosionEffects::NONE,
+        ..game_options(Difficulty::Beginner, settings)
+    };
+    FixedGenerator(tutorial_map()).options(GENERATOR, base)
+}
+
+// State following the tutorial, the changelog of the first launch or the menu
+fn next_state(settings: &Settings) -> AppState {
+    match changelog::has_news(settings) {
+        true => AppState::WhatsNew,
+        false => AppState::Menu,
+    }
+}
+
+fn setup_tutorial_hud(
+    mut cmds: Commands,
+    ui_assets: Res<UiAssets>,
+    tutorial: Option<ResMut<Tutorial>>,
+    locale: Res<Locale>,
+) {
+    let mut tutorial = match tutorial {
+        Some(t) => t,
+        None => return,
+    };
+    *tutorial = Tutorial::default();
+
+    let font = &ui_assets.font;
+    cmds.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexStart,
+            padding: UiRect::all(Val::Px(6.)),
+            ..Default::default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+    ))
+    .insert(TutorialRoot)
+    .insert(Name::new("Tutorial"))
+    .with_children(|parent| {
+        spawn_text(parent, font, "", 14.).insert(TutorialText);
+        spawn_button(parent, font, locale.get("tutorial.skip"), SkipButton);
+    });
+}
+
+// Moves through the steps the board went past, updating the prompt, the highlights and the
+// input gate of the step reached
+#[allow(clippy::too_many_arguments)]
+fn follow_steps(
+    mut cmds: Commands,
+    tutorial: Option<ResMut<Tutorial>>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    boards: Query<&Board>,
+    highlights: Query<Entity, With<TutorialHighlight>>,
+    mut texts: Query<&mut Text, With<TutorialText>>,
+    mut state: StateSwitch<AppState>,
+) {
+    let mut tutorial = match tutorial {
+        Some(t) => t,
+        None => return,
+    };
+    let board = match boards.iter().next() {
+        Some(b) => b,
+        None => return,
+    };
+    while STEPS
+        .get(tutorial.step)
+        .is_some_and(|step| step.is_done(board))
+    {
+        tutorial.step += 1;
+    }
+
+    if tutorial.shown != Some(tutorial.step) {
+        tutorial.shown = Some(tutorial.step);
+        for entity in highlights.iter() {
+            cmds.entity(entity).despawn();
+        }
+        let step = STEPS.get(tutorial.step);
+        let prompt = step.map_or("tutorial.done", |s| s.prompt);
+        for mut text in texts.iter_mut() {
+            text.0 = locale.get(prompt).to_string();
+        }
+        match step.and_then(|s| s.command(board.entity)) {
+            Some(command) => cmds.insert_resource(InputGate {
+                expected: Some(command),
+            }),
+            None => cmds.remove_resource::<InputGate>(),
+        }
+        if let Some(step) = step {
+            highlight(&mut cmds, board, step.number, NUMBER_HIGHLIGHT);
+            highlight(&mut cmds, board, step.target(), TARGET_HIGHLIGHT);
+        } else {
+            info!("Tutorial completed");
+            tutorial.cooldown = DONE_SECONDS;
+        }
+    }
+
+    if tutorial.step == STEPS.len() {
+        tutorial.cooldown -= time.delta_secs();
+        if tutorial.cooldown <= 0. {
+            state.set(next_state(&settings));
+        }
+    }
+}
+
+fn highlight(cmds: &mut Commands, board: &Board, coordinates: Option<Coordinates>, color: Color) {
+    let tile = match coordinates.and_then(|c| board.tiles.get(&c)) {
+        Some(t) => *t,
+        None => return,
+    };
+    let size = board.tile_size - board.options.tile_padding;
+    cmds.entity(tile).with_children(|parent| {
+        parent
+            .spawn((
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                }, // above the cover
+                Transform::from_xyz(0., 0., 3.),
+            ))
+            .insert(Name::new("Tutorial highlight"))
+            .insert(TutorialHighlight);
+    });
+}
+
+fn skip_button(
+    tutorial: Option<Res<Tutorial>>,
+    settings: Res<Settings>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<SkipButton>)>,
+    mut state: StateSwitch<AppState>,
+) {
+    if tutorial.is_none() {
+        return;
+    }
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        info!("Tutorial skipped");
+        state.set(next_state(&settings));
+    }
+}
+
+// Records the tutorial as done, whether it was cleared, skipped or left from the pause menu
+fn end_tutorial(
+    mut cmds: Commands,
+    tutorial: Option<Res<Tutorial>>,
+    mut settings: ResMut<Settings>,
+    profile: Res<Profile>,
+) {
+    if tutorial.is_none() {
+        return;
+    }
+    settings.tutorial_done = true;
+    settings.save(&profile);
+    cmds.remove_resource::<Tutorial>();
+    cmds.remove_resource::<InputGate>();
+}
@@ -0,0 +1,225 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+pub const NORMAL_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+pub const HOVERED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
+pub const PRESSED_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+
+// Black buttons turning blue, for white texts on a black background
+const HIGH_CONTRAST_BUTTON: Color = Color::BLACK;
+const HIGH_CONTRAST_HOVERED_BUTTON: Color = Color::srgb(0., 0., 0.6);
+const HIGH_CONTRAST_PRESSED_BUTTON: Color = Color::srgb(0., 0., 0.3);
+
+// Assets shared by every menu screen. Must be used as a resource
+#[derive(Resource)]
+pub struct UiAssets {
+    pub font: Handle<Font>,
+    // Heart of the lives HUD
+    pub heart: Handle<Image>,
+}
+
+impl FromWorld for UiAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        Self {
+            font: asset_server.load("fonts/pixeled.ttf"),
+            heart: asset_server.load("sprites/heart.png"),
+        }
+    }
+}
+
+// Size and colors of the menus and the HUD. Must be used as a resource
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct UiTheme {
+    // Multiplier of the font and button sizes
+    pub scale: f32,
+    pub high_contrast: bool,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            high_contrast: false,
+        }
+    }
+}
+
+impl UiTheme {
+    pub fn button_color(&self, interaction: Interaction) -> Color {
+        match (self.high_contrast, interaction) {
+            (false, Interaction::Pressed) => PRESSED_BUTTON,
+            (false, Interaction::Hovered) => HOVERED_BUTTON,
+            (false, Interaction::None) => NORMAL_BUTTON,
+            (true, Interaction::Pressed) => HIGH_CONTRAST_PRESSED_BUTTON,
+            (true, Interaction::Hovered) => HIGH_CONTRAST_HOVERED_BUTTON,
+            (true, Interaction::None) => HIGH_CONTRAST_BUTTON,
+        }
+    }
+
+    pub fn clear_color(&self) -> ClearColor {
+        match self.high_contrast {
+            true => ClearColor(Color::BLACK),
+            false => ClearColor::default(),
+        }
+    }
+}
+
+// Full screen root node laying out its children top to bottom
+pub fn screen_root() -> Node {
+    Node {
+        width: Val::Percent(100.),
+        height: Val::Percent(100.),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..Default::default()
+    }
+}
+
+// Spawns a text node
+pub fn spawn_text<'a>(
+    parent: &'a mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    value: &str,
+    size: f32,
+) -> EntityCommands<'a> {
+    parent.spawn((
+        Text::new(value),
+        TextFont {
+            font: font.clone(),
+            font_size: size,
+            ..Default::default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            margin: UiRect::all(Val::Px(4.)),
+            ..Default::default()
+        },
+    ))
+}
+
+// Spawns a labeled button carrying the given action component
+pub fn spawn_button<A: Component>(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    label: &str,
+    action: A,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(300.),
+                height: Val::Px(50.),
+                margin: UiRect::all(Val::Px(6.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+        ))
+        .insert(action)
+        .with_children(|parent| {
+            spawn_text(parent, font, label, 20.);
+        });
+}
+
+// Updates button colors on interaction
+pub fn button_colors(
+    theme: Res<UiTheme>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut color) in buttons.iter_mut() {
+        *color = theme.button_color(*interaction).into();
+    }
+}
+
+// Applies the theme to the new texts and buttons, and to all of them when the theme changes
+pub fn apply_theme(
+    theme: Res<UiTheme>,
+    mut applied: Local<UiTheme>,
+    mut texts: Query<(&mut TextFont, Ref<Text>), With<Node>>,
+    mut buttons: Query<(&mut Node, &mut BackgroundColor, &Interaction, Ref<Button>)>,
+) {
+    // the existing nodes are already at the applied scale
+    let rescale = theme.scale / applied.scale;
+    for (mut font, tracker) in texts.iter_mut() {
+        let factor = if tracker.is_added() {
+            theme.scale
+        } else {
+            rescale
+        };
+        if factor != 1. {
+            font.font_size *= factor;
+        }
+    }
+    for (mut node, mut color, interaction, tracker) in buttons.iter_mut() {
+        let factor = if tracker.is_added() {
+            theme.scale
+        } else {
+            rescale
+        };
+        if factor != 1. {
+            let node = &mut *node;
+            for val in [&mut node.width, &mut node.height] {
+                if let Val::Px(px) = val {
+                    *px *= factor;
+                }
+            }
+        }
+        if tracker.is_added() || *theme != *applied {
+            *color = theme.button_color(*interaction).into();
+        }
+    }
+    *applied = *theme;
+}
+
+// Despawns every entity with the `T` marker component
+pub fn cleanup<T: Component>(mut cmds: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        cmds.entity(entity).despawn();
+    }
+}
+
+// Single line text typed with the keyboard, displayed by the `Text` of the same entity
+#[derive(Debug, Default, Component)]
+pub struct TextInput {
+    pub value: String,
+    pub max_length: usize,
+}
+
+// Feeds the typed characters to the text inputs
+pub fn text_input(
+    mut keyboard_evr: MessageReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut inputs: Query<(&mut TextInput, &mut Text)>,
+) {
+    let typed: Vec<char> = keyboard_evr
+        .read()
+        .filter(|e| e.state.is_pressed())
+        .filter_map(|e| match &e.logical_key {
+            Key::Character(chars) => Some(chars.chars()),
+            _ => None,
+        })
+        .flatten()
+        .filter(|c| !c.is_control())
+        .collect();
+    let erase = keys.just_pressed(KeyCode::Backspace);
+    if typed.is_empty() && !erase {
+        return;
+    }
+
+    for (mut input, mut text) in inputs.iter_mut() {
+        for c in typed.iter() {
+            if input.value.chars().count() < input.max_length {
+                input.value.push(*c);
+            }
+        }
+        if erase {
+            input.value.pop();
+        }
+        text.0 = format!("{}_", input.value);
+    }
+}
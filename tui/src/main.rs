@@ -0,0 +1,289 @@
+// Terminal front-end, playing the core game without any window: arrow keys move the cursor,
+// Space uncovers, F flags, U undoes, R restarts and Q quits.
+// `--autoplay` lets the solver play a game and prints the board, for smoke tests
+use minesweeper_core::solver::Deduction;
+use minesweeper_core::{Coordinates, Game, GameState, Preset, Tile};
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::env;
+use std::io;
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: tui [beginner|intermediate|expert] [--seed <seed>] [--autoplay]";
+// Text of the bomb counts, two columns wide like every tile
+const NUMBERS: [&str; 9] = [" 0", " 1", " 2", " 3", " 4", " 5", " 6", " 7", " 8"];
+
+struct Args {
+    preset: Preset,
+    seed: Option<u64>,
+    autoplay: bool,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Args {
+            preset: Preset::Beginner,
+            seed: None,
+            autoplay: false,
+        };
+        let mut iter = env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--autoplay" => args.autoplay = true,
+                "--seed" => {
+                    let seed = iter.next().ok_or("Missing seed")?;
+                    args.seed = Some(seed.parse().map_err(|_| format!("Invalid seed {seed}"))?);
+                }
+                name => {
+                    args.preset = Preset::from_name(name)
+                        .ok_or_else(|| format!("Unknown argument {name}"))?;
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+// Game in progress and its cursor
+struct App {
+    preset: Preset,
+    game: Game,
+    seed: u64,
+    cursor: Coordinates,
+    // Set by the first move, the opening doesn't count
+    started: Option<Instant>,
+    // Play time of a finished game
+    finished: Option<Duration>,
+}
+
+impl App {
+    // Starts a game on a safe opening, the seed generating the same board again
+    fn new(preset: Preset, seed: u64) -> Self {
+        let tile_map = preset.tile_map(seed);
+        let cursor = tile_map.opening().unwrap_or_default();
+        let mut game = Game::new(tile_map);
+        game.open();
+        Self {
+            preset,
+            game,
+            seed,
+            cursor,
+            started: None,
+            finished: None,
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match (self.finished, self.started) {
+            (Some(time), _) => time,
+            (None, Some(start)) => start.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let tile_map = self.game.tile_map();
+        let (width, height) = (tile_map.width() as i32, tile_map.height() as i32);
+        self.cursor = Coordinates {
+            x: (self.cursor.x as i32 + dx).clamp(0, width - 1) as u16,
+            y: (self.cursor.y as i32 + dy).clamp(0, height - 1) as u16,
+        };
+    }
+
+    // Plays a move, then stops the timer if it ended the game
+    fn play(&mut self, coords: Coordinates, flag: bool) {
+        let played = match flag {
+            true => self.game.toggle_flag(coords).is_some(),
+            false => self.game.reveal(coords).is_some(),
+        };
+        if played && self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+        if self.game.state() != GameState::Playing && self.finished.is_none() {
+            self.finished = Some(self.elapsed());
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.game.undo().is_some() {
+            self.finished = None;
+        }
+    }
+
+    // Handles a key press, returning false to quit
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            // the first row is the bottom one, like on the bevy board
+            KeyCode::Up => self.move_cursor(0, 1),
+            KeyCode::Down => self.move_cursor(0, -1),
+            KeyCode::Char(' ') | KeyCode::Enter => self.play(self.cursor, false),
+            KeyCode::Char('f') => self.play(self.cursor, true),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') => *self = Self::new(self.preset, thread_rng().gen()),
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => (),
+        }
+        true
+    }
+
+    fn status(&self) -> String {
+        let seconds = self.elapsed().as_secs_f32();
+        match self.game.state() {
+            GameState::Won => format!("Cleared in {seconds:.2}s! R: new game, Q: quit"),
+            GameState::Lost => "Boom! U: undo, R: new game, Q: quit".to_string(),
+            GameState::Playing => {
+                let mines = self.game.mines_left();
+                format!("Mines: {mines} - Time: {seconds:.0}s")
+            }
+        }
+    }
+}
+
+// Text and color of a tile, the bombs are shown once the game is lost
+fn tile_span(game: &Game, coords: Coordinates) -> (&'static str, Style) {
+    let tile = game.tile_map()[coords.y as usize][coords.x as usize];
+    let show_bomb = game.state() == GameState::Lost && tile.is_bomb();
+    if game.is_flagged(coords) {
+        return (" F", Style::default().fg(Color::Red));
+    }
+    if game.is_covered(coords) && !show_bomb {
+        return (" .", Style::default().fg(Color::DarkGray));
+    }
+    match tile {
+        Tile::Bomb(_) => (" *", Style::default().fg(Color::LightRed)),
+        Tile::BombNeighbor(count) => {
+            let color = match count {
+                1 => Color::Cyan,
+                2 => Color::Green,
+                3 => Color::Yellow,
+                4 => Color::Magenta,
+                _ => Color::Red,
+            };
+            (NUMBERS[count as usize], Style::default().fg(color))
+        }
+        Tile::Empty => ("  ", Style::default()),
+        Tile::Void => ("  ", Style::default()),
+    }
+}
+
+// Board rows, top row first
+fn board_lines(game: &Game, cursor: Option<Coordinates>) -> Vec<Line<'static>> {
+    let tile_map = game.tile_map();
+    (0..tile_map.height())
+        .rev()
+        .map(|y| {
+            let spans: Vec<Span> = (0..tile_map.width())
+                .map(|x| {
+                    let coords = Coordinates { x, y };
+                    let (text, mut style) = tile_span(game, coords);
+                    if cursor == Some(coords) {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Span::styled(text, style.add_modifier(Modifier::BOLD))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [status, board, help] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    frame.render_widget(Paragraph::new(app.status()), status);
+    let title = format!(" Minesweeper - seed {} ", app.seed);
+    frame.render_widget(
+        Paragraph::new(board_lines(&app.game, Some(app.cursor)))
+            .block(Block::bordered().title(title)),
+        board,
+    );
+    let keys = "Arrows: move - Space: uncover - F: flag - U: undo - R: new game - Q: quit";
+    frame.render_widget(
+        Paragraph::new(keys).style(Style::default().fg(Color::DarkGray)),
+        help,
+    );
+}
+
+fn run(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, &app))?;
+        // wakes up regularly to refresh the timer
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// Plays the solver hints, guessing when none is left, and prints the final board
+fn autoplay(mut app: App) {
+    let mut rng = StdRng::seed_from_u64(app.seed);
+    while app.game.state() == GameState::Playing {
+        let hint = app.game.solver().hint(app.game.flags());
+        match hint {
+            Some(Deduction::Safe(coords)) => app.play(coords, false),
+            Some(Deduction::Bomb(coords)) => app.play(coords, true),
+            None => {
+                let game = &app.game;
+                let tile_map = game.tile_map();
+                let guess = (0..tile_map.height())
+                    .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinates { x, y }))
+                    .filter(|c| game.is_covered(*c) && !game.is_flagged(*c))
+                    .choose(&mut rng);
+                match guess {
+                    Some(coords) => app.play(coords, false),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    for line in board_lines(&app.game, None) {
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        println!("{text}");
+    }
+    match app.game.state() {
+        GameState::Won => println!("Cleared the board of seed {}", app.seed),
+        GameState::Lost => println!("Hit a mine on the board of seed {}", app.seed),
+        GameState::Playing => println!("Gave up on the board of seed {}", app.seed),
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args = match Args::parse() {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+    let app = App::new(args.preset, seed);
+    if args.autoplay {
+        autoplay(app);
+        return Ok(());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, app);
+    ratatui::restore();
+    result
+}